@@ -0,0 +1,84 @@
+//! SSE encoding throughput over a synthetic high-rate Gemini stream: the buffered
+//! `gemini_events` path against serializing each chunk with `Event::json_data`.
+//!
+//! Run with `cargo bench --bench gemini_sse_stream`. Uses `std` timing only, so no extra
+//! dependencies are needed.
+
+use axum::response::{
+    IntoResponse,
+    sse::{Event, Sse},
+};
+use futures::{Stream, stream};
+use pollux::server::gemini_sse::gemini_events;
+use pollux_schema::gemini::GeminiResponseBody;
+use serde_json::json;
+use std::convert::Infallible;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const CHUNKS: usize = 10_000;
+const ROUNDS: u32 = 20;
+
+fn chunks() -> Vec<GeminiResponseBody> {
+    (0..CHUNKS)
+        .map(|i| {
+            serde_json::from_value(json!({
+                "candidates": [{"index": 0, "content": {"role": "model", "parts": [
+                    {"text": format!("token {i} of a long streamed answer, with some more words. ")}
+                ]}}],
+                "usageMetadata": {"promptTokenCount": 1200, "candidatesTokenCount": i},
+                "modelVersion": "gemini-2.5-pro",
+                "responseId": "bench"
+            }))
+            .expect("chunk must parse")
+        })
+        .collect()
+}
+
+async fn drain(events: impl Stream<Item = Result<Event, Infallible>> + Send + 'static) -> usize {
+    let body = Sse::new(events).into_response().into_body();
+    axum::body::to_bytes(body, usize::MAX)
+        .await
+        .expect("body must collect")
+        .len()
+}
+
+async fn bench<F, S>(name: &str, events: F)
+where
+    F: Fn(Vec<GeminiResponseBody>) -> S,
+    S: Stream<Item = Result<Event, Infallible>> + Send + 'static,
+{
+    let mut best = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let input = chunks();
+        let started = Instant::now();
+        black_box(drain(events(input)).await);
+        best = best.min(started.elapsed());
+    }
+
+    println!(
+        "{name}/{CHUNKS} chunks: best {:?} per stream ({:?} per event) over {ROUNDS} rounds",
+        best,
+        best / CHUNKS as u32
+    );
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    bench("json_data_per_event", |input| {
+        stream::iter(
+            input
+                .into_iter()
+                .map(|chunk| Ok(Event::default().json_data(&chunk).expect("serialize"))),
+        )
+    })
+    .await;
+    bench("gemini_events_buffered", |input| {
+        gemini_events(stream::iter(input.into_iter().map(Ok)), false)
+    })
+    .await;
+    bench("gemini_events_buffered_with_complete", |input| {
+        gemini_events(stream::iter(input.into_iter().map(Ok)), true)
+    })
+    .await;
+}
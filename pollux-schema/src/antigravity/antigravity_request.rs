@@ -4,6 +4,7 @@
 
 use crate::gemini::{Content, GeminiGenerateContentRequest, Part};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::BTreeMap;
 
 /// Runtime metadata needed to wrap a Gemini request into
@@ -55,6 +56,20 @@ impl AntigravityRequestBody {
 
         *system_instruction = Some(next);
     }
+
+    /// Make sure the embedded request carries a `sessionId`, calling `generate` only when the
+    /// client sent none (or `null`). A client-provided value is kept as is. Returns the id.
+    pub fn ensure_session_id(&mut self, generate: impl FnOnce() -> String) -> &Value {
+        let session_id = self
+            .request
+            .extra
+            .entry(Self::SESSION_ID_KEY.to_string())
+            .or_insert(Value::Null);
+        if session_id.is_null() {
+            *session_id = Value::String(generate());
+        }
+        session_id
+    }
 }
 
 /// Antigravity upstream request envelope.
@@ -74,6 +89,8 @@ pub struct AntigravityRequestBody {
 impl AntigravityRequestBody {
     pub const USER_AGENT: &str = "antigravity";
     pub const REQUEST_TYPE: &str = "agent";
+    /// Key of the session id inside `request`.
+    pub const SESSION_ID_KEY: &str = "sessionId";
 }
 
 #[cfg(test)]
@@ -200,4 +217,37 @@ mod tests {
             .and_then(|part| part.text.as_deref());
         assert_eq!(text, Some("PREAMBLE\nPREAMBLE\nexisting"));
     }
+
+    #[test]
+    fn ensure_session_id_keeps_client_value_and_fills_missing_one() {
+        let meta = || AntigravityRequestMeta {
+            project: "project-1".to_string(),
+            request_id: "agent/1/00000000-0000-4000-8000-000000000000".to_string(),
+            model: "gemini-3-flash".to_string(),
+        };
+
+        let request: GeminiGenerateContentRequest = serde_json::from_value(json!({
+            "contents": [],
+            "sessionId": "client-session"
+        }))
+        .unwrap();
+        let mut body = meta().into_request(request);
+        let id = body.ensure_session_id(|| panic!("client session id must be kept"));
+        assert_eq!(id, &json!("client-session"));
+
+        for request in [
+            json!({"contents": []}),
+            json!({"contents": [], "sessionId": null}),
+        ] {
+            let request: GeminiGenerateContentRequest = serde_json::from_value(request).unwrap();
+            let mut body = meta().into_request(request);
+            assert_eq!(body.ensure_session_id(|| "-42".to_string()), &json!("-42"));
+            // Idempotent: a second call keeps the generated id.
+            assert_eq!(body.ensure_session_id(|| "-7".to_string()), &json!("-42"));
+            assert_eq!(
+                serde_json::to_value(&body).unwrap()["request"]["sessionId"],
+                "-42"
+            );
+        }
+    }
 }
@@ -0,0 +1,59 @@
+//! Typed response schema for the Antigravity upstream envelope.
+//!
+//! Unary bodies and every stream event wrap a Gemini response in `response`.
+
+use crate::gemini::GeminiResponseBody;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Antigravity `generateContent` / `streamGenerateContent` envelope.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AntigravityResponseBody {
+    pub response: GeminiResponseBody,
+
+    /// Envelope fields next to `response` (e.g. `traceId`); not forwarded to clients.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+
+impl AntigravityResponseBody {
+    /// Parse one SSE event's `data`.
+    pub fn from_sse_data(data: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(data)
+    }
+}
+
+impl From<AntigravityResponseBody> for GeminiResponseBody {
+    fn from(body: AntigravityResponseBody) -> Self {
+        body.response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sse_chunk_unwraps_to_the_gemini_response() {
+        let data = r#"{"response":{"candidates":[{"content":{"role":"model","parts":[{"text":"hi","thoughtSignature":"sig"}]},"index":0}],"modelVersion":"gemini-3-flash","responseId":"r1"},"traceId":"t1"}"#;
+        let envelope = AntigravityResponseBody::from_sse_data(data).expect("chunk parses");
+        assert_eq!(envelope.extra.get("traceId"), Some(&Value::from("t1")));
+
+        let body = GeminiResponseBody::from(envelope);
+        assert_eq!(body.modelVersion.as_deref(), Some("gemini-3-flash"));
+        assert_eq!(body.responseId.as_deref(), Some("r1"));
+        let part = &body.candidates[0].content.as_ref().expect("content").parts[0];
+        assert_eq!(part.text.as_deref(), Some("hi"));
+        assert_eq!(part.thought_signature.as_deref(), Some("sig"));
+        assert!(body.extra.is_empty());
+
+        // The mock upstream's bare chunk still parses.
+        let bare = AntigravityResponseBody::from_sse_data(r#"{"response":{"candidates":[{}]}}"#)
+            .expect("bare chunk parses");
+        assert_eq!(bare.response.candidates.len(), 1);
+
+        // Without the envelope it is not an Antigravity body.
+        assert!(AntigravityResponseBody::from_sse_data(r#"{"candidates":[]}"#).is_err());
+    }
+}
@@ -1,3 +1,5 @@
 mod antigravity_request;
+mod antigravity_response;
 
 pub use antigravity_request::{AntigravityRequestBody, AntigravityRequestMeta};
+pub use antigravity_response::AntigravityResponseBody;
@@ -19,7 +19,7 @@ use std::collections::BTreeMap;
 
 pub use content::{Content, Part};
 pub use generation::GenerationConfig;
-use system_instruction::deserialize_system_instruction;
+use system_instruction::{deserialize_system_instruction, system_texts, text_only_instruction};
 pub use tool::Tool;
 pub use tool_config::ToolConfig;
 
@@ -67,6 +67,69 @@ impl GeminiGenerateContentRequest {
     pub fn system_instruction_mut(&mut self) -> &mut Option<Content> {
         &mut self.system_instruction
     }
+
+    /// First turn (`contents` index, part count) with more than `max_parts` parts.
+    pub fn oversized_content(&self, max_parts: usize) -> Option<(usize, usize)> {
+        self.contents
+            .iter()
+            .map(|content| content.parts.len())
+            .enumerate()
+            .find(|&(_, parts)| parts > max_parts)
+    }
+
+    /// Why `generationConfig.responseSchema` is malformed, if it is.
+    pub fn response_schema_error(&self) -> Option<String> {
+        self.generation_config
+            .as_ref()
+            .and_then(GenerationConfig::response_schema_error)
+    }
+
+    /// Fold every system-level text the client sent into `systemInstruction`.
+    ///
+    /// Merge order is fixed: the camelCase `systemInstruction`, then a snake_case
+    /// `system_instruction` left in `extra`, then `contents` turns whose role is `system`
+    /// (case-insensitive) in conversation order. Those turns are removed from `contents`,
+    /// and texts are joined the same way multi-part instructions are. Running it again is
+    /// a no-op.
+    ///
+    /// Fails, leaving the request untouched, when `system_instruction` is not a valid
+    /// `Content`.
+    pub fn merge_system_instructions(&mut self) -> serde_json::Result<()> {
+        let snake_case = self
+            .extra
+            .get("system_instruction")
+            .cloned()
+            .map(serde_json::from_value::<Content>)
+            .transpose()?;
+        self.extra.remove("system_instruction");
+
+        let is_system_turn = |content: &Content| {
+            content
+                .role
+                .as_deref()
+                .is_some_and(|role| role.eq_ignore_ascii_case("system"))
+        };
+        if snake_case.is_none() && !self.contents.iter().any(is_system_turn) {
+            return Ok(());
+        }
+
+        let (system_turns, contents): (Vec<Content>, Vec<Content>) =
+            std::mem::take(&mut self.contents)
+                .into_iter()
+                .partition(is_system_turn);
+        self.contents = contents;
+
+        let texts = self
+            .system_instruction
+            .take()
+            .into_iter()
+            .chain(snake_case)
+            .chain(system_turns)
+            .flat_map(system_texts)
+            .collect();
+        self.system_instruction = text_only_instruction(texts);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -134,7 +197,7 @@ mod tests {
         assert_eq!(gc.top_p, Some(0.9));
         assert_eq!(gc.max_output_tokens, Some(1024));
         assert_eq!(gc.extra.get("stopSequences"), Some(&json!(["END"])));
-        assert_eq!(gc.extra.get("responseMimeType"), Some(&json!("text/plain")));
+        assert_eq!(gc.response_mime_type.as_deref(), Some("text/plain"));
         assert_eq!(
             gc.thinking_config,
             Some(json!({
@@ -202,6 +265,77 @@ mod tests {
         assert!(req.contents[0].parts[1].inline_data.is_some());
     }
 
+    #[test]
+    fn system_sources_merge_in_documented_order() {
+        let mut req: GeminiGenerateContentRequest = serde_json::from_value(json!({
+            "contents": [
+                {"role": "system", "parts": [{"text": "turn one"}]},
+                {"role": "user", "parts": [{"text": "hello"}]},
+                {"role": "SYSTEM", "parts": [{"text": "turn two"}]},
+                {"role": "model", "parts": [{"text": "hi"}]}
+            ],
+            "systemInstruction": {"parts": [{"text": "top level"}]},
+            "system_instruction": {"parts": [{"text": "snake case"}]}
+        }))
+        .unwrap();
+
+        req.merge_system_instructions().unwrap();
+
+        let si = req.system_instruction.as_ref().unwrap();
+        assert!(si.role.is_none());
+        assert_eq!(si.parts.len(), 1);
+        assert_eq!(
+            si.parts[0].text.as_deref(),
+            Some("top level\n\nsnake case\n\nturn one\n\nturn two")
+        );
+        assert!(!req.extra.contains_key("system_instruction"));
+        let roles: Vec<_> = req.contents.iter().map(|c| c.role.as_deref()).collect();
+        assert_eq!(roles, vec![Some("user"), Some("model")]);
+    }
+
+    #[test]
+    fn malformed_snake_case_instruction_is_an_error() {
+        let input = json!({
+            "contents": [{"role": "system", "parts": [{"text": "turn"}]}],
+            "system_instruction": {"parts": "not a list"}
+        });
+        let mut req: GeminiGenerateContentRequest = serde_json::from_value(input.clone()).unwrap();
+
+        assert!(req.merge_system_instructions().is_err());
+        assert_eq!(serde_json::to_value(&req).unwrap(), input);
+    }
+
+    #[test]
+    fn merge_system_instructions_is_idempotent() {
+        let mut req: GeminiGenerateContentRequest = serde_json::from_value(json!({
+            "contents": [
+                {"role": "system", "parts": [{"text": "turn"}]},
+                {"role": "user", "parts": [{"text": "hello"}]}
+            ],
+            "systemInstruction": {"parts": [{"text": "top"}]}
+        }))
+        .unwrap();
+
+        req.merge_system_instructions().unwrap();
+        let once = serde_json::to_value(&req).unwrap();
+        req.merge_system_instructions().unwrap();
+
+        assert_eq!(serde_json::to_value(&req).unwrap(), once);
+    }
+
+    #[test]
+    fn merge_without_extra_sources_keeps_request_untouched() {
+        let input = json!({
+            "contents": [{"role": "user", "parts": [{"text": "hello"}]}],
+            "systemInstruction": {"parts": [{"text": "top"}]}
+        });
+        let mut req: GeminiGenerateContentRequest = serde_json::from_value(input.clone()).unwrap();
+
+        req.merge_system_instructions().unwrap();
+
+        assert_eq!(serde_json::to_value(&req).unwrap(), input);
+    }
+
     /// Mirrors the real Antigravity IDE request captured in antiREV/.
     #[test]
     fn real_antigravity_ide_request_roundtrips() {
@@ -287,4 +421,19 @@ mod tests {
         }
         assert_eq!(output, expected);
     }
+
+    #[test]
+    fn oversized_content_reports_first_turn_over_the_cap() {
+        let request: GeminiGenerateContentRequest = serde_json::from_value(json!({
+            "contents": [
+                {"role": "user", "parts": [{"text": "a"}]},
+                {"role": "model", "parts": [{"text": "b"}, {"text": "c"}, {"text": "d"}]},
+                {"role": "user", "parts": [{"text": "e"}, {"text": "f"}, {"text": "g"}]}
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(request.oversized_content(3), None);
+        assert_eq!(request.oversized_content(2), Some((1, 3)));
+    }
 }
@@ -30,6 +30,15 @@ pub struct GenerationConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub image_config: Option<Value>,
 
+    /// MIME type of the generated text, e.g. `application/json` for JSON mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_mime_type: Option<String>,
+
+    /// OpenAPI-style schema the generated JSON must follow. Kept raw for pass-through;
+    /// check its shape with [`GenerationConfig::response_schema_error`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<Value>,
+
     #[serde(default, flatten)]
     pub extra: BTreeMap<String, Value>,
 }
@@ -39,6 +48,82 @@ impl GenerationConfig {
     pub fn thinking_config_mut(&mut self) -> &mut Option<Value> {
         &mut self.thinking_config
     }
+
+    /// Why `responseSchema` is malformed, if it is.
+    ///
+    /// Only the shape is checked: every schema node must be an object, `type` must name a
+    /// known type, `properties` must map names to schemas, `items` must be a schema, `anyOf`
+    /// a list of schemas and `required`/`enum` lists of strings.
+    pub fn response_schema_error(&self) -> Option<String> {
+        let schema = self.response_schema.as_ref()?;
+        schema_error(schema, "generationConfig.responseSchema")
+    }
+}
+
+/// Type names accepted in `responseSchema.type` (any case).
+const SCHEMA_TYPES: [&str; 7] = [
+    "string", "number", "integer", "boolean", "array", "object", "null",
+];
+
+fn schema_error(schema: &Value, path: &str) -> Option<String> {
+    let Some(node) = schema.as_object() else {
+        return Some(format!("{path} must be an object"));
+    };
+
+    if let Some(ty) = node.get("type") {
+        let known = ty
+            .as_str()
+            .is_some_and(|ty| SCHEMA_TYPES.iter().any(|t| t.eq_ignore_ascii_case(ty)));
+        if !known {
+            return Some(format!(
+                "{path}.type must be one of {}",
+                SCHEMA_TYPES.join(", ")
+            ));
+        }
+    }
+
+    if let Some(properties) = node.get("properties") {
+        let Some(properties) = properties.as_object() else {
+            return Some(format!("{path}.properties must be an object"));
+        };
+        if let Some(err) = properties
+            .iter()
+            .find_map(|(name, sub)| schema_error(sub, &format!("{path}.properties.{name}")))
+        {
+            return Some(err);
+        }
+    }
+
+    if let Some(items) = node.get("items")
+        && let Some(err) = schema_error(items, &format!("{path}.items"))
+    {
+        return Some(err);
+    }
+
+    if let Some(any_of) = node.get("anyOf") {
+        let Some(any_of) = any_of.as_array() else {
+            return Some(format!("{path}.anyOf must be an array"));
+        };
+        if let Some(err) = any_of
+            .iter()
+            .enumerate()
+            .find_map(|(i, sub)| schema_error(sub, &format!("{path}.anyOf[{i}]")))
+        {
+            return Some(err);
+        }
+    }
+
+    for key in ["required", "enum"] {
+        if let Some(list) = node.get(key)
+            && !list
+                .as_array()
+                .is_some_and(|list| list.iter().all(Value::is_string))
+        {
+            return Some(format!("{path}.{key} must be an array of strings"));
+        }
+    }
+
+    None
 }
 
 fn deserialize_temperature<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
@@ -68,7 +153,6 @@ mod tests {
         let input = json!({
             "temperature": 1.0,
             "candidateCount": 2,
-            "responseSchema": {"type": "object"},
             "responseJsonSchema": {"type": "object", "properties": {}},
             "responseModalities": ["TEXT"],
             "imageConfig": {
@@ -85,10 +169,6 @@ mod tests {
         let gc: GenerationConfig = serde_json::from_value(input).unwrap();
         assert_eq!(gc.temperature, Some(1.0));
         assert_eq!(gc.extra.get("candidateCount"), Some(&json!(2)));
-        assert_eq!(
-            gc.extra.get("responseSchema"),
-            Some(&json!({"type": "object"}))
-        );
         assert_eq!(
             gc.extra.get("responseJsonSchema"),
             Some(&json!({"type": "object", "properties": {}}))
@@ -123,4 +203,54 @@ mod tests {
         let gc: GenerationConfig = serde_json::from_value(input.clone()).unwrap();
         assert_eq!(serde_json::to_value(&gc).unwrap(), input);
     }
+
+    #[test]
+    fn json_mode_fields_are_typed_and_roundtrip() {
+        let input = json!({
+            "responseMimeType": "application/json",
+            "responseSchema": {
+                "type": "OBJECT",
+                "properties": {
+                    "name": {"type": "string"},
+                    "tags": {"type": "array", "items": {"type": "string"}}
+                },
+                "required": ["name"]
+            }
+        });
+
+        let gc: GenerationConfig = serde_json::from_value(input.clone()).unwrap();
+        assert_eq!(gc.response_mime_type.as_deref(), Some("application/json"));
+        assert!(gc.extra.is_empty());
+        assert_eq!(gc.response_schema_error(), None);
+        assert_eq!(serde_json::to_value(&gc).unwrap(), input);
+    }
+
+    #[test]
+    fn malformed_response_schema_is_reported_with_its_path() {
+        let error = |schema: Value| {
+            serde_json::from_value::<GenerationConfig>(json!({"responseSchema": schema}))
+                .unwrap()
+                .response_schema_error()
+        };
+
+        assert_eq!(
+            error(json!("object")).as_deref(),
+            Some("generationConfig.responseSchema must be an object")
+        );
+        assert_eq!(
+            error(json!({"type": "object", "properties": {"age": {"type": "int"}}})).as_deref(),
+            Some(
+                "generationConfig.responseSchema.properties.age.type must be one of \
+                 string, number, integer, boolean, array, object, null"
+            )
+        );
+        assert_eq!(
+            error(json!({"type": "array", "items": [{"type": "string"}]})).as_deref(),
+            Some("generationConfig.responseSchema.items must be an object")
+        );
+        assert_eq!(
+            error(json!({"type": "object", "required": "name"})).as_deref(),
+            Some("generationConfig.responseSchema.required must be an array of strings")
+        );
+    }
 }
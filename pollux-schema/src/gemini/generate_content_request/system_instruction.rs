@@ -2,6 +2,9 @@ use super::{Content, Part};
 use serde::Deserialize;
 use std::collections::BTreeMap;
 
+/// Separator used whenever several system texts are folded into one part.
+const SYSTEM_TEXT_SEPARATOR: &str = "\n\n";
+
 pub fn deserialize_system_instruction<'de, D>(deserializer: D) -> Result<Option<Content>, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -10,21 +13,30 @@ where
         return Ok(None);
     };
 
-    let merged_text = content
+    Ok(text_only_instruction(system_texts(content)))
+}
+
+/// Non-blank text parts of `content`, in order.
+pub(super) fn system_texts(content: Content) -> Vec<String> {
+    content
         .parts
         .into_iter()
         .filter_map(|part| part.text.filter(|text| !text.trim().is_empty()))
-        .collect::<Vec<_>>()
-        .join("\n\n");
+        .collect()
+}
+
+/// Build the normalized single-text-part instruction, or `None` when there is no text.
+pub(super) fn text_only_instruction(texts: Vec<String>) -> Option<Content> {
+    let merged_text = texts.join(SYSTEM_TEXT_SEPARATOR);
 
-    Ok((!merged_text.is_empty()).then(|| Content {
+    (!merged_text.is_empty()).then(|| Content {
         role: None,
         parts: vec![Part {
             text: Some(merged_text),
             ..Default::default()
         }],
         extra: BTreeMap::new(),
-    }))
+    })
 }
 
 #[cfg(test)]
@@ -1,4 +1,4 @@
-use super::Content;
+use super::{Content, Part};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::BTreeMap;
@@ -41,3 +41,189 @@ pub struct Candidate {
     #[serde(flatten)]
     pub extra: BTreeMap<String, Value>,
 }
+
+impl GeminiResponseBody {
+    /// Fold one streamed chunk into this accumulated response.
+    ///
+    /// Candidates are matched by `index`. Consecutive text parts with the same `thought` flag are
+    /// concatenated (the same per-part rule the thought-signature sniffer uses), keeping the
+    /// latest `thoughtSignature`; every other part is appended as-is. Response-level metadata
+    /// and `finishReason` take the latest value seen.
+    pub fn merge_chunk(&mut self, chunk: GeminiResponseBody) {
+        for candidate in chunk.candidates {
+            let index = candidate.index.unwrap_or(0);
+            match self
+                .candidates
+                .iter_mut()
+                .find(|existing| existing.index.unwrap_or(0) == index)
+            {
+                Some(existing) => existing.merge_chunk(candidate),
+                None => self.candidates.push(candidate),
+            }
+        }
+
+        if chunk.promptFeedback.is_some() {
+            self.promptFeedback = chunk.promptFeedback;
+        }
+        if chunk.usageMetadata.is_some() {
+            self.usageMetadata = chunk.usageMetadata;
+        }
+        if chunk.modelVersion.is_some() {
+            self.modelVersion = chunk.modelVersion;
+        }
+        if chunk.responseId.is_some() {
+            self.responseId = chunk.responseId;
+        }
+        self.extra.extend(chunk.extra);
+    }
+
+    /// True when the response carries nothing for the client: no candidate has content parts
+    /// or a `finishReason`, and there is no `promptFeedback` explaining why.
+    pub fn is_empty(&self) -> bool {
+        self.promptFeedback.is_none()
+            && self.candidates.iter().all(|candidate| {
+                candidate.finish_reason.is_none()
+                    && candidate
+                        .content
+                        .as_ref()
+                        .is_none_or(|content| content.parts.is_empty())
+            })
+    }
+}
+
+impl Candidate {
+    fn merge_chunk(&mut self, chunk: Candidate) {
+        if let Some(content) = chunk.content {
+            match &mut self.content {
+                Some(existing) => {
+                    if content.role.is_some() {
+                        existing.role = content.role;
+                    }
+                    for part in content.parts {
+                        push_part(&mut existing.parts, part);
+                    }
+                    existing.extra.extend(content.extra);
+                }
+                None => self.content = Some(content),
+            }
+        }
+        if chunk.finish_reason.is_some() {
+            self.finish_reason = chunk.finish_reason;
+        }
+        self.extra.extend(chunk.extra);
+    }
+}
+
+fn push_part(parts: &mut Vec<Part>, part: Part) {
+    if let Some(last) = parts.last_mut()
+        && is_plain_text(last)
+        && is_plain_text(&part)
+        && last.thought.unwrap_or(false) == part.thought.unwrap_or(false)
+    {
+        if let (Some(text), Some(more)) = (last.text.as_mut(), part.text) {
+            text.push_str(&more);
+        }
+        if part.thought_signature.is_some() {
+            last.thought_signature = part.thought_signature;
+        }
+        return;
+    }
+    parts.push(part);
+}
+
+fn is_plain_text(part: &Part) -> bool {
+    part.text.is_some()
+        && part.inline_data.is_none()
+        && part.function_call.is_none()
+        && part.function_response.is_none()
+        && part.file_data.is_none()
+        && part.executable_code.is_none()
+        && part.code_execution_result.is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn chunk(value: Value) -> GeminiResponseBody {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn merge_chunk_concatenates_text_per_kind_and_keeps_latest_metadata() {
+        let mut merged = chunk(json!({
+            "candidates": [{"index": 0, "content": {"role": "model", "parts": [
+                {"text": "Let me ", "thought": true}
+            ]}}],
+            "responseId": "r1"
+        }));
+        merged.merge_chunk(chunk(json!({
+            "candidates": [{"index": 0, "content": {"role": "model", "parts": [
+                {"text": "think.", "thought": true, "thoughtSignature": "sig"}
+            ]}}]
+        })));
+        merged.merge_chunk(chunk(json!({
+            "candidates": [{"index": 0, "content": {"role": "model", "parts": [
+                {"text": "Hello, "}
+            ]}}]
+        })));
+        merged.merge_chunk(chunk(json!({
+            "candidates": [{"index": 0, "content": {"role": "model", "parts": [
+                {"text": "world"},
+                {"functionCall": {"name": "f", "args": {}}}
+            ]}, "finishReason": "STOP"}],
+            "usageMetadata": {"totalTokenCount": 7}
+        })));
+
+        assert_eq!(
+            serde_json::to_value(&merged).unwrap(),
+            json!({
+                "candidates": [{"index": 0, "finishReason": "STOP", "content": {
+                    "role": "model",
+                    "parts": [
+                        {"text": "Let me think.", "thought": true, "thoughtSignature": "sig"},
+                        {"text": "Hello, world"},
+                        {"functionCall": {"name": "f", "args": {}}}
+                    ]
+                }}],
+                "usageMetadata": {"totalTokenCount": 7},
+                "responseId": "r1"
+            })
+        );
+    }
+
+    #[test]
+    fn merge_chunk_keeps_candidates_apart_by_index() {
+        let mut merged = chunk(json!({
+            "candidates": [{"index": 0, "content": {"parts": [{"text": "a"}]}}]
+        }));
+        merged.merge_chunk(chunk(json!({
+            "candidates": [{"index": 1, "content": {"parts": [{"text": "b"}]}}]
+        })));
+        merged.merge_chunk(chunk(json!({
+            "candidates": [{"index": 0, "content": {"parts": [{"text": "c"}]}}]
+        })));
+
+        let texts: Vec<_> = merged
+            .candidates
+            .iter()
+            .map(|c| c.content.as_ref().unwrap().parts[0].text.clone().unwrap())
+            .collect();
+        assert_eq!(texts, ["ac", "b"]);
+    }
+
+    #[test]
+    fn is_empty_only_for_responses_without_content_or_finish() {
+        assert!(chunk(json!({"candidates": []})).is_empty());
+        assert!(chunk(json!({})).is_empty());
+        assert!(chunk(json!({"candidates": [{"index": 0, "content": {"parts": []}}]})).is_empty());
+
+        assert!(!chunk(json!({"candidates": [{"index": 0, "finishReason": "SAFETY"}]})).is_empty());
+        assert!(!chunk(json!({"promptFeedback": {"blockReason": "SAFETY"}})).is_empty());
+        assert!(
+            !chunk(json!({"candidates": [{"index": 0, "content": {"parts": [{"text": "hi"}]}}]}))
+                .is_empty()
+        );
+    }
+}
@@ -4,7 +4,7 @@ pub mod gemini;
 pub mod geminicli;
 pub mod openai;
 
-pub use antigravity::{AntigravityRequestBody, AntigravityRequestMeta};
+pub use antigravity::{AntigravityRequestBody, AntigravityRequestMeta, AntigravityResponseBody};
 pub use codex::{CodexErrorBody, CodexRequestBody};
 pub use geminicli::{GeminiCliRequest, GeminiCliRequestMeta, GeminiCliResponseBody};
 pub use openai::{OpenaiRequestBody, OpenaiResponsesErrorBody, OpenaiResponsesErrorObject};
@@ -0,0 +1,119 @@
+//! Patch-key throughput over synthetic 1000-part model histories: mixed thought text and
+//! function calls, thought text only (the borrowed-slice fingerprint path), and `patch_all` with
+//! the decision phase run sequentially versus across threads.
+//!
+//! Run with `cargo bench -p pollux-thoughtsig-core`. Uses `std` timing only, so no extra
+//! dependencies are needed.
+
+use pollux_thoughtsig_core::{
+    PatchEvent, PatchOutcome, ThoughtSigPatchable, ThoughtSignatureEngine, patch_all,
+};
+use serde_json::{Value, json};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const PARTS: usize = 1000;
+const ROUNDS: u32 = 200;
+
+enum Data {
+    Thought(String),
+    FunctionCall(Value),
+}
+
+struct BenchPart {
+    data: Data,
+    signature: Option<String>,
+}
+
+impl ThoughtSigPatchable for BenchPart {
+    fn data(&self) -> PatchEvent<'_> {
+        match &self.data {
+            Data::Thought(text) => PatchEvent::ThoughtText(text),
+            Data::FunctionCall(call) => PatchEvent::FunctionCall(call),
+        }
+    }
+
+    fn thought_signature_mut(&mut self) -> &mut Option<String> {
+        &mut self.signature
+    }
+}
+
+fn thought(i: usize) -> Data {
+    Data::Thought(format!(
+        "step {i}: {}",
+        "reasoning about the task ".repeat(8)
+    ))
+}
+
+fn function_call(i: usize) -> Data {
+    Data::FunctionCall(json!({
+        "name": "read_file",
+        "args": {"path": format!("/src/module_{i}.rs"), "offset": i, "limit": 200}
+    }))
+}
+
+fn history(data: impl Fn(usize) -> Data) -> Vec<BenchPart> {
+    (0..PARTS)
+        .map(|i| BenchPart {
+            data: data(i),
+            signature: None,
+        })
+        .collect()
+}
+
+fn bench(name: &str, mut parts: Vec<BenchPart>, mut pass: impl FnMut(&mut [BenchPart])) {
+    let mut best = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let started = Instant::now();
+        pass(&mut parts);
+        best = best.min(started.elapsed());
+    }
+
+    println!(
+        "{name}/{PARTS} parts: best {:?} per pass ({:?} per part) over {ROUNDS} rounds",
+        best,
+        best / PARTS as u32
+    );
+}
+
+fn per_part(engine: &ThoughtSignatureEngine) -> impl FnMut(&mut [BenchPart]) + '_ {
+    move |parts| {
+        for part in parts.iter_mut() {
+            let outcome = part.patch_thought_signature(engine);
+            black_box(matches!(outcome, PatchOutcome::Patched { .. }));
+        }
+    }
+}
+
+fn batched(
+    engine: &ThoughtSignatureEngine,
+    parallel_threshold: usize,
+) -> impl FnMut(&mut [BenchPart]) + '_ {
+    move |parts| {
+        black_box(patch_all(parts, engine, parallel_threshold));
+    }
+}
+
+fn mixed(i: usize) -> Data {
+    if i.is_multiple_of(2) {
+        thought(i)
+    } else {
+        function_call(i)
+    }
+}
+
+fn main() {
+    let engine = ThoughtSignatureEngine::new(3600, 4096);
+    bench("patch_history_mixed", history(mixed), per_part(&engine));
+    bench(
+        "patch_history_thought_text",
+        history(thought),
+        per_part(&engine),
+    );
+    bench(
+        "patch_all_sequential",
+        history(mixed),
+        batched(&engine, usize::MAX),
+    );
+    bench("patch_all_parallel", history(mixed), batched(&engine, 1));
+}
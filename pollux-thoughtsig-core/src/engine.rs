@@ -1,46 +1,513 @@
+use crate::fingerprint::CacheKeyGenerator;
+use crate::patch::DEFAULT_PARALLEL_FILL_THRESHOLD;
+use crate::sniffer::DuplicatePolicy;
+use crate::store::{
+    AsyncSignatureStore, MokaSignatureStore, SignatureExpiry, SignatureStore, StoreError,
+    StoreFootprint,
+};
 use moka::sync::Cache;
-use std::{sync::Arc, time::Duration};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
 
 pub type CacheKey = u64;
 pub type ThoughtSignature = Arc<str>;
 pub type SignatureCacheStore = Cache<CacheKey, ThoughtSignature>;
 
+/// Most recent misses the negative cache remembers.
+const NEGATIVE_CACHE_CAPACITY: u64 = 65_536;
+
+/// What the fill does with a signature the client already sent on a patchable part.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExistingSignatures {
+    /// Overwrite it with the cached signature, or the dummy on a miss.
+    #[default]
+    Replace,
+    /// Keep it as sent.
+    Trust,
+    /// Keep it as sent, but warn when the cache holds a different signature for the same
+    /// content, which usually means the client is replaying stale history.
+    TrustVerified,
+}
+
+/// What the fill does with a part that yields no cache key, such as a thought part whose
+/// text is empty or only whitespace.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnkeyedParts {
+    /// Treat it as a cache miss and give it the dummy.
+    #[default]
+    Fill,
+    /// Leave it as sent, signature and all.
+    Keep,
+}
+
+/// Signature written when the cache has nothing for an item.
+///
+/// One default applies to every model unless the model has its own dummy, e.g. when a newer
+/// tier stops accepting the default validator-skip value, or has none at all because it
+/// rejects every placeholder; such misses are then left as sent. Models can instead opt out
+/// of replaying thought parts at all, in which case adapters remove them from the history.
+#[derive(Debug, Clone)]
+pub struct EnginePolicy {
+    default_dummy: Option<ThoughtSignature>,
+    model_dummies: HashMap<String, Option<ThoughtSignature>>,
+    strip_thought_models: HashSet<String>,
+    model_role_aliases: HashSet<String>,
+    existing: ExistingSignatures,
+    unkeyed: UnkeyedParts,
+    max_signature_len: Option<usize>,
+    fill_function_call_misses: bool,
+    borrow_sibling_signatures: bool,
+    model_namespaced_keys: bool,
+    negative_cache_ttl: Option<Duration>,
+    parallel_fill_threshold: usize,
+    duplicates: DuplicatePolicy,
+}
+
+impl Default for EnginePolicy {
+    fn default() -> Self {
+        Self {
+            default_dummy: Some(Arc::from("skip_thought_signature_validator")),
+            model_dummies: HashMap::new(),
+            strip_thought_models: HashSet::new(),
+            model_role_aliases: HashSet::new(),
+            existing: ExistingSignatures::default(),
+            unkeyed: UnkeyedParts::default(),
+            max_signature_len: None,
+            fill_function_call_misses: true,
+            borrow_sibling_signatures: false,
+            model_namespaced_keys: false,
+            negative_cache_ttl: None,
+            parallel_fill_threshold: DEFAULT_PARALLEL_FILL_THRESHOLD,
+            duplicates: DuplicatePolicy::default(),
+        }
+    }
+}
+
+impl EnginePolicy {
+    /// Use `signature` as the dummy for requests to `model`.
+    pub fn with_model_dummy(
+        mut self,
+        model: impl Into<String>,
+        signature: impl Into<ThoughtSignature>,
+    ) -> Self {
+        self.model_dummies
+            .insert(model.into(), Some(signature.into()));
+        self
+    }
+
+    /// Never dummy-fill requests to `model`: cache misses are left as sent.
+    pub fn without_model_dummy(mut self, model: impl Into<String>) -> Self {
+        self.model_dummies.insert(model.into(), None);
+        self
+    }
+
+    /// Only dummy-fill models with their own dummy; misses for any other model are left as sent.
+    pub fn without_default_dummy(mut self) -> Self {
+        self.default_dummy = None;
+        self
+    }
+
+    /// Drop `thought == true` parts from requests to `model` instead of signing them.
+    pub fn with_thoughts_stripped(mut self, model: impl Into<String>) -> Self {
+        self.strip_thought_models.insert(model.into());
+        self
+    }
+
+    pub fn strips_thoughts(&self, model: Option<&str>) -> bool {
+        model.is_some_and(|model| self.strip_thought_models.contains(model))
+    }
+
+    /// Treat contents with `role` (e.g. `assistant`) as model turns when patching.
+    pub fn with_model_role_alias(mut self, role: impl Into<String>) -> Self {
+        self.model_role_aliases.insert(role.into());
+        self
+    }
+
+    /// Whether a content with `role` is a model turn: `model` or one of the configured aliases.
+    pub fn is_model_role(&self, role: Option<&str>) -> bool {
+        role.is_some_and(|role| role == "model" || self.model_role_aliases.contains(role))
+    }
+
+    pub fn with_existing_signatures(mut self, existing: ExistingSignatures) -> Self {
+        self.existing = existing;
+        self
+    }
+
+    pub fn with_unkeyed_parts(mut self, unkeyed: UnkeyedParts) -> Self {
+        self.unkeyed = unkeyed;
+        self
+    }
+
+    /// Leave function-call parts with no cached signature as sent instead of dummy-filling
+    /// them; thought parts are still dummy-filled.
+    pub fn without_function_call_dummies(mut self) -> Self {
+        self.fill_function_call_misses = false;
+        self
+    }
+
+    /// On a thought-part miss, reuse the cached signature of a function call in the same
+    /// content before falling back to the dummy.
+    pub fn with_sibling_signatures(mut self) -> Self {
+        self.borrow_sibling_signatures = true;
+        self
+    }
+
+    /// Fold the request's model into cache keys, so a signature recorded under one model is
+    /// never replayed into a request for another. Off by default: all models share keys.
+    pub fn with_model_namespaced_keys(mut self) -> Self {
+        self.model_namespaced_keys = true;
+        self
+    }
+
+    /// Don't cache sniffed signatures longer than `bytes`.
+    pub fn with_max_signature_len(mut self, bytes: usize) -> Self {
+        self.max_signature_len = Some(bytes);
+        self
+    }
+
+    /// Remember keys that missed for `ttl`, so repeating the same miss skips the store. A
+    /// signature recorded under a remembered key ends its entry at once.
+    pub fn with_negative_cache(mut self, ttl: Duration) -> Self {
+        self.negative_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Key requests with at least `items` parts on the shared worker pool instead of the
+    /// calling thread. `usize::MAX` keeps every request on the calling thread.
+    pub fn with_parallel_fill_threshold(mut self, items: usize) -> Self {
+        self.parallel_fill_threshold = items;
+        self
+    }
+
+    /// Which signature sniffers keep when one response signs the same content twice.
+    pub fn with_duplicate_policy(mut self, duplicates: DuplicatePolicy) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// Dummy for `model`, or the default when the model is unknown or has no override.
+    /// `None` means misses for the model are not filled.
+    pub fn dummy_signature_for(&self, model: Option<&str>) -> Option<&ThoughtSignature> {
+        match model.and_then(|model| self.model_dummies.get(model)) {
+            Some(dummy) => dummy.as_ref(),
+            None => self.default_dummy.as_ref(),
+        }
+    }
+}
+
 pub struct ThoughtSignatureEngine {
-    cache: SignatureCacheStore,
-    dummy_signature: ThoughtSignature,
+    store: Box<dyn SignatureStore>,
+    policy: EnginePolicy,
+    /// Keys that recently missed, when the policy enables the negative cache.
+    misses: Option<Cache<CacheKey, ()>>,
+}
+
+/// Result of [`ThoughtSignatureEngine::lookup_signature`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureLookup {
+    Hit(ThoughtSignature),
+    Miss,
+    /// A miss answered by the negative cache, without asking the store.
+    KnownMiss,
 }
 
 impl ThoughtSignatureEngine {
     pub fn new(ttl_secs: u64, max_capacity: u64) -> Self {
-        let cache = SignatureCacheStore::builder()
-            .time_to_live(Duration::from_secs(ttl_secs.max(1)))
-            .max_capacity(max_capacity.max(1))
-            .build();
-        let dummy_signature: ThoughtSignature = Arc::from("skip_thought_signature_validator");
+        Self::with_store(Box::new(MokaSignatureStore::new(ttl_secs, max_capacity)))
+    }
+
+    pub fn with_expiry(expiry: SignatureExpiry, max_capacity: u64) -> Self {
+        Self::with_store(Box::new(MokaSignatureStore::with_expiry(
+            expiry,
+            max_capacity,
+        )))
+    }
 
+    pub fn with_store(store: Box<dyn SignatureStore>) -> Self {
         Self {
-            cache,
-            dummy_signature,
+            store,
+            policy: EnginePolicy::default(),
+            misses: None,
         }
     }
 
+    pub fn with_policy(mut self, policy: EnginePolicy) -> Self {
+        self.misses = policy.negative_cache_ttl.map(|ttl| {
+            Cache::builder()
+                .max_capacity(NEGATIVE_CACHE_CAPACITY)
+                .time_to_live(ttl)
+                .build()
+        });
+        self.policy = policy;
+        self
+    }
+
+    /// Look up a signature. A failing store counts as a miss, so
+    /// callers fall back to the dummy signature instead of failing the request.
     pub fn get_signature(&self, key: &CacheKey) -> Option<ThoughtSignature> {
-        self.cache.get(key)
+        match self.store.get(key) {
+            Ok(signature) => signature,
+            Err(err) => {
+                warn!(key, error = %err, "Signature store lookup failed; treating as miss");
+                None
+            }
+        }
+    }
+
+    /// Like [`Self::get_signature`], but consults and feeds the negative cache when the policy
+    /// enables it.
+    pub fn lookup_signature(&self, key: &CacheKey) -> SignatureLookup {
+        let Some(misses) = &self.misses else {
+            return self
+                .get_signature(key)
+                .map_or(SignatureLookup::Miss, SignatureLookup::Hit);
+        };
+        if misses.contains_key(key) {
+            return SignatureLookup::KnownMiss;
+        }
+        match self.get_signature(key) {
+            Some(signature) => SignatureLookup::Hit(signature),
+            None => {
+                misses.insert(*key, ());
+                SignatureLookup::Miss
+            }
+        }
+    }
+
+    /// [`Self::lookup_signature`] against `store` instead of the engine's own store. A failing
+    /// store counts as a miss.
+    pub async fn lookup_signature_async<S: AsyncSignatureStore>(
+        &self,
+        store: &S,
+        key: &CacheKey,
+    ) -> SignatureLookup {
+        if self
+            .misses
+            .as_ref()
+            .is_some_and(|misses| misses.contains_key(key))
+        {
+            return SignatureLookup::KnownMiss;
+        }
+        let signature = store.get_signature(key).await.unwrap_or_else(|err| {
+            warn!(key, error = %err, "Signature store lookup failed; treating as miss");
+            None
+        });
+        match signature {
+            Some(signature) => SignatureLookup::Hit(signature),
+            None => {
+                if let Some(misses) = &self.misses {
+                    misses.insert(*key, ());
+                }
+                SignatureLookup::Miss
+            }
+        }
+    }
+
+    /// [`Self::lookup_signature`] for a batch. Each distinct key is looked up once, however
+    /// often it repeats.
+    pub fn lookup_many(
+        &self,
+        keys: impl IntoIterator<Item = CacheKey>,
+    ) -> HashMap<CacheKey, SignatureLookup> {
+        let mut lookups = HashMap::new();
+        for key in keys {
+            lookups
+                .entry(key)
+                .or_insert_with(|| self.lookup_signature(&key));
+        }
+        lookups
     }
 
+    /// Record a signature. Store failures are logged and the write is dropped.
     pub fn put_signature(&self, key: CacheKey, signature: ThoughtSignature) {
-        self.cache.insert(key, signature);
+        self.forget_miss(&key);
+        if let Err(err) = self.store.put(key, signature) {
+            warn!(key, error = %err, "Signature store write failed; dropping signature");
+        }
+    }
+
+    /// [`Self::put_signature`] into `store` instead of the engine's own store.
+    pub async fn put_signature_async<S: AsyncSignatureStore>(
+        &self,
+        store: &S,
+        key: CacheKey,
+        signature: ThoughtSignature,
+    ) {
+        self.forget_miss(&key);
+        if let Err(err) = store.put_signature(key, signature).await {
+            warn!(key, error = %err, "Signature store write failed; dropping signature");
+        }
+    }
+
+    /// Dump every cached signature, e.g. to migrate to another instance.
+    pub fn snapshot(&self) -> Result<Vec<(CacheKey, ThoughtSignature)>, StoreError> {
+        self.store.snapshot()
+    }
+
+    /// Live entries and their signature bytes, for the metrics endpoint.
+    pub fn footprint(&self) -> Result<StoreFootprint, StoreError> {
+        self.store.footprint()
+    }
+
+    /// Forget `key`'s signature, e.g. after upstream rejected it, so the next request falls
+    /// back to the dummy instead of replaying it.
+    pub fn invalidate_signature(&self, key: &CacheKey) -> Result<(), StoreError> {
+        self.store.invalidate(key)
+    }
+
+    /// Forget every cached signature.
+    pub fn invalidate_all(&self) -> Result<(), StoreError> {
+        self.store.invalidate_all()
+    }
+
+    /// Load signatures in bulk and return how many were written.
+    pub fn put_many(
+        &self,
+        entries: Vec<(CacheKey, ThoughtSignature)>,
+    ) -> Result<usize, StoreError> {
+        for (key, _) in &entries {
+            self.forget_miss(key);
+        }
+        self.store.put_many(entries)
+    }
+
+    fn forget_miss(&self, key: &CacheKey) {
+        if let Some(misses) = &self.misses {
+            misses.invalidate(key);
+        }
+    }
+
+    pub fn fallback_signature(&self) -> Option<ThoughtSignature> {
+        self.fallback_signature_for(None)
+    }
+
+    /// Fallback for a request to `model`, honouring per-model dummies in the policy. `None`
+    /// when the model has no dummy and misses should be left as sent.
+    pub fn fallback_signature_for(&self, model: Option<&str>) -> Option<ThoughtSignature> {
+        self.policy.dummy_signature_for(model).cloned()
+    }
+
+    /// Whether replayed thought parts for `model` should be removed rather than signed.
+    pub fn strips_thoughts(&self, model: Option<&str>) -> bool {
+        self.policy.strips_thoughts(model)
+    }
+
+    /// Cache key for thought text sent to or received from `model`.
+    pub fn text_key(&self, model: Option<&str>, text: &str) -> Option<CacheKey> {
+        CacheKeyGenerator::generate_text_in(self.key_namespace(model), text)
+    }
+
+    /// Cache key for a function call sent to or received from `model`.
+    pub fn json_key(&self, model: Option<&str>, function_call: &Value) -> Option<CacheKey> {
+        CacheKeyGenerator::generate_json_in(self.key_namespace(model), function_call)
+    }
+
+    fn key_namespace<'a>(&self, model: Option<&'a str>) -> Option<&'a str> {
+        model.filter(|_| self.policy.model_namespaced_keys)
+    }
+
+    /// Whether a function-call part with no cached signature gets the dummy.
+    pub fn fills_function_call_misses(&self) -> bool {
+        self.policy.fill_function_call_misses
+    }
+
+    /// Whether a part that yields no cache key gets the dummy rather than being left as sent.
+    pub fn fills_unkeyed_parts(&self) -> bool {
+        self.policy.unkeyed == UnkeyedParts::Fill
+    }
+
+    /// Whether thought misses may borrow a sibling function call's signature.
+    pub fn borrows_sibling_signatures(&self) -> bool {
+        self.policy.borrow_sibling_signatures
+    }
+
+    /// Longest signature, in bytes, the sniffer will cache; `None` means unlimited.
+    pub fn max_signature_len(&self) -> Option<usize> {
+        self.policy.max_signature_len
+    }
+
+    /// The [`DuplicatePolicy`] to build sniffers with.
+    pub fn duplicate_policy(&self) -> DuplicatePolicy {
+        self.policy.duplicates
+    }
+
+    /// The `parallel_threshold` to hand [`crate::patch_all`] for this policy.
+    pub fn parallel_fill_threshold(&self) -> usize {
+        self.policy.parallel_fill_threshold
+    }
+
+    /// Whether contents with `role` get their parts patched.
+    pub fn is_model_role(&self, role: Option<&str>) -> bool {
+        self.policy.is_model_role(role)
     }
 
-    pub fn fallback_signature(&self) -> ThoughtSignature {
-        self.dummy_signature.clone()
+    /// Whether a signature the client sent for the content at `key` stays in place.
+    ///
+    /// Under [`ExistingSignatures::TrustVerified`] the cache is consulted and a mismatch is
+    /// logged; the client's signature is kept either way.
+    pub fn keep_existing(&self, key: Option<CacheKey>, existing: Option<&str>) -> bool {
+        let Some(existing) = existing else {
+            return false;
+        };
+        match self.policy.existing {
+            ExistingSignatures::Replace => false,
+            ExistingSignatures::Trust => true,
+            ExistingSignatures::TrustVerified => {
+                if let Some(cached) = key.and_then(|key| self.get_signature(&key))
+                    && cached.as_ref() != existing
+                {
+                    warn!(
+                        key = ?key,
+                        client = %preview(existing),
+                        cached = %preview(&cached),
+                        "Client thought signature differs from cached one; keeping client's"
+                    );
+                }
+                true
+            }
+        }
     }
 }
 
+fn preview(signature: &str) -> &str {
+    signature.get(..32).unwrap_or(signature)
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
+    /// Store whose every call fails.
+    pub(crate) struct BrokenStore;
+
+    impl SignatureStore for BrokenStore {
+        fn get(&self, _key: &CacheKey) -> Result<Option<ThoughtSignature>, StoreError> {
+            Err(StoreError("lock poisoned".to_string()))
+        }
+
+        fn put(&self, _key: CacheKey, _signature: ThoughtSignature) -> Result<(), StoreError> {
+            Err(StoreError("lock poisoned".to_string()))
+        }
+
+        fn snapshot(&self) -> Result<Vec<(CacheKey, ThoughtSignature)>, StoreError> {
+            Err(StoreError("lock poisoned".to_string()))
+        }
+
+        fn invalidate(&self, _key: &CacheKey) -> Result<(), StoreError> {
+            Err(StoreError("lock poisoned".to_string()))
+        }
+
+        fn invalidate_all(&self) -> Result<(), StoreError> {
+            Err(StoreError("lock poisoned".to_string()))
+        }
+    }
+
     #[test]
     fn get_signature_returns_none_when_no_cache() {
         let engine = ThoughtSignatureEngine::new(3600, 1024);
@@ -59,4 +526,190 @@ mod tests {
         let signature = engine.get_signature(&key);
         assert_eq!(signature.as_deref(), Some("sig_007"));
     }
+
+    #[test]
+    fn snapshot_round_trips_through_put_many() {
+        let source = ThoughtSignatureEngine::new(3600, 1024);
+        source.put_signature(1, Arc::from("sig_1"));
+        source.put_signature(2, Arc::from("sig_2"));
+
+        let target = ThoughtSignatureEngine::new(3600, 1024);
+        let written = target
+            .put_many(source.snapshot().expect("snapshot"))
+            .expect("put_many");
+
+        assert_eq!(written, 2);
+        assert_eq!(target.get_signature(&1).as_deref(), Some("sig_1"));
+        assert_eq!(target.get_signature(&2).as_deref(), Some("sig_2"));
+    }
+
+    #[test]
+    fn fallback_uses_model_dummy_when_configured() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024).with_policy(
+            EnginePolicy::default()
+                .with_model_dummy("gemini-3-pro-preview", "dummy_pro")
+                .with_model_dummy("gemini-3-flash-preview", "dummy_flash"),
+        );
+
+        let pro = engine.fallback_signature_for(Some("gemini-3-pro-preview"));
+        let flash = engine.fallback_signature_for(Some("gemini-3-flash-preview"));
+        assert_eq!(pro.as_deref(), Some("dummy_pro"));
+        assert_eq!(flash.as_deref(), Some("dummy_flash"));
+        assert_ne!(pro, flash);
+
+        let default = engine.fallback_signature();
+        assert_eq!(default.as_deref(), Some("skip_thought_signature_validator"));
+        assert_eq!(
+            engine.fallback_signature_for(Some("gemini-2.5-pro")),
+            default
+        );
+        assert_eq!(engine.fallback_signature_for(None), default);
+    }
+
+    #[test]
+    fn models_without_a_dummy_have_no_fallback() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024).with_policy(
+            EnginePolicy::default()
+                .without_model_dummy("gemini-3-pro-preview")
+                .with_model_dummy("gemini-3-flash-preview", "dummy_flash"),
+        );
+        assert_eq!(
+            engine.fallback_signature_for(Some("gemini-3-pro-preview")),
+            None
+        );
+        assert_eq!(
+            engine
+                .fallback_signature_for(Some("gemini-2.5-pro"))
+                .as_deref(),
+            Some("skip_thought_signature_validator")
+        );
+
+        let engine = ThoughtSignatureEngine::new(3600, 1024).with_policy(
+            EnginePolicy::default()
+                .without_default_dummy()
+                .with_model_dummy("gemini-3-flash-preview", "dummy_flash"),
+        );
+        assert_eq!(engine.fallback_signature(), None);
+        assert_eq!(engine.fallback_signature_for(Some("gemini-2.5-pro")), None);
+        assert_eq!(
+            engine
+                .fallback_signature_for(Some("gemini-3-flash-preview"))
+                .as_deref(),
+            Some("dummy_flash")
+        );
+    }
+
+    #[test]
+    fn trust_verified_logs_mismatch_and_keeps_client_signature() {
+        use std::io::Write;
+        use std::sync::Mutex;
+
+        #[derive(Clone, Default)]
+        struct Capture(Arc<Mutex<Vec<u8>>>);
+
+        impl Write for Capture {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let engine = ThoughtSignatureEngine::new(3600, 1024).with_policy(
+            EnginePolicy::default().with_existing_signatures(ExistingSignatures::TrustVerified),
+        );
+        engine.put_signature(9, Arc::from("sig_cached"));
+
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let logs = || String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+
+        tracing::subscriber::with_default(subscriber, || {
+            assert!(engine.keep_existing(Some(9), Some("sig_cached")));
+            assert!(!logs().contains("differs from cached"), "{}", logs());
+
+            assert!(engine.keep_existing(Some(9), Some("sig_stale")));
+            assert!(engine.keep_existing(Some(10), Some("sig_unknown")));
+            assert!(!engine.keep_existing(Some(9), None));
+        });
+
+        let logs = logs();
+        assert_eq!(logs.matches("differs from cached").count(), 1, "{logs}");
+        assert!(
+            logs.contains("sig_stale") && logs.contains("sig_cached"),
+            "{logs}"
+        );
+    }
+
+    #[test]
+    fn replace_mode_never_keeps_client_signatures() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024);
+        assert!(!engine.keep_existing(Some(1), Some("sig_client")));
+
+        let trusting = ThoughtSignatureEngine::new(3600, 1024).with_policy(
+            EnginePolicy::default().with_existing_signatures(ExistingSignatures::Trust),
+        );
+        assert!(trusting.keep_existing(None, Some("sig_client")));
+    }
+
+    #[test]
+    fn negative_cache_answers_repeated_misses_until_recorded() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024)
+            .with_policy(EnginePolicy::default().with_negative_cache(Duration::from_secs(60)));
+
+        assert_eq!(engine.lookup_signature(&5), SignatureLookup::Miss);
+        assert_eq!(engine.lookup_signature(&5), SignatureLookup::KnownMiss);
+
+        engine.put_signature(5, Arc::from("sig_5"));
+        assert_eq!(
+            engine.lookup_signature(&5),
+            SignatureLookup::Hit(Arc::from("sig_5"))
+        );
+
+        let plain = ThoughtSignatureEngine::new(3600, 1024);
+        assert_eq!(plain.lookup_signature(&5), SignatureLookup::Miss);
+        assert_eq!(plain.lookup_signature(&5), SignatureLookup::Miss);
+    }
+
+    #[test]
+    fn lookup_many_asks_once_per_distinct_key() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024)
+            .with_policy(EnginePolicy::default().with_negative_cache(Duration::from_secs(60)));
+        engine.put_signature(1, Arc::from("sig_1"));
+
+        let lookups = engine.lookup_many([1, 2, 1, 2]);
+        assert_eq!(lookups.len(), 2);
+        assert_eq!(lookups[&1], SignatureLookup::Hit(Arc::from("sig_1")));
+        // One real miss, not a known miss on the repeat.
+        assert_eq!(lookups[&2], SignatureLookup::Miss);
+        assert_eq!(engine.lookup_signature(&2), SignatureLookup::KnownMiss);
+    }
+
+    #[test]
+    fn parallel_fill_threshold_follows_policy() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024);
+        assert_eq!(
+            engine.parallel_fill_threshold(),
+            DEFAULT_PARALLEL_FILL_THRESHOLD
+        );
+
+        let engine = ThoughtSignatureEngine::new(3600, 1024)
+            .with_policy(EnginePolicy::default().with_parallel_fill_threshold(32));
+        assert_eq!(engine.parallel_fill_threshold(), 32);
+    }
+
+    #[test]
+    fn failing_store_degrades_to_miss() {
+        let engine = ThoughtSignatureEngine::with_store(Box::new(BrokenStore));
+
+        engine.put_signature(1, Arc::from("sig"));
+        assert!(engine.get_signature(&1).is_none());
+    }
 }
@@ -1,39 +1,274 @@
 use crate::CacheKey;
 
-use ahash::AHasher;
-use serde::Serialize;
-use std::hash::Hasher;
+use ahash::RandomState;
+use fnv::FnvHasher;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::OnceLock;
+use twox_hash::XxHash64;
 
 const DOMAIN_TEXT: u8 = 1;
 const DOMAIN_JSON: u8 = 2;
+const DOMAIN_TEXT_IN: u8 = 3;
+const DOMAIN_JSON_IN: u8 = 4;
 
-#[derive(Debug, Default, Clone, Copy)]
-pub struct CacheKeyGenerator;
+/// Seed used when none is configured. Fixed so keys are stable across restarts.
+pub const DEFAULT_HASH_SEED: u64 = 0x706f_6c6c_7578_7473;
+
+/// Odd constants that expand one `u64` seed into the four AHash keys.
+const SEED_MIX: [u64; 4] = [
+    0x9e37_79b9_7f4a_7c15,
+    0xbf58_476d_1ce4_e5b9,
+    0x94d0_49bb_1331_11eb,
+    0x2545_f491_4f6c_dd1d,
+];
+
+static GLOBAL: OnceLock<CacheKeyGenerator> = OnceLock::new();
+
+/// Hash function behind cache keys.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HashAlgo {
+    /// Seeded AHash. Fastest, but its output is only stable for one `ahash` version on one CPU
+    /// architecture, so keys shared or stored across builds or hosts may silently stop matching.
+    #[default]
+    #[serde(rename = "ahash")]
+    AHash,
+    /// 64-bit FNV-1a with the seed hashed in first. Fixed by its specification.
+    Fnv1a,
+    /// 64-bit xxHash (XXH64) seeded natively. Fixed by its specification.
+    #[serde(rename = "xxhash64")]
+    XxHash64,
+}
+
+thread_local! {
+    static JSON_BUFFER: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Fingerprints thought text and function calls into cache keys.
+///
+/// Keys depend only on the input, the seed and the [`HashAlgo`], so generators sharing both
+/// agree across restarts. With [`HashAlgo::AHash`] that only holds on the same CPU
+/// architecture and `ahash` version; the other algorithms agree everywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheKeyGenerator {
+    seed: u64,
+    algo: HashAlgo,
+}
+
+impl Default for CacheKeyGenerator {
+    fn default() -> Self {
+        Self::with_seed(DEFAULT_HASH_SEED)
+    }
+}
 
 impl CacheKeyGenerator {
+    pub const fn with_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            algo: HashAlgo::AHash,
+        }
+    }
+
+    pub const fn with_algo(mut self, algo: HashAlgo) -> Self {
+        self.algo = algo;
+        self
+    }
+
+    /// Install the process-wide seed used by [`Self::generate_text`] and
+    /// [`Self::generate_json`]. Returns `false` if a generator was already in use.
+    pub fn set_global_seed(seed: u64) -> bool {
+        Self::set_global(Self::with_seed(seed))
+    }
+
+    /// Like [`Self::set_global_seed`], for a generator with any seed and algorithm.
+    pub fn set_global(generator: Self) -> bool {
+        GLOBAL.set(generator).is_ok()
+    }
+
+    fn global() -> &'static Self {
+        GLOBAL.get_or_init(Self::default)
+    }
+
     pub fn generate_text(text: impl AsRef<str>) -> Option<CacheKey> {
-        Some(text.as_ref())
-            .filter(|&t| !t.trim().is_empty())
+        Self::global().text_key(text)
+    }
+
+    pub fn generate_json(value: &impl Serialize) -> Option<CacheKey> {
+        Self::global().json_key(value)
+    }
+
+    /// Like [`Self::generate_json`], but arrays of scalars hash as sets; see
+    /// [`Self::json_key_unordered`] for what that trades away.
+    pub fn generate_json_unordered(value: &impl Serialize) -> Option<CacheKey> {
+        Self::global().json_key_unordered(value)
+    }
+
+    /// [`Self::generate_text`] within `namespace`; `None` gives the plain key.
+    pub fn generate_text_in(namespace: Option<&str>, text: impl AsRef<str>) -> Option<CacheKey> {
+        Self::global().text_key_in(namespace, text)
+    }
+
+    /// [`Self::generate_json`] within `namespace`; `None` gives the plain key.
+    pub fn generate_json_in(namespace: Option<&str>, value: &impl Serialize) -> Option<CacheKey> {
+        Self::global().json_key_in(namespace, value)
+    }
+
+    /// Key for thought text. Hashes the trimmed slice in place, so borrowed (`&str`) and owned
+    /// (`String`) inputs give the same key and neither allocates.
+    pub fn text_key(&self, text: impl AsRef<str>) -> Option<CacheKey> {
+        self.text_key_in(None, text)
+    }
+
+    pub fn json_key(&self, value: &impl Serialize) -> Option<CacheKey> {
+        self.json_key_in(None, value)
+    }
+
+    /// Like [`Self::text_key`], but the same text in different namespaces (e.g. models) gets
+    /// different keys.
+    pub fn text_key_in(&self, namespace: Option<&str>, text: impl AsRef<str>) -> Option<CacheKey> {
+        Some(text.as_ref().trim())
+            .filter(|t| !t.is_empty())
             .map(|t| {
-                let mut hasher = AHasher::default();
-                hasher.write_u8(DOMAIN_TEXT);
+                let mut hasher = self.namespaced_hasher(DOMAIN_TEXT, DOMAIN_TEXT_IN, namespace);
                 hasher.write(t.as_bytes());
                 hasher.finish()
             })
     }
 
-    pub fn generate_json(value: &impl Serialize) -> Option<CacheKey> {
+    /// Like [`Self::json_key`], but the same value in different namespaces gets different keys.
+    pub fn json_key_in(&self, namespace: Option<&str>, value: &impl Serialize) -> Option<CacheKey> {
+        self.json_key_normalized(namespace, value, false)
+    }
+
+    /// Like [`Self::json_key`], but every array holding only scalars (strings, numbers, bools,
+    /// nulls) is sorted first, so `["read", "write"]` and `["write", "read"]` share a key.
+    ///
+    /// Meant for function-call args whose arrays are sets. The cost is that calls differing
+    /// only in the order of such an array become indistinguishable, which is wrong for args
+    /// where order matters (e.g. a list of steps): they would replay each other's signatures.
+    /// Arrays holding objects or arrays keep their order, though their contents are still
+    /// normalized. A value with no scalar arrays, or with them already sorted, gets the same
+    /// key as [`Self::json_key`].
+    pub fn json_key_unordered(&self, value: &impl Serialize) -> Option<CacheKey> {
+        self.json_key_unordered_in(None, value)
+    }
+
+    /// [`Self::json_key_unordered`] within `namespace`; `None` gives the plain key.
+    pub fn json_key_unordered_in(
+        &self,
+        namespace: Option<&str>,
+        value: &impl Serialize,
+    ) -> Option<CacheKey> {
+        self.json_key_normalized(namespace, value, true)
+    }
+
+    fn json_key_normalized(
+        &self,
+        namespace: Option<&str>,
+        value: &impl Serialize,
+        unordered: bool,
+    ) -> Option<CacheKey> {
         let mut normalized = serde_json::to_value(value).ok()?;
         if normalized.is_null() {
             return None;
         }
         normalized.sort_all_objects();
-        let bytes = serde_json::to_vec(&normalized).ok()?;
+        if unordered {
+            sort_scalar_arrays(&mut normalized);
+        }
+
+        // Reuse one serialization buffer per thread instead of allocating per key.
+        JSON_BUFFER.with_borrow_mut(|bytes| {
+            bytes.clear();
+            serde_json::to_writer(&mut *bytes, &normalized).ok()?;
+
+            let mut hasher = self.namespaced_hasher(DOMAIN_JSON, DOMAIN_JSON_IN, namespace);
+            hasher.write(bytes);
+            Some(hasher.finish())
+        })
+    }
+
+    /// Hasher primed with the domain and, if any, the length-prefixed namespace. Plain keys
+    /// hash exactly as they did before namespaces existed.
+    fn namespaced_hasher(&self, plain: u8, namespaced: u8, namespace: Option<&str>) -> impl Hasher {
+        let mut hasher = self.hasher();
+        match namespace {
+            None => hasher.write_u8(plain),
+            Some(namespace) => {
+                hasher.write_u8(namespaced);
+                // Fixed width and byte order, so the key does not depend on the platform.
+                hasher.write(&(namespace.len() as u64).to_le_bytes());
+                hasher.write(namespace.as_bytes());
+            }
+        }
+        hasher
+    }
+
+    fn hasher(&self) -> KeyHasher {
+        match self.algo {
+            HashAlgo::AHash => {
+                let [k0, k1, k2, k3] = SEED_MIX.map(|mix| self.seed ^ mix);
+                KeyHasher::AHash(RandomState::with_seeds(k0, k1, k2, k3).build_hasher())
+            }
+            HashAlgo::Fnv1a => {
+                let mut hasher = FnvHasher::default();
+                hasher.write(&self.seed.to_le_bytes());
+                KeyHasher::Fnv1a(hasher)
+            }
+            HashAlgo::XxHash64 => KeyHasher::XxHash64(XxHash64::with_seed(self.seed)),
+        }
+    }
+}
+
+/// Sort every array of scalars in `value` by its serialized elements.
+fn sort_scalar_arrays(value: &mut Value) {
+    match value {
+        Value::Array(items) if items.iter().all(is_scalar) => {
+            items.sort_by_cached_key(Value::to_string);
+        }
+        Value::Array(items) => items.iter_mut().for_each(sort_scalar_arrays),
+        Value::Object(map) => map.values_mut().for_each(sort_scalar_arrays),
+        _ => {}
+    }
+}
+
+fn is_scalar(value: &Value) -> bool {
+    !matches!(value, Value::Array(_) | Value::Object(_))
+}
 
-        let mut hasher = AHasher::default();
-        hasher.write_u8(DOMAIN_JSON);
-        hasher.write(&bytes);
-        Some(hasher.finish())
+/// One [`HashAlgo`]'s hasher, dispatched without boxing.
+enum KeyHasher {
+    AHash(ahash::AHasher),
+    Fnv1a(FnvHasher),
+    XxHash64(XxHash64),
+}
+
+impl Hasher for KeyHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        match self {
+            Self::AHash(hasher) => hasher.write(bytes),
+            Self::Fnv1a(hasher) => hasher.write(bytes),
+            Self::XxHash64(hasher) => hasher.write(bytes),
+        }
+    }
+
+    fn write_u8(&mut self, byte: u8) {
+        match self {
+            Self::AHash(hasher) => hasher.write_u8(byte),
+            Self::Fnv1a(hasher) => hasher.write_u8(byte),
+            Self::XxHash64(hasher) => hasher.write_u8(byte),
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        match self {
+            Self::AHash(hasher) => hasher.finish(),
+            Self::Fnv1a(hasher) => hasher.finish(),
+            Self::XxHash64(hasher) => hasher.finish(),
+        }
     }
 }
 
@@ -70,6 +305,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unordered_keys_ignore_the_order_of_scalar_arrays() {
+        let lhs = json!({"name": "grant", "args": {"scopes": ["read", "write", 1, null]}});
+        let rhs = json!({"name": "grant", "args": {"scopes": [null, "write", 1, "read"]}});
+        assert_ne!(
+            CacheKeyGenerator::generate_json(&lhs),
+            CacheKeyGenerator::generate_json(&rhs)
+        );
+        assert_eq!(
+            CacheKeyGenerator::generate_json_unordered(&lhs),
+            CacheKeyGenerator::generate_json_unordered(&rhs)
+        );
+
+        // Arrays of objects or arrays keep their order.
+        let steps = json!({"args": {"steps": [{"op": "a"}, {"op": "b"}]}});
+        let swapped = json!({"args": {"steps": [{"op": "b"}, {"op": "a"}]}});
+        assert_ne!(
+            CacheKeyGenerator::generate_json_unordered(&steps),
+            CacheKeyGenerator::generate_json_unordered(&swapped)
+        );
+        let nested = json!([["b", "a"], ["c"]]);
+        assert_eq!(
+            CacheKeyGenerator::generate_json_unordered(&nested),
+            CacheKeyGenerator::generate_json_unordered(&json!([["a", "b"], ["c"]]))
+        );
+        assert_ne!(
+            CacheKeyGenerator::generate_json_unordered(&nested),
+            CacheKeyGenerator::generate_json_unordered(&json!([["c"], ["a", "b"]]))
+        );
+
+        // Values without unsorted scalar arrays keep their ordered key.
+        let sorted = json!({"args": {"scopes": ["read", "write"]}});
+        assert_eq!(
+            CacheKeyGenerator::generate_json_unordered(&sorted),
+            CacheKeyGenerator::generate_json(&sorted)
+        );
+    }
+
+    #[test]
+    fn borrowed_and_owned_text_produce_identical_keys() {
+        let owned = String::from("  some model thought\n");
+        let borrowed: &str = owned.as_str();
+
+        let key = CacheKeyGenerator::generate_text(borrowed);
+        assert!(key.is_some());
+        assert_eq!(CacheKeyGenerator::generate_text(&owned), key);
+        assert_eq!(CacheKeyGenerator::generate_text(owned.clone()), key);
+        assert_eq!(CacheKeyGenerator::generate_text(borrowed.trim()), key);
+    }
+
     #[test]
     fn string_input_is_trimmed_before_hashing() {
         let lhs = "  alpha  ";
@@ -81,6 +366,94 @@ mod tests {
         );
     }
 
+    #[test]
+    fn same_seed_produces_identical_keys() {
+        let lhs = CacheKeyGenerator::with_seed(42);
+        let rhs = CacheKeyGenerator::with_seed(42);
+        let call = json!({ "name": "f", "args": { "x": 1 } });
+
+        assert_eq!(lhs.text_key("alpha"), rhs.text_key("alpha"));
+        assert_eq!(lhs.json_key(&call), rhs.json_key(&call));
+        assert!(lhs.text_key("alpha").is_some());
+    }
+
+    #[test]
+    fn different_seeds_produce_different_keys() {
+        let lhs = CacheKeyGenerator::with_seed(1);
+        let rhs = CacheKeyGenerator::with_seed(2);
+
+        assert_ne!(lhs.text_key("alpha"), rhs.text_key("alpha"));
+    }
+
+    #[test]
+    fn namespaces_separate_keys_and_none_is_the_plain_key() {
+        let generator = CacheKeyGenerator::with_seed(7);
+        let call = json!({ "name": "f", "args": { "x": 1 } });
+
+        assert_eq!(
+            generator.text_key_in(None, "alpha"),
+            generator.text_key("alpha")
+        );
+        assert_eq!(
+            generator.json_key_in(None, &call),
+            generator.json_key(&call)
+        );
+
+        let pro = generator.text_key_in(Some("gemini-2.5-pro"), "alpha");
+        let flash = generator.text_key_in(Some("gemini-3-pro-preview"), "alpha");
+        assert!(pro.is_some());
+        assert_ne!(pro, flash);
+        assert_ne!(pro, generator.text_key("alpha"));
+        assert_ne!(
+            generator.json_key_in(Some("gemini-2.5-pro"), &call),
+            generator.json_key(&call)
+        );
+    }
+
+    const FNV_ALPHA: u64 = 2772317903520484306;
+    const XXH_ALPHA: u64 = 1411677091147676794;
+
+    #[test]
+    fn deterministic_algorithms_give_fixed_keys() {
+        let call = json!({ "args": { "x": 1 }, "name": "f" });
+        for algo in [HashAlgo::Fnv1a, HashAlgo::XxHash64] {
+            let generator = CacheKeyGenerator::with_seed(42).with_algo(algo);
+            assert_eq!(generator.text_key("  alpha\n"), generator.text_key("alpha"));
+            assert_eq!(
+                generator.json_key(&call),
+                generator.json_key(&json!({ "name": "f", "args": { "x": 1 } }))
+            );
+            assert_ne!(
+                generator.text_key("alpha"),
+                CacheKeyGenerator::with_seed(43)
+                    .with_algo(algo)
+                    .text_key("alpha")
+            );
+        }
+
+        // Pinned values: a change here breaks every persisted or shared cache.
+        let fnv = CacheKeyGenerator::with_seed(42).with_algo(HashAlgo::Fnv1a);
+        let xxh = CacheKeyGenerator::with_seed(42).with_algo(HashAlgo::XxHash64);
+        assert_eq!(fnv.text_key("alpha"), Some(FNV_ALPHA));
+        assert_eq!(xxh.text_key("alpha"), Some(XXH_ALPHA));
+        assert_ne!(fnv.text_key("alpha"), xxh.text_key("alpha"));
+    }
+
+    #[test]
+    fn hash_algo_names_round_trip() {
+        for (algo, name) in [
+            (HashAlgo::AHash, "ahash"),
+            (HashAlgo::Fnv1a, "fnv1a"),
+            (HashAlgo::XxHash64, "xxhash64"),
+        ] {
+            assert_eq!(serde_json::to_value(algo).unwrap(), json!(name));
+            assert_eq!(
+                serde_json::from_value::<HashAlgo>(json!(name)).unwrap(),
+                algo
+            );
+        }
+    }
+
     #[test]
     fn empty_string_returns_none() {
         assert_eq!(CacheKeyGenerator::generate_text("   "), None);
@@ -0,0 +1,313 @@
+use crate::patch::{
+    PatchEvent, PatchOutcome, PatchStats, ThoughtSigPatchable, patch_all_for_model,
+};
+use crate::{CacheKey, ThoughtSignature, ThoughtSignatureEngine};
+use moka::sync::Cache;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Fill decisions remembered per conversation and model, so a history that only grows between
+/// turns has just its newly appended items decided.
+///
+/// The remembered prefix is reused when the request has at least as many items as last time
+/// and every remembered item still fingerprints the same. Otherwise (history edited,
+/// truncated or regenerated) every item is decided again.
+pub struct IncrementalFill {
+    memos: Cache<MemoKey, Arc<FillMemo>>,
+}
+
+/// Conversation id and the model the turn was filled for.
+type MemoKey = (String, Option<String>);
+
+struct FillMemo {
+    decisions: Vec<Remembered>,
+    /// Fingerprint of each remembered item, in order.
+    prefix: Vec<Option<CacheKey>>,
+}
+
+#[derive(Clone)]
+struct Remembered {
+    outcome: PatchOutcome,
+    /// Signature written into the item, if the fill wrote one.
+    signature: Option<ThoughtSignature>,
+}
+
+impl FillMemo {
+    fn covers<P: ThoughtSigPatchable>(
+        &self,
+        items: &[P],
+        engine: &ThoughtSignatureEngine,
+        model: Option<&str>,
+    ) -> bool {
+        !self.prefix.is_empty()
+            && items.len() >= self.prefix.len()
+            && items
+                .iter()
+                .zip(&self.prefix)
+                .all(|(item, key)| event_key(item, engine, model) == *key)
+    }
+
+    fn remembers(&self, key: CacheKey) -> bool {
+        self.prefix.contains(&Some(key))
+    }
+}
+
+impl IncrementalFill {
+    /// Remember up to `max_conversations`, each dropped after `idle` without a request.
+    pub fn new(max_conversations: u64, idle: Duration) -> Self {
+        Self {
+            memos: Cache::builder()
+                .max_capacity(max_conversations.max(1))
+                .time_to_idle(idle)
+                .build(),
+        }
+    }
+
+    /// Drop every remembered turn that replayed an item keyed by `key`, so the next turn looks
+    /// the item up again instead of writing back a signature the store no longer holds.
+    pub fn forget(&self, key: CacheKey) {
+        for (memo_key, memo) in self.memos.iter() {
+            if memo.remembers(key) {
+                self.memos.invalidate(memo_key.as_ref());
+            }
+        }
+    }
+
+    /// Drop every remembered turn.
+    pub fn clear(&self) {
+        self.memos.invalidate_all();
+    }
+
+    /// [`patch_all_for_model`] for one turn of `conversation`.
+    ///
+    /// Items covered by the previous turn get its signatures written back without a cache
+    /// lookup and are counted in [`PatchStats::reused`]; the rest are decided as usual.
+    pub fn patch<P>(
+        &self,
+        conversation: &str,
+        items: &mut [P],
+        engine: &ThoughtSignatureEngine,
+        model: Option<&str>,
+        parallel_threshold: usize,
+    ) -> (Vec<PatchOutcome>, PatchStats)
+    where
+        P: ThoughtSigPatchable + Sync,
+    {
+        let memo_key: MemoKey = (conversation.to_string(), model.map(str::to_string));
+        let previous = self
+            .memos
+            .get(&memo_key)
+            .filter(|memo| memo.covers(items, engine, model));
+        let reused = previous.as_ref().map_or(0, |memo| memo.decisions.len());
+
+        let (head, tail) = items.split_at_mut(reused);
+        let mut decisions = Vec::with_capacity(head.len() + tail.len());
+        for (item, remembered) in head
+            .iter_mut()
+            .zip(previous.iter().flat_map(|memo| memo.decisions.iter()))
+        {
+            if let Some(signature) = &remembered.signature {
+                *item.thought_signature_mut() = Some(signature.to_string());
+            }
+            decisions.push(remembered.clone());
+        }
+
+        let (tail_outcomes, mut stats) =
+            patch_all_for_model(tail, engine, model, parallel_threshold);
+        for (item, outcome) in tail.iter_mut().zip(&tail_outcomes) {
+            let signature = match outcome {
+                PatchOutcome::Patched { .. } => {
+                    item.thought_signature_mut().as_deref().map(Arc::from)
+                }
+                PatchOutcome::Skipped
+                | PatchOutcome::Kept { .. }
+                | PatchOutcome::Unfilled { .. } => None,
+            };
+            decisions.push(Remembered {
+                outcome: *outcome,
+                signature,
+            });
+        }
+        stats.reused = reused;
+
+        let outcomes = decisions.iter().map(|d| d.outcome).collect();
+        let prefix = items
+            .iter()
+            .map(|item| event_key(item, engine, model))
+            .collect();
+        self.memos
+            .insert(memo_key, Arc::new(FillMemo { decisions, prefix }));
+        (outcomes, stats)
+    }
+}
+
+/// The key the engine stores `item`'s signature under, so [`IncrementalFill::forget`] matches
+/// the key a caller drops from the store.
+fn event_key<P: ThoughtSigPatchable + ?Sized>(
+    item: &P,
+    engine: &ThoughtSignatureEngine,
+    model: Option<&str>,
+) -> Option<CacheKey> {
+    match item.data() {
+        PatchEvent::ThoughtText(text) => engine.text_key(model, text),
+        PatchEvent::FunctionCall(function_call) => engine.json_key(model, function_call),
+        PatchEvent::None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CacheKeyGenerator, DEFAULT_PARALLEL_FILL_THRESHOLD};
+
+    struct Item {
+        text: &'static str,
+        signature: Option<String>,
+    }
+
+    impl ThoughtSigPatchable for Item {
+        fn data(&self) -> PatchEvent<'_> {
+            PatchEvent::ThoughtText(self.text)
+        }
+
+        fn thought_signature_mut(&mut self) -> &mut Option<String> {
+            &mut self.signature
+        }
+    }
+
+    fn turn(texts: &[&'static str]) -> Vec<Item> {
+        texts
+            .iter()
+            .map(|text| Item {
+                text,
+                signature: None,
+            })
+            .collect()
+    }
+
+    fn fill(
+        incremental: &IncrementalFill,
+        engine: &ThoughtSignatureEngine,
+        items: &mut [Item],
+    ) -> PatchStats {
+        incremental
+            .patch(
+                "conv-1",
+                items,
+                engine,
+                None,
+                DEFAULT_PARALLEL_FILL_THRESHOLD,
+            )
+            .1
+    }
+
+    #[test]
+    fn appended_turn_only_decides_new_items() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024);
+        let incremental = IncrementalFill::new(16, Duration::from_secs(60));
+        let alpha = CacheKeyGenerator::generate_text("alpha").expect("text key must exist");
+        engine.put_signature(alpha, Arc::from("sig_alpha"));
+
+        let mut first = turn(&["alpha", "beta"]);
+        let stats = fill(&incremental, &engine, &mut first);
+        assert_eq!((stats.reused, stats.cache_hits, stats.fallbacks), (0, 1, 1));
+
+        // The cache entry is gone, but the remembered decision still applies.
+        engine.put_signature(alpha, Arc::from("sig_alpha_changed"));
+        let mut second = turn(&["alpha", "beta", "gamma"]);
+        let stats = fill(&incremental, &engine, &mut second);
+        assert_eq!((stats.reused, stats.cache_hits, stats.fallbacks), (2, 0, 1));
+        assert_eq!(second[0].signature.as_deref(), Some("sig_alpha"));
+        assert_eq!(second[1].signature, first[1].signature);
+        assert!(second[2].signature.is_some());
+    }
+
+    #[test]
+    fn edited_history_is_decided_again() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024);
+        let incremental = IncrementalFill::new(16, Duration::from_secs(60));
+
+        fill(&incremental, &engine, &mut turn(&["alpha", "beta"]));
+
+        let stats = fill(&incremental, &engine, &mut turn(&["alpha", "other"]));
+        assert_eq!((stats.reused, stats.fallbacks), (0, 2));
+
+        let stats = fill(&incremental, &engine, &mut turn(&["alpha"]));
+        assert_eq!((stats.reused, stats.fallbacks), (0, 1));
+    }
+
+    #[test]
+    fn edit_before_the_last_remembered_item_is_decided_again() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024);
+        let incremental = IncrementalFill::new(16, Duration::from_secs(60));
+
+        fill(&incremental, &engine, &mut turn(&["alpha", "beta"]));
+
+        let stats = fill(
+            &incremental,
+            &engine,
+            &mut turn(&["other", "beta", "gamma"]),
+        );
+        assert_eq!((stats.reused, stats.fallbacks), (0, 3));
+    }
+
+    #[test]
+    fn turn_for_another_model_is_decided_again() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024);
+        let incremental = IncrementalFill::new(16, Duration::from_secs(60));
+        let threshold = DEFAULT_PARALLEL_FILL_THRESHOLD;
+
+        let mut first = turn(&["alpha"]);
+        incremental.patch("conv-1", &mut first, &engine, Some("model-a"), threshold);
+
+        let mut second = turn(&["alpha", "beta"]);
+        let (_, stats) =
+            incremental.patch("conv-1", &mut second, &engine, Some("model-b"), threshold);
+        assert_eq!(stats.reused, 0);
+    }
+
+    #[test]
+    fn forget_matches_model_namespaced_keys() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024)
+            .with_policy(crate::EnginePolicy::default().with_model_namespaced_keys());
+        let incremental = IncrementalFill::new(16, Duration::from_secs(60));
+        let threshold = DEFAULT_PARALLEL_FILL_THRESHOLD;
+        let alpha = engine
+            .text_key(Some("model-a"), "alpha")
+            .expect("text key must exist");
+        engine.put_signature(alpha, Arc::from("sig_alpha"));
+
+        let mut first = turn(&["alpha"]);
+        incremental.patch("conv-1", &mut first, &engine, Some("model-a"), threshold);
+        assert_eq!(first[0].signature.as_deref(), Some("sig_alpha"));
+        engine
+            .invalidate_signature(&alpha)
+            .expect("invalidate must succeed");
+        incremental.forget(alpha);
+
+        let mut second = turn(&["alpha", "beta"]);
+        let (_, stats) =
+            incremental.patch("conv-1", &mut second, &engine, Some("model-a"), threshold);
+        assert_eq!(stats.reused, 0);
+        assert_ne!(second[0].signature.as_deref(), Some("sig_alpha"));
+    }
+
+    #[test]
+    fn forgotten_signature_drops_turns_that_replayed_it() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024);
+        let incremental = IncrementalFill::new(16, Duration::from_secs(60));
+        let alpha = CacheKeyGenerator::generate_text("alpha").expect("text key must exist");
+        engine.put_signature(alpha, Arc::from("sig_alpha"));
+
+        fill(&incremental, &engine, &mut turn(&["alpha"]));
+        engine
+            .invalidate_signature(&alpha)
+            .expect("invalidate must succeed");
+        incremental.forget(alpha);
+
+        let mut second = turn(&["alpha", "beta"]);
+        let stats = fill(&incremental, &engine, &mut second);
+        assert_eq!((stats.reused, stats.cache_hits), (0, 0));
+        assert_ne!(second[0].signature.as_deref(), Some("sig_alpha"));
+    }
+}
@@ -1,10 +1,22 @@
 pub mod engine;
 pub mod fingerprint;
+mod incremental;
 pub mod patch;
 mod sniffer;
+pub mod store;
 
-pub use engine::ThoughtSignatureEngine;
 pub use engine::{CacheKey, SignatureCacheStore, ThoughtSignature};
-pub use fingerprint::CacheKeyGenerator;
-pub use patch::{PatchEvent, PatchOutcome, ThoughtSigPatchable};
-pub use sniffer::{SignatureSniffer, SniffEvent, Sniffable};
+pub use engine::{
+    EnginePolicy, ExistingSignatures, SignatureLookup, ThoughtSignatureEngine, UnkeyedParts,
+};
+pub use fingerprint::{CacheKeyGenerator, DEFAULT_HASH_SEED, HashAlgo};
+pub use incremental::IncrementalFill;
+pub use patch::{
+    DEFAULT_PARALLEL_FILL_THRESHOLD, PatchEvent, PatchOutcome, PatchStats, ThoughtSigPatchable,
+    patch_all, patch_all_async, patch_all_for_model,
+};
+pub use sniffer::{DuplicatePolicy, RecordHook, SignatureSniffer, SniffEvent, Sniffable};
+pub use store::{
+    AsyncSignatureStore, MokaSignatureStore, SignatureExpiry, SignatureStore, StoreError,
+    StoreFootprint,
+};
@@ -1,5 +1,11 @@
-use crate::{CacheKey, CacheKeyGenerator, ThoughtSignatureEngine};
+use crate::{
+    AsyncSignatureStore, CacheKey, SignatureLookup, ThoughtSignature, ThoughtSignatureEngine,
+};
+use rayon::prelude::*;
 use serde_json::Value;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use tracing::warn;
 
 pub enum PatchEvent<'a> {
     ThoughtText(&'a str),
@@ -10,7 +16,39 @@ pub enum PatchEvent<'a> {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PatchOutcome {
     Skipped,
-    Patched { cache_key: Option<CacheKey> },
+    Patched {
+        cache_key: Option<CacheKey>,
+    },
+    /// The client's own signature was left in place (see [`crate::ExistingSignatures`]).
+    Kept {
+        cache_key: Option<CacheKey>,
+    },
+    /// A cache miss the policy does not dummy-fill; the item was left as sent.
+    Unfilled {
+        cache_key: Option<CacheKey>,
+    },
+}
+
+/// Below this many items [`patch_all`] decides sequentially; handing work to the pool
+/// outweighs the hashing it would spread out.
+pub const DEFAULT_PARALLEL_FILL_THRESHOLD: usize = 256;
+
+/// Tallies from one [`patch_all`] pass.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PatchStats {
+    pub skipped: usize,
+    pub cache_hits: usize,
+    pub fallbacks: usize,
+    pub kept: usize,
+    pub unfilled: usize,
+    /// Thought misses filled with a sibling function call's cached signature.
+    pub borrowed: usize,
+    /// Fallbacks whose miss the negative cache already knew, so the store was not asked (see
+    /// [`crate::EnginePolicy::with_negative_cache`]). Also counted in `fallbacks`.
+    pub negative_cache_hits: usize,
+    /// Items whose decision was carried over from an earlier turn (see
+    /// [`crate::IncrementalFill`]).
+    pub reused: usize,
 }
 
 pub trait ThoughtSigPatchable {
@@ -19,35 +57,447 @@ pub trait ThoughtSigPatchable {
     fn data(&self) -> PatchEvent<'_>;
     // Provide mutable access to the destination signature slot.
     fn thought_signature_mut(&mut self) -> &mut Option<String>;
+    // Signature the client already sent, if any. Only consulted when the engine
+    // policy trusts existing signatures.
+    fn existing_signature(&self) -> Option<&str> {
+        None
+    }
+    // Items in the same group (e.g. parts of one content) are siblings, which
+    // matters when the engine policy borrows sibling signatures.
+    fn group(&self) -> Option<usize> {
+        None
+    }
 
     // Shared patch pipeline:
     // 1) build cache key from event
     // 2) lookup signature (or fallback to dummy)
     // 3) write back to schema slot
     fn patch_thought_signature(&mut self, engine: &ThoughtSignatureEngine) -> PatchOutcome {
-        let cache_key = match self.data() {
-            PatchEvent::ThoughtText(text) => CacheKeyGenerator::generate_text(text),
-            PatchEvent::FunctionCall(function_call) => {
-                CacheKeyGenerator::generate_json(function_call)
-            }
-            PatchEvent::None => return PatchOutcome::Skipped,
-        };
+        let decision = decide(self.data(), self.existing_signature(), engine, None);
+        apply(self, decision)
+    }
+}
+
+/// What to write into one item, worked out without touching it.
+enum Decision {
+    Skip,
+    Keep {
+        cache_key: Option<CacheKey>,
+    },
+    Leave {
+        cache_key: Option<CacheKey>,
+    },
+    Fill {
+        cache_key: Option<CacheKey>,
+        signature: ThoughtSignature,
+        hit: bool,
+        /// The miss came from the negative cache.
+        known_miss: bool,
+    },
+    Borrow {
+        cache_key: Option<CacheKey>,
+        signature: ThoughtSignature,
+    },
+}
+
+/// An item's decision, or the cache key it still waits on before it can be decided.
+enum Pending {
+    Decided(Decision),
+    Lookup {
+        cache_key: Option<CacheKey>,
+        is_function_call: bool,
+    },
+}
+
+impl Decision {
+    fn cache_key(&self) -> Option<CacheKey> {
+        match self {
+            Decision::Skip => None,
+            Decision::Keep { cache_key }
+            | Decision::Leave { cache_key }
+            | Decision::Fill { cache_key, .. }
+            | Decision::Borrow { cache_key, .. } => *cache_key,
+        }
+    }
+}
+
+impl Pending {
+    fn cache_key(&self) -> Option<CacheKey> {
+        match self {
+            Pending::Decided(decision) => decision.cache_key(),
+            Pending::Lookup { cache_key, .. } => *cache_key,
+        }
+    }
+}
+
+/// A decision tagged with the cache key of the item it was made for, so write-back can
+/// check it lands on an item with that same key.
+struct Targeted {
+    cache_key: Option<CacheKey>,
+    decision: Decision,
+}
+
+fn decide(
+    event: PatchEvent<'_>,
+    existing: Option<&str>,
+    engine: &ThoughtSignatureEngine,
+    model: Option<&str>,
+) -> Decision {
+    match prepare(event, existing, engine, model) {
+        Pending::Decided(decision) => decision,
+        Pending::Lookup {
+            cache_key,
+            is_function_call,
+        } => {
+            let lookup =
+                cache_key.map_or(SignatureLookup::Miss, |key| engine.lookup_signature(&key));
+            resolve(cache_key, is_function_call, lookup, engine, model)
+        }
+    }
+}
+
+/// Key an item and settle everything that needs no cache lookup.
+fn prepare(
+    event: PatchEvent<'_>,
+    existing: Option<&str>,
+    engine: &ThoughtSignatureEngine,
+    model: Option<&str>,
+) -> Pending {
+    let is_function_call = match event {
+        PatchEvent::ThoughtText(_) => false,
+        PatchEvent::FunctionCall(_) => true,
+        PatchEvent::None => return Pending::Decided(Decision::Skip),
+    };
+    let cache_key = event_key(&event, engine, model);
+    if engine.keep_existing(cache_key, existing) {
+        return Pending::Decided(Decision::Keep { cache_key });
+    }
+    if cache_key.is_none() && !engine.fills_unkeyed_parts() {
+        return Pending::Decided(Decision::Leave { cache_key });
+    }
+    Pending::Lookup {
+        cache_key,
+        is_function_call,
+    }
+}
+
+/// The cache key `event` is stored and looked up under, if it has one.
+fn event_key(
+    event: &PatchEvent<'_>,
+    engine: &ThoughtSignatureEngine,
+    model: Option<&str>,
+) -> Option<CacheKey> {
+    match event {
+        PatchEvent::ThoughtText(text) => engine.text_key(model, text),
+        PatchEvent::FunctionCall(function_call) => engine.json_key(model, function_call),
+        PatchEvent::None => None,
+    }
+}
+
+fn resolve(
+    cache_key: Option<CacheKey>,
+    is_function_call: bool,
+    lookup: SignatureLookup,
+    engine: &ThoughtSignatureEngine,
+    model: Option<&str>,
+) -> Decision {
+    let known_miss = lookup == SignatureLookup::KnownMiss;
+    let (signature, hit) = match lookup {
+        SignatureLookup::Hit(signature) => (signature, true),
+        _ if is_function_call && !engine.fills_function_call_misses() => {
+            return Decision::Leave { cache_key };
+        }
+        _ => match engine.fallback_signature_for(model) {
+            Some(dummy) => (dummy, false),
+            None => return Decision::Leave { cache_key },
+        },
+    };
+    Decision::Fill {
+        cache_key,
+        signature,
+        hit,
+        known_miss,
+    }
+}
+
+fn apply<P: ThoughtSigPatchable + ?Sized>(item: &mut P, decision: Decision) -> PatchOutcome {
+    match decision {
+        Decision::Skip => PatchOutcome::Skipped,
+        Decision::Keep { cache_key } => PatchOutcome::Kept { cache_key },
+        Decision::Leave { cache_key } => PatchOutcome::Unfilled { cache_key },
+        Decision::Fill {
+            cache_key,
+            signature,
+            ..
+        }
+        | Decision::Borrow {
+            cache_key,
+            signature,
+        } => {
+            *item.thought_signature_mut() = Some(signature.to_string());
+            PatchOutcome::Patched { cache_key }
+        }
+    }
+}
+
+/// Patch every item, returning per-item outcomes in input order plus totals.
+///
+/// Each item is keyed once, and each distinct key is then looked up once in a single pass,
+/// so a history that repeats the same part pays for one lookup. Key hashing only reads the
+/// items, so once there are at least `parallel_threshold` of them (and more than one CPU) it
+/// is spread over rayon's global pool. Signatures are then written back sequentially, so the
+/// result does not depend on which path ran.
+pub fn patch_all<P>(
+    items: &mut [P],
+    engine: &ThoughtSignatureEngine,
+    parallel_threshold: usize,
+) -> (Vec<PatchOutcome>, PatchStats)
+where
+    P: ThoughtSigPatchable + Sync,
+{
+    patch_all_for_model(items, engine, None, parallel_threshold)
+}
+
+/// [`patch_all`] for a request to `model`; cache misses get that model's dummy signature.
+pub fn patch_all_for_model<P>(
+    items: &mut [P],
+    engine: &ThoughtSignatureEngine,
+    model: Option<&str>,
+    parallel_threshold: usize,
+) -> (Vec<PatchOutcome>, PatchStats)
+where
+    P: ThoughtSigPatchable + Sync,
+{
+    let workers = rayon::current_num_threads();
+    let mut decisions = if workers > 1 && items.len() >= parallel_threshold.max(1) {
+        decide_parallel(items, engine, model)
+    } else {
+        let prepared = items
+            .iter()
+            .map(|item| prepare(item.data(), item.existing_signature(), engine, model))
+            .collect();
+        decide_batch(prepared, engine, model)
+    };
+    if engine.borrows_sibling_signatures() {
+        borrow_sibling_signatures(items, &mut decisions);
+    }
+    apply_all(items, decisions, engine, model)
+}
+
+/// [`patch_all_for_model`] with signatures looked up in `store` rather than the engine's own
+/// store, e.g. a cache shared between instances. The engine still supplies keys, dummies
+/// and the rest of its policy.
+///
+/// Items are keyed on the calling task and each distinct key is awaited once, in turn.
+pub async fn patch_all_async<P, S>(
+    items: &mut [P],
+    engine: &ThoughtSignatureEngine,
+    store: &S,
+    model: Option<&str>,
+) -> (Vec<PatchOutcome>, PatchStats)
+where
+    P: ThoughtSigPatchable,
+    S: AsyncSignatureStore,
+{
+    let prepared: Vec<_> = items
+        .iter()
+        .map(|item| prepare(item.data(), item.existing_signature(), engine, model))
+        .collect();
+    let mut lookups = HashMap::new();
+    for key in pending_keys(&prepared) {
+        if let Entry::Vacant(slot) = lookups.entry(key) {
+            slot.insert(engine.lookup_signature_async(store, &key).await);
+        }
+    }
+    let mut decisions = settle(prepared, &lookups, engine, model);
+    if engine.borrows_sibling_signatures() {
+        borrow_sibling_signatures(items, &mut decisions);
+    }
+    apply_all(items, decisions, engine, model)
+}
 
-        let signature = match cache_key {
-            Some(key) => engine
-                .get_signature(&key)
-                .unwrap_or_else(|| engine.fallback_signature()),
-            None => engine.fallback_signature(),
+/// Give each thought miss the cached signature of the nearest function call in its group,
+/// looking only at the unbroken run of items sharing that group.
+fn borrow_sibling_signatures<P: ThoughtSigPatchable>(items: &[P], decisions: &mut [Targeted]) {
+    for index in 0..items.len() {
+        let Some(group) = items[index].group() else {
+            continue;
         };
+        let Decision::Fill {
+            cache_key,
+            hit: false,
+            ..
+        } = decisions[index].decision
+        else {
+            continue;
+        };
+        if !matches!(items[index].data(), PatchEvent::ThoughtText(_)) {
+            continue;
+        }
 
-        *self.thought_signature_mut() = Some(signature.to_string());
-        PatchOutcome::Patched { cache_key }
+        let in_group = |j: &usize| items[*j].group() == Some(group);
+        let before = (0..index).rev().take_while(in_group);
+        let after = (index + 1..items.len()).take_while(in_group);
+        let sibling = before
+            .chain(after)
+            .filter(|&j| matches!(items[j].data(), PatchEvent::FunctionCall(_)))
+            .filter_map(|j| match &decisions[j].decision {
+                Decision::Fill {
+                    signature,
+                    hit: true,
+                    ..
+                } => Some((j.abs_diff(index), signature.clone())),
+                _ => None,
+            })
+            .min_by_key(|(distance, _)| *distance);
+        if let Some((_, signature)) = sibling {
+            decisions[index].decision = Decision::Borrow {
+                cache_key,
+                signature,
+            };
+        }
     }
 }
 
+/// Write decisions back in input order. Each item is keyed again first; a decision made for an
+/// item with a different key is logged and the item left unfilled, rather than pairing a
+/// signature with the wrong part.
+fn apply_all<P: ThoughtSigPatchable>(
+    items: &mut [P],
+    decisions: Vec<Targeted>,
+    engine: &ThoughtSignatureEngine,
+    model: Option<&str>,
+) -> (Vec<PatchOutcome>, PatchStats) {
+    debug_assert_eq!(
+        items.len(),
+        decisions.len(),
+        "one signature decision per item"
+    );
+
+    let mut stats = PatchStats::default();
+    let mut decisions = decisions.into_iter();
+    let outcomes = items
+        .iter_mut()
+        .enumerate()
+        .map(|(position, item)| {
+            let item_key = event_key(&item.data(), engine, model);
+            let Some(Targeted {
+                cache_key,
+                decision,
+            }) = decisions.next()
+            else {
+                warn!(
+                    position,
+                    "No signature decision for item; leaving it unfilled"
+                );
+                stats.unfilled += 1;
+                return PatchOutcome::Unfilled {
+                    cache_key: item_key,
+                };
+            };
+            if cache_key != item_key {
+                warn!(
+                    position,
+                    "Signature decision made for an item with another key; leaving it unfilled"
+                );
+                stats.unfilled += 1;
+                return PatchOutcome::Unfilled {
+                    cache_key: item_key,
+                };
+            }
+            match &decision {
+                Decision::Skip => stats.skipped += 1,
+                Decision::Keep { .. } => stats.kept += 1,
+                Decision::Leave { .. } => stats.unfilled += 1,
+                Decision::Fill { hit: true, .. } => stats.cache_hits += 1,
+                Decision::Fill {
+                    hit: false,
+                    known_miss,
+                    ..
+                } => {
+                    stats.fallbacks += 1;
+                    stats.negative_cache_hits += usize::from(*known_miss);
+                }
+                Decision::Borrow { .. } => stats.borrowed += 1,
+            }
+            apply(item, decision)
+        })
+        .collect();
+    (outcomes, stats)
+}
+
+/// Key `items` on rayon's global pool, which is shared by every request and sized to the CPUs,
+/// so concurrent large requests queue for workers instead of each starting threads.
+fn decide_parallel<P>(
+    items: &[P],
+    engine: &ThoughtSignatureEngine,
+    model: Option<&str>,
+) -> Vec<Targeted>
+where
+    P: ThoughtSigPatchable + Sync,
+{
+    let prepared = items
+        .par_iter()
+        .map(|item| prepare(item.data(), item.existing_signature(), engine, model))
+        .collect();
+    decide_batch(prepared, engine, model)
+}
+
+/// Settle prepared items, looking each distinct pending key up once.
+fn decide_batch(
+    prepared: Vec<Pending>,
+    engine: &ThoughtSignatureEngine,
+    model: Option<&str>,
+) -> Vec<Targeted> {
+    let lookups = engine.lookup_many(pending_keys(&prepared));
+    settle(prepared, &lookups, engine, model)
+}
+
+/// Keys the prepared items still wait on, repeats included.
+fn pending_keys(prepared: &[Pending]) -> impl Iterator<Item = CacheKey> + '_ {
+    prepared.iter().filter_map(|pending| match pending {
+        Pending::Lookup {
+            cache_key: Some(key),
+            ..
+        } => Some(*key),
+        _ => None,
+    })
+}
+
+fn settle(
+    prepared: Vec<Pending>,
+    lookups: &HashMap<CacheKey, SignatureLookup>,
+    engine: &ThoughtSignatureEngine,
+    model: Option<&str>,
+) -> Vec<Targeted> {
+    prepared
+        .into_iter()
+        .map(|pending| {
+            let cache_key = pending.cache_key();
+            let decision = match pending {
+                Pending::Decided(decision) => decision,
+                Pending::Lookup {
+                    cache_key,
+                    is_function_call,
+                } => {
+                    let lookup = cache_key
+                        .and_then(|key| lookups.get(&key).cloned())
+                        .unwrap_or(SignatureLookup::Miss);
+                    resolve(cache_key, is_function_call, lookup, engine, model)
+                }
+            };
+            Targeted {
+                cache_key,
+                decision,
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::CacheKeyGenerator;
     use serde_json::{Value, json};
     use std::sync::Arc;
 
@@ -123,6 +573,167 @@ mod tests {
         );
     }
 
+    #[test]
+    fn function_call_miss_is_left_unsigned_when_dummies_disabled() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024)
+            .with_policy(crate::EnginePolicy::default().without_function_call_dummies());
+        let function_call = json!({"name": "get_weather", "args": {}});
+        let mut items = vec![
+            FakePatchable {
+                data: FakeData::FunctionCall(function_call.clone()),
+                signature: None,
+            },
+            FakePatchable {
+                data: FakeData::Text("thinking"),
+                signature: None,
+            },
+        ];
+
+        let (outcomes, stats) = patch_all(&mut items, &engine, DEFAULT_PARALLEL_FILL_THRESHOLD);
+        assert_eq!(
+            outcomes[0],
+            PatchOutcome::Unfilled {
+                cache_key: CacheKeyGenerator::generate_json(&function_call),
+            }
+        );
+        assert!(items[0].signature.is_none());
+        assert_eq!(
+            items[1].signature.as_deref(),
+            Some("skip_thought_signature_validator")
+        );
+        assert_eq!((stats.unfilled, stats.fallbacks), (1, 1));
+    }
+
+    /// Drive a future whose store answers without waiting.
+    fn ready<F: std::future::Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+        match future.as_mut().poll(&mut cx) {
+            std::task::Poll::Ready(output) => output,
+            std::task::Poll::Pending => panic!("in-memory store should answer at once"),
+        }
+    }
+
+    #[test]
+    fn async_patch_looks_signatures_up_in_the_given_store() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024).with_policy(
+            crate::EnginePolicy::default().with_negative_cache(std::time::Duration::from_secs(60)),
+        );
+        let shared = crate::MokaSignatureStore::new(3600, 1024);
+        let function_call = json!({"name": "get_weather", "args": {}});
+        let key = CacheKeyGenerator::generate_json(&function_call).expect("call key");
+        ready(engine.put_signature_async(&shared, key, Arc::from("shared_sig")));
+        // Only the shared store knows the signature.
+        assert_eq!(engine.get_signature(&key), None);
+
+        let mut items = vec![
+            FakePatchable {
+                data: FakeData::FunctionCall(function_call.clone()),
+                signature: None,
+            },
+            FakePatchable {
+                data: FakeData::Text("thinking"),
+                signature: None,
+            },
+            FakePatchable {
+                data: FakeData::Text("thinking"),
+                signature: None,
+            },
+        ];
+        let (outcomes, stats) = ready(patch_all_async(&mut items, &engine, &shared, None));
+        assert_eq!(
+            outcomes[0],
+            PatchOutcome::Patched {
+                cache_key: Some(key)
+            }
+        );
+        assert_eq!(items[0].signature.as_deref(), Some("shared_sig"));
+        assert_eq!(
+            items[1].signature.as_deref(),
+            Some("skip_thought_signature_validator")
+        );
+        assert_eq!((stats.cache_hits, stats.fallbacks), (1, 2));
+
+        // The miss was remembered, and recording the signature forgets it.
+        let text_key = CacheKeyGenerator::generate_text("thinking").expect("text key");
+        assert_eq!(
+            ready(engine.lookup_signature_async(&shared, &text_key)),
+            SignatureLookup::KnownMiss
+        );
+        ready(engine.put_signature_async(&shared, text_key, Arc::from("text_sig")));
+        assert_eq!(
+            ready(engine.lookup_signature_async(&shared, &text_key)),
+            SignatureLookup::Hit(Arc::from("text_sig"))
+        );
+    }
+
+    #[test]
+    fn misses_are_left_unsigned_for_models_without_a_dummy() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024).with_policy(
+            crate::EnginePolicy::default().without_model_dummy("gemini-3-pro-preview"),
+        );
+        let function_call = json!({"name": "get_weather", "args": {}});
+        let items = || {
+            vec![
+                FakePatchable {
+                    data: FakeData::FunctionCall(function_call.clone()),
+                    signature: None,
+                },
+                FakePatchable {
+                    data: FakeData::Text("thinking"),
+                    signature: None,
+                },
+            ]
+        };
+
+        let mut skipped = items();
+        let (outcomes, stats) = patch_all_for_model(
+            &mut skipped,
+            &engine,
+            Some("gemini-3-pro-preview"),
+            DEFAULT_PARALLEL_FILL_THRESHOLD,
+        );
+        assert!(
+            outcomes
+                .iter()
+                .all(|outcome| matches!(outcome, PatchOutcome::Unfilled { .. }))
+        );
+        assert!(skipped.iter().all(|item| item.signature.is_none()));
+        assert_eq!((stats.unfilled, stats.fallbacks), (2, 0));
+
+        let mut filled = items();
+        let (_, stats) = patch_all_for_model(
+            &mut filled,
+            &engine,
+            Some("gemini-2.5-pro"),
+            DEFAULT_PARALLEL_FILL_THRESHOLD,
+        );
+        assert_eq!((stats.unfilled, stats.fallbacks), (0, 2));
+    }
+
+    #[test]
+    fn patch_with_broken_store_still_fills_dummy() {
+        use crate::engine::tests::BrokenStore;
+
+        let engine = ThoughtSignatureEngine::with_store(Box::new(BrokenStore));
+        let mut item = FakePatchable {
+            data: FakeData::Text("alpha"),
+            signature: None,
+        };
+
+        let applied = item.patch_thought_signature(&engine);
+        assert_eq!(
+            applied,
+            PatchOutcome::Patched {
+                cache_key: CacheKeyGenerator::generate_text("alpha")
+            }
+        );
+        assert_eq!(
+            item.signature.as_deref(),
+            Some("skip_thought_signature_validator")
+        );
+    }
+
     #[test]
     fn patch_none_event_is_skipped() {
         let engine = ThoughtSignatureEngine::new(3600, 1024);
@@ -151,4 +762,198 @@ mod tests {
             Some("skip_thought_signature_validator")
         );
     }
+
+    #[test]
+    fn empty_thought_text_follows_the_unkeyed_parts_policy() {
+        let empty_thought = || {
+            vec![FakePatchable {
+                data: FakeData::Text(""),
+                signature: Some("client_sig".to_string()),
+            }]
+        };
+
+        let fill = ThoughtSignatureEngine::new(3600, 1024);
+        let mut items = empty_thought();
+        let (outcomes, stats) = patch_all(&mut items, &fill, DEFAULT_PARALLEL_FILL_THRESHOLD);
+        assert_eq!(outcomes, [PatchOutcome::Patched { cache_key: None }]);
+        assert_eq!(
+            items[0].signature.as_deref(),
+            Some("skip_thought_signature_validator")
+        );
+        assert_eq!(stats.fallbacks, 1);
+
+        let keep = ThoughtSignatureEngine::new(3600, 1024).with_policy(
+            crate::EnginePolicy::default().with_unkeyed_parts(crate::UnkeyedParts::Keep),
+        );
+        let mut items = empty_thought();
+        let (outcomes, stats) = patch_all(&mut items, &keep, DEFAULT_PARALLEL_FILL_THRESHOLD);
+        assert_eq!(outcomes, [PatchOutcome::Unfilled { cache_key: None }]);
+        assert_eq!(items[0].signature.as_deref(), Some("client_sig"));
+        assert_eq!((stats.unfilled, stats.fallbacks), (1, 0));
+
+        // Keyed parts are unaffected.
+        let mut item = FakePatchable {
+            data: FakeData::Text("thinking"),
+            signature: None,
+        };
+        item.patch_thought_signature(&keep);
+        assert_eq!(
+            item.signature.as_deref(),
+            Some("skip_thought_signature_validator")
+        );
+    }
+
+    fn mixed_items(count: usize) -> Vec<FakePatchable> {
+        (0..count)
+            .map(|i| FakePatchable {
+                data: match i % 4 {
+                    0 => FakeData::Text(if i.is_multiple_of(8) {
+                        "cached"
+                    } else {
+                        "uncached"
+                    }),
+                    1 => FakeData::FunctionCall(json!({"name": "f", "args": {"i": i % 3}})),
+                    2 => FakeData::Text("   "),
+                    _ => FakeData::None,
+                },
+                signature: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parallel_and_sequential_fills_agree() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024);
+        let cached = CacheKeyGenerator::generate_text("cached").expect("text key must exist");
+        engine.put_signature(cached, Arc::from("sig_cached"));
+        let call = CacheKeyGenerator::generate_json(&json!({"name": "f", "args": {"i": 1}}))
+            .expect("json key must exist");
+        engine.put_signature(call, Arc::from("sig_call"));
+
+        let mut sequential = mixed_items(1000);
+        let mut parallel = mixed_items(1000);
+        let (seq_outcomes, seq_stats) = patch_all(&mut sequential, &engine, usize::MAX);
+        let (par_outcomes, par_stats) = patch_all(&mut parallel, &engine, 1);
+        // Exercise the threaded path even on a single-CPU machine.
+        let mut forced = mixed_items(1000);
+        let forced_decisions = decide_parallel(&forced, &engine, None);
+        let (forced_outcomes, _) = apply_all(&mut forced, forced_decisions, &engine, None);
+
+        assert_eq!(seq_outcomes, par_outcomes);
+        assert_eq!(seq_outcomes, forced_outcomes);
+        assert_eq!(seq_stats, par_stats);
+        assert_eq!(seq_stats.skipped, 250);
+        assert!(seq_stats.cache_hits > 0 && seq_stats.fallbacks > 0);
+        assert_eq!(
+            seq_stats.skipped + seq_stats.cache_hits + seq_stats.fallbacks,
+            1000
+        );
+        let signatures = |items: &[FakePatchable]| {
+            items
+                .iter()
+                .map(|item| item.signature.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(signatures(&sequential), signatures(&parallel));
+        assert_eq!(signatures(&sequential), signatures(&forced));
+    }
+
+    #[test]
+    fn repeated_keys_are_looked_up_once_per_pass() {
+        use crate::{MokaSignatureStore, SignatureStore, StoreError};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// Counts lookups reaching the backing store.
+        struct CountingStore {
+            inner: MokaSignatureStore,
+            gets: Arc<AtomicUsize>,
+        }
+
+        impl SignatureStore for CountingStore {
+            fn get(&self, key: &CacheKey) -> Result<Option<ThoughtSignature>, StoreError> {
+                self.gets.fetch_add(1, Ordering::Relaxed);
+                self.inner.get(key)
+            }
+
+            fn put(&self, key: CacheKey, signature: ThoughtSignature) -> Result<(), StoreError> {
+                self.inner.put(key, signature)
+            }
+
+            fn snapshot(&self) -> Result<Vec<(CacheKey, ThoughtSignature)>, StoreError> {
+                self.inner.snapshot()
+            }
+
+            fn invalidate(&self, key: &CacheKey) -> Result<(), StoreError> {
+                self.inner.invalidate(key)
+            }
+
+            fn invalidate_all(&self) -> Result<(), StoreError> {
+                self.inner.invalidate_all()
+            }
+        }
+
+        let gets = Arc::new(AtomicUsize::new(0));
+        let engine = ThoughtSignatureEngine::with_store(Box::new(CountingStore {
+            inner: MokaSignatureStore::new(3600, 1024),
+            gets: gets.clone(),
+        }));
+        let cached = CacheKeyGenerator::generate_text("cached").expect("text key must exist");
+        engine.put_signature(cached, Arc::from("sig_cached"));
+
+        for threshold in [usize::MAX, 1] {
+            gets.store(0, Ordering::Relaxed);
+            let mut items = mixed_items(1000);
+            let (_, stats) = patch_all(&mut items, &engine, threshold);
+
+            // "cached", "uncached" and three distinct function calls.
+            assert_eq!(gets.load(Ordering::Relaxed), 5);
+            assert_eq!(stats.cache_hits, 125);
+            assert_eq!(
+                items[0].signature.as_deref(),
+                Some("sig_cached"),
+                "repeats share the looked-up signature"
+            );
+            assert_eq!(items[992].signature.as_deref(), Some("sig_cached"));
+        }
+    }
+
+    #[test]
+    fn decisions_for_reordered_parts_leave_them_unfilled() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024);
+        let call = json!({"name": "f", "args": {}});
+        let thought = CacheKeyGenerator::generate_text("plan").expect("text key must exist");
+        let call_key = CacheKeyGenerator::generate_json(&call).expect("json key must exist");
+        engine.put_signature(thought, Arc::from("sig_thought"));
+        engine.put_signature(call_key, Arc::from("sig_call"));
+
+        let part = |data| FakePatchable {
+            data,
+            signature: None,
+        };
+        let mut items = vec![
+            part(FakeData::Text("plan")),
+            part(FakeData::FunctionCall(call.clone())),
+            part(FakeData::None),
+        ];
+        let decisions = decide_parallel(&items, &engine, None);
+        // The parts no longer line up with the decisions made for them.
+        items.swap(0, 1);
+
+        let (outcomes, stats) = apply_all(&mut items, decisions, &engine, None);
+        assert_eq!(
+            outcomes,
+            vec![
+                PatchOutcome::Unfilled {
+                    cache_key: Some(call_key)
+                },
+                PatchOutcome::Unfilled {
+                    cache_key: Some(thought)
+                },
+                PatchOutcome::Skipped,
+            ]
+        );
+        assert!(items.iter().all(|item| item.signature.is_none()));
+        assert_eq!(stats.unfilled, 2);
+        assert_eq!(stats.cache_hits, 0);
+    }
 }
@@ -1,7 +1,9 @@
-use crate::ThoughtSignatureEngine;
-use crate::fingerprint::CacheKeyGenerator;
+use crate::{CacheKey, ThoughtSignature, ThoughtSignatureEngine};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::sync::Arc;
+use tracing::warn;
 
 pub enum SniffEvent<'a> {
     ThoughtText(&'a str),
@@ -9,6 +11,10 @@ pub enum SniffEvent<'a> {
     None,
 }
 
+/// One content part of a response candidate, as the sniffer sees it.
+///
+/// Candidates are told apart by `index` (absent means `0`), so an adapter hands the sniffer
+/// every part of every candidate and streamed chunks for different candidates may interleave.
 pub trait Sniffable {
     fn data(&self) -> SniffEvent<'_>;
     fn thought_signature(&self) -> Option<&str>;
@@ -16,69 +22,106 @@ pub trait Sniffable {
     fn is_finished(&self) -> bool;
 }
 
+/// What one candidate has streamed so far.
 #[derive(Debug, Default)]
 pub struct SessionState {
     thought_buffer: String,
     function_buffer: Option<Value>,
     pending_signature: Option<String>,
-    current_index: Option<u32>,
 }
 
-impl SessionState {
-    fn reset(&mut self, new_index: u32) {
-        self.thought_buffer.clear();
-        self.function_buffer = None;
-        self.pending_signature = None;
-        self.current_index = Some(new_index);
-    }
+/// Which signature to keep when one response yields two different signatures
+/// for the same cache key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicatePolicy {
+    /// Keep the signature seen first; later ones are logged and dropped.
+    FirstWins,
+    /// Overwrite with each newer signature.
+    #[default]
+    LastWins,
 }
 
 pub struct SignatureSniffer {
     engine: Arc<ThoughtSignatureEngine>,
-    state: SessionState,
+    /// Unfinished candidates by index. A candidate is flushed when it finishes, or when the
+    /// sniffer is dropped if it never does.
+    states: HashMap<u32, SessionState>,
+    policy: DuplicatePolicy,
+    /// Signatures stored by this sniffer, i.e. within the current response.
+    recorded: HashMap<CacheKey, ThoughtSignature>,
+    /// Model the response came from, for engines that namespace keys by model.
+    model: Option<String>,
+    /// Also handed every signature the engine stores, e.g. to copy it to a shared store.
+    on_record: Option<RecordHook>,
 }
 
+/// Callback for [`SignatureSniffer::with_record_hook`].
+pub type RecordHook = Arc<dyn Fn(CacheKey, ThoughtSignature) + Send + Sync>;
+
 impl SignatureSniffer {
     pub fn new(engine: Arc<ThoughtSignatureEngine>) -> Self {
         Self {
             engine,
-            state: SessionState::default(),
+            states: HashMap::new(),
+            policy: DuplicatePolicy::default(),
+            recorded: HashMap::new(),
+            model: None,
+            on_record: None,
         }
     }
 
+    /// Record signatures under `model`'s keys, matching requests patched for that model.
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    pub fn with_duplicate_policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Call `hook` with each signature after the engine stores it.
+    pub fn with_record_hook(mut self, hook: RecordHook) -> Self {
+        self.on_record = Some(hook);
+        self
+    }
+
     pub fn inspect<T: Sniffable>(&mut self, item: &T) {
-        if let Some(next_index) = item.index()
-            && self.state.current_index != Some(next_index)
-        {
-            self.flush();
-            self.state.reset(next_index);
-        }
+        let index = item.index().unwrap_or(0);
+        let state = self.states.entry(index).or_default();
 
         match item.data() {
-            SniffEvent::ThoughtText(thought) => self.state.thought_buffer.push_str(thought),
-            SniffEvent::FunctionCall(function) => {
-                self.state.function_buffer = Some(function.clone())
+            SniffEvent::ThoughtText(thought) => state.thought_buffer.push_str(thought),
+            // Upstream signs the first call of a parallel batch; later calls must not take its key.
+            SniffEvent::FunctionCall(function)
+                if state.function_buffer.is_none() || state.pending_signature.is_none() =>
+            {
+                state.function_buffer = Some(function.clone())
             }
+            SniffEvent::FunctionCall(_) => {}
             SniffEvent::None => {}
         }
 
         if let Some(signature) = item.thought_signature() {
-            self.state.pending_signature = Some(signature.to_string());
+            state.pending_signature = Some(signature.to_string());
         }
 
-        if item.is_finished() {
-            self.flush();
+        if item.is_finished()
+            && let Some(state) = self.states.remove(&index)
+        {
+            self.flush(state);
         }
     }
 
-    fn flush(&mut self) {
-        if self.state.thought_buffer.is_empty() && self.state.function_buffer.is_none() {
+    fn flush(&mut self, state: SessionState) {
+        if state.thought_buffer.is_empty() && state.function_buffer.is_none() {
             // No data, so we skip flushing to avoid storing empty keys
             return;
         }
 
-        let Some(signature) = self
-            .state
+        let Some(signature) = state
             .pending_signature
             .as_deref()
             .filter(|&s| !s.is_empty())
@@ -87,19 +130,62 @@ impl SignatureSniffer {
             return;
         };
 
-        let signature: crate::ThoughtSignature = Arc::from(signature);
-
-        if let Some(text_key) = CacheKeyGenerator::generate_text(&self.state.thought_buffer) {
-            self.engine.put_signature(text_key, signature.clone());
+        if let Some(max) = self.engine.max_signature_len()
+            && signature.len() > max
+        {
+            warn!(
+                len = signature.len(),
+                max, "Thought signature exceeds the maximum length; not caching it"
+            );
+            return;
         }
 
-        if let Some(function_key) = self
-            .state
+        let signature: ThoughtSignature = Arc::from(signature);
+
+        let model = self.model.as_deref();
+        let text_key = self.engine.text_key(model, &state.thought_buffer);
+        let function_key = state
             .function_buffer
             .as_ref()
-            .and_then(CacheKeyGenerator::generate_json)
-        {
-            self.engine.put_signature(function_key, signature);
+            .and_then(|function_call| self.engine.json_key(model, function_call));
+
+        for key in [text_key, function_key].into_iter().flatten() {
+            self.record(key, signature.clone());
+        }
+    }
+
+    fn record(&mut self, key: CacheKey, signature: ThoughtSignature) {
+        if let Some(existing) = self.recorded.get(&key) {
+            if *existing == signature {
+                return;
+            }
+            warn!(
+                key,
+                existing_len = existing.len(),
+                incoming_len = signature.len(),
+                policy = ?self.policy,
+                "Conflicting thought signatures for the same cache key in one response"
+            );
+            if self.policy == DuplicatePolicy::FirstWins {
+                return;
+            }
+        }
+
+        self.recorded.insert(key, signature.clone());
+        self.engine.put_signature(key, signature.clone());
+        if let Some(hook) = &self.on_record {
+            hook(key, signature);
+        }
+    }
+}
+
+impl Drop for SignatureSniffer {
+    /// Record candidates the response ended without finishing.
+    fn drop(&mut self) {
+        let mut states: Vec<_> = std::mem::take(&mut self.states).into_iter().collect();
+        states.sort_unstable_by_key(|(index, _)| *index);
+        for (_, state) in states {
+            self.flush(state);
         }
     }
 }
@@ -107,6 +193,7 @@ impl SignatureSniffer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fingerprint::CacheKeyGenerator;
 
     enum DataKind {
         Text(&'static str),
@@ -178,6 +265,28 @@ mod tests {
         assert_eq!(cached, Arc::from("sig_001"));
     }
 
+    #[test]
+    fn record_hook_sees_every_stored_signature() {
+        let engine = Arc::new(ThoughtSignatureEngine::new(3600, 128));
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let hook_seen = seen.clone();
+        let mut sniffer =
+            SignatureSniffer::new(engine.clone()).with_record_hook(Arc::new(move |key, sig| {
+                hook_seen.lock().unwrap().push((key, sig));
+            }));
+
+        sniffer.inspect(&FakeSniffable {
+            data_kind: DataKind::Text("hooked"),
+            signature: Some("sig_hook"),
+            index: Some(0),
+            finished: true,
+        });
+
+        let key = CacheKeyGenerator::generate_text("hooked").expect("text key must be generated");
+        assert_eq!(*seen.lock().unwrap(), vec![(key, Arc::from("sig_hook"))]);
+        assert_eq!(engine.get_signature(&key), Some(Arc::from("sig_hook")));
+    }
+
     #[test]
     fn function_json_hash_is_used_as_key() {
         let engine = Arc::new(ThoughtSignatureEngine::new(3600, 128));
@@ -205,6 +314,100 @@ mod tests {
         assert_eq!(cached, Arc::from("sig_fn_001"));
     }
 
+    #[test]
+    fn unsigned_call_after_signed_call_in_batch_is_not_keyed() {
+        let engine = Arc::new(ThoughtSignatureEngine::new(3600, 128));
+        let mut sniffer = SignatureSniffer::new(engine.clone());
+
+        let first_call = serde_json::json!({ "name": "get_weather", "args": { "city": "Berlin" } });
+        let second_call = serde_json::json!({ "name": "get_time", "args": { "city": "Berlin" } });
+
+        sniffer.inspect(&FakeSniffable {
+            data_kind: DataKind::FunctionCall(first_call.clone()),
+            signature: Some("sig_batch"),
+            index: Some(0),
+            finished: false,
+        });
+        sniffer.inspect(&FakeSniffable {
+            data_kind: DataKind::FunctionCall(second_call.clone()),
+            signature: None,
+            index: Some(0),
+            finished: true,
+        });
+
+        let first_key =
+            CacheKeyGenerator::generate_json(&first_call).expect("function key must be generated");
+        let second_key =
+            CacheKeyGenerator::generate_json(&second_call).expect("function key must be generated");
+        assert_eq!(
+            engine.get_signature(&first_key),
+            Some(Arc::from("sig_batch"))
+        );
+        assert_eq!(engine.get_signature(&second_key), None);
+    }
+
+    #[test]
+    fn interleaved_candidates_accumulate_text_per_index() {
+        let engine = Arc::new(ThoughtSignatureEngine::new(3600, 128));
+        let mut sniffer = SignatureSniffer::new(engine.clone());
+
+        for (index, text, signature, finished) in [
+            (0, "alpha ", None, false),
+            (1, "gamma ", None, false),
+            (0, "beta", Some("sig_zero"), true),
+            (1, "delta", Some("sig_one"), false),
+        ] {
+            sniffer.inspect(&FakeSniffable {
+                data_kind: DataKind::Text(text),
+                signature,
+                index: Some(index),
+                finished,
+            });
+        }
+
+        let zero = CacheKeyGenerator::generate_text("alpha beta").unwrap();
+        let one = CacheKeyGenerator::generate_text("gamma delta").unwrap();
+        assert_eq!(engine.get_signature(&zero), Some(Arc::from("sig_zero")));
+        // Candidate 1 never finished; it is recorded once the response is over.
+        assert!(engine.get_signature(&one).is_none());
+        drop(sniffer);
+        assert_eq!(engine.get_signature(&one), Some(Arc::from("sig_one")));
+    }
+
+    fn sniff_same_text_twice(sniffer: &mut SignatureSniffer) {
+        for (index, signature) in [(0, "sig_first"), (1, "sig_second")] {
+            sniffer.inspect(&FakeSniffable {
+                data_kind: DataKind::Text("same thought"),
+                signature: Some(signature),
+                index: Some(index),
+                finished: true,
+            });
+        }
+    }
+
+    #[test]
+    fn conflicting_signatures_for_same_key_keep_last_by_default() {
+        let engine = Arc::new(ThoughtSignatureEngine::new(3600, 128));
+        let mut sniffer = SignatureSniffer::new(engine.clone());
+
+        sniff_same_text_twice(&mut sniffer);
+
+        let key = CacheKeyGenerator::generate_text("same thought").unwrap();
+        assert_eq!(engine.get_signature(&key), Some(Arc::from("sig_second")));
+    }
+
+    #[test]
+    fn conflicting_signatures_for_same_key_keep_first_when_configured() {
+        let engine = Arc::new(ThoughtSignatureEngine::new(3600, 128));
+        let mut sniffer =
+            SignatureSniffer::new(engine.clone()).with_duplicate_policy(DuplicatePolicy::FirstWins);
+
+        sniff_same_text_twice(&mut sniffer);
+
+        let key = CacheKeyGenerator::generate_text("same thought").unwrap();
+        assert_eq!(engine.get_signature(&key), Some(Arc::from("sig_first")));
+    }
+
     #[test]
     fn finished_event_without_signature_does_not_store() {
         let engine = Arc::new(ThoughtSignatureEngine::new(3600, 128));
@@ -221,4 +424,30 @@ mod tests {
         let key = CacheKeyGenerator::generate_text("alpha").expect("text key must be generated");
         assert!(engine.get_signature(&key).is_none());
     }
+
+    #[test]
+    fn over_length_signature_is_not_stored() {
+        let engine = Arc::new(
+            ThoughtSignatureEngine::new(3600, 128)
+                .with_policy(crate::EnginePolicy::default().with_max_signature_len(8)),
+        );
+        let mut sniffer = SignatureSniffer::new(engine.clone());
+
+        for (index, (text, signature)) in [("short", "sig_001"), ("long", "sig_0000001")]
+            .into_iter()
+            .enumerate()
+        {
+            sniffer.inspect(&FakeSniffable {
+                data_kind: DataKind::Text(text),
+                signature: Some(signature),
+                index: Some(index as u32),
+                finished: true,
+            });
+        }
+
+        let short = CacheKeyGenerator::generate_text("short").expect("text key must be generated");
+        let long = CacheKeyGenerator::generate_text("long").expect("text key must be generated");
+        assert_eq!(engine.get_signature(&short), Some(Arc::from("sig_001")));
+        assert!(engine.get_signature(&long).is_none());
+    }
 }
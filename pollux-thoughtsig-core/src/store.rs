@@ -0,0 +1,347 @@
+use crate::{CacheKey, SignatureCacheStore, ThoughtSignature};
+use serde::Serialize;
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError};
+use std::time::Duration;
+
+/// Failure reported by a [`SignatureStore`] backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreError(pub String);
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "signature store error: {}", self.0)
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl<T> From<PoisonError<T>> for StoreError {
+    fn from(err: PoisonError<T>) -> Self {
+        Self(err.to_string())
+    }
+}
+
+/// Backend that maps fingerprints to thought signatures.
+///
+/// Errors are never fatal for callers: the engine treats a failed lookup as a miss
+/// and a failed write as dropped. Report every failure, poisoned locks included, as a
+/// [`StoreError`] instead of panicking: release builds abort on panic, so a panicking
+/// store takes the whole process down.
+pub trait SignatureStore: Send + Sync {
+    fn get(&self, key: &CacheKey) -> Result<Option<ThoughtSignature>, StoreError>;
+    fn put(&self, key: CacheKey, signature: ThoughtSignature) -> Result<(), StoreError>;
+
+    /// Every live entry, in no particular order.
+    fn snapshot(&self) -> Result<Vec<(CacheKey, ThoughtSignature)>, StoreError>;
+
+    /// Drop `key`'s entry, if there is one.
+    fn invalidate(&self, key: &CacheKey) -> Result<(), StoreError>;
+
+    /// Drop every entry.
+    fn invalidate_all(&self) -> Result<(), StoreError>;
+
+    /// How many entries are live and how large their signatures are. Walks a
+    /// [`Self::snapshot`] unless the backend can do better.
+    fn footprint(&self) -> Result<StoreFootprint, StoreError> {
+        let entries = self.snapshot()?;
+        Ok(StoreFootprint::of(
+            entries.iter().map(|(_, signature)| signature),
+        ))
+    }
+
+    /// Insert entries in bulk and return how many were written.
+    fn put_many(&self, entries: Vec<(CacheKey, ThoughtSignature)>) -> Result<usize, StoreError> {
+        let count = entries.len();
+        for (key, signature) in entries {
+            self.put(key, signature)?;
+        }
+        Ok(count)
+    }
+}
+
+/// Size of a store's live entries, for sizing the cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct StoreFootprint {
+    pub entries: u64,
+    /// Summed signature lengths. An estimate of the memory values hold: keys, reference counts
+    /// and the cache's own bookkeeping are not counted.
+    pub signature_bytes: u64,
+}
+
+impl StoreFootprint {
+    pub fn of(signatures: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        signatures
+            .into_iter()
+            .fold(Self::default(), |footprint, signature| Self {
+                entries: footprint.entries + 1,
+                signature_bytes: footprint.signature_bytes + signature.as_ref().len() as u64,
+            })
+    }
+}
+
+/// Backend that answers over I/O, such as a cache shared by several proxy instances.
+///
+/// Used by [`crate::patch_all_async`] in place of the engine's own store. Errors are never
+/// fatal, as with [`SignatureStore`]. Method names differ from [`SignatureStore`]'s so a type
+/// can implement both.
+pub trait AsyncSignatureStore: Send + Sync {
+    fn get_signature(
+        &self,
+        key: &CacheKey,
+    ) -> impl Future<Output = Result<Option<ThoughtSignature>, StoreError>> + Send;
+
+    fn put_signature(
+        &self,
+        key: CacheKey,
+        signature: ThoughtSignature,
+    ) -> impl Future<Output = Result<(), StoreError>> + Send;
+
+    /// Drop `key`'s entry, if there is one.
+    fn invalidate_signature(
+        &self,
+        key: &CacheKey,
+    ) -> impl Future<Output = Result<(), StoreError>> + Send;
+}
+
+/// When cached signatures expire.
+///
+/// `ttl` is an absolute lifetime counted from insertion; `idle` restarts on every read or
+/// write, so signatures a long conversation keeps replaying stay cached. With both set, an
+/// entry goes at whichever comes first. With neither, entries only leave on eviction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignatureExpiry {
+    pub ttl: Option<Duration>,
+    pub idle: Option<Duration>,
+}
+
+impl SignatureExpiry {
+    /// Absolute lifetime only.
+    pub fn ttl(ttl_secs: u64) -> Self {
+        Self {
+            ttl: Some(Duration::from_secs(ttl_secs.max(1))),
+            idle: None,
+        }
+    }
+
+    /// Refresh-on-access lifetime only.
+    pub fn idle(idle_secs: u64) -> Self {
+        Self {
+            ttl: None,
+            idle: Some(Duration::from_secs(idle_secs.max(1))),
+        }
+    }
+}
+
+/// In-memory TTL/LRU store backed by moka.
+pub struct MokaSignatureStore {
+    cache: SignatureCacheStore,
+    /// Summed length of live signatures: added on insert, taken back by the eviction listener
+    /// whenever moka drops or replaces a value.
+    signature_bytes: Arc<AtomicU64>,
+}
+
+impl MokaSignatureStore {
+    pub fn new(ttl_secs: u64, max_capacity: u64) -> Self {
+        Self::with_expiry(SignatureExpiry::ttl(ttl_secs), max_capacity)
+    }
+
+    pub fn with_expiry(expiry: SignatureExpiry, max_capacity: u64) -> Self {
+        let signature_bytes = Arc::new(AtomicU64::new(0));
+        let evicted_bytes = signature_bytes.clone();
+        let mut builder = SignatureCacheStore::builder()
+            .max_capacity(max_capacity.max(1))
+            .eviction_listener(move |_, signature: ThoughtSignature, _| {
+                evicted_bytes.fetch_sub(signature.len() as u64, Ordering::Relaxed);
+            });
+        if let Some(ttl) = expiry.ttl {
+            builder = builder.time_to_live(ttl);
+        }
+        if let Some(idle) = expiry.idle {
+            builder = builder.time_to_idle(idle);
+        }
+        Self {
+            cache: builder.build(),
+            signature_bytes,
+        }
+    }
+}
+
+impl SignatureStore for MokaSignatureStore {
+    fn get(&self, key: &CacheKey) -> Result<Option<ThoughtSignature>, StoreError> {
+        Ok(self.cache.get(key))
+    }
+
+    fn put(&self, key: CacheKey, signature: ThoughtSignature) -> Result<(), StoreError> {
+        self.signature_bytes
+            .fetch_add(signature.len() as u64, Ordering::Relaxed);
+        self.cache.insert(key, signature);
+        Ok(())
+    }
+
+    fn snapshot(&self) -> Result<Vec<(CacheKey, ThoughtSignature)>, StoreError> {
+        Ok(self.cache.iter().map(|(key, sig)| (*key, sig)).collect())
+    }
+
+    fn invalidate(&self, key: &CacheKey) -> Result<(), StoreError> {
+        self.cache.invalidate(key);
+        Ok(())
+    }
+
+    fn invalidate_all(&self) -> Result<(), StoreError> {
+        self.cache.invalidate_all();
+        Ok(())
+    }
+
+    /// Read from moka's entry count and a running byte total rather than by walking the cache.
+    fn footprint(&self) -> Result<StoreFootprint, StoreError> {
+        // Apply queued writes and expiries so both numbers reflect the same state.
+        self.cache.run_pending_tasks();
+        Ok(StoreFootprint {
+            entries: self.cache.entry_count(),
+            signature_bytes: self.signature_bytes.load(Ordering::Relaxed),
+        })
+    }
+}
+
+/// Answers at once; for running the async path against a process-local cache.
+impl AsyncSignatureStore for MokaSignatureStore {
+    async fn get_signature(&self, key: &CacheKey) -> Result<Option<ThoughtSignature>, StoreError> {
+        self.get(key)
+    }
+
+    async fn put_signature(
+        &self,
+        key: CacheKey,
+        signature: ThoughtSignature,
+    ) -> Result<(), StoreError> {
+        self.put(key, signature)
+    }
+
+    async fn invalidate_signature(&self, key: &CacheKey) -> Result<(), StoreError> {
+        self.invalidate(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poisoned_lock_converts_to_store_error() {
+        let lock = Arc::new(std::sync::Mutex::new(()));
+        let poisoner = lock.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoner.lock().unwrap();
+            panic!("poison the lock");
+        })
+        .join();
+
+        let locked = || -> Result<(), StoreError> {
+            let _guard = lock.lock()?;
+            Ok(())
+        };
+        assert!(locked().is_err());
+    }
+
+    #[test]
+    fn invalidated_entries_are_gone() {
+        let store = MokaSignatureStore::new(3600, 16);
+        for key in 1..=3 {
+            store.put(key, Arc::from("sig")).unwrap();
+        }
+
+        store.invalidate(&1).unwrap();
+        assert!(store.get(&1).unwrap().is_none());
+        assert_eq!(store.get(&2).unwrap().as_deref(), Some("sig"));
+        // Unknown keys are not an error.
+        store.invalidate(&99).unwrap();
+
+        store.invalidate_all().unwrap();
+        assert!(store.get(&2).unwrap().is_none());
+        assert!(store.get(&3).unwrap().is_none());
+    }
+
+    #[test]
+    fn footprint_sums_signature_lengths() {
+        let store = MokaSignatureStore::new(3600, 16);
+        assert_eq!(store.footprint().unwrap(), StoreFootprint::default());
+
+        store.put(1, Arc::from("a".repeat(10))).unwrap();
+        store.put(2, Arc::from("b".repeat(32))).unwrap();
+        store.put(3, Arc::from("c".repeat(100))).unwrap();
+        let expected = StoreFootprint {
+            entries: 3,
+            signature_bytes: 142,
+        };
+        assert_eq!(store.footprint().unwrap(), expected);
+
+        // Replacing a signature counts only the new one; a snapshot walk agrees.
+        store.put(3, Arc::from("d".repeat(4))).unwrap();
+        store.invalidate(&1).unwrap();
+        let expected = StoreFootprint {
+            entries: 2,
+            signature_bytes: 36,
+        };
+        assert_eq!(store.footprint().unwrap(), expected);
+        assert_eq!(
+            StoreFootprint::of(store.snapshot().unwrap().iter().map(|(_, sig)| sig)),
+            expected
+        );
+
+        store.invalidate_all().unwrap();
+        assert_eq!(store.footprint().unwrap(), StoreFootprint::default());
+    }
+
+    #[test]
+    fn idle_expiry_keeps_entries_that_are_read() {
+        use std::time::Instant;
+
+        // Timings are measured, not assumed: a read is only expected to hit while every gap
+        // so far was well inside the idle window, so a stalled test thread cannot fail it.
+        let window = Duration::from_secs(1);
+        let slack = Duration::from_millis(50);
+        let ttl_store = MokaSignatureStore::with_expiry(
+            SignatureExpiry {
+                ttl: Some(window),
+                idle: None,
+            },
+            16,
+        );
+        let idle_store = MokaSignatureStore::with_expiry(
+            SignatureExpiry {
+                ttl: None,
+                idle: Some(window),
+            },
+            16,
+        );
+        let mut touched = Instant::now();
+        ttl_store.put(1, Arc::from("sig")).unwrap();
+        idle_store.put(1, Arc::from("sig")).unwrap();
+        let written = Instant::now();
+
+        // Read well inside the idle window until the TTL has run out.
+        let mut kept_reads = 0;
+        let mut stalled = false;
+        while written.elapsed() < window + slack {
+            std::thread::sleep(window / 10);
+            let before = Instant::now();
+            let signature = idle_store.get(&1).unwrap();
+            stalled |= touched.elapsed() >= window - slack;
+            if !stalled {
+                assert_eq!(signature.as_deref(), Some("sig"));
+                kept_reads += 1;
+            }
+            touched = before;
+        }
+        assert!(kept_reads > 0, "no read landed inside the idle window");
+        assert!(ttl_store.get(&1).unwrap().is_none());
+
+        // Once reads stop, the idle window still expires the entry.
+        idle_store.get(&1).unwrap();
+        std::thread::sleep(window + slack);
+        assert!(idle_store.get(&1).unwrap().is_none());
+    }
+}
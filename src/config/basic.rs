@@ -1,6 +1,13 @@
+use pollux_thoughtsig_core::{
+    DEFAULT_PARALLEL_FILL_THRESHOLD, DuplicatePolicy, ExistingSignatures, HashAlgo,
+    SignatureExpiry, UnkeyedParts,
+};
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
+use std::time::Duration;
 
 /// Basic (core) configuration managed by Figment.
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -20,6 +27,12 @@ pub struct BasicConfig {
     #[serde(default)]
     pub database_url: String,
 
+    /// Record every credential lease (request id, credential, model, outcome) in the
+    /// `lease_log` table, queryable at `GET /admin/lease-log`.
+    /// TOML: `basic.lease_log`. Default: `false`.
+    #[serde(default)]
+    pub lease_log: bool,
+
     /// Log level for tracing subscriber initialization (e.g., "error", "warn", "info", "debug", "trace").
     /// TOML: `basic.loglevel`. Default: `info`.
     #[serde(default)]
@@ -37,6 +50,280 @@ pub struct BasicConfig {
     /// Keep `false` in production/HTTPS. Set `true` only for local plain-HTTP testing.
     #[serde(default)]
     pub insecure_cookie: bool,
+
+    /// `Domain` attribute for OAuth cookies, for deployments spanning subdomains.
+    /// TOML: `basic.cookie_domain`. Default: unset (host-only cookie).
+    #[serde(default)]
+    pub cookie_domain: Option<String>,
+
+    /// Explicit `Secure` flag for OAuth cookies.
+    /// TOML: `basic.cookie_secure`. Default: `!insecure_cookie`.
+    #[serde(default)]
+    pub cookie_secure: Option<bool>,
+
+    /// `SameSite` attribute for OAuth cookies (`lax`, `strict` or `none`).
+    /// TOML: `basic.cookie_same_site`. Default: `lax`.
+    ///
+    /// Browsers only accept `none` together with `Secure`.
+    #[serde(default)]
+    pub cookie_same_site: CookieSameSite,
+
+    /// Max SSE events buffered between the upstream reader and a client.
+    /// TOML: `basic.sse_buffer_capacity`. Default: `64`.
+    #[serde(default = "default_sse_buffer_capacity")]
+    pub sse_buffer_capacity: usize,
+
+    /// What to do when a slow client lets the SSE buffer fill up.
+    /// TOML: `basic.sse_overflow`. Default: `backpressure`.
+    ///
+    /// `backpressure` pauses upstream reads until the client catches up; `error` stops reading
+    /// upstream and ends the client stream with an error.
+    #[serde(default)]
+    pub sse_overflow: SseOverflowPolicy,
+
+    /// End Gemini-format streams with an `event: complete` carrying the fully merged response,
+    /// for clients that want the assembled body as well as the deltas.
+    /// TOML: `basic.sse_complete_event`. Default: `false`.
+    #[serde(default)]
+    pub sse_complete_event: bool,
+
+    /// Reconnect delay sent to SSE clients as a `retry:` field, in milliseconds.
+    /// TOML: `basic.sse_retry_ms`. Default: unset (no hint).
+    ///
+    /// Sent when a stream starts and again before a stream error.
+    #[serde(default)]
+    pub sse_retry_ms: Option<u64>,
+
+    /// Seconds to wait for an upstream stream's first event (time to first token).
+    /// TOML: `basic.sse_first_event_timeout_secs`. Default: `60`.
+    #[serde(default = "default_sse_timeout_secs")]
+    pub sse_first_event_timeout_secs: u64,
+
+    /// Seconds an upstream stream may go without an event once it has started.
+    /// TOML: `basic.sse_idle_timeout_secs`. Default: `60`.
+    #[serde(default = "default_sse_timeout_secs")]
+    pub sse_idle_timeout_secs: u64,
+
+    /// Give function calls in Gemini-format responses a deterministic `id` when upstream sent
+    /// none, and strip those ids (from calls and matching function responses) before the next
+    /// request goes upstream.
+    /// TOML: `basic.tool_call_ids`. Default: `false`.
+    ///
+    /// For OpenAI-style tool-calling clients that correlate calls and results by id.
+    #[serde(default)]
+    pub tool_call_ids: bool,
+
+    /// Drop parts that are only an empty `text` from Gemini-format responses.
+    /// TOML: `basic.strip_empty_text_parts`. Default: `false`.
+    ///
+    /// Stream chunks left with nothing else are skipped; function calls, signed parts and
+    /// finish reasons always reach the client.
+    #[serde(default)]
+    pub strip_empty_text_parts: bool,
+
+    /// Max SSE streams open at once across all routes; further streaming requests get 503.
+    /// TOML: `basic.max_concurrent_streams`. Default: unset (unlimited).
+    ///
+    /// Unary requests are not counted.
+    #[serde(default)]
+    pub max_concurrent_streams: Option<usize>,
+
+    /// Let identical non-streaming requests that overlap share one upstream call and response.
+    /// TOML: `basic.coalesce_requests`. Default: `false`.
+    ///
+    /// Saves quota on client retries and fan-out, but callers receive the very same generation.
+    #[serde(default)]
+    pub coalesce_requests: bool,
+
+    /// Compress responses with gzip or brotli when the client's `Accept-Encoding` allows it.
+    /// TOML: `basic.compress_responses`. Default: `false`.
+    ///
+    /// SSE streams and tiny bodies are always sent uncompressed.
+    #[serde(default)]
+    pub compress_responses: bool,
+
+    /// Finish reasons that count as a successful Gemini-format response, e.g. `["STOP",
+    /// "MAX_TOKENS"]`.
+    /// TOML: `basic.success_finish_reasons`. Default: unset (every finish reason succeeds).
+    ///
+    /// Unary responses finishing for another reason (such as `SAFETY` or `RECITATION`) are
+    /// answered with `400 FAILED_PRECONDITION`. Streams are not affected.
+    #[serde(default)]
+    pub success_finish_reasons: Option<Vec<String>>,
+
+    /// TOML or JSON file declaring extra models: pinned masks, serving providers and aliases.
+    /// TOML: `basic.model_catalog`. Default: unset (the catalog is the providers' `model_list`s).
+    ///
+    /// Catalog models are appended to each listed provider's `model_list`; aliases are rewritten
+    /// to the model name before a request is forwarded.
+    #[serde(default)]
+    pub model_catalog: Option<PathBuf>,
+
+    /// Seed for thought-signature cache keys.
+    /// TOML: `basic.thoughtsig_hash_seed`. Default: a fixed built-in constant.
+    ///
+    /// Instances that should agree on cache keys must share this value.
+    #[serde(default = "default_thoughtsig_hash_seed")]
+    pub thoughtsig_hash_seed: u64,
+
+    /// Hash function for thought-signature cache keys: `ahash`, `fnv1a` or `xxhash64`.
+    /// TOML: `basic.thoughtsig_hash_algo`. Default: `ahash`.
+    ///
+    /// `ahash` keys can change with the build or CPU architecture; pick one of the others when
+    /// keys are persisted or shared between differently built hosts.
+    #[serde(default)]
+    pub thoughtsig_hash_algo: HashAlgo,
+
+    /// Per-model dummy thought signatures, keyed by model name.
+    /// TOML: `basic.thoughtsig_dummy_signatures`. Default: empty (every model gets
+    /// `skip_thought_signature_validator`).
+    ///
+    /// Used when a model part has no cached signature to replay.
+    #[serde(default)]
+    pub thoughtsig_dummy_signatures: HashMap<String, String>,
+
+    /// Models that reject every dummy signature.
+    /// TOML: `basic.thoughtsig_no_dummy_models`. Default: empty.
+    ///
+    /// Parts with no cached signature are sent to these models as the client did.
+    #[serde(default)]
+    pub thoughtsig_no_dummy_models: Vec<String>,
+
+    /// Models whose replayed history drops thought parts instead of carrying signatures.
+    /// TOML: `basic.thoughtsig_strip_thoughts`. Default: empty (thought parts are signed).
+    ///
+    /// Function-call parts are still signed for these models.
+    #[serde(default)]
+    pub thoughtsig_strip_thoughts: Vec<String>,
+
+    /// Content roles patched like `model` turns, for clients that send e.g. `assistant`.
+    /// TOML: `basic.thoughtsig_model_role_aliases`. Default: empty (only `model`).
+    #[serde(default)]
+    pub thoughtsig_model_role_aliases: Vec<String>,
+
+    /// Whether function-call parts with no cached signature get the dummy signature.
+    /// TOML: `basic.thoughtsig_dummy_function_calls`. Default: `true`.
+    ///
+    /// Upstreams tend to validate function-call signatures strictly; set `false` to send such
+    /// parts as the client did while thought parts are still dummy-filled.
+    #[serde(default = "default_thoughtsig_dummy_function_calls")]
+    pub thoughtsig_dummy_function_calls: bool,
+
+    /// Whether a thought part with no cached signature reuses the cached signature of a
+    /// function call in the same content before falling back to the dummy.
+    /// TOML: `basic.thoughtsig_borrow_sibling_signatures`. Default: `false`.
+    ///
+    /// Some upstreams accept the borrowed signature where they would reject the dummy.
+    #[serde(default)]
+    pub thoughtsig_borrow_sibling_signatures: bool,
+
+    /// What to do with thought signatures clients send themselves: `replace`, `trust` or
+    /// `trust_verified`.
+    /// TOML: `basic.thoughtsig_existing_signatures`. Default: `replace`.
+    ///
+    /// `trust_verified` keeps the client's signature but logs a warning when the cache holds a
+    /// different one for the same content.
+    #[serde(default)]
+    pub thoughtsig_existing_signatures: ExistingSignatures,
+
+    /// What to do with parts that yield no cache key, such as thought parts with empty or
+    /// whitespace-only text: `fill` or `keep`.
+    /// TOML: `basic.thoughtsig_unkeyed_parts`. Default: `fill`.
+    ///
+    /// `fill` gives them the dummy signature (Antigravity drops such thought parts instead);
+    /// `keep` sends them as the client did.
+    #[serde(default)]
+    pub thoughtsig_unkeyed_parts: UnkeyedParts,
+
+    /// Which signature to cache when one response signs the same content twice:
+    /// `last_wins` or `first_wins`.
+    /// TOML: `basic.thoughtsig_duplicate_signatures`. Default: `last_wins`.
+    ///
+    /// Either way the conflict is logged.
+    #[serde(default)]
+    pub thoughtsig_duplicate_signatures: DuplicatePolicy,
+
+    /// Longest upstream thought signature cached, in bytes; `0` disables the limit.
+    /// TOML: `basic.thoughtsig_max_signature_bytes`. Default: `1048576` (1 MiB).
+    ///
+    /// Longer signatures are logged and not cached, so a misbehaving upstream cannot fill
+    /// memory with them.
+    #[serde(default = "default_thoughtsig_max_signature_bytes")]
+    pub thoughtsig_max_signature_bytes: usize,
+
+    /// Absolute lifetime of cached thought signatures, in seconds; `0` disables it.
+    /// TOML: `basic.thoughtsig_ttl_secs`. Default: `3600`.
+    #[serde(default = "default_thoughtsig_ttl_secs")]
+    pub thoughtsig_ttl_secs: u64,
+
+    /// Idle lifetime of cached thought signatures, in seconds, restarted on every use.
+    /// TOML: `basic.thoughtsig_idle_secs`. Default: unset.
+    ///
+    /// Set this (optionally with `thoughtsig_ttl_secs = 0`) so signatures a long conversation
+    /// keeps replaying do not expire mid-session.
+    #[serde(default)]
+    pub thoughtsig_idle_secs: Option<u64>,
+
+    /// Mirror cached thought signatures into the `signature_cache` table and reload the
+    /// unexpired ones on startup, so a restart does not break ongoing conversations.
+    /// TOML: `basic.thoughtsig_persist`. Default: `false`.
+    #[serde(default)]
+    pub thoughtsig_persist: bool,
+
+    /// How long a thought-signature cache miss is remembered, in seconds.
+    /// TOML: `basic.thoughtsig_negative_cache_secs`. Default: unset (every miss asks the cache).
+    ///
+    /// Clients retrying the same history then dummy-fill known misses without another lookup.
+    /// A signature recorded for the content replaces its remembered miss at once.
+    #[serde(default)]
+    pub thoughtsig_negative_cache_secs: Option<u64>,
+
+    /// Requests with at least this many parts are keyed on the shared worker pool.
+    /// TOML: `basic.thoughtsig_parallel_fill_threshold`. Default: `256`.
+    ///
+    /// The pool has one worker per CPU and is shared by all requests; `0` and `1` treat
+    /// every request as large.
+    #[serde(default = "default_thoughtsig_parallel_fill_threshold")]
+    pub thoughtsig_parallel_fill_threshold: usize,
+
+    /// Redis shared by every instance behind one load balancer, e.g. `redis://127.0.0.1/`.
+    /// TOML: `basic.thoughtsig_redis_url`. Default: unset (each instance keeps its own cache).
+    ///
+    /// Gemini CLI requests are then filled from Redis and sniffed signatures are written to it,
+    /// expiring per `thoughtsig_ttl_secs`. Needs a build with the `redis` feature; if Redis
+    /// cannot be reached at startup the instance keeps its own cache.
+    #[serde(default)]
+    pub thoughtsig_redis_url: Option<String>,
+}
+
+/// `SameSite` policy for OAuth cookies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CookieSameSite {
+    #[default]
+    Lax,
+    Strict,
+    None,
+}
+
+/// Behavior of the bounded SSE buffer once it is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SseOverflowPolicy {
+    #[default]
+    Backpressure,
+    Error,
+}
+
+impl BasicConfig {
+    /// Thought-signature cache expiry from `thoughtsig_ttl_secs` / `thoughtsig_idle_secs`.
+    pub fn thoughtsig_expiry(&self) -> SignatureExpiry {
+        let secs = |secs: u64| (secs > 0).then(|| Duration::from_secs(secs));
+        SignatureExpiry {
+            ttl: secs(self.thoughtsig_ttl_secs),
+            idle: self.thoughtsig_idle_secs.and_then(secs),
+        }
+    }
 }
 
 impl Default for BasicConfig {
@@ -45,10 +332,45 @@ impl Default for BasicConfig {
             listen_addr: default_listen_ip(),
             listen_port: default_listen_port(),
             database_url: "sqlite://data.db".to_string(),
+            lease_log: false,
             loglevel: "info".to_string(),
             // No insecure default. `Config::from_toml()` enforces non-empty.
             pollux_key: "".to_string(),
             insecure_cookie: false,
+            cookie_domain: None,
+            cookie_secure: None,
+            cookie_same_site: CookieSameSite::default(),
+            sse_buffer_capacity: default_sse_buffer_capacity(),
+            sse_overflow: SseOverflowPolicy::default(),
+            sse_complete_event: false,
+            sse_retry_ms: None,
+            sse_first_event_timeout_secs: default_sse_timeout_secs(),
+            sse_idle_timeout_secs: default_sse_timeout_secs(),
+            tool_call_ids: false,
+            strip_empty_text_parts: false,
+            max_concurrent_streams: None,
+            coalesce_requests: false,
+            compress_responses: false,
+            success_finish_reasons: None,
+            model_catalog: None,
+            thoughtsig_hash_seed: default_thoughtsig_hash_seed(),
+            thoughtsig_hash_algo: HashAlgo::default(),
+            thoughtsig_dummy_signatures: HashMap::new(),
+            thoughtsig_no_dummy_models: Vec::new(),
+            thoughtsig_strip_thoughts: Vec::new(),
+            thoughtsig_model_role_aliases: Vec::new(),
+            thoughtsig_existing_signatures: ExistingSignatures::default(),
+            thoughtsig_unkeyed_parts: UnkeyedParts::default(),
+            thoughtsig_duplicate_signatures: DuplicatePolicy::default(),
+            thoughtsig_dummy_function_calls: default_thoughtsig_dummy_function_calls(),
+            thoughtsig_borrow_sibling_signatures: false,
+            thoughtsig_max_signature_bytes: default_thoughtsig_max_signature_bytes(),
+            thoughtsig_ttl_secs: default_thoughtsig_ttl_secs(),
+            thoughtsig_idle_secs: None,
+            thoughtsig_persist: false,
+            thoughtsig_negative_cache_secs: None,
+            thoughtsig_parallel_fill_threshold: default_thoughtsig_parallel_fill_threshold(),
+            thoughtsig_redis_url: None,
         }
     }
 }
@@ -77,3 +399,34 @@ fn default_listen_ip() -> IpAddr {
 fn default_listen_port() -> u16 {
     8188
 }
+
+/// Default SSE buffer capacity (events) per client stream.
+fn default_sse_buffer_capacity() -> usize {
+    64
+}
+
+fn default_sse_timeout_secs() -> u64 {
+    60
+}
+
+fn default_thoughtsig_dummy_function_calls() -> bool {
+    true
+}
+
+fn default_thoughtsig_max_signature_bytes() -> usize {
+    1024 * 1024
+}
+
+fn default_thoughtsig_parallel_fill_threshold() -> usize {
+    DEFAULT_PARALLEL_FILL_THRESHOLD
+}
+
+/// Default absolute lifetime (seconds) of cached thought signatures.
+fn default_thoughtsig_ttl_secs() -> u64 {
+    60 * 60
+}
+
+/// Default seed for thought-signature cache keys.
+fn default_thoughtsig_hash_seed() -> u64 {
+    pollux_thoughtsig_core::DEFAULT_HASH_SEED
+}
@@ -1,19 +1,23 @@
 mod basic;
 mod providers;
 
-pub use basic::BasicConfig;
+pub use basic::{BasicConfig, CookieSameSite, SseOverflowPolicy};
 pub use providers::{
-    AntigravityConfig, AntigravityResolvedConfig, CLAUDE_SYSTEM_PREAMBLE, CodexConfig,
-    CodexResolvedConfig, GeminiCliConfig, GeminiCliResolvedConfig, ProviderDefaults,
-    ProvidersConfig,
+    AntigravityConfig, AntigravityResolvedConfig, CLAUDE_PREAMBLE_MARKER, CLAUDE_SYSTEM_PREAMBLE,
+    CodexConfig, CodexResolvedConfig, GeminiCliConfig, GeminiCliResolvedConfig,
+    MAX_RETRY_MAX_TIMES, ProviderDefaults, ProvidersConfig, RetryBackoff, preamble_marker,
 };
 
+use crate::model_catalog::{ModelCatalogError, ModelCatalogFile};
 use figment::{
     Figment,
     providers::{Format, Serialized, Toml},
 };
 use serde::{Deserialize, Serialize};
-use std::{path::PathBuf, sync::LazyLock};
+use std::{
+    path::PathBuf,
+    sync::{Arc, LazyLock},
+};
 
 /// Application configuration managed by Figment.
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
@@ -25,6 +29,10 @@ pub struct Config {
     /// Provider and upstream settings (see `providers` table in config.toml).
     #[serde(default)]
     pub providers: ProvidersConfig,
+
+    /// The file at `basic.model_catalog`, once loaded by `with_model_catalog`.
+    #[serde(skip)]
+    pub model_catalog: Option<Arc<ModelCatalogFile>>,
 }
 
 const DEFAULT_CONFIG_FILE: &str = "config.toml";
@@ -46,9 +54,11 @@ impl Config {
     /// call `Config::from_toml()` instead (or validate explicitly) to avoid running with insecure
     /// defaults.
     pub fn from_optional_toml() -> Self {
-        Self::figment().extract().unwrap_or_else(|err| {
+        let cfg: Self = Self::figment().extract().unwrap_or_else(|err| {
             panic!("failed to extract configuration (defaults + optional config.toml): {err}")
-        })
+        });
+        cfg.with_model_catalog()
+            .unwrap_or_else(|err| panic!("{err}"))
     }
 
     /// Loads configuration from the TOML file (with defaults) and validates required fields.
@@ -65,7 +75,31 @@ impl Config {
         if cfg.basic.pollux_key.trim().is_empty() {
             panic!("basic.pollux_key must be set and non-empty");
         }
-        cfg
+        cfg.with_model_catalog()
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Load `basic.model_catalog`, if set, and append its models to the providers they name.
+    pub fn with_model_catalog(mut self) -> Result<Self, ModelCatalogError> {
+        let Some(path) = self.basic.model_catalog.as_deref() else {
+            return Ok(self);
+        };
+        let catalog = ModelCatalogFile::load(path)?;
+
+        let providers = &mut self.providers;
+        for (provider, model_list) in [
+            ("geminicli", &mut providers.geminicli.model_list),
+            ("codex", &mut providers.codex.model_list),
+            ("antigravity", &mut providers.antigravity.model_list),
+        ] {
+            for name in catalog.models_for(provider) {
+                if !model_list.iter().any(|model| model == name) {
+                    model_list.push(name.to_string());
+                }
+            }
+        }
+        self.model_catalog = Some(Arc::new(catalog));
+        Ok(self)
     }
 
     pub fn geminicli(&self) -> GeminiCliResolvedConfig {
@@ -1,7 +1,13 @@
+use reqwest::header::{HeaderMap, HeaderName};
 use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use std::time::Duration;
 use url::Url;
 
-use super::ProviderDefaults;
+use super::{
+    ProviderDefaults, RetryBackoff, clamp_retry_max_times, resolve_capture_headers,
+    resolve_retry_backoff, resolve_telemetry_headers,
+};
 
 /// Claude system preamble for Antigravity upstream strict-match validation.
 ///
@@ -17,6 +23,33 @@ use super::ProviderDefaults;
 /// (including missing spaces) may fail validation and trigger HTTP 429.
 pub const CLAUDE_SYSTEM_PREAMBLE: &str = env!("CLAUDE_SYSTEM_PREAMBLE");
 
+/// Lowercased marker used to detect an already-injected [`CLAUDE_SYSTEM_PREAMBLE`].
+///
+/// Derived from the preamble itself so a rebuilt preamble keeps detection in sync.
+pub static CLAUDE_PREAMBLE_MARKER: LazyLock<String> =
+    LazyLock::new(|| preamble_marker(CLAUDE_SYSTEM_PREAMBLE));
+
+/// Derive a detection marker from a preamble: its first `**heading**` if any,
+/// otherwise its first non-empty line. The result is trimmed and lowercased.
+pub fn preamble_marker(preamble: &str) -> String {
+    let heading = preamble
+        .split("**")
+        .skip(1)
+        .step_by(2)
+        .map(str::trim)
+        .find(|segment| !segment.is_empty());
+
+    heading
+        .or_else(|| {
+            preamble
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+        })
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
 /// Antigravity provider configuration managed by Figment.
 ///
 /// Notes:
@@ -50,6 +83,12 @@ pub struct AntigravityConfig {
     #[serde(default = "default_model_list")]
     pub model_list: Vec<String>,
 
+    /// Model served when a request names none (an empty model in the path, e.g. `models/:generateContent`); it still has to be in
+    /// `model_list`.
+    /// TOML: `providers.antigravity.default_model`. Default: unset (such requests are rejected).
+    #[serde(default)]
+    pub default_model: Option<String>,
+
     /// Allow HTTP/2 multiplexing for reqwest clients; disabled forces HTTP/1.
     /// TOML: `providers.antigravity.enable_multiplexing`.
     /// Falls back to `providers.defaults.enable_multiplexing`.
@@ -58,9 +97,70 @@ pub struct AntigravityConfig {
 
     /// Max retry attempts for antigravity upstream calls.
     /// TOML: `providers.antigravity.retry_max_times`.
-    /// Falls back to `providers.defaults.retry_max_times`.
+    /// Falls back to `providers.defaults.retry_max_times`. Clamped to `MAX_RETRY_MAX_TIMES`.
     #[serde(default)]
     pub retry_max_times: Option<usize>,
+
+    /// First delay between retry attempts, in milliseconds.
+    /// TOML: `providers.antigravity.retry_min_delay_ms`.
+    /// Falls back to `providers.defaults.retry_min_delay_ms`.
+    #[serde(default)]
+    pub retry_min_delay_ms: Option<u64>,
+
+    /// Upper bound on the delay between retry attempts, in milliseconds.
+    /// TOML: `providers.antigravity.retry_max_delay_ms`.
+    /// Falls back to `providers.defaults.retry_max_delay_ms`.
+    #[serde(default)]
+    pub retry_max_delay_ms: Option<u64>,
+
+    /// Randomize retry delays.
+    /// TOML: `providers.antigravity.retry_jitter`.
+    /// Falls back to `providers.defaults.retry_jitter`.
+    #[serde(default)]
+    pub retry_jitter: Option<bool>,
+
+    /// `x-goog-api-client` header sent upstream; some upstream behavior is gated on it.
+    /// TOML: `providers.antigravity.api_client_header`. Default: the official Antigravity client's
+    /// (`google-cloud-sdk vscode_cloudshelleditor/0.1`). Empty sends none.
+    #[serde(default)]
+    pub api_client_header: Option<String>,
+
+    /// `client-metadata` header sent upstream.
+    /// TOML: `providers.antigravity.client_metadata_header`. Default: the official Antigravity client's.
+    /// Empty sends none.
+    #[serde(default)]
+    pub client_metadata_header: Option<String>,
+
+    /// Most `parts` one `contents` turn may carry; larger requests are rejected before the
+    /// thought-signature patcher walks them.
+    /// TOML: `providers.antigravity.max_parts_per_content`. Default: `4096`.
+    #[serde(default = "default_max_parts_per_content")]
+    pub max_parts_per_content: usize,
+
+    /// Models that may be sent a request with empty `contents`, for empty-prompt generation.
+    /// TOML: `providers.antigravity.empty_contents_models`. Default: empty (such requests are
+    /// rejected with `400`).
+    #[serde(default)]
+    pub empty_contents_models: Vec<String>,
+
+    /// Text whose presence in an incoming `systemInstruction` means the Claude preamble is
+    /// already there, so it is not injected again. Matched case-insensitively.
+    /// TOML: `providers.antigravity.preamble_marker`.
+    /// Default: the first `**heading**` of the built-in preamble.
+    #[serde(default)]
+    pub preamble_marker: Option<String>,
+
+    /// Match the model in the request path against `model_list` ignoring ASCII case; the
+    /// configured spelling is what gets forwarded upstream.
+    /// TOML: `providers.antigravity.case_insensitive_models`. Default: `false`.
+    #[serde(default)]
+    pub case_insensitive_models: bool,
+
+    /// Serve a `model_list` entry the global model catalog has no mask for, using the mask of
+    /// the first `model_list` entry the catalog does know, instead of rejecting it.
+    /// TOML: `providers.antigravity.trust_model_list`. Default: `false`.
+    #[serde(default)]
+    pub trust_model_list: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -69,8 +169,19 @@ pub struct AntigravityResolvedConfig {
     pub proxy: Option<Url>,
     pub oauth_tps: usize,
     pub model_list: Vec<String>,
+    pub default_model: Option<String>,
     pub enable_multiplexing: bool,
     pub retry_max_times: usize,
+    pub expiry_skew: Duration,
+    pub retry_backoff: RetryBackoff,
+    pub retry_empty_responses: bool,
+    pub capture_response_headers: Vec<HeaderName>,
+    pub telemetry_headers: HeaderMap,
+    pub max_parts_per_content: usize,
+    pub empty_contents_models: Vec<String>,
+    pub preamble_marker: String,
+    pub case_insensitive_models: bool,
+    pub trust_model_list: bool,
     pub oauth_auth_url: Url,
     pub oauth_token_url: Url,
     pub oauth_redirect_url: Url,
@@ -86,10 +197,49 @@ impl AntigravityConfig {
             proxy: self.proxy.clone().or_else(|| defaults.proxy.clone()),
             oauth_tps: self.oauth_tps,
             model_list: self.model_list.clone(),
+            default_model: self
+                .default_model
+                .as_deref()
+                .map(str::trim)
+                .filter(|model| !model.is_empty())
+                .map(str::to_string),
             enable_multiplexing: self
                 .enable_multiplexing
                 .unwrap_or(defaults.enable_multiplexing),
-            retry_max_times: self.retry_max_times.unwrap_or(defaults.retry_max_times),
+            retry_max_times: clamp_retry_max_times(
+                "antigravity",
+                self.retry_max_times.unwrap_or(defaults.retry_max_times),
+            ),
+            expiry_skew: Duration::from_secs(defaults.expiry_skew_secs),
+            retry_backoff: resolve_retry_backoff(
+                self.retry_min_delay_ms,
+                self.retry_max_delay_ms,
+                self.retry_jitter,
+                defaults,
+            ),
+            retry_empty_responses: defaults.retry_empty_responses,
+            capture_response_headers: resolve_capture_headers(&defaults.capture_response_headers),
+            telemetry_headers: resolve_telemetry_headers(
+                "antigravity",
+                [
+                    (self.api_client_header.as_deref(), DEFAULT_API_CLIENT_HEADER),
+                    (
+                        self.client_metadata_header.as_deref(),
+                        DEFAULT_CLIENT_METADATA_HEADER,
+                    ),
+                ],
+            ),
+            max_parts_per_content: self.max_parts_per_content,
+            empty_contents_models: self.empty_contents_models.clone(),
+            preamble_marker: self
+                .preamble_marker
+                .as_deref()
+                .map(str::trim)
+                .filter(|marker| !marker.is_empty())
+                .map(str::to_lowercase)
+                .unwrap_or_else(|| CLAUDE_PREAMBLE_MARKER.clone()),
+            case_insensitive_models: self.case_insensitive_models,
+            trust_model_list: self.trust_model_list,
             oauth_auth_url: default_oauth_auth_url(),
             oauth_token_url: default_oauth_token_url(),
             oauth_redirect_url: default_oauth_redirect_url(),
@@ -107,8 +257,19 @@ impl Default for AntigravityConfig {
             proxy: None,
             oauth_tps: default_oauth_tps(),
             model_list: default_model_list(),
+            default_model: None,
             enable_multiplexing: None,
             retry_max_times: None,
+            retry_min_delay_ms: None,
+            retry_max_delay_ms: None,
+            retry_jitter: None,
+            api_client_header: None,
+            client_metadata_header: None,
+            max_parts_per_content: default_max_parts_per_content(),
+            empty_contents_models: Vec::new(),
+            preamble_marker: None,
+            case_insensitive_models: false,
+            trust_model_list: false,
         }
     }
 }
@@ -118,6 +279,17 @@ fn default_api_url() -> Url {
         .expect("default antigravity api_url must be a valid URL")
 }
 
+/// `x-goog-api-client` the official Antigravity client sends.
+const DEFAULT_API_CLIENT_HEADER: &str = "google-cloud-sdk vscode_cloudshelleditor/0.1";
+
+/// `client-metadata` the official Antigravity client sends.
+const DEFAULT_CLIENT_METADATA_HEADER: &str =
+    r#"{"ideType":"IDE_UNSPECIFIED","platform":"PLATFORM_UNSPECIFIED","pluginType":"GEMINI"}"#;
+
+fn default_max_parts_per_content() -> usize {
+    4096
+}
+
 fn default_oauth_tps() -> usize {
     5
 }
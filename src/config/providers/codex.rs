@@ -1,7 +1,9 @@
+use reqwest::header::HeaderName;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use url::Url;
 
-use super::ProviderDefaults;
+use super::{ProviderDefaults, clamp_retry_max_times, resolve_capture_headers};
 
 /// Codex provider configuration managed by Figment.
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -24,6 +26,12 @@ pub struct CodexConfig {
     #[serde(default = "default_model_list")]
     pub model_list: Vec<String>,
 
+    /// Model served when a request names none (a missing or empty `model`); it still has to be in
+    /// `model_list`.
+    /// TOML: `providers.codex.default_model`. Default: unset (such requests are rejected).
+    #[serde(default)]
+    pub default_model: Option<String>,
+
     /// Allow HTTP/2 multiplexing for reqwest clients; disabled forces HTTP/1.
     /// TOML: `providers.codex.enable_multiplexing`.
     /// Falls back to `providers.defaults.enable_multiplexing`.
@@ -32,9 +40,15 @@ pub struct CodexConfig {
 
     /// Max retry attempts for Codex upstream calls.
     /// TOML: `providers.codex.retry_max_times`.
-    /// Falls back to `providers.defaults.retry_max_times`.
+    /// Falls back to `providers.defaults.retry_max_times`. Clamped to `MAX_RETRY_MAX_TIMES`.
     #[serde(default)]
     pub retry_max_times: Option<usize>,
+
+    /// Spool `/codex/v1/responses` bodies larger than this many bytes (or without a
+    /// `Content-Length`) to a temp file and parse them from disk instead of buffering in RAM.
+    /// TOML: `providers.codex.body_spool_threshold`. Default: unset (always buffer in memory).
+    #[serde(default)]
+    pub body_spool_threshold: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -42,8 +56,12 @@ pub struct CodexResolvedConfig {
     pub proxy: Option<Url>,
     pub oauth_tps: usize,
     pub model_list: Vec<String>,
+    pub default_model: Option<String>,
     pub enable_multiplexing: bool,
     pub retry_max_times: usize,
+    pub expiry_skew: Duration,
+    pub body_spool_threshold: Option<usize>,
+    pub capture_response_headers: Vec<HeaderName>,
 }
 
 impl CodexConfig {
@@ -52,10 +70,22 @@ impl CodexConfig {
             proxy: self.proxy.clone().or_else(|| defaults.proxy.clone()),
             oauth_tps: self.oauth_tps,
             model_list: self.model_list.clone(),
+            default_model: self
+                .default_model
+                .as_deref()
+                .map(str::trim)
+                .filter(|model| !model.is_empty())
+                .map(str::to_string),
             enable_multiplexing: self
                 .enable_multiplexing
                 .unwrap_or(defaults.enable_multiplexing),
-            retry_max_times: self.retry_max_times.unwrap_or(defaults.retry_max_times),
+            retry_max_times: clamp_retry_max_times(
+                "codex",
+                self.retry_max_times.unwrap_or(defaults.retry_max_times),
+            ),
+            expiry_skew: Duration::from_secs(defaults.expiry_skew_secs),
+            body_spool_threshold: self.body_spool_threshold,
+            capture_response_headers: resolve_capture_headers(&defaults.capture_response_headers),
         }
     }
 }
@@ -66,8 +96,10 @@ impl Default for CodexConfig {
             proxy: None,
             oauth_tps: default_oauth_tps(),
             model_list: default_model_list(),
+            default_model: None,
             enable_multiplexing: None,
             retry_max_times: None,
+            body_spool_threshold: None,
         }
     }
 }
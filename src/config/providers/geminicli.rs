@@ -1,7 +1,13 @@
+use reqwest::header::{HeaderMap, HeaderName};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
 use url::Url;
 
-use super::ProviderDefaults;
+use super::{
+    ProviderDefaults, RetryBackoff, clamp_retry_max_times, resolve_capture_headers,
+    resolve_retry_backoff, resolve_telemetry_headers,
+};
 
 /// Gemini CLI provider configuration managed by Figment.
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -22,6 +28,12 @@ pub struct GeminiCliConfig {
     #[serde(default = "default_model_list")]
     pub model_list: Vec<String>,
 
+    /// Model served when a request names none (an empty model in the path, e.g. `models/:generateContent`); it still has to be in
+    /// `model_list`.
+    /// TOML: `providers.geminicli.default_model`. Default: unset (such requests are rejected).
+    #[serde(default)]
+    pub default_model: Option<String>,
+
     /// Allow HTTP/2 multiplexing for reqwest clients; disabled forces HTTP/1.
     /// TOML: `providers.geminicli.enable_multiplexing`.
     /// Falls back to `providers.defaults.enable_multiplexing`.
@@ -30,9 +42,108 @@ pub struct GeminiCliConfig {
 
     /// Max retry attempts for Gemini CLI upstream calls.
     /// TOML: `providers.geminicli.retry_max_times`.
-    /// Falls back to `providers.defaults.retry_max_times`.
+    /// Falls back to `providers.defaults.retry_max_times`. Clamped to `MAX_RETRY_MAX_TIMES`.
     #[serde(default)]
     pub retry_max_times: Option<usize>,
+
+    /// First delay between retry attempts, in milliseconds.
+    /// TOML: `providers.geminicli.retry_min_delay_ms`.
+    /// Falls back to `providers.defaults.retry_min_delay_ms`.
+    #[serde(default)]
+    pub retry_min_delay_ms: Option<u64>,
+
+    /// Upper bound on the delay between retry attempts, in milliseconds.
+    /// TOML: `providers.geminicli.retry_max_delay_ms`.
+    /// Falls back to `providers.defaults.retry_max_delay_ms`.
+    #[serde(default)]
+    pub retry_max_delay_ms: Option<u64>,
+
+    /// Randomize retry delays.
+    /// TOML: `providers.geminicli.retry_jitter`.
+    /// Falls back to `providers.defaults.retry_jitter`.
+    #[serde(default)]
+    pub retry_jitter: Option<bool>,
+
+    /// `x-goog-api-client` header sent upstream; some upstream behavior is gated on it.
+    /// TOML: `providers.geminicli.api_client_header`. Default: the official Gemini CLI client's
+    /// (`gl-node/22.17.0`). Empty sends none.
+    #[serde(default)]
+    pub api_client_header: Option<String>,
+
+    /// `client-metadata` header sent upstream.
+    /// TOML: `providers.geminicli.client_metadata_header`. Default: the official Gemini CLI client's.
+    /// Empty sends none.
+    #[serde(default)]
+    pub client_metadata_header: Option<String>,
+
+    /// Most `parts` one `contents` turn may carry; larger requests are rejected before the
+    /// thought-signature patcher walks them.
+    /// TOML: `providers.geminicli.max_parts_per_content`. Default: `4096`.
+    #[serde(default = "default_max_parts_per_content")]
+    pub max_parts_per_content: usize,
+
+    /// Models that may be sent a request with empty `contents`, for empty-prompt generation.
+    /// TOML: `providers.geminicli.empty_contents_models`. Default: empty (such requests are
+    /// rejected with `400`).
+    #[serde(default)]
+    pub empty_contents_models: Vec<String>,
+
+    /// Project id to use when `loadCodeAssist` returns no `cloudaicompanionProject`.
+    /// TOML: `providers.geminicli.default_project_id`. Default: unset (onboard a new project).
+    ///
+    /// Some Workspace accounts cannot be onboarded automatically and need a project set manually.
+    #[serde(default)]
+    pub default_project_id: Option<String>,
+
+    /// Tier id requested in `onboardUser` when a new companion project is provisioned
+    /// (e.g. `free-tier`, `standard-tier`).
+    /// TOML: `providers.geminicli.onboard_tier`.
+    /// Default: unset (the tier `loadCodeAssist` reports as current or default).
+    #[serde(default)]
+    pub onboard_tier: Option<String>,
+
+    /// Models each Code Assist quota tier may use, keyed by tier id (e.g. `free-tier`).
+    /// TOML: `providers.geminicli.tier_models`. Default: empty (no tier gating).
+    ///
+    /// Credentials whose tier is listed are only queued for the listed models; tiers not
+    /// listed here, and credentials with an unknown tier, may use every model.
+    #[serde(default)]
+    pub tier_models: BTreeMap<String, Vec<String>>,
+
+    /// When every credential for a model has an expired access token, hold the lease until one
+    /// refresh finishes instead of answering "no credential".
+    /// TOML: `providers.geminicli.refresh_on_lease`. Default: `true`.
+    #[serde(default = "default_refresh_on_lease")]
+    pub refresh_on_lease: bool,
+
+    /// Seconds a banned credential stays out of rotation before it is re-probed with a token
+    /// refresh and `loadCodeAssist`; it is restored if the probe succeeds.
+    /// TOML: `providers.geminicli.ban_probe_after_secs`. Default: unset (bans are permanent).
+    #[serde(default)]
+    pub ban_probe_after_secs: Option<u64>,
+
+    /// Most credentials queued for leasing at once. The rest are held in reserve and rotate in,
+    /// oldest first, when an active one is rate limited, banned or removed.
+    /// TOML: `providers.geminicli.max_active_credentials`. Default: unset (all are active).
+    #[serde(default)]
+    pub max_active_credentials: Option<usize>,
+
+    /// Refuse to store an onboarded credential whose project already has an active one, instead
+    /// of overwriting it, so a re-auth cannot clobber a working credential by accident.
+    /// TOML: `providers.geminicli.reject_duplicate_projects`. Default: `false`.
+    #[serde(default)]
+    pub reject_duplicate_projects: bool,
+
+    /// Send an `x-pollux-fillstats` header summarizing how the request's thought signatures
+    /// were filled, so clients can tell when they are getting dummy signatures.
+    /// TOML: `providers.geminicli.fill_stats_header`. Default: `false`.
+    #[serde(default)]
+    pub fill_stats_header: bool,
+
+    /// OAuth token endpoint used to refresh access tokens.
+    /// TOML: `providers.geminicli.oauth_token_url`. Default: `https://oauth2.googleapis.com/token`.
+    #[serde(default = "default_oauth_token_url")]
+    pub oauth_token_url: Url,
 }
 
 #[derive(Debug, Clone)]
@@ -40,8 +151,27 @@ pub struct GeminiCliResolvedConfig {
     pub proxy: Option<Url>,
     pub oauth_tps: usize,
     pub model_list: Vec<String>,
+    pub default_model: Option<String>,
     pub enable_multiplexing: bool,
     pub retry_max_times: usize,
+    pub expiry_skew: Duration,
+    pub retry_backoff: RetryBackoff,
+    pub retry_empty_responses: bool,
+    pub capture_response_headers: Vec<HeaderName>,
+    pub telemetry_headers: HeaderMap,
+    pub max_parts_per_content: usize,
+    pub empty_contents_models: Vec<String>,
+    pub default_project_id: Option<String>,
+    pub onboard_tier: Option<String>,
+    pub tier_models: BTreeMap<String, Vec<String>>,
+    pub refresh_on_lease: bool,
+    pub ban_probe_after: Option<Duration>,
+    pub max_active_credentials: Option<usize>,
+    pub reject_duplicate_projects: bool,
+    pub fill_stats_header: bool,
+    pub oauth_token_url: Url,
+    /// Google token revocation endpoint (fixed; not configurable via `config.toml`).
+    pub oauth_revoke_url: Url,
 }
 
 impl GeminiCliConfig {
@@ -50,10 +180,63 @@ impl GeminiCliConfig {
             proxy: self.proxy.clone().or_else(|| defaults.proxy.clone()),
             oauth_tps: self.oauth_tps,
             model_list: self.model_list.clone(),
+            default_model: self
+                .default_model
+                .as_deref()
+                .map(str::trim)
+                .filter(|model| !model.is_empty())
+                .map(str::to_string),
             enable_multiplexing: self
                 .enable_multiplexing
                 .unwrap_or(defaults.enable_multiplexing),
-            retry_max_times: self.retry_max_times.unwrap_or(defaults.retry_max_times),
+            retry_max_times: clamp_retry_max_times(
+                "geminicli",
+                self.retry_max_times.unwrap_or(defaults.retry_max_times),
+            ),
+            expiry_skew: Duration::from_secs(defaults.expiry_skew_secs),
+            retry_backoff: resolve_retry_backoff(
+                self.retry_min_delay_ms,
+                self.retry_max_delay_ms,
+                self.retry_jitter,
+                defaults,
+            ),
+            retry_empty_responses: defaults.retry_empty_responses,
+            capture_response_headers: resolve_capture_headers(&defaults.capture_response_headers),
+            telemetry_headers: resolve_telemetry_headers(
+                "geminicli",
+                [
+                    (self.api_client_header.as_deref(), DEFAULT_API_CLIENT_HEADER),
+                    (
+                        self.client_metadata_header.as_deref(),
+                        DEFAULT_CLIENT_METADATA_HEADER,
+                    ),
+                ],
+            ),
+            max_parts_per_content: self.max_parts_per_content,
+            empty_contents_models: self.empty_contents_models.clone(),
+            default_project_id: self
+                .default_project_id
+                .as_deref()
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string),
+            onboard_tier: self
+                .onboard_tier
+                .as_deref()
+                .map(str::trim)
+                .filter(|tier| !tier.is_empty())
+                .map(str::to_string),
+            tier_models: self.tier_models.clone(),
+            refresh_on_lease: self.refresh_on_lease,
+            ban_probe_after: self
+                .ban_probe_after_secs
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs),
+            max_active_credentials: self.max_active_credentials.filter(|cap| *cap > 0),
+            reject_duplicate_projects: self.reject_duplicate_projects,
+            fill_stats_header: self.fill_stats_header,
+            oauth_token_url: self.oauth_token_url.clone(),
+            oauth_revoke_url: default_oauth_revoke_url(),
         }
     }
 }
@@ -64,12 +247,40 @@ impl Default for GeminiCliConfig {
             proxy: None,
             oauth_tps: default_oauth_tps(),
             model_list: default_model_list(),
+            default_model: None,
             enable_multiplexing: None,
             retry_max_times: None,
+            retry_min_delay_ms: None,
+            retry_max_delay_ms: None,
+            retry_jitter: None,
+            api_client_header: None,
+            client_metadata_header: None,
+            max_parts_per_content: default_max_parts_per_content(),
+            empty_contents_models: Vec::new(),
+            default_project_id: None,
+            onboard_tier: None,
+            tier_models: BTreeMap::new(),
+            refresh_on_lease: default_refresh_on_lease(),
+            ban_probe_after_secs: None,
+            max_active_credentials: None,
+            reject_duplicate_projects: false,
+            fill_stats_header: false,
+            oauth_token_url: default_oauth_token_url(),
         }
     }
 }
 
+/// `x-goog-api-client` the official Gemini CLI client sends.
+const DEFAULT_API_CLIENT_HEADER: &str = "gl-node/22.17.0";
+
+/// `client-metadata` the official Gemini CLI client sends.
+const DEFAULT_CLIENT_METADATA_HEADER: &str =
+    "ideType=IDE_UNSPECIFIED,platform=PLATFORM_UNSPECIFIED,pluginType=GEMINI";
+
+fn default_max_parts_per_content() -> usize {
+    4096
+}
+
 fn default_oauth_tps() -> usize {
     5
 }
@@ -77,3 +288,17 @@ fn default_oauth_tps() -> usize {
 fn default_model_list() -> Vec<String> {
     vec!["gemini-2.5-pro".to_string()]
 }
+
+fn default_refresh_on_lease() -> bool {
+    true
+}
+
+fn default_oauth_token_url() -> Url {
+    Url::parse("https://oauth2.googleapis.com/token")
+        .expect("default oauth_token_url must be a valid URL")
+}
+
+fn default_oauth_revoke_url() -> Url {
+    Url::parse("https://oauth2.googleapis.com/revoke")
+        .expect("default oauth_revoke_url must be a valid URL")
+}
@@ -2,13 +2,100 @@ mod antigravity;
 mod codex;
 mod geminicli;
 
-pub use antigravity::{AntigravityConfig, AntigravityResolvedConfig, CLAUDE_SYSTEM_PREAMBLE};
+pub use antigravity::{
+    AntigravityConfig, AntigravityResolvedConfig, CLAUDE_PREAMBLE_MARKER, CLAUDE_SYSTEM_PREAMBLE,
+    preamble_marker,
+};
 pub use codex::{CodexConfig, CodexResolvedConfig};
 pub use geminicli::{GeminiCliConfig, GeminiCliResolvedConfig};
 
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::warn;
 use url::Url;
 
+/// Upper bound for any provider's `retry_max_times`; larger values would multiply upstream load
+/// on every failing request, so they are clamped when the config is resolved.
+pub const MAX_RETRY_MAX_TIMES: usize = 10;
+
+/// Clamp a provider's effective retry count to `MAX_RETRY_MAX_TIMES`, warning when it was lowered.
+fn clamp_retry_max_times(provider: &str, configured: usize) -> usize {
+    if configured <= MAX_RETRY_MAX_TIMES {
+        return configured;
+    }
+    warn!(
+        provider,
+        configured,
+        max = MAX_RETRY_MAX_TIMES,
+        "retry_max_times is above the supported maximum; clamping"
+    );
+    MAX_RETRY_MAX_TIMES
+}
+
+/// Parse `providers.defaults.capture_response_headers`, warning about and skipping invalid names.
+fn resolve_capture_headers(names: &[String]) -> Vec<HeaderName> {
+    names
+        .iter()
+        .filter_map(|name| match HeaderName::try_from(name.trim()) {
+            Ok(header) => Some(header),
+            Err(_) => {
+                warn!(name = %name, "capture_response_headers entry is not a header name; ignoring");
+                None
+            }
+        })
+        .collect()
+}
+
+/// `x-goog-api-client` and `client-metadata` headers for a provider's upstream requests, each
+/// the configured value or the official client's when unset. An empty value sends none; an
+/// invalid one is warned about and dropped.
+fn resolve_telemetry_headers(provider: &str, configured: [(Option<&str>, &str); 2]) -> HeaderMap {
+    let names = [
+        HeaderName::from_static("x-goog-api-client"),
+        HeaderName::from_static("client-metadata"),
+    ];
+    let mut headers = HeaderMap::new();
+    for (name, (value, default)) in names.into_iter().zip(configured) {
+        let value = value.map_or(default, str::trim);
+        if value.is_empty() {
+            continue;
+        }
+        match HeaderValue::from_str(value) {
+            Ok(value) => {
+                headers.insert(name, value);
+            }
+            Err(_) => warn!(provider, header = %name, "invalid header value; not sending it"),
+        }
+    }
+    headers
+}
+
+/// Resolved backoff between upstream retry attempts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryBackoff {
+    pub min_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: bool,
+}
+
+/// Provider-level backoff overrides, each falling back to `providers.defaults`.
+fn resolve_retry_backoff(
+    min_delay_ms: Option<u64>,
+    max_delay_ms: Option<u64>,
+    jitter: Option<bool>,
+    defaults: &ProviderDefaults,
+) -> RetryBackoff {
+    let min_delay = Duration::from_millis(min_delay_ms.unwrap_or(defaults.retry_min_delay_ms));
+    let max_delay = Duration::from_millis(max_delay_ms.unwrap_or(defaults.retry_max_delay_ms));
+    RetryBackoff {
+        min_delay,
+        // An inverted range would make every delay the max; treat the min as the floor instead.
+        max_delay: max_delay.max(min_delay),
+        jitter: jitter.unwrap_or(defaults.retry_jitter),
+    }
+}
+
 /// Global provider defaults (used when provider-level config is unset).
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ProviderDefaults {
@@ -23,9 +110,44 @@ pub struct ProviderDefaults {
     pub enable_multiplexing: bool,
 
     /// Max retry attempts for upstream calls.
-    /// TOML: `providers.defaults.retry_max_times`. Default: `3`.
+    /// TOML: `providers.defaults.retry_max_times`. Default: `3`. Max: `MAX_RETRY_MAX_TIMES`.
     #[serde(default = "default_retry_max_times")]
     pub retry_max_times: usize,
+
+    /// First delay between upstream retry attempts, in milliseconds; doubles on each retry.
+    /// Applies to Gemini CLI and Antigravity (Codex retries immediately).
+    /// TOML: `providers.defaults.retry_min_delay_ms`. Default: `100`.
+    #[serde(default = "default_retry_min_delay_ms")]
+    pub retry_min_delay_ms: u64,
+
+    /// Upper bound on the delay between upstream retry attempts, in milliseconds.
+    /// TOML: `providers.defaults.retry_max_delay_ms`. Default: `300`.
+    #[serde(default = "default_retry_max_delay_ms")]
+    pub retry_max_delay_ms: u64,
+
+    /// Randomize retry delays so concurrent requests do not retry in lockstep.
+    /// TOML: `providers.defaults.retry_jitter`. Default: `true`.
+    #[serde(default = "default_retry_jitter")]
+    pub retry_jitter: bool,
+
+    /// Treat a unary 200 with no candidate content or finish reason as a retryable failure
+    /// (Gemini CLI and Antigravity); once retries run out the client gets a 502.
+    /// TOML: `providers.defaults.retry_empty_responses`. Default: `true`.
+    #[serde(default = "default_retry_empty_responses")]
+    pub retry_empty_responses: bool,
+
+    /// Extra seconds by which an access token is treated as expired ahead of its `expiry`, on
+    /// top of the built-in 5-minute buffer, for hosts whose clock runs behind upstream's.
+    /// Applies when leasing credentials and when deciding whether to refresh.
+    /// TOML: `providers.defaults.expiry_skew_secs`. Default: `0`.
+    #[serde(default)]
+    pub expiry_skew_secs: u64,
+
+    /// Upstream response headers (quota info, request ids) to log with each upstream error,
+    /// matched ignoring case. Others are never logged.
+    /// TOML: `providers.defaults.capture_response_headers`. Default: empty.
+    #[serde(default)]
+    pub capture_response_headers: Vec<String>,
 }
 
 impl Default for ProviderDefaults {
@@ -34,6 +156,12 @@ impl Default for ProviderDefaults {
             proxy: None,
             enable_multiplexing: default_enable_multiplexing(),
             retry_max_times: default_retry_max_times(),
+            retry_min_delay_ms: default_retry_min_delay_ms(),
+            retry_max_delay_ms: default_retry_max_delay_ms(),
+            retry_jitter: default_retry_jitter(),
+            retry_empty_responses: default_retry_empty_responses(),
+            expiry_skew_secs: 0,
+            capture_response_headers: Vec::new(),
         }
     }
 }
@@ -65,3 +193,109 @@ fn default_enable_multiplexing() -> bool {
 fn default_retry_max_times() -> usize {
     3
 }
+
+fn default_retry_min_delay_ms() -> u64 {
+    100
+}
+
+fn default_retry_max_delay_ms() -> u64 {
+    300
+}
+
+fn default_retry_jitter() -> bool {
+    true
+}
+
+fn default_retry_empty_responses() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn over_large_retry_counts_are_clamped() {
+        let defaults = ProviderDefaults {
+            retry_max_times: 1000,
+            ..ProviderDefaults::default()
+        };
+
+        let codex = CodexConfig {
+            retry_max_times: Some(1000),
+            ..CodexConfig::default()
+        };
+        assert_eq!(
+            codex.resolve(&defaults).retry_max_times,
+            MAX_RETRY_MAX_TIMES
+        );
+
+        // The fallback from `providers.defaults` is clamped too; in-range values pass through.
+        let geminicli = GeminiCliConfig::default();
+        assert_eq!(
+            geminicli.resolve(&defaults).retry_max_times,
+            MAX_RETRY_MAX_TIMES
+        );
+        let antigravity = AntigravityConfig {
+            retry_max_times: Some(2),
+            ..AntigravityConfig::default()
+        };
+        assert_eq!(antigravity.resolve(&defaults).retry_max_times, 2);
+    }
+
+    #[test]
+    fn retry_backoff_falls_back_to_defaults_and_keeps_max_above_min() {
+        let defaults = ProviderDefaults::default();
+
+        let geminicli = GeminiCliConfig {
+            retry_max_delay_ms: Some(2_000),
+            retry_jitter: Some(false),
+            ..GeminiCliConfig::default()
+        };
+        assert_eq!(
+            geminicli.resolve(&defaults).retry_backoff,
+            RetryBackoff {
+                min_delay: Duration::from_millis(100),
+                max_delay: Duration::from_millis(2_000),
+                jitter: false,
+            }
+        );
+
+        let antigravity = AntigravityConfig {
+            retry_min_delay_ms: Some(500),
+            ..AntigravityConfig::default()
+        };
+        let backoff = antigravity.resolve(&defaults).retry_backoff;
+        assert_eq!(backoff.min_delay, Duration::from_millis(500));
+        assert_eq!(backoff.max_delay, Duration::from_millis(500));
+        assert!(backoff.jitter);
+    }
+
+    #[test]
+    fn telemetry_headers_default_and_can_be_overridden_or_dropped() {
+        let defaults = ProviderDefaults::default();
+
+        let headers = GeminiCliConfig::default()
+            .resolve(&defaults)
+            .telemetry_headers;
+        assert_eq!(headers["x-goog-api-client"], "gl-node/22.17.0");
+        assert!(headers.contains_key("client-metadata"));
+
+        let antigravity = AntigravityConfig {
+            api_client_header: Some("custom-client/1.0".to_string()),
+            client_metadata_header: Some(String::new()),
+            ..AntigravityConfig::default()
+        };
+        let headers = antigravity.resolve(&defaults).telemetry_headers;
+        assert_eq!(headers["x-goog-api-client"], "custom-client/1.0");
+        assert!(!headers.contains_key("client-metadata"));
+
+        // A value that cannot be a header is dropped rather than failing startup.
+        let geminicli = GeminiCliConfig {
+            api_client_header: Some("bad\nvalue".to_string()),
+            ..GeminiCliConfig::default()
+        };
+        let headers = geminicli.resolve(&defaults).telemetry_headers;
+        assert!(!headers.contains_key("x-goog-api-client"));
+    }
+}
@@ -1,6 +1,11 @@
-use crate::db::models::{DbAntigravityResource, DbCodexResource, DbGeminiCliResource};
-use crate::db::patch::{ProviderCreate, ProviderPatch};
-use crate::db::schema::SQLITE_INIT;
+use crate::db::lease_log::{LeaseLogCreate, LeaseLogFilter};
+use crate::db::models::{
+    DbAntigravityResource, DbCodexResource, DbGeminiCliResource, DbLeaseLogEntry,
+    DbSignatureCacheEntry,
+};
+use crate::db::patch::{GeminiCliCreate, ProviderCreate, ProviderPatch};
+use crate::db::schema::{SQLITE_ADDED_COLUMNS, SQLITE_INIT};
+use crate::db::signature_cache::SignatureCacheWrite;
 use crate::db::traits::DbPatchable;
 use crate::error::PolluxError;
 use chrono::Utc;
@@ -8,13 +13,16 @@ use ractor::{Actor, ActorProcessingErr, ActorRef, RpcReplyPort};
 use sqlx::SqlitePool;
 use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
 use std::{str::FromStr, time::Duration};
-use tracing::info;
+use tracing::{info, warn};
 
 #[derive(Debug)]
 pub enum DbActorMessage {
     /// Create (or upsert) a provider record and return its id.
     Create(ProviderCreate, RpcReplyPort<Result<i64, PolluxError>>),
 
+    /// Create a Gemini CLI record unless its project already has an active one.
+    CreateNewGeminiCli(GeminiCliCreate, RpcReplyPort<Result<i64, PolluxError>>),
+
     /// Patch a provider record by id.
     Patch(ProviderPatch, RpcReplyPort<Result<(), PolluxError>>),
 
@@ -29,6 +37,32 @@ pub enum DbActorMessage {
 
     /// Get Codex key by id.
     GetCodexById(i64, RpcReplyPort<Result<DbCodexResource, PolluxError>>),
+
+    /// Append a lease-log row; failures are only logged.
+    RecordLease(LeaseLogCreate),
+
+    /// List lease-log rows matching a filter, newest first.
+    ListLeaseLog(
+        LeaseLogFilter,
+        RpcReplyPort<Result<Vec<DbLeaseLogEntry>, PolluxError>>,
+    ),
+
+    /// Upsert a provider's thought signatures in one transaction; failures are only logged.
+    PutSignatures(String, Vec<SignatureCacheWrite>),
+
+    /// Load up to `limit` of a provider's unexpired thought signatures, newest first.
+    LoadSignatures(
+        String,
+        u64,
+        RpcReplyPort<Result<Vec<DbSignatureCacheEntry>, PolluxError>>,
+    ),
+
+    /// Delete a provider's expired thought signatures; failures are only logged.
+    PruneSignatures(String),
+
+    /// Delete one of a provider's thought signatures, or all of them with `None`; failures
+    /// are only logged.
+    DeleteSignatures(String, Option<i64>),
 }
 
 #[derive(Clone)]
@@ -42,6 +76,14 @@ impl DbActorHandle {
             .map_err(|e| PolluxError::RactorError(format!("DbActor Create RPC failed: {e}")))?
     }
 
+    /// Like [`Self::create`] for Gemini CLI, but fails with [`PolluxError::Conflict`] instead of
+    /// overwriting when the project already has an active credential.
+    pub async fn create_new_geminicli(&self, create: GeminiCliCreate) -> Result<i64, PolluxError> {
+        ractor::call!(self.actor, DbActorMessage::CreateNewGeminiCli, create).map_err(|e| {
+            PolluxError::RactorError(format!("DbActor CreateNewGeminiCli RPC failed: {e}"))
+        })?
+    }
+
     pub async fn patch(&self, patch: ProviderPatch) -> Result<(), PolluxError> {
         ractor::call!(self.actor, DbActorMessage::Patch, patch)
             .map_err(|e| PolluxError::RactorError(format!("DbActor Patch RPC failed: {e}")))?
@@ -70,6 +112,66 @@ impl DbActorHandle {
             PolluxError::RactorError(format!("DbActor GetCodexById RPC failed: {e}"))
         })?
     }
+
+    /// Queue a lease-log row without waiting for the write.
+    pub fn record_lease(&self, entry: LeaseLogCreate) -> Result<(), PolluxError> {
+        ractor::cast!(self.actor, DbActorMessage::RecordLease(entry))
+            .map_err(|e| PolluxError::RactorError(format!("DbActor RecordLease cast failed: {e}")))
+    }
+
+    pub async fn list_lease_log(
+        &self,
+        filter: LeaseLogFilter,
+    ) -> Result<Vec<DbLeaseLogEntry>, PolluxError> {
+        ractor::call!(self.actor, DbActorMessage::ListLeaseLog, filter).map_err(|e| {
+            PolluxError::RactorError(format!("DbActor ListLeaseLog RPC failed: {e}"))
+        })?
+    }
+
+    /// Queue thought signatures for `provider` without waiting for the write.
+    pub fn put_signatures(
+        &self,
+        provider: &str,
+        writes: Vec<SignatureCacheWrite>,
+    ) -> Result<(), PolluxError> {
+        ractor::cast!(
+            self.actor,
+            DbActorMessage::PutSignatures(provider.to_string(), writes)
+        )
+        .map_err(|e| PolluxError::RactorError(format!("DbActor PutSignatures cast failed: {e}")))
+    }
+
+    pub async fn load_signatures(
+        &self,
+        provider: &str,
+        limit: u64,
+    ) -> Result<Vec<DbSignatureCacheEntry>, PolluxError> {
+        ractor::call!(
+            self.actor,
+            DbActorMessage::LoadSignatures,
+            provider.to_string(),
+            limit
+        )
+        .map_err(|e| PolluxError::RactorError(format!("DbActor LoadSignatures RPC failed: {e}")))?
+    }
+
+    /// Queue deletion of `provider`'s expired thought signatures.
+    pub fn prune_signatures(&self, provider: &str) -> Result<(), PolluxError> {
+        ractor::cast!(
+            self.actor,
+            DbActorMessage::PruneSignatures(provider.to_string())
+        )
+        .map_err(|e| PolluxError::RactorError(format!("DbActor PruneSignatures cast failed: {e}")))
+    }
+
+    /// Queue deletion of `provider`'s signature under `key`, or of all of them with `None`.
+    pub fn delete_signatures(&self, provider: &str, key: Option<i64>) -> Result<(), PolluxError> {
+        ractor::cast!(
+            self.actor,
+            DbActorMessage::DeleteSignatures(provider.to_string(), key)
+        )
+        .map_err(|e| PolluxError::RactorError(format!("DbActor DeleteSignatures cast failed: {e}")))
+    }
 }
 
 struct DbActorState {
@@ -120,6 +222,10 @@ impl Actor for DbActor {
                 let res = self.create_provider(&state.pool, create).await;
                 let _ = reply.send(res);
             }
+            DbActorMessage::CreateNewGeminiCli(create, reply) => {
+                let res = self.create_new_geminicli(&state.pool, create).await;
+                let _ = reply.send(res);
+            }
             DbActorMessage::Patch(patch, reply) => {
                 let res = patch.apply_patch(&state.pool).await;
                 let _ = reply.send(res);
@@ -140,12 +246,63 @@ impl Actor for DbActor {
                 let res = self.get_codex_by_id(&state.pool, id).await;
                 let _ = reply.send(res);
             }
+            DbActorMessage::RecordLease(entry) => {
+                if let Err(e) = self.record_lease(&state.pool, entry).await {
+                    warn!("Lease log write failed: {e}");
+                }
+            }
+            DbActorMessage::ListLeaseLog(filter, reply) => {
+                let res = self.list_lease_log(&state.pool, filter).await;
+                let _ = reply.send(res);
+            }
+            DbActorMessage::PutSignatures(provider, writes) => {
+                if let Err(e) = self.put_signatures(&state.pool, provider, writes).await {
+                    warn!("Signature cache write failed: {e}");
+                }
+            }
+            DbActorMessage::LoadSignatures(provider, limit, reply) => {
+                let res = self.load_signatures(&state.pool, provider, limit).await;
+                let _ = reply.send(res);
+            }
+            DbActorMessage::PruneSignatures(provider) => {
+                if let Err(e) = self.prune_signatures(&state.pool, provider).await {
+                    warn!("Signature cache prune failed: {e}");
+                }
+            }
+            DbActorMessage::DeleteSignatures(provider, key) => {
+                if let Err(e) = self.delete_signatures(&state.pool, provider, key).await {
+                    warn!("Signature cache delete failed: {e}");
+                }
+            }
         }
         Ok(())
     }
 }
 
 impl DbActor {
+    async fn create_new_geminicli(
+        &self,
+        pool: &SqlitePool,
+        create: GeminiCliCreate,
+    ) -> Result<i64, PolluxError> {
+        // The actor handles one message at a time, so nothing can insert between the check
+        // and the insert.
+        let existing: Option<i64> = sqlx::query_scalar(
+            "SELECT id FROM gemini_cli WHERE project_id = ? AND status = 1 LIMIT 1",
+        )
+        .bind(&create.project_id)
+        .fetch_optional(pool)
+        .await?;
+        if let Some(id) = existing {
+            return Err(PolluxError::Conflict(format!(
+                "project {} already has an active credential (id {id})",
+                create.project_id
+            )));
+        }
+        self.create_provider(pool, ProviderCreate::GeminiCli(create))
+            .await
+    }
+
     async fn create_provider(
         &self,
         pool: &SqlitePool,
@@ -157,14 +314,16 @@ impl DbActor {
                 let id: i64 = sqlx::query_scalar(
                     r#"
                 INSERT INTO gemini_cli (
-                    email, sub, project_id, refresh_token, access_token, expiry, status, created_at, updated_at
+                    email, sub, project_id, refresh_token, access_token, expiry, quota_tier, supported_models, status, created_at, updated_at
                 )
-                VALUES (?, ?, ?, ?, ?, ?, 1, ?, ?)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, 1, ?, ?)
                 ON CONFLICT(sub, project_id) DO UPDATE SET
                     email=excluded.email,
                     refresh_token=excluded.refresh_token,
                     access_token=excluded.access_token,
                     expiry=excluded.expiry,
+                    quota_tier=COALESCE(excluded.quota_tier, quota_tier),
+                    supported_models=COALESCE(excluded.supported_models, supported_models),
                     status=1,
                     updated_at=excluded.updated_at
                 RETURNING id
@@ -176,6 +335,8 @@ impl DbActor {
                 .bind(c.refresh_token)
                 .bind(c.access_token)
                 .bind(c.expiry)
+                .bind(c.quota_tier)
+                .bind(c.supported_models)
                 .bind(now)
                 .bind(now)
                 .fetch_one(pool)
@@ -263,7 +424,7 @@ impl DbActor {
     ) -> Result<Vec<DbGeminiCliResource>, PolluxError> {
         let rows = sqlx::query_as::<_, DbGeminiCliResource>(
             r#"
-        SELECT id, email, sub, project_id, refresh_token, access_token, expiry, status, created_at, updated_at
+        SELECT id, email, sub, project_id, refresh_token, access_token, expiry, quota_tier, supported_models, status, created_at, updated_at
         FROM gemini_cli
         WHERE status = 1
         ORDER BY id
@@ -329,6 +490,143 @@ impl DbActor {
 
         Ok(row)
     }
+
+    async fn record_lease(
+        &self,
+        pool: &SqlitePool,
+        entry: LeaseLogCreate,
+    ) -> Result<(), PolluxError> {
+        sqlx::query(
+            r#"
+        INSERT INTO lease_log (request_id, provider, credential_id, model, outcome, created_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+        )
+        .bind(entry.request_id)
+        .bind(entry.provider)
+        .bind(entry.credential_id)
+        .bind(entry.model)
+        .bind(entry.outcome)
+        .bind(Utc::now())
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_lease_log(
+        &self,
+        pool: &SqlitePool,
+        filter: LeaseLogFilter,
+    ) -> Result<Vec<DbLeaseLogEntry>, PolluxError> {
+        let rows = sqlx::query_as::<_, DbLeaseLogEntry>(
+            r#"
+        SELECT id, request_id, provider, credential_id, model, outcome, created_at
+        FROM lease_log
+        WHERE (?1 IS NULL OR provider = ?1)
+          AND (?2 IS NULL OR credential_id = ?2)
+          AND (?3 IS NULL OR created_at >= ?3)
+          AND (?4 IS NULL OR created_at <= ?4)
+        ORDER BY id DESC
+        LIMIT ?5
+        "#,
+        )
+        .bind(filter.provider)
+        .bind(filter.credential_id)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(filter.limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    async fn put_signatures(
+        &self,
+        pool: &SqlitePool,
+        provider: String,
+        writes: Vec<SignatureCacheWrite>,
+    ) -> Result<(), PolluxError> {
+        let now = Utc::now();
+        let mut tx = pool.begin().await?;
+        for write in writes {
+            sqlx::query(
+                r#"
+            INSERT INTO signature_cache (provider, key, signature, created_at, expires_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(provider, key) DO UPDATE SET
+                signature=excluded.signature,
+                created_at=excluded.created_at,
+                expires_at=excluded.expires_at
+            "#,
+            )
+            .bind(&provider)
+            .bind(write.key)
+            .bind(write.signature)
+            .bind(now)
+            .bind(write.expires_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn load_signatures(
+        &self,
+        pool: &SqlitePool,
+        provider: String,
+        limit: u64,
+    ) -> Result<Vec<DbSignatureCacheEntry>, PolluxError> {
+        let rows = sqlx::query_as::<_, DbSignatureCacheEntry>(
+            r#"
+        SELECT key, signature
+        FROM signature_cache
+        WHERE provider = ? AND (expires_at IS NULL OR expires_at > ?)
+        ORDER BY created_at DESC
+        LIMIT ?
+        "#,
+        )
+        .bind(provider)
+        .bind(Utc::now())
+        .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    async fn prune_signatures(
+        &self,
+        pool: &SqlitePool,
+        provider: String,
+    ) -> Result<(), PolluxError> {
+        sqlx::query("DELETE FROM signature_cache WHERE provider = ? AND expires_at <= ?")
+            .bind(provider)
+            .bind(Utc::now())
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_signatures(
+        &self,
+        pool: &SqlitePool,
+        provider: String,
+        key: Option<i64>,
+    ) -> Result<(), PolluxError> {
+        sqlx::query("DELETE FROM signature_cache WHERE provider = ? AND (? IS NULL OR key = ?)")
+            .bind(provider)
+            .bind(key)
+            .bind(key)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
 }
 
 fn synthetic_sub_from_refresh_token(refresh_token: &str) -> String {
@@ -361,5 +659,22 @@ async fn apply_schema(pool: &SqlitePool) -> Result<(), PolluxError> {
         }
         sqlx::query(s).execute(pool).await?;
     }
+
+    for (table, column, definition) in SQLITE_ADDED_COLUMNS {
+        let exists: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?")
+                .bind(table)
+                .bind(column)
+                .fetch_one(pool)
+                .await?;
+        if exists == 0 {
+            info!(table, column, "Adding missing column to existing database");
+            sqlx::query(&format!(
+                "ALTER TABLE {table} ADD COLUMN {column} {definition}"
+            ))
+            .execute(pool)
+            .await?;
+        }
+    }
     Ok(())
 }
@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// One lease to append to the `lease_log` table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaseLogCreate {
+    pub request_id: Option<String>,
+    pub provider: String,
+    pub credential_id: i64,
+    pub model: String,
+    pub outcome: String,
+}
+
+/// Which `lease_log` rows to list, newest first. Unset filters match every row.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LeaseLogFilter {
+    pub provider: Option<String>,
+    pub credential_id: Option<i64>,
+    /// Inclusive lower bound on `created_at`.
+    pub since: Option<DateTime<Utc>>,
+    /// Inclusive upper bound on `created_at`.
+    pub until: Option<DateTime<Utc>>,
+    pub limit: u32,
+}
@@ -3,20 +3,29 @@
 //! Layout:
 //! - `models.rs`: Rust structs mirroring DB rows
 //! - `schema.rs`: SQL DDL for initializing the database (SQLite-first)
+//! - `lease_log.rs`: inputs for the credential lease audit log
+//! - `signature_cache.rs`: inputs for the persisted thought-signature cache
 
 pub mod actor;
+pub mod lease_log;
 pub mod models;
 pub mod patch;
 pub mod schema;
+pub mod signature_cache;
 pub mod traits;
 
 mod patch_impl;
 
-pub use models::{DbAntigravityResource, DbCodexResource, DbGeminiCliResource};
+pub use lease_log::{LeaseLogCreate, LeaseLogFilter};
+pub use models::{
+    DbAntigravityResource, DbCodexResource, DbGeminiCliResource, DbLeaseLogEntry,
+    DbSignatureCacheEntry,
+};
 pub use patch::{
     AntigravityCreate, AntigravityPatch, CodexCreate, CodexPatch, GeminiCliCreate, GeminiCliPatch,
     ProviderCreate, ProviderPatch,
 };
 pub use schema::SQLITE_INIT;
+pub use signature_cache::SignatureCacheWrite;
 
 pub use actor::{DbActorHandle, spawn};
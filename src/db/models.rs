@@ -11,6 +11,11 @@ pub struct DbGeminiCliResource {
     pub refresh_token: String,
     pub access_token: Option<String>,
     pub expiry: DateTime<Utc>,
+    /// Code Assist quota tier from `loadCodeAssist` (e.g. `free-tier`), if known.
+    pub quota_tier: Option<String>,
+    /// Model mask the quota tier allowed when the credential was onboarded (the `u64` bits
+    /// reinterpreted as `i64`), if known.
+    pub supported_models: Option<i64>,
     pub status: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -31,6 +36,19 @@ pub struct DbCodexResource {
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, FromRow)]
+pub struct DbLeaseLogEntry {
+    pub id: i64,
+    /// `x-request-id` of the client request the lease served.
+    pub request_id: Option<String>,
+    pub provider: String,
+    pub credential_id: i64,
+    pub model: String,
+    /// How the upstream attempt made with the lease ended (e.g. `ok`, `rate_limited`).
+    pub outcome: String,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, FromRow)]
 pub struct DbAntigravityResource {
     pub id: i64,
@@ -45,3 +63,10 @@ pub struct DbAntigravityResource {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, FromRow)]
+pub struct DbSignatureCacheEntry {
+    /// The `u64` cache key as its `i64` bit pattern.
+    pub key: i64,
+    pub signature: String,
+}
@@ -14,6 +14,11 @@ pub struct GeminiCliCreate {
     pub refresh_token: String,
     pub access_token: Option<String>,
     pub expiry: DateTime<Utc>,
+    #[serde(default)]
+    pub quota_tier: Option<String>,
+    /// See `DbGeminiCliResource::supported_models`.
+    #[serde(default)]
+    pub supported_models: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -5,6 +5,8 @@
 /// - `gemini_cli` table (Gemini CLI provider, one (sub, project_id) per row)
 /// - `codex` table (Codex provider, one (sub, account_id) per row)
 /// - `antigravity` table (Antigravity provider, one (sub, project_id) per row)
+/// - `lease_log` table (one row per credential lease, written when `basic.lease_log` is on)
+/// - `signature_cache` table (thought signatures, written when `basic.thoughtsig_persist` is on)
 pub const SQLITE_INIT: &str = r#"
 -- ---------------------------------------------------------------------------
 -- Gemini CLI provider
@@ -17,6 +19,8 @@ CREATE TABLE IF NOT EXISTS gemini_cli (
     refresh_token TEXT NOT NULL,
     access_token TEXT NULL,
     expiry TEXT NOT NULL, -- RFC3339
+    quota_tier TEXT NULL,
+    supported_models INTEGER NULL, -- u64 model mask, stored as its i64 bit pattern
     status INTEGER NOT NULL DEFAULT 1,
     created_at TEXT NOT NULL, -- RFC3339
     updated_at TEXT NOT NULL, -- RFC3339
@@ -63,4 +67,41 @@ CREATE TABLE IF NOT EXISTS antigravity (
 );
 
 CREATE INDEX IF NOT EXISTS idx_antigravity_status ON antigravity(status);
+
+-- ---------------------------------------------------------------------------
+-- Lease log (which credential served which request)
+-- ---------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS lease_log (
+    id INTEGER PRIMARY KEY NOT NULL,
+    request_id TEXT NULL,
+    provider TEXT NOT NULL, -- geminicli | codex | antigravity
+    credential_id INTEGER NOT NULL, -- id in the provider's table
+    model TEXT NOT NULL,
+    outcome TEXT NOT NULL,
+    created_at TEXT NOT NULL -- RFC3339
+);
+
+CREATE INDEX IF NOT EXISTS idx_lease_log_credential ON lease_log(provider, credential_id);
+CREATE INDEX IF NOT EXISTS idx_lease_log_created_at ON lease_log(created_at);
+
+-- ---------------------------------------------------------------------------
+-- Thought signature cache (mirror of the in-memory cache, reloaded on startup)
+-- ---------------------------------------------------------------------------
+CREATE TABLE IF NOT EXISTS signature_cache (
+    provider TEXT NOT NULL, -- geminicli | antigravity
+    key INTEGER NOT NULL, -- u64 cache key, stored as its i64 bit pattern
+    signature TEXT NOT NULL,
+    created_at TEXT NOT NULL, -- RFC3339
+    expires_at TEXT NULL, -- RFC3339, NULL never expires
+    PRIMARY KEY (provider, key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_signature_cache_expires_at ON signature_cache(expires_at);
 "#;
+
+/// Columns added after the initial schema, as `(table, column, definition)`.
+/// Applied with `ALTER TABLE ... ADD COLUMN` when missing from an existing database.
+pub const SQLITE_ADDED_COLUMNS: &[(&str, &str, &str)] = &[
+    ("gemini_cli", "quota_tier", "TEXT NULL"),
+    ("gemini_cli", "supported_models", "INTEGER NULL"),
+];
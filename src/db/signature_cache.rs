@@ -0,0 +1,12 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// One thought signature to write to the `signature_cache` table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignatureCacheWrite {
+    /// The `u64` cache key as its `i64` bit pattern.
+    pub key: i64,
+    pub signature: String,
+    /// `None` keeps the row until it is overwritten.
+    pub expires_at: Option<DateTime<Utc>>,
+}
@@ -8,6 +8,7 @@ use thiserror::Error as ThisError;
 
 use super::IsRetryable;
 use crate::providers::UPSTREAM_BODY_PREVIEW_CHARS;
+use crate::utils::spool::SpoolError;
 use pollux_schema::{CodexErrorBody, OpenaiResponsesErrorBody, OpenaiResponsesErrorObject};
 
 #[derive(Debug, ThisError)]
@@ -49,20 +50,90 @@ pub(crate) enum CodexError {
     Internal(String),
 }
 
-impl From<JsonRejection> for CodexError {
-    fn from(rejection: JsonRejection) -> Self {
-        let debug_message = rejection.to_string();
-        match rejection {
-            JsonRejection::BytesRejection(_) => CodexError::RequestRejected {
-                status: StatusCode::PAYLOAD_TOO_LARGE,
+impl CodexError {
+    /// Reject a request whose body is not declared as JSON, explaining what was received.
+    pub(crate) fn invalid_content_type(message: String) -> Self {
+        CodexError::RequestRejected {
+            status: StatusCode::BAD_REQUEST,
+            body: OpenaiResponsesErrorObject {
+                code: Some("INVALID_CONTENT_TYPE".to_string()),
+                message,
+                r#type: "INVALID_CONTENT_TYPE".to_string(),
+                param: None,
+            },
+            debug_message: None,
+        }
+    }
+
+    /// Reject a request body over the route's size limit, naming the limit.
+    pub(crate) fn payload_too_large(limit: usize, debug_message: Option<String>) -> Self {
+        CodexError::RequestRejected {
+            status: StatusCode::PAYLOAD_TOO_LARGE,
+            body: OpenaiResponsesErrorObject {
+                code: Some("PAYLOAD_TOO_LARGE".to_string()),
+                message: format!("request body too large; limit is {limit} bytes"),
+                r#type: "PAYLOAD_TOO_LARGE".to_string(),
+                param: None,
+            },
+            debug_message,
+        }
+    }
+
+    /// Reject a streaming request while `basic.max_concurrent_streams` streams are open.
+    pub(crate) fn too_many_streams() -> Self {
+        CodexError::RequestRejected {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            body: OpenaiResponsesErrorObject {
+                code: Some("TOO_MANY_STREAMS".to_string()),
+                message: "too many concurrent streams; retry later".to_string(),
+                r#type: "TOO_MANY_STREAMS".to_string(),
+                param: None,
+            },
+            debug_message: None,
+        }
+    }
+}
+
+impl From<SpoolError> for CodexError {
+    fn from(error: SpoolError) -> Self {
+        let debug_message = error.to_string();
+        match error {
+            SpoolError::TooLarge { limit } => {
+                CodexError::payload_too_large(limit, Some(debug_message))
+            }
+            SpoolError::Io(_) => CodexError::Internal(debug_message),
+            SpoolError::Json(ref e) if e.is_syntax() || e.is_eof() => CodexError::RequestRejected {
+                status: StatusCode::BAD_REQUEST,
                 body: OpenaiResponsesErrorObject {
-                    code: Some("PAYLOAD_TOO_LARGE".to_string()),
-                    message: "request body too large".to_string(),
-                    r#type: "PAYLOAD_TOO_LARGE".to_string(),
+                    code: Some("INVALID_JSON".to_string()),
+                    message: "invalid JSON".to_string(),
+                    r#type: "INVALID_JSON".to_string(),
                     param: None,
                 },
                 debug_message: Some(debug_message),
             },
+            SpoolError::Json(_) | SpoolError::Body(_) => CodexError::RequestRejected {
+                status: StatusCode::BAD_REQUEST,
+                body: OpenaiResponsesErrorObject {
+                    code: Some("INVALID_REQUEST".to_string()),
+                    message: "invalid request".to_string(),
+                    r#type: "INVALID_REQUEST".to_string(),
+                    param: None,
+                },
+                debug_message: Some(debug_message),
+            },
+        }
+    }
+}
+
+impl CodexError {
+    /// Map a `Json` extractor rejection; body read failures report `body_limit`.
+    pub(crate) fn json_rejection(rejection: JsonRejection, body_limit: usize) -> Self {
+        let debug_message = rejection.to_string();
+        match rejection {
+            JsonRejection::BytesRejection(_) => {
+                CodexError::payload_too_large(body_limit, Some(debug_message))
+            }
             JsonRejection::JsonSyntaxError(_) => CodexError::RequestRejected {
                 status: StatusCode::BAD_REQUEST,
                 body: OpenaiResponsesErrorObject {
@@ -204,6 +275,16 @@ impl From<crate::PolluxError> for CodexError {
         match err {
             crate::PolluxError::NoAvailableCredential => CodexError::NoAvailableCredential,
             crate::PolluxError::ReqwestError(e) => CodexError::Reqwest(e),
+            crate::PolluxError::UpstreamUnavailable(message) => CodexError::RequestRejected {
+                status: StatusCode::SERVICE_UNAVAILABLE,
+                body: OpenaiResponsesErrorObject {
+                    code: Some("UPSTREAM_UNAVAILABLE".to_string()),
+                    message,
+                    r#type: "UPSTREAM_UNAVAILABLE".to_string(),
+                    param: None,
+                },
+                debug_message: None,
+            },
             crate::PolluxError::StreamProtocolError(s) => CodexError::StreamProtocolError(s),
             other => CodexError::Internal(other.to_string()),
         }
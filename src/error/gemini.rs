@@ -49,23 +49,77 @@ pub enum GeminiCliError {
     #[error("Stream protocol error: {0}")]
     StreamProtocolError(String),
 
+    /// Upstream answered 200 with no candidates content or finish reason.
+    #[error("Upstream returned an empty response")]
+    EmptyResponse,
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
 
-impl From<JsonRejection> for GeminiCliError {
-    fn from(rejection: JsonRejection) -> Self {
+impl GeminiCliError {
+    /// Reject a request whose body is not declared as JSON, explaining what was received.
+    pub(crate) fn invalid_content_type(message: String) -> Self {
+        GeminiCliError::RequestRejected {
+            status: StatusCode::BAD_REQUEST,
+            body: GeminiErrorObject::for_status(
+                StatusCode::BAD_REQUEST,
+                "INVALID_ARGUMENT",
+                message,
+            ),
+            debug_message: None,
+        }
+    }
+
+    /// Reject a request body over the route's size limit, naming the limit.
+    pub(crate) fn payload_too_large(limit: usize, debug_message: Option<String>) -> Self {
+        GeminiCliError::RequestRejected {
+            status: StatusCode::PAYLOAD_TOO_LARGE,
+            body: GeminiErrorObject::for_status(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "PAYLOAD_TOO_LARGE",
+                format!("request body too large; limit is {limit} bytes"),
+            ),
+            debug_message,
+        }
+    }
+
+    /// Turn a response that finished for a reason outside `basic.success_finish_reasons` into
+    /// a client error.
+    pub(crate) fn finish_reason_rejected(reason: &str) -> Self {
+        GeminiCliError::RequestRejected {
+            status: StatusCode::BAD_REQUEST,
+            body: GeminiErrorObject::for_status(
+                StatusCode::BAD_REQUEST,
+                "FAILED_PRECONDITION",
+                format!("response finished with {reason}, which is not a successful finish reason"),
+            ),
+            debug_message: None,
+        }
+    }
+
+    /// Reject a streaming request while `basic.max_concurrent_streams` streams are open.
+    pub(crate) fn too_many_streams() -> Self {
+        GeminiCliError::RequestRejected {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            body: GeminiErrorObject::for_status(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "UNAVAILABLE",
+                "too many concurrent streams; retry later",
+            ),
+            debug_message: None,
+        }
+    }
+}
+
+impl GeminiCliError {
+    /// Map a `Json` extractor rejection; body read failures report `body_limit`.
+    pub(crate) fn json_rejection(rejection: JsonRejection, body_limit: usize) -> Self {
         let debug_message = rejection.to_string();
         match rejection {
-            JsonRejection::BytesRejection(_) => GeminiCliError::RequestRejected {
-                status: StatusCode::PAYLOAD_TOO_LARGE,
-                body: GeminiErrorObject::for_status(
-                    StatusCode::PAYLOAD_TOO_LARGE,
-                    "PAYLOAD_TOO_LARGE",
-                    "request body too large",
-                ),
-                debug_message: Some(debug_message),
-            },
+            JsonRejection::BytesRejection(_) => {
+                GeminiCliError::payload_too_large(body_limit, Some(debug_message))
+            }
             JsonRejection::JsonSyntaxError(_) => GeminiCliError::RequestRejected {
                 status: StatusCode::BAD_REQUEST,
                 body: GeminiErrorObject::for_status(
@@ -185,6 +239,18 @@ impl IntoResponse for GeminiCliError {
                 )
             }
 
+            GeminiCliError::EmptyResponse => {
+                tracing::warn!("Gemini upstream returned an empty response");
+                (
+                    StatusCode::BAD_GATEWAY,
+                    GeminiErrorObject::for_status(
+                        StatusCode::BAD_GATEWAY,
+                        "UNAVAILABLE",
+                        "Upstream returned an empty response.",
+                    ),
+                )
+            }
+
             GeminiCliError::Internal(e) => {
                 tracing::error!(error = %e, "Gemini internal error");
                 (
@@ -207,8 +273,27 @@ impl From<crate::PolluxError> for GeminiCliError {
     fn from(err: crate::PolluxError) -> Self {
         match err {
             crate::PolluxError::NoAvailableCredential => GeminiCliError::NoAvailableCredential,
+            crate::PolluxError::TierNotAllowed(message) => GeminiCliError::RequestRejected {
+                status: StatusCode::FORBIDDEN,
+                body: GeminiErrorObject::for_status(
+                    StatusCode::FORBIDDEN,
+                    "PERMISSION_DENIED",
+                    message,
+                ),
+                debug_message: None,
+            },
             crate::PolluxError::ReqwestError(e) => GeminiCliError::Reqwest(e),
+            crate::PolluxError::UpstreamUnavailable(message) => GeminiCliError::RequestRejected {
+                status: StatusCode::SERVICE_UNAVAILABLE,
+                body: GeminiErrorObject::for_status(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "UNAVAILABLE",
+                    message,
+                ),
+                debug_message: None,
+            },
             crate::PolluxError::StreamProtocolError(s) => GeminiCliError::StreamProtocolError(s),
+            crate::PolluxError::EmptyResponse => GeminiCliError::EmptyResponse,
             other => GeminiCliError::Internal(other.to_string()),
         }
     }
@@ -220,6 +305,9 @@ impl IsRetryable for GeminiCliError {
             // Transport errors are already retried inside GeminiApi.
             GeminiCliError::Reqwest(_) => false,
 
+            // Transient upstream hiccup; another attempt usually has content.
+            GeminiCliError::EmptyResponse => true,
+
             GeminiCliError::UpstreamFallbackError { status, .. } => matches!(
                 *status,
                 StatusCode::TOO_MANY_REQUESTS
@@ -479,7 +567,7 @@ mod tests {
         assert!(e429_2.inner.details.is_some());
         assert_eq!(
             e429_2.try_match_rule(StatusCode::TOO_MANY_REQUESTS),
-            Some(ActionForError::RateLimit(Duration::from_secs(60 * 60)))
+            Some(ActionForError::RateLimit(Duration::from_secs(10 * 60)))
         );
 
         let e404_1 = GeminiCliErrorBody {
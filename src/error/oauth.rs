@@ -1,10 +1,10 @@
 use super::IsRetryable;
 use super::pollux::PolluxError;
 use axum::http::StatusCode;
-use oauth2::basic::BasicErrorResponseType;
 use oauth2::reqwest::Error as ReqwestClientError;
-use oauth2::{HttpClientError, RequestTokenError, StandardErrorResponse};
+use oauth2::{ErrorResponseType, HttpClientError, RequestTokenError, StandardErrorResponse};
 use serde_json::Value;
+use std::fmt::Display;
 use thiserror::Error as ThisError;
 
 #[derive(Debug, ThisError)]
@@ -45,13 +45,13 @@ impl IsRetryable for OauthError {
     }
 }
 
-type PkgsRequestTokenError = RequestTokenError<
-    HttpClientError<ReqwestClientError>,
-    StandardErrorResponse<BasicErrorResponseType>,
->;
+/// Token endpoint errors from `oauth2`, generic over the error code type so that both the
+/// standard grants and the device-code grant share one mapping.
+type PkgsRequestTokenError<T> =
+    RequestTokenError<HttpClientError<ReqwestClientError>, StandardErrorResponse<T>>;
 
-impl From<PkgsRequestTokenError> for OauthError {
-    fn from(e: PkgsRequestTokenError) -> Self {
+impl<T: ErrorResponseType + Display> From<PkgsRequestTokenError<T>> for OauthError {
+    fn from(e: PkgsRequestTokenError<T>) -> Self {
         match e {
             RequestTokenError::ServerResponse(err) => OauthError::ServerResponse {
                 error: err.error().to_string(),
@@ -79,8 +79,8 @@ impl From<PkgsRequestTokenError> for OauthError {
     }
 }
 
-impl From<PkgsRequestTokenError> for PolluxError {
-    fn from(e: PkgsRequestTokenError) -> Self {
+impl<T: ErrorResponseType + Display> From<PkgsRequestTokenError<T>> for PolluxError {
+    fn from(e: PkgsRequestTokenError<T>) -> Self {
         OauthError::from(e).into()
     }
 }
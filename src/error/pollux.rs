@@ -41,6 +41,26 @@ pub enum PolluxError {
     #[error("No available credential")]
     NoAvailableCredential,
 
+    /// The upstream host's circuit breaker is open after repeated connection failures.
+    #[error("Upstream unavailable: {0}")]
+    UpstreamUnavailable(String),
+
+    /// Upstream answered 200 with no candidates content or finish reason.
+    #[error("Upstream returned an empty response")]
+    EmptyResponse,
+
+    #[error("Model not allowed for credential quota tier: {0}")]
+    TierNotAllowed(String),
+
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
     #[error("Ractor error: {0}")]
     RactorError(String),
 
@@ -93,6 +113,16 @@ impl IntoResponse for PolluxError {
                 (status, body)
             }
 
+            PolluxError::EmptyResponse => {
+                let status = StatusCode::BAD_GATEWAY;
+                let body = ApiErrorObject {
+                    code: "EMPTY_UPSTREAM_RESPONSE".to_string(),
+                    message: "Upstream returned an empty response.".to_string(),
+                    details: None,
+                };
+                (status, body)
+            }
+
             PolluxError::StreamProtocolError(_)
             | PolluxError::Oauth(OauthError::Request(_))
             | PolluxError::Oauth(OauthError::ServerResponse { .. })
@@ -107,6 +137,46 @@ impl IntoResponse for PolluxError {
                 (status, body)
             }
 
+            PolluxError::BadRequest(message) => {
+                let status = StatusCode::BAD_REQUEST;
+                let body = ApiErrorObject {
+                    code: "INVALID_ARGUMENT".to_string(),
+                    message,
+                    details: None,
+                };
+                (status, body)
+            }
+
+            PolluxError::NotFound(message) => {
+                let status = StatusCode::NOT_FOUND;
+                let body = ApiErrorObject {
+                    code: "NOT_FOUND".to_string(),
+                    message,
+                    details: None,
+                };
+                (status, body)
+            }
+
+            PolluxError::Conflict(message) => {
+                let status = StatusCode::CONFLICT;
+                let body = ApiErrorObject {
+                    code: "CONFLICT".to_string(),
+                    message,
+                    details: None,
+                };
+                (status, body)
+            }
+
+            PolluxError::TierNotAllowed(message) => {
+                let status = StatusCode::FORBIDDEN;
+                let body = ApiErrorObject {
+                    code: "MODEL_NOT_ALLOWED_FOR_TIER".to_string(),
+                    message,
+                    details: None,
+                };
+                (status, body)
+            }
+
             PolluxError::NoAvailableCredential => {
                 let status = StatusCode::SERVICE_UNAVAILABLE;
                 let body = ApiErrorObject {
@@ -117,6 +187,16 @@ impl IntoResponse for PolluxError {
                 (status, body)
             }
 
+            PolluxError::UpstreamUnavailable(message) => {
+                let status = StatusCode::SERVICE_UNAVAILABLE;
+                let body = ApiErrorObject {
+                    code: "UPSTREAM_UNAVAILABLE".to_string(),
+                    message,
+                    details: None,
+                };
+                (status, body)
+            }
+
             PolluxError::UpstreamStatus(code)
             | PolluxError::Oauth(OauthError::UpstreamStatus(code)) => {
                 let (err_code, msg) = match code {
@@ -161,6 +241,7 @@ impl IsRetryable for PolluxError {
     fn is_retryable(&self) -> bool {
         match self {
             PolluxError::ReqwestError(_) => true,
+            PolluxError::EmptyResponse => true,
             PolluxError::UpstreamStatus(status) => matches!(
                 *status,
                 reqwest::StatusCode::TOO_MANY_REQUESTS
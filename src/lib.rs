@@ -6,6 +6,8 @@ pub(crate) mod oauth_utils;
 mod patches;
 pub mod providers;
 pub mod server;
+#[cfg(feature = "testutil")]
+pub mod testutil;
 pub(crate) mod utils;
 
 pub use error::PolluxError;
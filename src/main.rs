@@ -2,7 +2,7 @@ use mimalloc::MiMalloc;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::{net::TcpListener, signal};
-use tracing::info;
+use tracing::{info, warn};
 use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
 
 #[global_allocator]
@@ -27,12 +27,40 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         )
         .init();
 
+    pollux_thoughtsig_core::CacheKeyGenerator::set_global(
+        pollux_thoughtsig_core::CacheKeyGenerator::with_seed(cfg.basic.thoughtsig_hash_seed)
+            .with_algo(cfg.basic.thoughtsig_hash_algo),
+    );
+
     let db = pollux::db::spawn(cfg.basic.database_url.as_str()).await;
     let providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+    match pollux::server::summary::StartupSummary::collect(&cfg, &providers, &db).await {
+        Ok(summary) => summary.log(),
+        Err(e) => warn!("Failed to collect startup summary: {}", e),
+    }
     // Build axum router and serve
     let pollux_key: Arc<str> = Arc::from(cfg.basic.pollux_key.clone());
     let state =
-        pollux::server::router::PolluxState::new(providers, pollux_key, cfg.basic.insecure_cookie);
+        pollux::server::router::PolluxState::new(providers, pollux_key, cfg.basic.insecure_cookie)
+            .with_sse_buffer(pollux::server::sse_buffer::SseBufferConfig {
+                capacity: cfg.basic.sse_buffer_capacity,
+                overflow: cfg.basic.sse_overflow,
+            })
+            .with_sse_complete_event(cfg.basic.sse_complete_event)
+            .with_sse_retry(cfg.basic.sse_retry_ms.map(std::time::Duration::from_millis))
+            .with_sse_timeouts(pollux::server::sse_timeout::SseTimeouts {
+                first_event: std::time::Duration::from_secs(cfg.basic.sse_first_event_timeout_secs),
+                idle: std::time::Duration::from_secs(cfg.basic.sse_idle_timeout_secs),
+            })
+            .with_tool_call_ids(cfg.basic.tool_call_ids)
+            .with_empty_text_stripped(cfg.basic.strip_empty_text_parts)
+            .with_max_concurrent_streams(cfg.basic.max_concurrent_streams)
+            .with_request_coalescing(cfg.basic.coalesce_requests)
+            .with_response_compression(cfg.basic.compress_responses)
+            .with_success_finish_reasons(cfg.basic.success_finish_reasons.clone())
+            .with_oauth_cookies(pollux::server::cookies::OauthCookieConfig::from_basic(
+                &cfg.basic,
+            ));
     let app = pollux::server::router::pollux_router(state);
 
     let addr = SocketAddr::from((cfg.basic.listen_addr, cfg.basic.listen_port));
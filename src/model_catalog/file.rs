@@ -0,0 +1,178 @@
+use figment::{
+    Figment,
+    providers::{Format, Toml},
+};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use thiserror::Error as ThisError;
+
+/// Providers a catalog entry may name in `providers`.
+pub const CATALOG_PROVIDERS: [&str; 3] = ["geminicli", "codex", "antigravity"];
+
+#[derive(Debug, ThisError)]
+pub enum ModelCatalogError {
+    #[error("failed to read model catalog {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("failed to parse model catalog {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+
+    #[error("invalid model catalog: {0}")]
+    Invalid(String),
+}
+
+/// Models declared in the file at `basic.model_catalog`.
+///
+/// ```toml
+/// [[models]]
+/// name = "gemini-3-pro-preview"
+/// mask = 64                      # optional; pins the model to bit 6
+/// providers = ["geminicli", "antigravity"]
+/// aliases = ["gemini-pro-latest"]
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ModelCatalogFile {
+    #[serde(default)]
+    pub models: Vec<CatalogModel>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CatalogModel {
+    /// Canonical name; this is what gets forwarded upstream.
+    pub name: String,
+
+    /// Single-bit mask to pin the model to, so persisted per-credential model bits keep their
+    /// meaning when the list changes. Unpinned models take the lowest free bits.
+    #[serde(default)]
+    pub mask: Option<u64>,
+
+    /// Providers that serve the model; it is added to each one's `model_list`.
+    #[serde(default)]
+    pub providers: Vec<String>,
+
+    /// Other names clients may use; requests are rewritten to `name`.
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+impl ModelCatalogFile {
+    /// Read and validate a catalog. `.json` files are parsed as JSON, anything else as TOML.
+    pub fn load(path: &Path) -> Result<Self, ModelCatalogError> {
+        let raw = std::fs::read_to_string(path).map_err(|source| ModelCatalogError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let parse_error = |message: String| ModelCatalogError::Parse {
+            path: path.to_path_buf(),
+            message,
+        };
+        let catalog: Self = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&raw).map_err(|e| parse_error(e.to_string()))?
+        } else {
+            Figment::from(Toml::string(&raw))
+                .extract()
+                .map_err(|e| parse_error(e.to_string()))?
+        };
+        catalog.validate()?;
+        Ok(catalog)
+    }
+
+    /// Names and aliases are unique, masks are single distinct bits, providers are known.
+    pub fn validate(&self) -> Result<(), ModelCatalogError> {
+        let invalid = |message: String| Err(ModelCatalogError::Invalid(message));
+        let mut names = HashSet::new();
+        let mut masks = HashSet::new();
+
+        for model in &self.models {
+            if model.name.trim().is_empty() {
+                return invalid("model name must not be empty".to_string());
+            }
+            for name in std::iter::once(&model.name).chain(&model.aliases) {
+                if !names.insert(name.as_str()) {
+                    return invalid(format!("name `{name}` is declared more than once"));
+                }
+            }
+            if let Some(mask) = model.mask {
+                if mask.count_ones() != 1 {
+                    return invalid(format!(
+                        "mask for `{}` must have exactly one bit set, got 0x{mask:x}",
+                        model.name
+                    ));
+                }
+                if !masks.insert(mask) {
+                    return invalid(format!(
+                        "mask 0x{mask:x} for `{}` is already used by another model",
+                        model.name
+                    ));
+                }
+            }
+            if let Some(provider) = model
+                .providers
+                .iter()
+                .find(|provider| !CATALOG_PROVIDERS.contains(&provider.as_str()))
+            {
+                return invalid(format!(
+                    "unknown provider `{provider}` for `{}`; expected one of {CATALOG_PROVIDERS:?}",
+                    model.name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Models the catalog assigns to `provider`, in file order.
+    pub fn models_for<'a>(&'a self, provider: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.models
+            .iter()
+            .filter(move |model| model.providers.iter().any(|p| p == provider))
+            .map(|model| model.name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(toml: &str) -> Result<ModelCatalogFile, ModelCatalogError> {
+        let catalog: ModelCatalogFile = Figment::from(Toml::string(toml)).extract().unwrap();
+        catalog.validate().map(|()| catalog)
+    }
+
+    #[test]
+    fn duplicate_masks_and_names_are_rejected() {
+        let err = parse(
+            r#"
+            [[models]]
+            name = "a"
+            mask = 4
+            [[models]]
+            name = "b"
+            mask = 4
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("already used"), "{err}");
+
+        let err = parse(
+            r#"
+            [[models]]
+            name = "a"
+            [[models]]
+            name = "b"
+            aliases = ["a"]
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("more than once"), "{err}");
+
+        let err = parse("[[models]]\nname = \"a\"\nmask = 3").unwrap_err();
+        assert!(err.to_string().contains("exactly one bit"), "{err}");
+
+        let err = parse("[[models]]\nname = \"a\"\nproviders = [\"openai\"]").unwrap_err();
+        assert!(err.to_string().contains("unknown provider"), "{err}");
+    }
+}
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use std::ops::BitOr;
+
+/// Newtype wrapper around the `u64` model masks passed through routing and the actors.
+///
+/// Bit `i` stands for the model at registry index `i` (see `MODEL_REGISTRY`). A request carries a
+/// mask with exactly one bit set, the model it asked for; a provider's supported set or a quota
+/// tier's allowance is a mask with one bit per model. Model bits are assigned at startup, so the
+/// named flags are looked up with `ModelMask::named` rather than declared as constants.
+///
+/// APIs such as `get_credential(model_mask: u64)` keep taking raw bits; convert at the edge with
+/// `ModelMask::from_bits` / `bits`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash, Serialize, Deserialize)]
+#[repr(transparent)]
+pub struct ModelMask(u64);
+
+impl ModelMask {
+    /// No models.
+    pub const NONE: Self = Self(0);
+
+    #[inline(always)]
+    pub const fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    #[inline(always)]
+    pub const fn bits(self) -> u64 {
+        self.0
+    }
+
+    /// The flag for the model at registry index `index`.
+    #[inline(always)]
+    pub const fn single(index: usize) -> Self {
+        Self(1u64 << index)
+    }
+
+    /// The flag for a model (or catalog alias) by name; `None` if the catalog does not know it.
+    pub fn named(name: &str) -> Option<Self> {
+        super::mask(name).map(Self)
+    }
+
+    #[inline(always)]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Registry index of a single-model mask; `None` when zero or several bits are set.
+    #[inline(always)]
+    pub const fn single_index(self) -> Option<usize> {
+        if self.0.count_ones() == 1 {
+            Some(self.0.trailing_zeros() as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Every model in `other` is also in `self`. An empty `other` is contained in anything.
+    #[inline(always)]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// `self` and `other` share at least one model.
+    #[inline(always)]
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    #[inline(always)]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    #[inline(always)]
+    pub const fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// Models in `self` that are not in `other`.
+    #[inline(always)]
+    pub const fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    /// Registry indices of the set bits, lowest first.
+    pub fn indices(self) -> impl Iterator<Item = usize> {
+        (0..u64::BITS as usize).filter(move |&index| self.0 & (1u64 << index) != 0)
+    }
+}
+
+impl From<u64> for ModelMask {
+    fn from(bits: u64) -> Self {
+        Self(bits)
+    }
+}
+
+impl From<ModelMask> for u64 {
+    fn from(mask: ModelMask) -> Self {
+        mask.0
+    }
+}
+
+impl BitOr for ModelMask {
+    type Output = Self;
+    #[inline(always)]
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_combine_and_contain() {
+        let pro = ModelMask::single(0);
+        let flash = ModelMask::single(3);
+        let both = pro | flash;
+
+        assert_eq!(both.bits(), 0b1001);
+        assert_eq!(both, pro.union(flash));
+        assert!(both.contains(pro) && both.contains(flash));
+        assert!(!pro.contains(both));
+        assert!(both.contains(ModelMask::NONE));
+
+        assert!(both.intersects(flash));
+        assert!(!pro.intersects(flash));
+        assert!(!ModelMask::NONE.intersects(both));
+
+        assert_eq!(both.intersection(flash), flash);
+        assert_eq!(both.difference(flash), pro);
+        assert_eq!(both.indices().collect::<Vec<_>>(), [0, 3]);
+    }
+
+    #[test]
+    fn single_index_requires_exactly_one_bit() {
+        assert_eq!(ModelMask::single(5).single_index(), Some(5));
+        assert_eq!(ModelMask::single(63).single_index(), Some(63));
+        assert_eq!(ModelMask::NONE.single_index(), None);
+        assert_eq!(ModelMask::from_bits(0b110).single_index(), None);
+        assert!(ModelMask::NONE.is_empty());
+    }
+}
@@ -1,17 +1,25 @@
 pub mod capabilities;
+pub mod file;
+pub mod mask;
 pub mod registry;
 
 pub use capabilities::ModelCapabilities;
+pub use file::{ModelCatalogError, ModelCatalogFile};
+pub use mask::ModelMask;
 pub use registry::ModelRegistry;
 
 use crate::config::{CONFIG, Config};
+use serde::Serialize;
 use std::collections::HashSet;
 use std::sync::LazyLock;
 
 pub static MODEL_REGISTRY: LazyLock<ModelRegistry> = LazyLock::new(|| {
     let cfg = &*CONFIG;
     let models = collect_global_model_names(cfg);
-    ModelRegistry::new(&models)
+    match cfg.model_catalog.as_deref() {
+        Some(catalog) => ModelRegistry::with_catalog(&models, catalog),
+        None => ModelRegistry::new(&models),
+    }
 });
 
 pub static MODEL_MASK_ALL: LazyLock<u64> = LazyLock::new(|| {
@@ -27,35 +35,84 @@ pub fn mask(name: &str) -> Option<u64> {
     MODEL_REGISTRY.get_index(name).map(|idx| 1u64 << idx)
 }
 
+/// The model name `name` resolves to: itself, or the model it is a catalog alias of.
+pub fn canonical_name(name: &str) -> Option<&'static str> {
+    MODEL_REGISTRY
+        .get_index(name)
+        .map(|idx| MODEL_REGISTRY.get_name(idx))
+}
+
+/// How a model name resolves in the global catalog, for logs and the admin API.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ModelInfo {
+    pub name: String,
+    /// Registry index; the model's bit in every mask is `1 << index`.
+    pub index: usize,
+    pub mask: u64,
+    /// Which providers list the model in their `model_list`.
+    pub geminicli: bool,
+    pub codex: bool,
+    pub antigravity: bool,
+    /// Other names that resolve to the same model (from `basic.model_catalog`).
+    pub aliases: Vec<String>,
+}
+
+/// Describe `name` as `mask` would resolve it: exact match against the global catalog, where
+/// an alias resolves to its model.
+///
+/// `None` means the name is in no provider's `model_list` nor the catalog file, so every route
+/// rejects it.
+pub fn describe(name: &str) -> Option<ModelInfo> {
+    describe_in(&CONFIG, &MODEL_REGISTRY, name)
+}
+
+fn describe_in(cfg: &Config, registry: &ModelRegistry, name: &str) -> Option<ModelInfo> {
+    let index = registry.get_index(name)?;
+    let name = registry.get_name(index);
+    let listed = |models: &[String]| models.iter().any(|model| model == name);
+    let providers = &cfg.providers;
+    Some(ModelInfo {
+        name: name.to_string(),
+        index,
+        mask: 1u64 << index,
+        geminicli: listed(&providers.geminicli.model_list),
+        codex: listed(&providers.codex.model_list),
+        antigravity: listed(&providers.antigravity.model_list),
+        aliases: registry
+            .aliases(index)
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+    })
+}
+
 /// Resolve a bitmask into a list of model names (best-effort).
 ///
 /// Unknown bits (outside the registry) are ignored here; use `format_model_mask` if you want
 /// those shown explicitly in logs.
 pub fn model_names_from_mask(model_mask: u64) -> Vec<String> {
-    let mut names = Vec::new();
-    for idx in 0..MODEL_REGISTRY.len() {
-        let bit = 1u64 << idx;
-        if (model_mask & bit) != 0 {
-            names.push(MODEL_REGISTRY.get_name(idx).to_string());
-        }
-    }
-    names
+    ModelMask::from_bits(model_mask)
+        .indices()
+        .take_while(|&idx| idx < MODEL_REGISTRY.len())
+        .map(|idx| MODEL_REGISTRY.get_name(idx).to_string())
+        .collect()
 }
 
 /// Human-friendly formatting for model masks, intended for logs.
 pub fn format_model_mask(model_mask: u64) -> String {
-    if model_mask == 0 {
+    let mask = ModelMask::from_bits(model_mask);
+    if mask.is_empty() {
         return "[]".to_string();
     }
 
     let names = model_names_from_mask(model_mask);
-    let unknown_bits = model_mask & !*MODEL_MASK_ALL;
+    let unknown_bits = mask.difference(ModelMask::from_bits(*MODEL_MASK_ALL));
 
-    if unknown_bits != 0 {
+    if !unknown_bits.is_empty() {
         format!(
             "[{}] (unknown_bits=0x{:016x})",
             names.join(", "),
-            unknown_bits
+            unknown_bits.bits()
         )
     } else {
         format!("[{}]", names.join(", "))
@@ -92,3 +149,40 @@ fn collect_global_model_names(cfg: &Config) -> Vec<String> {
 
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describe_reports_mask_and_providers_for_known_models() {
+        let mut cfg = Config::default();
+        cfg.providers.geminicli.model_list =
+            vec!["gemini-2.5-pro".into(), "gemini-2.5-flash".into()];
+        cfg.providers.codex.model_list = vec!["gpt-5".into()];
+        cfg.providers.antigravity.model_list = vec!["gemini-2.5-pro".into()];
+        let registry = ModelRegistry::new(&collect_global_model_names(&cfg));
+
+        assert_eq!(
+            describe_in(&cfg, &registry, "gemini-2.5-pro"),
+            Some(ModelInfo {
+                name: "gemini-2.5-pro".to_string(),
+                index: 0,
+                mask: 1,
+                geminicli: true,
+                codex: false,
+                antigravity: true,
+                aliases: Vec::new(),
+            })
+        );
+        let codex = describe_in(&cfg, &registry, "gpt-5").unwrap();
+        assert_eq!(
+            (codex.mask, codex.geminicli, codex.codex),
+            (1 << 2, false, true)
+        );
+
+        assert_eq!(describe_in(&cfg, &registry, "gemini-9-ultra"), None);
+        // Resolution is exact, like `mask`.
+        assert_eq!(describe_in(&cfg, &registry, "Gemini-2.5-Pro"), None);
+    }
+}
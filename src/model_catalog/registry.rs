@@ -1,3 +1,4 @@
+use super::file::ModelCatalogFile;
 use std::collections::HashMap;
 
 /// Immutable registry of model names and indices.
@@ -5,10 +6,11 @@ use std::collections::HashMap;
 /// `Index (usize)`, typically initialized once at startup (e.g., via `LazyLock`).
 #[derive(Debug, Clone)]
 pub struct ModelRegistry {
-    /// Name-to-index lookup for fast resolution during routing.
+    /// Name-to-index lookup for fast resolution during routing; aliases included.
     name_to_index: HashMap<String, usize>,
-    /// Index-to-name lookup for logs and diagnostics.
-    index_to_name: Vec<String>,
+    /// Index-to-name lookup for logs and diagnostics. `None` marks a bit left free between
+    /// models pinned by a catalog file.
+    index_to_name: Vec<Option<String>>,
 }
 
 impl ModelRegistry {
@@ -18,20 +20,67 @@ impl ModelRegistry {
     /// # Panics
     /// Panics if the number of models exceeds 64, because the bitset is `u64`.
     pub fn new(models: &[String]) -> Self {
-        if models.len() > 64 {
+        Self::with_catalog(models, &ModelCatalogFile::default())
+    }
+
+    /// Builds a registry from a validated catalog file plus the configured model names.
+    ///
+    /// Catalog models with a `mask` take that bit; the remaining catalog models, then any
+    /// configured name the catalog does not declare, fill the lowest free bits in order.
+    /// Catalog aliases resolve to their model's index.
+    ///
+    /// # Panics
+    /// Panics if the models need more than 64 bits, because the bitset is `u64`.
+    pub fn with_catalog(models: &[String], catalog: &ModelCatalogFile) -> Self {
+        let mut index_to_name: Vec<Option<String>> = Vec::new();
+        let mut name_to_index = HashMap::new();
+
+        for model in &catalog.models {
+            if let Some(mask) = model.mask {
+                let idx = mask.trailing_zeros() as usize;
+                if index_to_name.len() <= idx {
+                    index_to_name.resize(idx + 1, None);
+                }
+                index_to_name[idx] = Some(model.name.clone());
+                name_to_index.insert(model.name.clone(), idx);
+            }
+        }
+
+        let unpinned = catalog
+            .models
+            .iter()
+            .filter(|model| model.mask.is_none())
+            .map(|model| &model.name)
+            .chain(models);
+        for name in unpinned {
+            if name_to_index.contains_key(name) {
+                continue;
+            }
+            let idx = match index_to_name.iter().position(Option::is_none) {
+                Some(free) => free,
+                None => {
+                    index_to_name.push(None);
+                    index_to_name.len() - 1
+                }
+            };
+            index_to_name[idx] = Some(name.clone());
+            name_to_index.insert(name.clone(), idx);
+        }
+
+        if index_to_name.len() > 64 {
             panic!(
                 "ModelRegistry limits to 64 models (current: {}). \
                 Consider upgrading to u128 or separating clusters.",
-                models.len()
+                index_to_name.len()
             );
         }
 
-        let mut name_to_index = HashMap::with_capacity(models.len());
-        let mut index_to_name = Vec::with_capacity(models.len());
-
-        for (idx, name) in models.iter().enumerate() {
-            name_to_index.insert(name.clone(), idx);
-            index_to_name.push(name.clone());
+        for model in &catalog.models {
+            let idx = name_to_index[&model.name];
+            for alias in &model.aliases {
+                // A configured model name keeps its own bit; an alias never shadows it.
+                name_to_index.entry(alias.clone()).or_insert(idx);
+            }
         }
 
         Self {
@@ -54,11 +103,24 @@ impl ModelRegistry {
         // Index is expected to be valid internally; fallback avoids panic.
         self.index_to_name
             .get(index)
-            .map(|s| s.as_str())
+            .and_then(Option::as_deref)
             .unwrap_or("UNKNOWN_MODEL")
     }
 
-    /// Returns the total number of models in the registry.
+    /// Other names that resolve to `index`, sorted.
+    pub fn aliases(&self, index: usize) -> Vec<&str> {
+        let canonical = self.get_name(index);
+        let mut aliases: Vec<&str> = self
+            .name_to_index
+            .iter()
+            .filter(|(name, idx)| **idx == index && name.as_str() != canonical)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        aliases.sort_unstable();
+        aliases
+    }
+
+    /// Returns the number of model indices in use, counting bits left free by pinned masks.
     ///
     /// Used by: sizing the manager queue vectors.
     pub fn len(&self) -> usize {
@@ -70,3 +132,38 @@ impl ModelRegistry {
         self.index_to_name.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_catalog::file::CatalogModel;
+
+    fn model(name: &str, mask: Option<u64>, aliases: &[&str]) -> CatalogModel {
+        CatalogModel {
+            name: name.to_string(),
+            mask,
+            providers: Vec::new(),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn pinned_masks_hold_their_bit_and_the_rest_fill_free_bits() {
+        let catalog = ModelCatalogFile {
+            models: vec![
+                model("pinned", Some(1 << 3), &["pinned-latest"]),
+                model("catalog-only", None, &[]),
+            ],
+        };
+        let configured = ["gemini-2.5-pro".to_string(), "pinned".to_string()];
+        let registry = ModelRegistry::with_catalog(&configured, &catalog);
+
+        assert_eq!(registry.get_index("pinned"), Some(3));
+        assert_eq!(registry.get_index("pinned-latest"), Some(3));
+        assert_eq!(registry.get_index("catalog-only"), Some(0));
+        assert_eq!(registry.get_index("gemini-2.5-pro"), Some(1));
+        assert_eq!(registry.get_name(2), "UNKNOWN_MODEL");
+        assert_eq!(registry.len(), 4);
+        assert_eq!(registry.aliases(3), ["pinned-latest"]);
+    }
+}
@@ -1,19 +1,27 @@
 use crate::config::AntigravityResolvedConfig;
 use crate::error::{GeminiCliErrorBody, IsRetryable, PolluxError};
+use crate::providers::UpstreamClient;
 use crate::providers::antigravity::AntigravityActorHandle;
-use crate::providers::policy::classify_upstream_error;
+use crate::providers::conversation::ConversationId;
+use crate::providers::lease_log::{LeaseOutcome, LeaseRecorder};
+use crate::providers::policy::{
+    CapturedHeaders, classify_upstream_error, reject_empty_response, strip_internal_headers,
+};
 use crate::providers::provider_endpoints::ProviderEndpoints;
-use crate::providers::upstream_retry::post_json_with_retry;
+use crate::providers::upstream_retry::{post_json_with_retry, provider_retry_policy};
 use crate::utils::logging::with_pretty_json_debug;
+use async_trait::async_trait;
 use backon::{ExponentialBuilder, Retryable};
 use chrono::Utc;
 use pollux_schema::{
-    antigravity::AntigravityRequestMeta, gemini::GeminiGenerateContentRequest,
+    antigravity::{AntigravityRequestBody, AntigravityRequestMeta},
+    gemini::GeminiGenerateContentRequest,
     gemini::GenerationConfig,
 };
 use rand::Rng as _;
-use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue, USER_AGENT};
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderName, HeaderValue, USER_AGENT};
 use serde_json::{Value, json};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 use url::Url;
@@ -29,12 +37,18 @@ pub struct AntigravityContext {
     pub stream: bool,
     pub path: String,
     pub model_mask: u64,
+    pub conversation: Option<ConversationId>,
 }
 
 pub struct AntigravityClient {
     client: reqwest::Client,
     retry_policy: ExponentialBuilder,
+    retry_empty_responses: bool,
+    capture_headers: Arc<[HeaderName]>,
+    telemetry_headers: HeaderMap,
     endpoints: ProviderEndpoints,
+    leases: LeaseRecorder,
+    preamble_marker: String,
 }
 
 impl AntigravityClient {
@@ -43,11 +57,7 @@ impl AntigravityClient {
         client: reqwest::Client,
         base_url: Option<Url>,
     ) -> Self {
-        let retry_policy = ExponentialBuilder::default()
-            .with_min_delay(Duration::from_millis(100))
-            .with_max_delay(Duration::from_millis(300))
-            .with_max_times(cfg.retry_max_times)
-            .with_jitter();
+        let retry_policy = provider_retry_policy(cfg.retry_backoff, cfg.retry_max_times);
         let endpoints = base_url
             .map(Self::endpoints_for_base)
             .unwrap_or_else(Self::default_endpoints);
@@ -55,10 +65,21 @@ impl AntigravityClient {
         Self {
             client,
             retry_policy,
+            retry_empty_responses: cfg.retry_empty_responses,
+            capture_headers: cfg.capture_response_headers.clone().into(),
+            telemetry_headers: cfg.telemetry_headers.clone(),
             endpoints,
+            leases: LeaseRecorder::default(),
+            preamble_marker: cfg.preamble_marker.clone(),
         }
     }
 
+    /// Log every lease this client takes through `leases`.
+    pub(crate) fn with_lease_recorder(mut self, leases: LeaseRecorder) -> Self {
+        self.leases = leases;
+        self
+    }
+
     fn default_endpoints() -> ProviderEndpoints {
         Self::endpoints_for_base(
             Url::parse("https://daily-cloudcode-pa.googleapis.com")
@@ -76,12 +97,132 @@ impl AntigravityClient {
         )
     }
 
-    pub async fn call_antigravity(
+    /// Forward a raw upstream payload with a leased credential, once.
+    ///
+    /// Unlike [`UpstreamClient::call`], the payload is not wrapped, no preamble or
+    /// session id is injected, and upstream errors are returned as-is without
+    /// being reported to the actor. Only a missing top-level `project` is filled
+    /// from the lease.
+    pub async fn passthrough(
         &self,
         handle: &AntigravityActorHandle,
-        ctx: &AntigravityContext,
-        body: &GeminiGenerateContentRequest,
+        model_mask: u64,
+        stream: bool,
+        mut payload: Value,
     ) -> Result<reqwest::Response, PolluxError> {
+        let assigned = handle
+            .get_credential(model_mask)
+            .await?
+            .ok_or(PolluxError::NoAvailableCredential)?;
+
+        if let Some(obj) = payload.as_object_mut() {
+            obj.entry("project")
+                .or_insert_with(|| Value::String(assigned.project_id.clone()));
+        }
+
+        info!(
+            channel = "antigravity",
+            lease.id = assigned.id,
+            req.stream = stream,
+            "[Antigravity] [ID: {}] Raw passthrough",
+            assigned.id
+        );
+
+        let resp = self
+            .client
+            .post(self.endpoints.select(stream).clone())
+            .headers(Self::headers(
+                assigned.access_token.as_str(),
+                &self.telemetry_headers,
+            ))
+            .json(&payload)
+            .send()
+            .await?;
+        Ok(resp)
+    }
+
+    fn headers(access_token: &str, telemetry_headers: &HeaderMap) -> HeaderMap {
+        let mut headers = telemetry_headers.clone();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {access_token}"))
+                .expect("invalid fixed auth header value"),
+        );
+        headers.insert(
+            USER_AGENT,
+            HeaderValue::from_static("antigravity/1.16.5 linux/amd64"),
+        );
+        strip_internal_headers(&mut headers);
+        headers
+    }
+
+    fn request_id_from_parts(timestamp_ms: i64, request_uuid: Uuid) -> String {
+        format!("{REQUEST_ID_PREFIX}/{timestamp_ms}/{request_uuid}")
+    }
+
+    fn generate_request_id() -> String {
+        Self::request_id_from_parts(Utc::now().timestamp_millis(), Uuid::new_v4())
+    }
+
+    fn session_id_from_int(value: i64) -> String {
+        format!("-{value}")
+    }
+
+    fn generate_session_id() -> String {
+        let value = rand::rng().random_range(0..SESSION_ID_MAX_EXCLUSIVE);
+        Self::session_id_from_int(value)
+    }
+
+    /// Prepend `preamble` unless the first `systemInstruction` text already carries
+    /// `marker` (lowercased), so retried or client-forwarded requests are not injected twice.
+    fn ensure_claude_system_instruction(
+        payload: &mut AntigravityRequestBody,
+        preamble: &str,
+        marker: &str,
+    ) {
+        let already_present = payload
+            .request
+            .system_instruction
+            .as_ref()
+            .and_then(|content| content.parts.first())
+            .and_then(|part| part.text.as_deref())
+            .is_some_and(|text| text.to_lowercase().contains(marker));
+
+        if !already_present {
+            payload.prepend_system_instruction(preamble);
+        }
+    }
+
+    fn apply_claude_thinking_defaults(model: &str, request: &mut GeminiGenerateContentRequest) {
+        if !model.starts_with("claude") {
+            return;
+        }
+
+        let gen_config = request
+            .generation_config
+            .get_or_insert_with(GenerationConfig::default);
+
+        if gen_config.thinking_config.is_none() {
+            gen_config.thinking_config = Some(json!({
+                "includeThoughts": true,
+                "thinkingBudget": CLAUDE_THINKING_BUDGET,
+            }));
+        }
+    }
+}
+
+#[async_trait]
+impl UpstreamClient for AntigravityClient {
+    type Handle = AntigravityActorHandle;
+    type Context = AntigravityContext;
+    type Error = PolluxError;
+
+    async fn call(
+        &self,
+        handle: &Self::Handle,
+        ctx: &Self::Context,
+        body: &GeminiGenerateContentRequest,
+    ) -> Result<reqwest::Response, Self::Error> {
         let handle = handle.clone();
         let client = self.client.clone();
         let endpoints = self.endpoints.clone();
@@ -90,6 +231,11 @@ impl AntigravityClient {
         let model_mask = ctx.model_mask;
         let path = ctx.path.clone();
         let gemini_request = body.clone();
+        let preamble_marker = self.preamble_marker.clone();
+        let retry_empty_responses = self.retry_empty_responses;
+        let capture_headers = self.capture_headers.clone();
+        let telemetry_headers = self.telemetry_headers.clone();
+        let leases = self.leases.clone();
 
         let op = {
             let gemini_request = gemini_request.clone();
@@ -100,6 +246,10 @@ impl AntigravityClient {
                 let gemini_request = gemini_request.clone();
                 let model = model.clone();
                 let path = path.clone();
+                let preamble_marker = preamble_marker.clone();
+                let capture_headers = capture_headers.clone();
+                let telemetry_headers = telemetry_headers.clone();
+                let leases = leases.clone();
                 async move {
                     let start = Instant::now();
                     let assigned = handle
@@ -130,13 +280,13 @@ impl AntigravityClient {
 
                     Self::apply_claude_thinking_defaults(model.as_str(), &mut payload.request);
 
-                    payload.prepend_system_instruction(crate::config::CLAUDE_SYSTEM_PREAMBLE);
+                    Self::ensure_claude_system_instruction(
+                        &mut payload,
+                        crate::config::CLAUDE_SYSTEM_PREAMBLE,
+                        preamble_marker.as_str(),
+                    );
 
-                    payload
-                        .request
-                        .extra
-                        .entry("sessionId".to_string())
-                        .or_insert_with(|| Value::String(Self::generate_session_id()));
+                    payload.ensure_session_id(Self::generate_session_id);
 
                     with_pretty_json_debug(&payload, |pretty_payload| {
                         debug!(
@@ -154,20 +304,27 @@ impl AntigravityClient {
                         "Antigravity",
                         &client,
                         endpoints.select(stream),
-                        Some(Self::headers(assigned.access_token.as_str())),
+                        Some(Self::headers(
+                            assigned.access_token.as_str(),
+                            &telemetry_headers,
+                        )),
                         &payload,
                     )
-                    .await?;
+                    .await
+                    .inspect_err(|_| leases.record(assigned.id, &model, LeaseOutcome::Error))?;
 
                     if !resp.status().is_success() {
                         let status = resp.status();
+                        let captured = CapturedHeaders::capture(resp.headers(), &capture_headers);
 
                         let (action, final_error) = classify_upstream_error(
+                            "antigravity",
                             resp,
                             |_json: GeminiCliErrorBody| PolluxError::UpstreamStatus(status),
                             |status, _body| PolluxError::UpstreamStatus(status),
                         )
                         .await;
+                        leases.record(assigned.id, &model, LeaseOutcome::for_action(&action));
 
                         match &action {
                             crate::providers::ActionForError::RateLimit(duration) => {
@@ -201,11 +358,28 @@ impl AntigravityClient {
                             model = %model,
                             status = %status,
                             action = ?action,
+                            headers = %captured,
                             "[Antigravity] Upstream error"
                         );
 
                         return Err(final_error);
                     }
+                    if retry_empty_responses && !stream {
+                        let checked = reject_empty_response(resp).await.inspect_err(|_| {
+                            warn!(
+                                lease_id = assigned.id,
+                                model = %model,
+                                "[Antigravity] Upstream returned an empty response"
+                            );
+                        });
+                        let outcome = match &checked {
+                            Ok(_) => LeaseOutcome::Ok,
+                            Err(_) => LeaseOutcome::Empty,
+                        };
+                        leases.record(assigned.id, &model, outcome);
+                        return checked;
+                    }
+                    leases.record(assigned.id, &model, LeaseOutcome::Ok);
                     Ok(resp)
                 }
             }
@@ -222,54 +396,6 @@ impl AntigravityClient {
             })
             .await
     }
-
-    fn headers(access_token: &str) -> HeaderMap {
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            AUTHORIZATION,
-            HeaderValue::from_str(&format!("Bearer {access_token}"))
-                .expect("invalid fixed auth header value"),
-        );
-        headers.insert(
-            USER_AGENT,
-            HeaderValue::from_static("antigravity/1.16.5 linux/amd64"),
-        );
-        headers
-    }
-
-    fn request_id_from_parts(timestamp_ms: i64, request_uuid: Uuid) -> String {
-        format!("{REQUEST_ID_PREFIX}/{timestamp_ms}/{request_uuid}")
-    }
-
-    fn generate_request_id() -> String {
-        Self::request_id_from_parts(Utc::now().timestamp_millis(), Uuid::new_v4())
-    }
-
-    fn session_id_from_int(value: i64) -> String {
-        format!("-{value}")
-    }
-
-    fn generate_session_id() -> String {
-        let value = rand::rng().random_range(0..SESSION_ID_MAX_EXCLUSIVE);
-        Self::session_id_from_int(value)
-    }
-
-    fn apply_claude_thinking_defaults(model: &str, request: &mut GeminiGenerateContentRequest) {
-        if !model.starts_with("claude") {
-            return;
-        }
-
-        let gen_config = request
-            .generation_config
-            .get_or_insert_with(GenerationConfig::default);
-
-        if gen_config.thinking_config.is_none() {
-            gen_config.thinking_config = Some(json!({
-                "includeThoughts": true,
-                "thinkingBudget": CLAUDE_THINKING_BUDGET,
-            }));
-        }
-    }
 }
 
 #[cfg(test)]
@@ -359,6 +485,114 @@ mod tests {
         );
     }
 
+    fn payload_with_system_instruction(text: Option<&str>) -> AntigravityRequestBody {
+        let mut request = json!({
+            "contents": [{"role": "user", "parts": [{"text": "hello"}]}]
+        });
+        if let Some(text) = text {
+            request["systemInstruction"] = json!({"parts": [{"text": text}]});
+        }
+        AntigravityRequestMeta {
+            project: "project-1".to_string(),
+            request_id: "agent/1/00000000-0000-4000-8000-000000000000".to_string(),
+            model: "claude-sonnet-4-5-thinking".to_string(),
+        }
+        .into_request(serde_json::from_value(request).expect("request must parse"))
+    }
+
+    fn system_text(payload: &AntigravityRequestBody) -> Option<&str> {
+        payload
+            .request
+            .system_instruction
+            .as_ref()
+            .and_then(|content| content.parts.first())
+            .and_then(|part| part.text.as_deref())
+    }
+
+    #[test]
+    fn preamble_marker_uses_first_heading_or_first_line() {
+        assert_eq!(
+            crate::config::preamble_marker("You are X.**Absolute paths only****Proactiveness**"),
+            "absolute paths only"
+        );
+        assert_eq!(
+            crate::config::preamble_marker("\n  First Line  \nsecond"),
+            "first line"
+        );
+    }
+
+    #[test]
+    fn claude_preamble_injection_is_idempotent_for_any_preamble() {
+        for preamble in [
+            "You are Antigravity.**Absolute paths only****Proactiveness**",
+            "You are Someone Else.\n**Tool Etiquette**\nBe brief.",
+        ] {
+            let marker = crate::config::preamble_marker(preamble);
+
+            let mut payload = payload_with_system_instruction(Some("client rules"));
+            AntigravityClient::ensure_claude_system_instruction(&mut payload, preamble, &marker);
+            let once = system_text(&payload).map(str::to_owned);
+            assert_eq!(once, Some(format!("{preamble}\nclient rules")));
+
+            AntigravityClient::ensure_claude_system_instruction(&mut payload, preamble, &marker);
+            assert_eq!(system_text(&payload).map(str::to_owned), once);
+        }
+    }
+
+    #[test]
+    fn merged_system_turns_get_a_single_preamble() {
+        let preamble = "Intro **Proactiveness** rules";
+        let marker = crate::config::preamble_marker(preamble);
+        let mut request: GeminiGenerateContentRequest = serde_json::from_value(json!({
+            "contents": [
+                {"role": "system", "parts": [{"text": "turn rules"}]},
+                {"role": "user", "parts": [{"text": "hello"}]}
+            ],
+            "systemInstruction": {"parts": [{"text": "top rules"}]}
+        }))
+        .expect("request must parse");
+        request.merge_system_instructions().unwrap();
+
+        let mut payload = AntigravityRequestMeta {
+            project: "project-1".to_string(),
+            request_id: "agent/1/00000000-0000-4000-8000-000000000000".to_string(),
+            model: "claude-sonnet-4-5-thinking".to_string(),
+        }
+        .into_request(request);
+        for _ in 0..2 {
+            payload.request.merge_system_instructions().unwrap();
+            AntigravityClient::ensure_claude_system_instruction(&mut payload, preamble, &marker);
+        }
+
+        assert_eq!(
+            system_text(&payload),
+            Some("Intro **Proactiveness** rules\ntop rules\n\nturn rules")
+        );
+        assert_eq!(payload.request.contents.len(), 1);
+    }
+
+    #[test]
+    fn claude_preamble_detection_is_case_insensitive() {
+        let preamble = "Intro **Proactiveness** rules";
+        let marker = crate::config::preamble_marker(preamble);
+        let mut payload = payload_with_system_instruction(Some("Already has **PROACTIVENESS**"));
+
+        AntigravityClient::ensure_claude_system_instruction(&mut payload, preamble, &marker);
+
+        assert_eq!(system_text(&payload), Some("Already has **PROACTIVENESS**"));
+    }
+
+    #[test]
+    fn claude_preamble_is_injected_when_system_instruction_missing() {
+        let preamble = "Intro **Proactiveness** rules";
+        let marker = crate::config::preamble_marker(preamble);
+        let mut payload = payload_with_system_instruction(None);
+
+        AntigravityClient::ensure_claude_system_instruction(&mut payload, preamble, &marker);
+
+        assert_eq!(system_text(&payload), Some(preamble));
+    }
+
     #[test]
     fn non_claude_requests_do_not_get_thinking_config_default() {
         let request: GeminiGenerateContentRequest = serde_json::from_value(json!({
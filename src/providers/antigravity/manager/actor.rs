@@ -3,13 +3,15 @@ use super::scheduler::{CredentialId, CredentialManager};
 use crate::config::AntigravityResolvedConfig;
 use crate::db::{AntigravityCreate, AntigravityPatch};
 use crate::error::{OauthError, PolluxError};
-use crate::model_catalog::MODEL_REGISTRY;
+use crate::model_catalog::{MODEL_REGISTRY, ModelMask};
 use crate::oauth_utils::OauthTokenResponse;
 use crate::providers::antigravity::resource::AntigravityResource;
 use crate::providers::antigravity::workers::refresher::{
     AntigravityRefreshTokenSeed, RefreshOutcome,
 };
+use crate::providers::clock::SharedClock;
 use crate::providers::manifest::AntigravityLease;
+use crate::providers::pool_status::CredentialStatus;
 use oauth2::TokenResponse;
 use ractor::{Actor, ActorProcessingErr, ActorRef, RpcReplyPort};
 use std::{sync::Arc, time::Duration};
@@ -37,6 +39,9 @@ pub enum AntigravityActorMessage {
     /// Report a credential as banned/unusable; remove from queues and storage.
     ReportBaned { id: CredentialId },
 
+    /// Snapshot every credential's availability for the admin pool view.
+    PoolStatus(RpcReplyPort<Vec<CredentialStatus>>),
+
     /// Submit a trusted OAuth token response to the actor for onboarding + persistence.
     SubmitTrustedOauth(OauthTokenResponse),
 
@@ -100,6 +105,12 @@ impl AntigravityActorHandle {
         let _ = ractor::cast!(self.actor, AntigravityActorMessage::ReportBaned { id });
     }
 
+    /// Availability of every credential currently held by the actor.
+    pub async fn pool_status(&self) -> Result<Vec<CredentialStatus>, PolluxError> {
+        ractor::call!(self.actor, AntigravityActorMessage::PoolStatus)
+            .map_err(|e| PolluxError::RactorError(format!("PoolStatus RPC failed: {e}")))
+    }
+
     /// Submit a trusted OAuth token response to the actor.
     pub(crate) async fn submit_trusted_oauth(&self, token_response: OauthTokenResponse) {
         let _ = ractor::cast!(
@@ -134,7 +145,9 @@ struct AntigravityActorState {
     refresh_handle: crate::providers::antigravity::workers::refresher::AntigravityRefresherHandle,
 }
 
-struct AntigravityActor;
+struct AntigravityActor {
+    clock: SharedClock,
+}
 
 #[ractor::async_trait]
 impl Actor for AntigravityActor {
@@ -162,7 +175,9 @@ impl Actor for AntigravityActor {
             "AntigravityActor initializing"
         );
 
-        let mut manager = CredentialManager::new(model_count);
+        let mut manager = CredentialManager::new(model_count)
+            .with_clock(self.clock.clone())
+            .with_expiry_skew(cfg.expiry_skew);
         let rows = ops
             .load_active()
             .await
@@ -228,6 +243,10 @@ impl Actor for AntigravityActor {
                 self.handle_report_baned(state, id).await;
             }
 
+            AntigravityActorMessage::PoolStatus(reply_port) => {
+                let _ = reply_port.send(state.manager.pool_status());
+            }
+
             AntigravityActorMessage::SubmitTrustedOauth(token_response) => {
                 self.handle_submit_trusted_oauth(state, token_response)
                     .await;
@@ -259,7 +278,7 @@ impl AntigravityActor {
         id: CredentialId,
         model_mask: u64,
     ) {
-        if model_mask == 0 || !state.manager.contains(id) {
+        if ModelMask::from_bits(model_mask).is_empty() || !state.manager.contains(id) {
             return;
         }
 
@@ -564,12 +583,13 @@ impl AntigravityActor {
 pub(in crate::providers) async fn spawn(
     db: crate::db::DbActorHandle,
     cfg: Arc<AntigravityResolvedConfig>,
+    clock: SharedClock,
 ) -> AntigravityActorHandle {
     let ops = CredentialOps::new(db);
 
     let (actor, _jh) = Actor::spawn(
         Some("AntigravityMain".to_string()),
-        AntigravityActor,
+        AntigravityActor { clock },
         (ops, cfg),
     )
     .await
@@ -577,3 +597,82 @@ pub(in crate::providers) async fn spawn(
 
     AntigravityActorHandle { actor }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::clock::system_clock;
+    use axum::{Json, Router, routing::post};
+    use chrono::Utc;
+    use serde_json::{Value, json};
+    use tokio::net::TcpListener;
+
+    /// Token endpoint that rotates the refresh token on every refresh.
+    async fn rotating_token_handler() -> Json<Value> {
+        Json(json!({
+            "access_token": "access-new",
+            "refresh_token": "refresh-rotated",
+            "token_type": "Bearer",
+            "expires_in": 3600
+        }))
+    }
+
+    #[tokio::test]
+    async fn rotated_refresh_token_is_persisted() {
+        // NOTE: `crate::db::spawn()` and this actor register singleton ractor names within a
+        // process. Keep this the only test that spawns them in the library crate.
+        let mut db_path = std::env::temp_dir();
+        db_path.push(format!(
+            "pollux-antigravity-rotation-{}-{}.sqlite",
+            std::process::id(),
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let db = crate::db::spawn(&format!("sqlite:{}", db_path.display())).await;
+
+        // Expired access token, so the first lease request triggers a refresh.
+        db.create(crate::db::ProviderCreate::Antigravity(AntigravityCreate {
+            email: None,
+            project_id: "project-rotate".to_string(),
+            sub: Some("sub-rotate".to_string()),
+            refresh_token: "refresh-original".to_string(),
+            access_token: Some("access-old".to_string()),
+            expiry: Utc::now() - chrono::Duration::hours(1),
+        }))
+        .await
+        .expect("insert antigravity credential");
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/token", post(rotating_token_handler));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let mut cfg = crate::config::Config::default().antigravity();
+        cfg.model_list = vec!["gemini-2.5-pro".to_string()];
+        cfg.oauth_token_url = format!("http://{addr}/token").parse().unwrap();
+        let handle = spawn(db.clone(), Arc::new(cfg), system_clock()).await;
+
+        let model_mask = crate::model_catalog::mask("gemini-2.5-pro").expect("model in registry");
+        let _ = handle.get_credential(model_mask).await;
+
+        let mut row = None;
+        for _ in 0..100 {
+            let rows = db
+                .list_active_antigravity()
+                .await
+                .expect("list credentials");
+            if let Some(r) = rows
+                .into_iter()
+                .find(|r| r.access_token.as_deref() == Some("access-new"))
+            {
+                row = Some(r);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        let row = row.expect("refreshed credential persisted");
+        assert_eq!(row.refresh_token, "refresh-rotated");
+
+        let _ = tokio::fs::remove_file(&db_path).await;
+    }
+}
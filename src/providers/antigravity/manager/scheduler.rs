@@ -1,6 +1,8 @@
-use crate::model_catalog::ModelCapabilities;
+use crate::model_catalog::{ModelCapabilities, ModelMask};
 use crate::providers::antigravity::resource::AntigravityResource;
+use crate::providers::clock::{SharedClock, system_clock};
 use crate::providers::manifest::AntigravityLease;
+use crate::providers::pool_status::{self, CredentialStatus};
 use std::{
     cmp::Reverse,
     collections::{BinaryHeap, HashMap, HashSet, VecDeque},
@@ -27,10 +29,10 @@ impl RuntimeCredential {
         }
     }
 
-    /// Proxy: check expiration via the inner credential.
+    /// Proxy: check expiration via the inner credential as of `now`.
     #[inline(always)]
-    pub fn is_expired(&self) -> bool {
-        self.inner.is_expired()
+    pub fn is_expired_at(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.inner.is_expired_at(now)
     }
 }
 
@@ -53,6 +55,9 @@ pub struct CredentialManager {
     waiting_room: BinaryHeap<CooldownTicket>,
     cooldown_map: HashMap<(CredentialId, ModelIndex), Instant>,
     refreshing: HashSet<CredentialId>,
+    /// Added to "now" before token expiry checks; see `providers.defaults.expiry_skew_secs`.
+    expiry_skew: chrono::Duration,
+    clock: SharedClock,
 }
 
 impl Default for CredentialManager {
@@ -69,9 +74,23 @@ impl CredentialManager {
             waiting_room: BinaryHeap::new(),
             cooldown_map: HashMap::new(),
             refreshing: HashSet::new(),
+            expiry_skew: chrono::Duration::zero(),
+            clock: system_clock(),
         }
     }
 
+    /// Use `clock` for cooldown deadlines and token expiry instead of the system clock.
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Treat access tokens as expiring `skew` earlier than their recorded expiry.
+    pub fn with_expiry_skew(mut self, skew: Duration) -> Self {
+        self.expiry_skew = chrono::Duration::from_std(skew).unwrap_or(chrono::TimeDelta::MAX);
+        self
+    }
+
     pub fn add_credential(
         &mut self,
         id: CredentialId,
@@ -100,10 +119,7 @@ impl CredentialManager {
     }
 
     fn index_from_mask(&self, model_mask: u64) -> Option<ModelIndex> {
-        if model_mask == 0 || (model_mask & (model_mask - 1)) != 0 {
-            return None;
-        }
-        let index = model_mask.trailing_zeros() as usize;
+        let index = ModelMask::from_bits(model_mask).single_index()?;
         if index >= self.queues.len() {
             return None;
         }
@@ -140,7 +156,7 @@ impl CredentialManager {
         let Some(model_index) = self.index_from_mask(model_mask) else {
             return;
         };
-        let deadline = Instant::now() + cooldown;
+        let deadline = self.clock.now() + cooldown;
 
         self.cooldown_map.insert((id, model_index), deadline);
         self.waiting_room
@@ -170,6 +186,7 @@ impl CredentialManager {
             return result;
         };
 
+        let now = self.clock.utc_now() + self.expiry_skew;
         while let Some(id) = self.queues.get_mut(model_index).and_then(|q| q.pop_front()) {
             let Some(cred) = self.creds.get(&id) else {
                 continue;
@@ -186,7 +203,7 @@ impl CredentialManager {
             let Some(token) = cred
                 .inner
                 .access_token()
-                .filter(|_| !cred.is_expired())
+                .filter(|_| !cred.is_expired_at(now))
                 .map(str::to_owned)
             else {
                 result.refresh_ids.push(id);
@@ -208,7 +225,7 @@ impl CredentialManager {
     }
 
     fn process_waiting_room(&mut self) {
-        let now = Instant::now();
+        let now = self.clock.now();
 
         while self.waiting_room.peek().is_some_and(|t| (t.0).0 <= now) {
             let CooldownTicket(Reverse(ticket_deadline), credential_id, model_index) =
@@ -250,9 +267,25 @@ impl CredentialManager {
         self.cooldown_map.len()
     }
 
+    /// Per-credential availability for the admin pool view.
+    pub fn pool_status(&self) -> Vec<CredentialStatus> {
+        let now = self.clock.utc_now() + self.expiry_skew;
+        pool_status::collect(
+            self.creds.iter().map(|(id, cred)| {
+                (
+                    *id,
+                    cred.inner.access_token().is_some() && !cred.is_expired_at(now),
+                )
+            }),
+            |id| self.refreshing.contains(&id),
+            &self.cooldown_map,
+            self.clock.as_ref(),
+        )
+    }
+
     fn is_model_cooling(&self, id: CredentialId, model_index: ModelIndex) -> bool {
         match self.cooldown_map.get(&(id, model_index)) {
-            Some(deadline) => Instant::now() < *deadline,
+            Some(deadline) => self.clock.now() < *deadline,
             None => false,
         }
     }
@@ -1,5 +1,6 @@
 use crate::config::AntigravityResolvedConfig;
 use crate::db::DbActorHandle;
+use crate::providers::clock::SharedClock;
 use std::sync::Arc;
 
 pub mod client;
@@ -18,6 +19,7 @@ pub use thoughtsig::AntigravityThoughtSigService;
 pub(in crate::providers) async fn spawn(
     db: DbActorHandle,
     cfg: Arc<AntigravityResolvedConfig>,
+    clock: SharedClock,
 ) -> AntigravityActorHandle {
-    manager::spawn(db, cfg).await
+    manager::spawn(db, cfg, clock).await
 }
@@ -32,10 +32,10 @@ impl Default for AntigravityResource {
 }
 
 impl AntigravityResource {
-    /// Return true if current time is within 5 minutes of expiry (inclusive).
+    /// Return true if `now` is within 5 minutes of expiry (inclusive).
     /// This early-expiry buffer avoids edge cases during requests.
-    pub fn is_expired(&self) -> bool {
-        Utc::now() + Duration::minutes(5) >= self.expiry
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        now + Duration::minutes(5) >= self.expiry
     }
 
     pub fn project_id(&self) -> &str {
@@ -8,25 +8,49 @@ enum PatchDecision {
     Dropped { cache_key: Option<CacheKey> },
 }
 
-fn patch_part(part: &mut Part, engine: &ThoughtSignatureEngine) -> PatchDecision {
+fn patch_part(
+    part: &mut Part,
+    engine: &ThoughtSignatureEngine,
+    model: Option<&str>,
+) -> PatchDecision {
     // Keep the same priority as GeminiCLI: functionCall first, then thought text.
     if let Some(function_call) = part.function_call.as_ref() {
         let cache_key = CacheKeyGenerator::generate_json(function_call);
+        if engine.keep_existing(cache_key, part.thought_signature.as_deref()) {
+            return PatchDecision::Patched { cache_key };
+        }
         if let Some(signature) = cache_key.and_then(|key| engine.get_signature(&key)) {
             *part.thought_signature_mut() = Some(signature.to_string());
             return PatchDecision::Patched { cache_key };
         }
+        if !engine.fills_function_call_misses()
+            || (cache_key.is_none() && !engine.fills_unkeyed_parts())
+        {
+            return PatchDecision::Skipped;
+        }
+        let Some(dummy) = engine.fallback_signature_for(model) else {
+            return PatchDecision::Skipped;
+        };
 
-        *part.thought_signature_mut() = Some(engine.fallback_signature().to_string());
+        *part.thought_signature_mut() = Some(dummy.to_string());
         return PatchDecision::Patched { cache_key };
     }
 
     if part.thought == Some(true) {
+        if engine.strips_thoughts(model) {
+            return PatchDecision::Dropped { cache_key: None };
+        }
         let cache_key = part
             .text
             .as_deref()
             .and_then(CacheKeyGenerator::generate_text);
+        if engine.keep_existing(cache_key, part.thought_signature.as_deref()) {
+            return PatchDecision::Patched { cache_key };
+        }
         let Some(cache_key) = cache_key else {
+            if !engine.fills_unkeyed_parts() {
+                return PatchDecision::Skipped;
+            }
             return PatchDecision::Dropped { cache_key: None };
         };
 
@@ -48,12 +72,13 @@ fn patch_part(part: &mut Part, engine: &ThoughtSignatureEngine) -> PatchDecision
 pub(super) fn patch_request(
     request: &mut GeminiGenerateContentRequest,
     engine: &ThoughtSignatureEngine,
+    model: Option<&str>,
 ) {
     // Single-pass patch flow:
     // request.contents(model only) -> content.parts -> patch each part.
     // No pre-scan stage is needed.
     for (content_idx, content) in request.contents.iter_mut().enumerate() {
-        if content.role.as_deref() != Some("model") {
+        if !engine.is_model_role(content.role.as_deref()) {
             continue;
         }
 
@@ -62,7 +87,7 @@ pub(super) fn patch_request(
             let current_part_idx = part_idx;
             part_idx += 1;
 
-            match patch_part(part, engine) {
+            match patch_part(part, engine, model) {
                 PatchDecision::Skipped => true,
                 PatchDecision::Patched { cache_key } => {
                     debug!(
@@ -107,7 +132,7 @@ fn preview_signature(signature: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use pollux_thoughtsig_core::CacheKeyGenerator;
+    use pollux_thoughtsig_core::{CacheKeyGenerator, EnginePolicy, UnkeyedParts};
     use serde_json::json;
     use std::sync::Arc;
 
@@ -141,7 +166,7 @@ mod tests {
             ]
         }));
 
-        patch_request(&mut request, &engine);
+        patch_request(&mut request, &engine, None);
 
         assert!(request.contents[0].parts[0].thought_signature.is_none());
         assert!(request.contents[1].parts.is_empty());
@@ -180,7 +205,7 @@ mod tests {
             ]
         }));
 
-        patch_request(&mut request, &engine);
+        patch_request(&mut request, &engine, None);
 
         assert_eq!(
             request.contents[0].parts[0].thought_signature.as_deref(),
@@ -209,7 +234,7 @@ mod tests {
             ]
         }));
 
-        patch_request(&mut request, &engine);
+        patch_request(&mut request, &engine, None);
 
         assert_eq!(
             request.contents[0].parts[0].thought_signature.as_deref(),
@@ -233,7 +258,7 @@ mod tests {
             ]
         }));
 
-        patch_request(&mut request, &engine);
+        patch_request(&mut request, &engine, None);
         assert!(request.contents[0].parts[0].thought_signature.is_none());
     }
 
@@ -254,7 +279,7 @@ mod tests {
             ]
         }));
 
-        patch_request(&mut request, &engine);
+        patch_request(&mut request, &engine, None);
         assert!(request.contents[0].parts.is_empty());
     }
 
@@ -278,7 +303,7 @@ mod tests {
             ]
         }));
 
-        patch_request(&mut request, &engine);
+        patch_request(&mut request, &engine, None);
 
         assert_eq!(request.contents[0].parts.len(), 1);
         assert_eq!(
@@ -304,7 +329,32 @@ mod tests {
             ]
         }));
 
-        patch_request(&mut request, &engine);
+        patch_request(&mut request, &engine, None);
         assert!(request.contents[0].parts.is_empty());
     }
+
+    #[test]
+    fn patch_request_keeps_blank_thought_part_when_unkeyed_parts_are_kept() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024)
+            .with_policy(EnginePolicy::default().with_unkeyed_parts(UnkeyedParts::Keep));
+        let mut request = parse_request(json!({
+            "contents": [
+                {
+                    "role": "model",
+                    "parts": [
+                        {
+                            "thought": true,
+                            "text": "   "
+                        }
+                    ]
+                }
+            ]
+        }));
+
+        patch_request(&mut request, &engine, None);
+        let parts = &request.contents[0].parts;
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].text.as_deref(), Some("   "));
+        assert!(parts[0].thought_signature.is_none());
+    }
 }
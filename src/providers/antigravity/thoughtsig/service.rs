@@ -1,8 +1,15 @@
 use super::adapter_request::patch_request;
 use super::adapter_response::GeminiResponseAdapter;
+use crate::db::DbActorHandle;
+use crate::providers::signature_store::SqliteSignatureStore;
+use crate::providers::thoughtsig_selftest::{SELF_TEST_MODEL, SelfTestExchange};
 use pollux_schema::gemini::{GeminiGenerateContentRequest, GeminiResponseBody};
-use pollux_thoughtsig_core::{SignatureSniffer, ThoughtSignatureEngine};
+use pollux_thoughtsig_core::{
+    CacheKey, EnginePolicy, MokaSignatureStore, SignatureExpiry, SignatureSniffer, SignatureStore,
+    StoreError, StoreFootprint, ThoughtSignature, ThoughtSignatureEngine,
+};
 use std::sync::Arc;
+use std::time::Duration;
 
 const DEFAULT_TTL_SECS: u64 = 60 * 60;
 const DEFAULT_MAX_CAPACITY: u64 = 200_000;
@@ -20,24 +27,104 @@ impl Default for AntigravityThoughtSigService {
 
 impl AntigravityThoughtSigService {
     pub fn new() -> Self {
-        let engine = ThoughtSignatureEngine::new(DEFAULT_TTL_SECS, DEFAULT_MAX_CAPACITY);
+        Self::with_policy(EnginePolicy::default())
+    }
+
+    /// Service whose cache misses fall back to the dummy signatures in `policy`.
+    pub fn with_policy(policy: EnginePolicy) -> Self {
+        Self::with_policy_and_expiry(policy, SignatureExpiry::ttl(DEFAULT_TTL_SECS))
+    }
+
+    /// Like `with_policy`, with cache entries expiring per `expiry`.
+    pub fn with_policy_and_expiry(policy: EnginePolicy, expiry: SignatureExpiry) -> Self {
+        let store = MokaSignatureStore::with_expiry(expiry, DEFAULT_MAX_CAPACITY);
+        Self::with_policy_and_store(policy, Box::new(store))
+    }
+
+    /// Like `with_policy_and_expiry`, with signatures also kept in the database and the
+    /// unexpired ones reloaded from it.
+    pub async fn persisted(
+        policy: EnginePolicy,
+        expiry: SignatureExpiry,
+        db: DbActorHandle,
+    ) -> Self {
+        let store = SqliteSignatureStore::open(
+            db,
+            "antigravity",
+            expiry,
+            DEFAULT_MAX_CAPACITY,
+            Duration::from_secs(DEFAULT_TTL_SECS),
+        )
+        .await;
+        Self::with_policy_and_store(policy, Box::new(store))
+    }
+
+    fn with_policy_and_store(policy: EnginePolicy, store: Box<dyn SignatureStore>) -> Self {
+        let engine = ThoughtSignatureEngine::with_store(store).with_policy(policy);
 
         Self {
             engine: Arc::new(engine),
         }
     }
 
+    /// Maximum number of signatures the cache holds before evicting.
+    pub fn max_capacity(&self) -> u64 {
+        DEFAULT_MAX_CAPACITY
+    }
+
     pub fn patch_request(&self, request: &mut GeminiGenerateContentRequest) {
-        patch_request(request, self.engine.as_ref())
+        patch_request(request, self.engine.as_ref(), None)
+    }
+
+    /// Like `patch_request`, but cache misses get `model`'s dummy signature if one is configured.
+    pub fn patch_request_for_model(&self, model: &str, request: &mut GeminiGenerateContentRequest) {
+        patch_request(request, self.engine.as_ref(), Some(model))
     }
 
     pub fn build_sniffer(&self) -> SignatureSniffer {
         SignatureSniffer::new(self.engine.clone())
+            .with_duplicate_policy(self.engine.duplicate_policy())
     }
 
     pub fn sniff_response(&self, response: &GeminiResponseBody, sniffer: &mut SignatureSniffer) {
-        let adapter = GeminiResponseAdapter(response);
-        sniffer.inspect(&adapter);
+        for part in GeminiResponseAdapter(response).parts() {
+            sniffer.inspect(&part);
+        }
+    }
+
+    pub fn snapshot(&self) -> Result<Vec<(CacheKey, ThoughtSignature)>, StoreError> {
+        self.engine.snapshot()
+    }
+
+    pub fn footprint(&self) -> Result<StoreFootprint, StoreError> {
+        self.engine.footprint()
+    }
+
+    pub fn put_many(
+        &self,
+        entries: Vec<(CacheKey, ThoughtSignature)>,
+    ) -> Result<usize, StoreError> {
+        self.engine.put_many(entries)
+    }
+
+    /// Record a synthetic signature through the sniffer, then patch a request replaying it and
+    /// report whether the fill found it. The synthetic entry is dropped afterwards.
+    pub fn self_test(&self) -> bool {
+        let mut exchange = SelfTestExchange::new();
+        let mut sniffer = self.build_sniffer().with_model(SELF_TEST_MODEL);
+        self.sniff_response(&exchange.response, &mut sniffer);
+        patch_request(
+            &mut exchange.request,
+            self.engine.as_ref(),
+            Some(SELF_TEST_MODEL),
+        );
+        if let Some(key) = self
+            .engine
+            .json_key(Some(SELF_TEST_MODEL), &exchange.function_call)
+        {
+            let _ = self.engine.invalidate_signature(&key);
+        }
+        exchange.hit()
     }
 }
 
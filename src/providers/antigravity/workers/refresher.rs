@@ -219,8 +219,14 @@ async fn refresh_existing(
     let expiry = Utc::now()
         + ChronoDuration::from_std(expires_in).unwrap_or_else(|_| ChronoDuration::seconds(3600));
 
+    // Google may rotate the refresh token on use; persist the new one when it is returned.
+    let refresh_token = token
+        .refresh_token()
+        .map(|t| t.secret().to_string())
+        .filter(|t| !t.is_empty());
+
     Ok(AntigravityPatch {
-        refresh_token: None,
+        refresh_token,
         email: None,
         access_token: Some(access_token),
         expiry: Some(expiry),
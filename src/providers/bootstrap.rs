@@ -4,10 +4,15 @@ use crate::config::{
 use crate::db::DbActorHandle;
 use crate::providers::antigravity::AntigravityActorHandle;
 use crate::providers::antigravity::AntigravityThoughtSigService;
+use crate::providers::clock::system_clock;
 use crate::providers::codex::CodexActorHandle;
 use crate::providers::geminicli::{GeminiCliActorHandle, GeminiThoughtSigService};
+use crate::providers::lease_log::LeaseLog;
+use crate::providers::shared_signature_store::SharedSignatureStore;
+use pollux_thoughtsig_core::EnginePolicy;
 use std::sync::Arc;
-use tracing::info;
+use std::time::Duration;
+use tracing::{info, warn};
 
 /// Aggregates handles for all enabled providers.
 ///
@@ -23,14 +28,28 @@ pub struct Providers {
     pub antigravity: AntigravityActorHandle,
     pub antigravity_cfg: Arc<AntigravityResolvedConfig>,
     pub antigravity_thoughtsig: AntigravityThoughtSigService,
+    /// Lease audit log; `None` unless `basic.lease_log` is on.
+    pub lease_log: Option<LeaseLog>,
 }
 
 impl Providers {
     pub async fn spawn(db: DbActorHandle, cfg: &Config) -> Self {
+        Self::spawn_with_antigravity(db, cfg, cfg.antigravity()).await
+    }
+
+    /// Like [`Providers::spawn`], with the Antigravity config resolved by the caller.
+    ///
+    /// Its OAuth endpoints are not configurable from `config.toml`; tests use this to point
+    /// them (including the refresh worker's) at a local server.
+    pub async fn spawn_with_antigravity(
+        db: DbActorHandle,
+        cfg: &Config,
+        antigravity_cfg: AntigravityResolvedConfig,
+    ) -> Self {
         let provider_defaults = &cfg.providers.defaults;
         let geminicli_cfg = Arc::new(cfg.geminicli());
         let codex_cfg = Arc::new(cfg.codex());
-        let antigravity_cfg = Arc::new(cfg.antigravity());
+        let antigravity_cfg = Arc::new(antigravity_cfg);
 
         // Log resolved provider configs here so `main` stays wiring-only.
         info!(
@@ -68,11 +87,110 @@ impl Providers {
             "Antigravity config (effective)"
         );
 
-        let geminicli = crate::providers::geminicli::spawn(db.clone(), geminicli_cfg.clone()).await;
-        let geminicli_thoughtsig = GeminiThoughtSigService::new();
-        let codex = crate::providers::codex::spawn(db.clone(), codex_cfg.clone()).await;
-        let antigravity = crate::providers::antigravity::spawn(db, antigravity_cfg.clone()).await;
-        let antigravity_thoughtsig = AntigravityThoughtSigService::new();
+        let thoughtsig_policy = cfg.basic.thoughtsig_dummy_signatures.iter().fold(
+            EnginePolicy::default(),
+            |policy, (model, signature)| {
+                policy.with_model_dummy(model.as_str(), signature.as_str())
+            },
+        );
+        let thoughtsig_policy = cfg
+            .basic
+            .thoughtsig_no_dummy_models
+            .iter()
+            .fold(thoughtsig_policy, |policy, model| {
+                policy.without_model_dummy(model.as_str())
+            });
+        let thoughtsig_policy = cfg
+            .basic
+            .thoughtsig_strip_thoughts
+            .iter()
+            .fold(thoughtsig_policy, |policy, model| {
+                policy.with_thoughts_stripped(model.as_str())
+            });
+        let thoughtsig_policy = cfg
+            .basic
+            .thoughtsig_model_role_aliases
+            .iter()
+            .fold(thoughtsig_policy, |policy, role| {
+                policy.with_model_role_alias(role.as_str())
+            })
+            .with_existing_signatures(cfg.basic.thoughtsig_existing_signatures)
+            .with_unkeyed_parts(cfg.basic.thoughtsig_unkeyed_parts)
+            .with_duplicate_policy(cfg.basic.thoughtsig_duplicate_signatures);
+        let thoughtsig_policy = if cfg.basic.thoughtsig_dummy_function_calls {
+            thoughtsig_policy
+        } else {
+            thoughtsig_policy.without_function_call_dummies()
+        };
+        let thoughtsig_policy = if cfg.basic.thoughtsig_borrow_sibling_signatures {
+            thoughtsig_policy.with_sibling_signatures()
+        } else {
+            thoughtsig_policy
+        };
+        let thoughtsig_policy = match cfg.basic.thoughtsig_max_signature_bytes {
+            0 => thoughtsig_policy,
+            bytes => thoughtsig_policy.with_max_signature_len(bytes),
+        };
+        let thoughtsig_policy = match cfg.basic.thoughtsig_negative_cache_secs {
+            Some(secs) if secs > 0 => {
+                thoughtsig_policy.with_negative_cache(Duration::from_secs(secs))
+            }
+            _ => thoughtsig_policy,
+        }
+        .with_parallel_fill_threshold(cfg.basic.thoughtsig_parallel_fill_threshold);
+        let thoughtsig_expiry = cfg.basic.thoughtsig_expiry();
+
+        let lease_log = cfg.basic.lease_log.then(|| LeaseLog::new(db.clone()));
+        let clock = system_clock();
+        let geminicli =
+            crate::providers::geminicli::spawn(db.clone(), geminicli_cfg.clone(), clock.clone())
+                .await;
+        let (geminicli_thoughtsig, antigravity_thoughtsig) = if cfg.basic.thoughtsig_persist {
+            (
+                GeminiThoughtSigService::persisted(
+                    thoughtsig_policy.clone(),
+                    thoughtsig_expiry,
+                    db.clone(),
+                )
+                .await,
+                AntigravityThoughtSigService::persisted(
+                    thoughtsig_policy,
+                    thoughtsig_expiry,
+                    db.clone(),
+                )
+                .await,
+            )
+        } else {
+            (
+                GeminiThoughtSigService::with_policy_and_expiry(
+                    thoughtsig_policy.clone(),
+                    thoughtsig_expiry,
+                ),
+                AntigravityThoughtSigService::with_policy_and_expiry(
+                    thoughtsig_policy,
+                    thoughtsig_expiry,
+                ),
+            )
+        };
+        let geminicli_thoughtsig = match &cfg.basic.thoughtsig_redis_url {
+            Some(url) => {
+                match SharedSignatureStore::connect(url, "geminicli", thoughtsig_expiry).await {
+                    Ok(shared) => {
+                        info!("Sharing Gemini CLI thought signatures through Redis");
+                        geminicli_thoughtsig.with_shared_store(shared)
+                    }
+                    Err(err) => {
+                        warn!(error = %err, "Shared signature store unavailable; using the local cache only");
+                        geminicli_thoughtsig
+                    }
+                }
+            }
+            None => geminicli_thoughtsig,
+        };
+        let codex =
+            crate::providers::codex::spawn(db.clone(), codex_cfg.clone(), clock.clone()).await;
+        let antigravity =
+            crate::providers::antigravity::spawn(db, antigravity_cfg.clone(), clock).await;
 
         Self {
             geminicli,
@@ -83,6 +201,7 @@ impl Providers {
             antigravity,
             antigravity_cfg,
             antigravity_thoughtsig,
+            lease_log,
         }
     }
 }
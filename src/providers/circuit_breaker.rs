@@ -0,0 +1,155 @@
+use crate::providers::clock::{SharedClock, system_clock};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Per-host circuit breaker for upstream connections.
+///
+/// After `failure_threshold` consecutive connection failures to a host the circuit opens and
+/// calls to that host fail fast for `open_for`. Once that elapses a single half-open probe is let
+/// through: success closes the circuit, failure re-opens it for another `open_for`.
+#[derive(Debug)]
+pub(crate) struct HostCircuitBreaker {
+    failure_threshold: u32,
+    open_for: Duration,
+    hosts: Mutex<HashMap<String, HostState>>,
+    clock: SharedClock,
+}
+
+#[derive(Debug, Default)]
+struct HostState {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    /// Start of the in-flight half-open probe. A probe older than `open_for` is assumed lost
+    /// (e.g. its request was cancelled) and another one is allowed.
+    probe_started: Option<Instant>,
+}
+
+impl HostCircuitBreaker {
+    pub(crate) fn new(failure_threshold: u32, open_for: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            open_for,
+            hosts: Mutex::new(HashMap::new()),
+            clock: system_clock(),
+        }
+    }
+
+    #[cfg(test)]
+    fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Admit a call to `host`, or return how long until the open circuit allows a probe.
+    pub(crate) fn check(&self, host: &str) -> Result<(), Duration> {
+        let mut hosts = self.hosts.lock().expect("circuit breaker mutex poisoned");
+        let Some(state) = hosts.get_mut(host) else {
+            return Ok(());
+        };
+        let Some(opened_at) = state.opened_at else {
+            return Ok(());
+        };
+
+        let now = self.clock.now();
+        let open_until = opened_at + self.open_for;
+        if now < open_until {
+            return Err(open_until - now);
+        }
+        match state.probe_started {
+            Some(started) if now < started + self.open_for => Err(started + self.open_for - now),
+            _ => {
+                state.probe_started = Some(now);
+                Ok(())
+            }
+        }
+    }
+
+    /// The host answered (any HTTP status): close its circuit.
+    pub(crate) fn record_success(&self, host: &str) {
+        let mut hosts = self.hosts.lock().expect("circuit breaker mutex poisoned");
+        if let Some(state) = hosts.remove(host)
+            && state.opened_at.is_some()
+        {
+            info!(host, "Upstream host reachable again; circuit closed");
+        }
+    }
+
+    /// The host could not be reached: count it, opening the circuit at the threshold.
+    pub(crate) fn record_failure(&self, host: &str) {
+        let mut hosts = self.hosts.lock().expect("circuit breaker mutex poisoned");
+        let state = hosts.entry(host.to_string()).or_default();
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+
+        let probe_failed = state.probe_started.take().is_some();
+        if probe_failed || state.consecutive_failures >= self.failure_threshold {
+            if state.opened_at.is_none() || probe_failed {
+                warn!(
+                    host,
+                    consecutive_failures = state.consecutive_failures,
+                    open_for = ?self.open_for,
+                    "Upstream host unreachable; circuit opened"
+                );
+            }
+            state.opened_at = Some(self.clock.now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::clock::ManualClock;
+
+    #[test]
+    fn opens_after_threshold_and_fails_fast() {
+        let breaker = HostCircuitBreaker::new(3, Duration::from_secs(60));
+
+        for _ in 0..2 {
+            breaker.record_failure("a");
+            assert!(breaker.check("a").is_ok());
+        }
+        breaker.record_failure("a");
+
+        let retry_in = breaker.check("a").expect_err("circuit must be open");
+        assert!(retry_in <= Duration::from_secs(60));
+        // Other hosts are unaffected.
+        assert!(breaker.check("b").is_ok());
+    }
+
+    #[test]
+    fn success_resets_the_failure_count() {
+        let breaker = HostCircuitBreaker::new(2, Duration::from_secs(60));
+
+        breaker.record_failure("a");
+        breaker.record_success("a");
+        breaker.record_failure("a");
+        assert!(breaker.check("a").is_ok());
+    }
+
+    #[test]
+    fn half_open_probe_closes_or_reopens() {
+        let clock = ManualClock::new();
+        let breaker =
+            HostCircuitBreaker::new(1, Duration::from_secs(20)).with_clock(clock.shared());
+
+        breaker.record_failure("a");
+        assert!(breaker.check("a").is_err());
+        clock.advance(Duration::from_secs(30));
+
+        // One probe goes through; concurrent callers still fail fast.
+        assert!(breaker.check("a").is_ok());
+        assert!(breaker.check("a").is_err());
+
+        // A failed probe re-opens the circuit for another full period.
+        breaker.record_failure("a");
+        assert!(breaker.check("a").is_err());
+        clock.advance(Duration::from_secs(30));
+
+        assert!(breaker.check("a").is_ok());
+        breaker.record_success("a");
+        assert!(breaker.check("a").is_ok());
+        assert!(breaker.check("a").is_ok());
+    }
+}
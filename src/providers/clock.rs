@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Source of "now" for cooldowns, circuit breakers and token expiry.
+///
+/// Production code uses [`SystemClock`]; tests inject a [`ManualClock`] and advance it instead
+/// of sleeping.
+pub trait Clock: Send + Sync + fmt::Debug {
+    /// Monotonic time, for deadlines.
+    fn now(&self) -> Instant;
+
+    /// Wall-clock time, for comparing against persisted token expiries.
+    fn utc_now(&self) -> DateTime<Utc>;
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The process clock.
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn utc_now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock that only moves when [`ManualClock::advance`] is called. Clones share the same time.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    start: Instant,
+    start_utc: DateTime<Utc>,
+    elapsed: Arc<Mutex<Duration>>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ManualClock {
+    /// Frozen at the current system time.
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            start_utc: Utc::now(),
+            elapsed: Arc::default(),
+        }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.elapsed.lock().expect("manual clock mutex poisoned") += by;
+    }
+
+    pub fn shared(&self) -> SharedClock {
+        Arc::new(self.clone())
+    }
+
+    fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().expect("manual clock mutex poisoned")
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn utc_now(&self) -> DateTime<Utc> {
+        self.start_utc + chrono::Duration::from_std(self.elapsed()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock_moves_only_when_advanced() {
+        let clock = ManualClock::new();
+        let shared = clock.shared();
+        let (t0, w0) = (shared.now(), shared.utc_now());
+        assert_eq!(shared.now(), t0);
+
+        clock.advance(Duration::from_secs(90));
+        assert_eq!(shared.now() - t0, Duration::from_secs(90));
+        assert_eq!(shared.utc_now() - w0, chrono::Duration::seconds(90));
+    }
+}
@@ -1,15 +1,19 @@
 use crate::config::CodexResolvedConfig;
-use crate::error::{CodexError, IsRetryable};
+use crate::error::{CodexError, IsRetryable, PolluxError};
+use crate::providers::ActionForError;
 use crate::providers::codex::CodexActorHandle;
+use crate::providers::lease_log::{LeaseOutcome, LeaseRecorder};
 use crate::providers::manifest::CodexLease;
+use crate::providers::policy::{CapturedHeaders, classify_upstream_error, strip_internal_headers};
 use crate::providers::provider_endpoints::ProviderEndpoints;
 use crate::providers::upstream_retry::post_json_with_retry;
-use crate::providers::{ActionForError, policy::classify_upstream_error};
 use crate::utils::logging::with_pretty_json_debug;
 use backon::{ExponentialBuilder, Retryable};
 use pollux_schema::{CodexErrorBody, CodexRequestBody};
-use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderName, HeaderValue};
+use serde_json::Value;
 
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::info;
 use url::Url;
@@ -23,6 +27,8 @@ pub(crate) struct CodexClient {
     client: reqwest::Client,
     retry_policy: ExponentialBuilder,
     endpoints: ProviderEndpoints,
+    capture_headers: Arc<[HeaderName]>,
+    leases: LeaseRecorder,
 }
 
 impl CodexClient {
@@ -45,9 +51,17 @@ impl CodexClient {
             client,
             retry_policy,
             endpoints,
+            capture_headers: cfg.capture_response_headers.clone().into(),
+            leases: LeaseRecorder::default(),
         }
     }
 
+    /// Log every lease this client takes through `leases`.
+    pub(crate) fn with_lease_recorder(mut self, leases: LeaseRecorder) -> Self {
+        self.leases = leases;
+        self
+    }
+
     fn default_endpoints() -> ProviderEndpoints {
         Self::endpoints_for_base(
             Url::parse("https://chatgpt.com").expect("invalid fixed Codex base URL"),
@@ -77,6 +91,8 @@ impl CodexClient {
         let endpoints = self.endpoints.clone();
         let body = body.clone();
         let model = model.to_string();
+        let capture_headers = self.capture_headers.clone();
+        let leases = self.leases.clone();
 
         let op = move || {
             let handle = handle.clone();
@@ -84,6 +100,8 @@ impl CodexClient {
             let endpoints = endpoints.clone();
             let body = body.clone();
             let model = model.clone();
+            let capture_headers = capture_headers.clone();
+            let leases = leases.clone();
             async move {
                 let start = Instant::now();
                 let lease = handle
@@ -124,19 +142,24 @@ impl CodexClient {
                     Some(Self::headers(&lease)),
                     &body,
                 )
-                .await?;
+                .await
+                .inspect_err(|_| leases.record(lease.id, &model, LeaseOutcome::Error))?;
 
                 if resp.status().is_success() {
+                    leases.record(lease.id, &model, LeaseOutcome::Ok);
                     return Ok(resp);
                 }
 
                 let status = resp.status();
+                let captured = CapturedHeaders::capture(resp.headers(), &capture_headers);
                 let (action, final_error) = classify_upstream_error(
+                    "codex",
                     resp,
                     |json: CodexErrorBody| CodexError::UpstreamMappedError { status, body: json },
                     |status, body| CodexError::UpstreamFallbackError { status, body },
                 )
                 .await;
+                leases.record(lease.id, &model, LeaseOutcome::for_action(&action));
 
                 match &action {
                     ActionForError::RateLimit(duration) => {
@@ -166,6 +189,7 @@ impl CodexClient {
                             model = %model,
                             status = %status,
                             action = ?action,
+                            headers = %captured,
                             "[Codex] Upstream mapped error"
                         );
                     }
@@ -175,6 +199,7 @@ impl CodexClient {
                             model = %model,
                             status = %status,
                             action = ?action,
+                            headers = %captured,
                             "[Codex] Upstream fallback error"
                         );
                     }
@@ -210,6 +235,40 @@ impl CodexClient {
             .await
     }
 
+    /// Forward a raw upstream payload with a leased credential, once.
+    ///
+    /// The body is sent verbatim and the upstream response is returned regardless of
+    /// status; nothing is retried or reported back to the actor.
+    pub(crate) async fn passthrough(
+        &self,
+        handle: &CodexActorHandle,
+        model_mask: u64,
+        stream: bool,
+        payload: &Value,
+    ) -> Result<reqwest::Response, PolluxError> {
+        let lease = handle
+            .get_credential(model_mask)
+            .await?
+            .ok_or(PolluxError::NoAvailableCredential)?;
+
+        info!(
+            channel = "codex",
+            lease.id = lease.id,
+            req.stream = stream,
+            "[Codex] [ID: {}] Raw passthrough",
+            lease.id
+        );
+
+        let resp = self
+            .client
+            .post(self.endpoints.select(stream).clone())
+            .headers(Self::headers(&lease))
+            .json(payload)
+            .send()
+            .await?;
+        Ok(resp)
+    }
+
     fn headers(lease: &CodexLease) -> HeaderMap {
         let mut headers = HeaderMap::new();
         headers.insert(
@@ -222,6 +281,7 @@ impl CodexClient {
             HeaderValue::from_str(lease.account_id.as_str())
                 .expect("invalid fixed account id header value"),
         );
+        strip_internal_headers(&mut headers);
         headers
     }
 }
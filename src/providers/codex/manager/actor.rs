@@ -5,12 +5,14 @@ use super::{
 use crate::config::CodexResolvedConfig;
 use crate::db::CodexPatch;
 use crate::error::{OauthError, PolluxError};
-use crate::model_catalog::MODEL_REGISTRY;
+use crate::model_catalog::{MODEL_REGISTRY, ModelMask};
+use crate::providers::clock::SharedClock;
 use crate::providers::codex::resource::CodexResource;
 use crate::providers::codex::{
     CodexRefreshTokenSeed, SUPPORTED_MODEL_MASK, SUPPORTED_MODEL_NAMES, oauth::OauthTokenResponse,
 };
 use crate::providers::manifest::CodexLease;
+use crate::providers::pool_status::CredentialStatus;
 use ractor::{Actor, ActorProcessingErr, ActorRef, RpcReplyPort};
 use std::{sync::Arc, time::Duration};
 use tracing::{debug, error, info, warn};
@@ -39,6 +41,9 @@ pub enum CodexActorMessage {
     /// Report a credential as banned/unusable; remove from queues and storage.
     ReportBaned { id: CredentialId },
 
+    /// Snapshot every credential's availability for the admin pool view.
+    PoolStatus(RpcReplyPort<Vec<CredentialStatus>>),
+
     /// Submit a trusted OAuth token response (from the server-side OAuth exchange).
     ///
     /// This should already contain access_token + expiry + id_token. The actor will decode
@@ -104,6 +109,12 @@ impl CodexActorHandle {
         let _ = ractor::cast!(self.actor, CodexActorMessage::ReportBaned { id });
     }
 
+    /// Availability of every credential currently held by the actor.
+    pub async fn pool_status(&self) -> Result<Vec<CredentialStatus>, PolluxError> {
+        ractor::call!(self.actor, CodexActorMessage::PoolStatus)
+            .map_err(|e| PolluxError::RactorError(format!("PoolStatus RPC failed: {e}")))
+    }
+
     /// Submit a trusted OAuth token response to the actor for persistence + activation.
     pub(crate) async fn submit_trusted_oauth(&self, token_response: OauthTokenResponse) {
         let _ = ractor::cast!(
@@ -142,7 +153,9 @@ struct CodexActorState {
     refresh_handle: CodexRefresherHandle,
 }
 
-struct CodexActor;
+struct CodexActor {
+    clock: SharedClock,
+}
 
 #[ractor::async_trait]
 impl Actor for CodexActor {
@@ -168,7 +181,9 @@ impl Actor for CodexActor {
         let model_count = MODEL_REGISTRY.len();
         let model_caps_all = *SUPPORTED_MODEL_MASK;
 
-        let mut manager = CredentialManager::new(model_count);
+        let mut manager = CredentialManager::new(model_count)
+            .with_clock(self.clock.clone())
+            .with_expiry_skew(cfg.expiry_skew);
 
         let model_names = (*SUPPORTED_MODEL_NAMES).clone();
         info!(
@@ -239,6 +254,10 @@ impl Actor for CodexActor {
                 self.handle_report_baned(state, id).await;
             }
 
+            CodexActorMessage::PoolStatus(reply_port) => {
+                let _ = reply_port.send(state.manager.pool_status());
+            }
+
             CodexActorMessage::SubmitTrustedOauth(token_response) => {
                 self.handle_ingest_oauth_response(myself.clone(), state, token_response, None)
                     .await;
@@ -272,7 +291,7 @@ impl CodexActor {
         id: CredentialId,
         model_mask: u64,
     ) {
-        if model_mask == 0 || !state.manager.contains(id) {
+        if ModelMask::from_bits(model_mask).is_empty() || !state.manager.contains(id) {
             return;
         }
 
@@ -585,10 +604,15 @@ impl CodexActor {
 pub(in crate::providers) async fn spawn(
     db: crate::db::DbActorHandle,
     cfg: Arc<CodexResolvedConfig>,
+    clock: SharedClock,
 ) -> CodexActorHandle {
-    let (actor, _jh) = ractor::Actor::spawn(Some("CodexMain".to_string()), CodexActor, (db, cfg))
-        .await
-        .expect("failed to spawn CodexActor");
+    let (actor, _jh) = ractor::Actor::spawn(
+        Some("CodexMain".to_string()),
+        CodexActor { clock },
+        (db, cfg),
+    )
+    .await
+    .expect("failed to spawn CodexActor");
 
     CodexActorHandle { actor }
 }
@@ -1,6 +1,8 @@
-use crate::model_catalog::ModelCapabilities;
+use crate::model_catalog::{ModelCapabilities, ModelMask};
+use crate::providers::clock::{SharedClock, system_clock};
 use crate::providers::codex::resource::CodexResource;
 use crate::providers::manifest::CodexLease;
+use crate::providers::pool_status::{self, CredentialStatus};
 use std::{
     cmp::Reverse,
     collections::{BinaryHeap, HashMap, HashSet, VecDeque},
@@ -27,10 +29,10 @@ impl RuntimeCredential {
         }
     }
 
-    /// Proxy: check expiration via the inner credential.
+    /// Proxy: check expiration via the inner credential as of `now`.
     #[inline(always)]
-    pub fn is_expired(&self) -> bool {
-        self.inner.is_expired()
+    pub fn is_expired_at(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.inner.is_expired_at(now)
     }
 }
 
@@ -53,6 +55,9 @@ pub struct CredentialManager {
     waiting_room: BinaryHeap<CooldownTicket>,
     cooldown_map: HashMap<(CredentialId, ModelIndex), Instant>,
     refreshing: HashSet<CredentialId>,
+    /// Added to "now" before token expiry checks; see `providers.defaults.expiry_skew_secs`.
+    expiry_skew: chrono::Duration,
+    clock: SharedClock,
 }
 
 impl Default for CredentialManager {
@@ -69,9 +74,23 @@ impl CredentialManager {
             waiting_room: BinaryHeap::new(),
             cooldown_map: HashMap::new(),
             refreshing: HashSet::new(),
+            expiry_skew: chrono::Duration::zero(),
+            clock: system_clock(),
         }
     }
 
+    /// Use `clock` for cooldown deadlines and token expiry instead of the system clock.
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Treat access tokens as expiring `skew` earlier than their recorded expiry.
+    pub fn with_expiry_skew(mut self, skew: Duration) -> Self {
+        self.expiry_skew = chrono::Duration::from_std(skew).unwrap_or(chrono::TimeDelta::MAX);
+        self
+    }
+
     pub fn add_credential(
         &mut self,
         id: CredentialId,
@@ -108,6 +127,7 @@ impl CredentialManager {
             return result;
         };
 
+        let now = self.clock.utc_now() + self.expiry_skew;
         while let Some(id) = self.queues.get_mut(model_index).and_then(|q| q.pop_front()) {
             let Some(cred) = self.creds.get(&id) else {
                 continue;
@@ -121,7 +141,7 @@ impl CredentialManager {
                 continue;
             }
 
-            if cred.is_expired() {
+            if cred.is_expired_at(now) {
                 result.refresh_ids.push(id);
                 continue;
             }
@@ -143,7 +163,7 @@ impl CredentialManager {
     }
 
     fn process_waiting_room(&mut self) {
-        let now = Instant::now();
+        let now = self.clock.now();
 
         while self.waiting_room.peek().is_some_and(|t| (t.0).0 <= now) {
             let CooldownTicket(Reverse(ticket_deadline), credential_id, model_index) =
@@ -189,7 +209,7 @@ impl CredentialManager {
         let Some(model_index) = self.index_from_mask(model_mask) else {
             return;
         };
-        let deadline = Instant::now() + cooldown;
+        let deadline = self.clock.now() + cooldown;
 
         self.cooldown_map.insert((id, model_index), deadline);
         self.waiting_room
@@ -203,10 +223,7 @@ impl CredentialManager {
     }
 
     fn index_from_mask(&self, model_mask: u64) -> Option<ModelIndex> {
-        if model_mask == 0 || (model_mask & (model_mask - 1)) != 0 {
-            return None;
-        }
-        let index = model_mask.trailing_zeros() as usize;
+        let index = ModelMask::from_bits(model_mask).single_index()?;
         if index >= self.queues.len() {
             return None;
         }
@@ -249,9 +266,22 @@ impl CredentialManager {
         self.cooldown_map.len()
     }
 
+    /// Per-credential availability for the admin pool view.
+    pub fn pool_status(&self) -> Vec<CredentialStatus> {
+        let now = self.clock.utc_now() + self.expiry_skew;
+        pool_status::collect(
+            self.creds
+                .iter()
+                .map(|(id, cred)| (*id, !cred.is_expired_at(now))),
+            |id| self.refreshing.contains(&id),
+            &self.cooldown_map,
+            self.clock.as_ref(),
+        )
+    }
+
     fn is_model_cooling(&self, id: CredentialId, model_index: ModelIndex) -> bool {
         match self.cooldown_map.get(&(id, model_index)) {
-            Some(deadline) => Instant::now() < *deadline,
+            Some(deadline) => self.clock.now() < *deadline,
             None => false,
         }
     }
@@ -264,6 +294,7 @@ impl CredentialManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::providers::clock::ManualClock;
     use chrono::{Duration, Utc};
     use serde_json::json;
 
@@ -293,18 +324,22 @@ mod tests {
 
     #[test]
     fn cooldown_blocks_and_requeues() {
-        let mut manager = CredentialManager::new(1);
+        let clock = ManualClock::new();
+        let mut manager = CredentialManager::new(1).with_clock(clock.shared());
 
         let mut caps = ModelCapabilities::none();
         caps.enable(0);
         manager.add_credential(1, make_credential("acct1"), caps.bits());
 
-        manager.report_rate_limit(1, mask(0), std::time::Duration::from_millis(10));
+        manager.report_rate_limit(1, mask(0), std::time::Duration::from_secs(60));
 
         let assigned_during_cooldown = manager.get_assigned(mask(0)).assigned;
         assert!(assigned_during_cooldown.is_none());
 
-        std::thread::sleep(std::time::Duration::from_millis(30));
+        clock.advance(std::time::Duration::from_secs(59));
+        assert!(manager.get_assigned(mask(0)).assigned.is_none());
+
+        clock.advance(std::time::Duration::from_secs(1));
 
         let assigned_after = manager
             .get_assigned(mask(0))
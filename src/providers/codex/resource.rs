@@ -42,7 +42,12 @@ impl CodexResource {
     /// This early-expiry buffer avoids edge cases during requests.
     #[allow(dead_code)]
     pub fn is_expired(&self) -> bool {
-        Utc::now() + Duration::minutes(5) >= self.expiry
+        self.is_expired_at(Utc::now())
+    }
+
+    /// [`Self::is_expired`] as of `now`.
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        now + Duration::minutes(5) >= self.expiry
     }
 
     #[allow(dead_code)]
@@ -0,0 +1,105 @@
+use axum::http::HeaderMap;
+use pollux_schema::gemini::GeminiGenerateContentRequest;
+use pollux_thoughtsig_core::CacheKeyGenerator;
+
+/// Header a client sets to name the conversation a request belongs to.
+pub const CONVERSATION_ID_HEADER: &str = "x-pollux-conversation-id";
+
+/// Identifies the conversation a request continues, for features that follow one
+/// conversation across requests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversationId {
+    pub id: String,
+    /// `true` when the client sent the id in [`CONVERSATION_ID_HEADER`]; `false` when it was
+    /// derived from the first user turn, which distinct conversations can share.
+    pub explicit: bool,
+}
+
+impl ConversationId {
+    /// The header value if present, otherwise a fingerprint of the first user turn; `None`
+    /// when the request has neither.
+    ///
+    /// Every later turn of a conversation replays that first turn unchanged, so the derived
+    /// id stays the same as the history grows.
+    pub fn extract(headers: &HeaderMap, request: &GeminiGenerateContentRequest) -> Option<Self> {
+        let from_header = headers
+            .get(CONVERSATION_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|value| !value.is_empty());
+        if let Some(id) = from_header {
+            return Some(Self {
+                id: id.to_string(),
+                explicit: true,
+            });
+        }
+
+        let first_user_turn = request
+            .contents
+            .iter()
+            .find(|content| content.role.as_deref().is_none_or(|role| role == "user"))?;
+        // The default seed keeps derived ids independent of `basic.thoughtsig_hash_seed`.
+        let key = CacheKeyGenerator::default().json_key(&first_user_turn.parts)?;
+        Some(Self {
+            id: format!("derived-{key:016x}"),
+            explicit: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use serde_json::json;
+
+    fn request(turns: &[(&str, &str)]) -> GeminiGenerateContentRequest {
+        let contents: Vec<_> = turns
+            .iter()
+            .map(|(role, text)| json!({"role": role, "parts": [{"text": text}]}))
+            .collect();
+        serde_json::from_value(json!({ "contents": contents })).expect("request json must parse")
+    }
+
+    #[test]
+    fn header_id_is_used_as_sent() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONVERSATION_ID_HEADER, HeaderValue::from_static("chat-42"));
+
+        for turns in [
+            &[("user", "hi")][..],
+            &[("user", "hi"), ("model", "hello"), ("user", "more")][..],
+        ] {
+            let id = ConversationId::extract(&headers, &request(turns));
+            assert_eq!(
+                id,
+                Some(ConversationId {
+                    id: "chat-42".to_string(),
+                    explicit: true,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn derived_id_is_stable_across_turns() {
+        let headers = HeaderMap::new();
+        let first = ConversationId::extract(&headers, &request(&[("user", "hi")]))
+            .expect("user turn gives an id");
+        let later = ConversationId::extract(
+            &headers,
+            &request(&[("user", "hi"), ("model", "hello"), ("user", "more")]),
+        )
+        .expect("user turn gives an id");
+        let other = ConversationId::extract(&headers, &request(&[("user", "bye")]))
+            .expect("user turn gives an id");
+
+        assert!(!first.explicit);
+        assert_eq!(first, later);
+        assert_ne!(first, other);
+        assert_eq!(
+            ConversationId::extract(&headers, &request(&[("model", "hello")])),
+            None
+        );
+    }
+}
@@ -1,13 +1,20 @@
 use crate::config::GeminiCliResolvedConfig;
-use crate::error::{GeminiCliError, GeminiCliErrorBody, IsRetryable};
+use crate::error::{GeminiCliError, GeminiCliErrorBody, IsRetryable, PolluxError};
+use crate::providers::UpstreamClient;
 use crate::providers::geminicli::{GeminiCliActorHandle, GeminiContext};
-use crate::providers::policy::classify_upstream_error;
+use crate::providers::lease_log::{LeaseOutcome, LeaseRecorder};
+use crate::providers::policy::{
+    CapturedHeaders, classify_upstream_error, reject_empty_response, strip_internal_headers,
+};
 use crate::providers::provider_endpoints::ProviderEndpoints;
-use crate::providers::upstream_retry::post_json_with_retry;
+use crate::providers::upstream_retry::{post_json_with_retry, provider_retry_policy};
 use crate::utils::logging::with_pretty_json_debug;
+use async_trait::async_trait;
 use backon::{ExponentialBuilder, Retryable};
 use pollux_schema::{gemini::GeminiGenerateContentRequest, geminicli::GeminiCliRequestMeta};
-use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderName, HeaderValue};
+use serde_json::Value;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{debug, error, info, warn};
 use url::Url;
@@ -15,7 +22,11 @@ use url::Url;
 pub struct GeminiClient {
     client: reqwest::Client,
     retry_policy: ExponentialBuilder,
+    retry_empty_responses: bool,
+    capture_headers: Arc<[HeaderName]>,
+    telemetry_headers: HeaderMap,
     endpoints: ProviderEndpoints,
+    leases: LeaseRecorder,
 }
 
 impl GeminiClient {
@@ -24,11 +35,7 @@ impl GeminiClient {
         client: reqwest::Client,
         base_url: Option<Url>,
     ) -> Self {
-        let retry_policy = ExponentialBuilder::default()
-            .with_min_delay(Duration::from_millis(100))
-            .with_max_delay(Duration::from_millis(300))
-            .with_max_times(cfg.retry_max_times)
-            .with_jitter();
+        let retry_policy = provider_retry_policy(cfg.retry_backoff, cfg.retry_max_times);
         let endpoints = base_url
             .map(Self::endpoints_for_base)
             .unwrap_or_else(Self::default_endpoints);
@@ -36,10 +43,20 @@ impl GeminiClient {
         Self {
             client,
             retry_policy,
+            retry_empty_responses: cfg.retry_empty_responses,
+            capture_headers: cfg.capture_response_headers.clone().into(),
+            telemetry_headers: cfg.telemetry_headers.clone(),
             endpoints,
+            leases: LeaseRecorder::default(),
         }
     }
 
+    /// Log every lease this client takes through `leases`.
+    pub(crate) fn with_lease_recorder(mut self, leases: LeaseRecorder) -> Self {
+        self.leases = leases;
+        self
+    }
+
     fn default_endpoints() -> ProviderEndpoints {
         Self::endpoints_for_base(
             Url::parse("https://cloudcode-pa.googleapis.com")
@@ -57,20 +74,87 @@ impl GeminiClient {
         )
     }
 
-    pub async fn call_gemini_cli(
+    /// Forward a raw upstream payload with a leased credential, once.
+    ///
+    /// Used by the admin passthrough endpoint to observe upstream behavior without
+    /// any proxy logic in between: no retries, no error classification, and no
+    /// credential state reporting. A missing top-level `project` is filled from
+    /// the lease since callers cannot know which project they will be assigned.
+    pub async fn passthrough(
         &self,
         handle: &GeminiCliActorHandle,
-        ctx: &GeminiContext,
+        model_mask: u64,
+        stream: bool,
+        mut payload: Value,
+    ) -> Result<reqwest::Response, PolluxError> {
+        let assigned = handle
+            .get_credential(model_mask)
+            .await?
+            .ok_or(PolluxError::NoAvailableCredential)?;
+
+        if let Some(obj) = payload.as_object_mut() {
+            obj.entry("project")
+                .or_insert_with(|| Value::String(assigned.project_id.clone()));
+        }
+
+        info!(
+            channel = "geminicli",
+            lease.id = assigned.id,
+            req.stream = stream,
+            "[GeminiCli] [ID: {}] Raw passthrough",
+            assigned.id
+        );
+
+        let resp = self
+            .client
+            .post(self.endpoints.select(stream).clone())
+            .headers(Self::headers(
+                &assigned.access_token,
+                &self.telemetry_headers,
+            ))
+            .json(&payload)
+            .send()
+            .await?;
+        Ok(resp)
+    }
+
+    fn headers(access_token: &str, telemetry_headers: &HeaderMap) -> HeaderMap {
+        let mut headers = telemetry_headers.clone();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {access_token}"))
+                .expect("invalid fixed auth header value"),
+        );
+        strip_internal_headers(&mut headers);
+        headers
+    }
+}
+
+#[async_trait]
+impl UpstreamClient for GeminiClient {
+    type Handle = GeminiCliActorHandle;
+    type Context = GeminiContext;
+    type Error = GeminiCliError;
+
+    async fn call(
+        &self,
+        handle: &Self::Handle,
+        ctx: &Self::Context,
         body: &GeminiGenerateContentRequest,
-    ) -> Result<reqwest::Response, GeminiCliError> {
+    ) -> Result<reqwest::Response, Self::Error> {
         let base_request = body.clone();
         let model = ctx.model.clone();
         let model_mask = ctx.model_mask;
+        let priority = ctx.priority;
 
         let handle = handle.clone();
         let client = self.client.clone();
         let endpoints = self.endpoints.clone();
         let stream = ctx.stream;
+        let retry_empty_responses = self.retry_empty_responses;
+        let capture_headers = self.capture_headers.clone();
+        let telemetry_headers = self.telemetry_headers.clone();
+        let leases = self.leases.clone();
 
         let op = {
             move || {
@@ -79,10 +163,13 @@ impl GeminiClient {
                 let endpoints = endpoints.clone();
                 let base_request = base_request.clone();
                 let model = model.clone();
+                let capture_headers = capture_headers.clone();
+                let telemetry_headers = telemetry_headers.clone();
+                let leases = leases.clone();
                 async move {
                     let start = Instant::now();
                     let assigned = handle
-                        .get_credential(model_mask)
+                        .get_credential_with_priority(model_mask, priority)
                         .await?
                         .ok_or(GeminiCliError::NoAvailableCredential)?;
 
@@ -117,12 +204,7 @@ impl GeminiClient {
                         );
                     });
 
-                    let mut headers = HeaderMap::new();
-                    headers.insert(
-                        AUTHORIZATION,
-                        HeaderValue::from_str(&format!("Bearer {}", assigned.access_token))
-                            .expect("invalid fixed auth header value"),
-                    );
+                    let headers = Self::headers(&assigned.access_token, &telemetry_headers);
 
                     let resp = post_json_with_retry(
                         "GeminiCLI",
@@ -131,11 +213,14 @@ impl GeminiClient {
                         Some(headers),
                         &payload,
                     )
-                    .await?;
+                    .await
+                    .inspect_err(|_| leases.record(assigned.id, &model, LeaseOutcome::Error))?;
                     if !resp.status().is_success() {
                         let status = resp.status();
+                        let captured = CapturedHeaders::capture(resp.headers(), &capture_headers);
 
                         let (action, final_error) = classify_upstream_error(
+                            "geminicli",
                             resp,
                             |json: GeminiCliErrorBody| GeminiCliError::UpstreamMappedError {
                                 status,
@@ -144,6 +229,7 @@ impl GeminiClient {
                             |status, body| GeminiCliError::UpstreamFallbackError { status, body },
                         )
                         .await;
+                        leases.record(assigned.id, &model, LeaseOutcome::for_action(&action));
 
                         match &action {
                             crate::providers::ActionForError::RateLimit(duration) => {
@@ -179,6 +265,7 @@ impl GeminiClient {
                                     model = %model,
                                     status = %status,
                                     action = ?action,
+                                    headers = %captured,
                                     "[GeminiCli] Upstream mapped error"
                                 );
                             }
@@ -188,6 +275,7 @@ impl GeminiClient {
                                     model = %model,
                                     status = %status,
                                     action = ?action,
+                                    headers = %captured,
                                     "[GeminiCli] Upstream fallback error"
                                 );
                             }
@@ -213,6 +301,23 @@ impl GeminiClient {
 
                         return Err(final_error);
                     }
+                    if retry_empty_responses && !stream {
+                        let checked = reject_empty_response(resp).await.map_err(|e| {
+                            warn!(
+                                lease_id = assigned.id,
+                                model = %model,
+                                "[GeminiCli] Upstream returned an empty response"
+                            );
+                            GeminiCliError::from(e)
+                        });
+                        let outcome = match &checked {
+                            Ok(_) => LeaseOutcome::Ok,
+                            Err(_) => LeaseOutcome::Empty,
+                        };
+                        leases.record(assigned.id, &model, outcome);
+                        return checked;
+                    }
+                    leases.record(assigned.id, &model, LeaseOutcome::Ok);
                     Ok(resp)
                 }
             }
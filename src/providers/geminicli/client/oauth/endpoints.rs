@@ -1,13 +1,14 @@
 use super::types::UserTier;
 use crate::error::{OauthError, PolluxError};
 use crate::providers::geminicli::{
-    GEMINICLI_SCOPES, GOOGLE_AUTH_URL, GOOGLE_TOKEN_URI, LOAD_CODE_ASSIST_URL, OAUTH_CALLBACK_URL,
-    ONBOARD_CODE_ASSIST_URL,
+    GEMINICLI_SCOPES, GOOGLE_AUTH_URL, GOOGLE_DEVICE_AUTH_URL, GOOGLE_TOKEN_URI,
+    LOAD_CODE_ASSIST_URL, OAUTH_CALLBACK_URL, ONBOARD_CODE_ASSIST_URL,
 };
 use oauth2::{
     AuthUrl, AuthorizationCode, Client as OAuth2Client, ClientId, ClientSecret, CsrfToken,
-    EndpointNotSet, EndpointSet, ExtraTokenFields, PkceCodeChallenge, PkceCodeVerifier,
-    RefreshToken, StandardRevocableToken, StandardTokenResponse, TokenUrl,
+    DeviceAuthorizationUrl, EndpointNotSet, EndpointSet, ExtraTokenFields, PkceCodeChallenge,
+    PkceCodeVerifier, RefreshToken, StandardDeviceAuthorizationResponse, StandardRevocableToken,
+    StandardTokenResponse, TokenUrl,
     basic::{
         BasicErrorResponse, BasicRevocationErrorResponse, BasicTokenIntrospectionResponse,
         BasicTokenType,
@@ -26,6 +27,24 @@ const GCLI_CLIENT_ID: &str =
     "681255809395-oo8ft2oprdrnp9e3aqf6av3hmdib135j.apps.googleusercontent.com";
 const GCLI_CLIENT_SECRET: &str = "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";
 
+/// Endpoints used by the device-code grant (RFC 8628).
+///
+/// Defaults to Google; tests point both at a local mock server.
+#[derive(Debug, Clone)]
+pub(crate) struct DeviceFlowUrls {
+    pub device_auth_url: String,
+    pub token_url: String,
+}
+
+impl Default for DeviceFlowUrls {
+    fn default() -> Self {
+        Self {
+            device_auth_url: GOOGLE_DEVICE_AUTH_URL.to_string(),
+            token_url: GOOGLE_TOKEN_URI.to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct OnboardMetadata {
@@ -48,9 +67,10 @@ impl Default for OnboardMetadata {
     }
 }
 
+/// Body of Cloud Code's `onboardUser` call.
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct OnboardRequest {
+pub(crate) struct OnboardRequest {
     tier_id: UserTier,
     #[serde(skip_serializing_if = "Option::is_none")]
     cloudaicompanion_project: Option<String>,
@@ -58,6 +78,19 @@ struct OnboardRequest {
     metadata: OnboardMetadata,
 }
 
+impl OnboardRequest {
+    pub(crate) fn new(tier: UserTier, cloudaicompanion_project: Option<String>) -> Self {
+        Self {
+            tier_id: tier,
+            cloudaicompanion_project: cloudaicompanion_project.clone(),
+            metadata: OnboardMetadata {
+                duet_project: cloudaicompanion_project,
+                ..Default::default()
+            },
+        }
+    }
+}
+
 pub(crate) static OAUTH_CLIENT: LazyLock<GoogleOauth2Client> =
     LazyLock::new(|| build_oauth2_client().expect("valid Google OAuth2 client with redirect"));
 
@@ -82,12 +115,15 @@ impl GoogleOauthEndpoints {
         req.url()
     }
 
-    /// Refresh the access token using the current refresh token.
+    /// Refresh the access token at `token_url` using the current refresh token.
     pub(crate) async fn refresh_access_token(
+        token_url: &url::Url,
         refresh_token: &str,
         http_client: reqwest::Client,
     ) -> Result<GoogleTokenResponse, OauthError> {
         let token_result: GoogleTokenResponse = Self::client()
+            .clone()
+            .set_token_uri(TokenUrl::from_url(token_url.clone()))
             .exchange_refresh_token(&RefreshToken::new(refresh_token.to_string()))
             .request_async(&http_client)
             .await?;
@@ -109,6 +145,52 @@ impl GoogleOauthEndpoints {
         Ok(token_result)
     }
 
+    /// Start a device-code grant: returns the user code and verification URL to show the user.
+    pub(crate) async fn request_device_code(
+        urls: &DeviceFlowUrls,
+        http_client: reqwest::Client,
+    ) -> Result<StandardDeviceAuthorizationResponse, OauthError> {
+        let details: StandardDeviceAuthorizationResponse = build_device_client(urls)?
+            .exchange_device_code()
+            .add_scopes(GEMINICLI_SCOPES.iter().cloned())
+            .request_async(&http_client)
+            .await?;
+        Ok(details)
+    }
+
+    /// Poll the token endpoint until the user approves the device code, it expires, or is denied.
+    pub(crate) async fn poll_device_token(
+        urls: &DeviceFlowUrls,
+        details: &StandardDeviceAuthorizationResponse,
+        http_client: reqwest::Client,
+    ) -> Result<GoogleTokenResponse, OauthError> {
+        let token_result: GoogleTokenResponse = build_device_client(urls)?
+            .exchange_device_access_token(details)
+            .request_async(&http_client, tokio::time::sleep, None)
+            .await?;
+        info!("OAuth2 device code exchange completed successfully");
+        Ok(token_result)
+    }
+
+    /// Revoke a token at Google; revoking a refresh token also invalidates its access tokens.
+    pub(crate) async fn revoke_token(
+        revoke_url: &url::Url,
+        token: &str,
+        http_client: reqwest::Client,
+    ) -> Result<(), OauthError> {
+        let resp = http_client
+            .post(revoke_url.as_str())
+            .form(&[("token", token)])
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(OauthError::UpstreamStatus(resp.status()));
+        }
+        info!("OAuth2 token revoked");
+        Ok(())
+    }
+
     /// Call Cloud Code's loadCodeAssist to fetch subscription metadata and the companion project.
     pub(crate) async fn load_code_assist(
         access_token: impl AsRef<str>,
@@ -136,14 +218,7 @@ impl GoogleOauthEndpoints {
         cloudaicompanion_project: Option<String>,
         http_client: reqwest::Client,
     ) -> Result<Value, OauthError> {
-        let request = OnboardRequest {
-            tier_id: tier,
-            cloudaicompanion_project: cloudaicompanion_project.clone(),
-            metadata: OnboardMetadata {
-                duet_project: cloudaicompanion_project,
-                ..Default::default()
-            },
-        };
+        let request = OnboardRequest::new(tier, cloudaicompanion_project);
 
         let resp = http_client
             .post(ONBOARD_CODE_ASSIST_URL)
@@ -171,6 +246,22 @@ fn build_oauth2_client() -> Result<GoogleOauth2Client, PolluxError> {
     Ok(client)
 }
 
+/// Build a Google OAuth2 client for the device-code grant.
+fn build_device_client(
+    urls: &DeviceFlowUrls,
+) -> Result<GoogleOauth2Client<EndpointNotSet, EndpointSet>, OauthError> {
+    let invalid = |e: url::ParseError| OauthError::Other {
+        message: format!("invalid device flow URL: {e}"),
+    };
+    let client = OAuth2Client::new(ClientId::new(GCLI_CLIENT_ID.to_string()))
+        .set_client_secret(ClientSecret::new(GCLI_CLIENT_SECRET.to_string()))
+        .set_device_authorization_url(
+            DeviceAuthorizationUrl::new(urls.device_auth_url.clone()).map_err(invalid)?,
+        )
+        .set_token_uri(TokenUrl::new(urls.token_url.clone()).map_err(invalid)?);
+    Ok(client)
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub(crate) struct GoogleTokenField {
     #[serde(rename = "id_token")]
@@ -78,6 +78,8 @@ pub struct ProjectObject {
 pub struct OnboardResultPayload {
     #[serde(rename = "cloudaicompanionProject")]
     pub project_details: Option<ProjectObject>,
+    #[serde(default)]
+    pub current_tier: Option<TierInfo>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -90,6 +92,16 @@ pub struct OnboardOperationResponse {
     pub response: Option<OnboardResultPayload>,
 }
 
+impl OnboardOperationResponse {
+    /// Quota tier the provisioned project ended up on, if the operation reports one.
+    pub fn quota_tier(&self) -> Option<UserTier> {
+        self.response
+            .as_ref()
+            .and_then(|r| r.current_tier.as_ref())
+            .and_then(|t| t.quota_tier.clone().or_else(|| Some(t.id.clone().into())))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(from = "String", into = "String")]
 pub enum UserTier {
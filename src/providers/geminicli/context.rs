@@ -1,7 +1,12 @@
+use crate::providers::conversation::ConversationId;
+use crate::providers::priority::LeasePriority;
+
 #[derive(Debug, Clone)]
 pub struct GeminiContext {
     pub model: String,
     pub stream: bool,
     pub path: String,
     pub model_mask: u64,
+    pub conversation: Option<ConversationId>,
+    pub priority: LeasePriority,
 }
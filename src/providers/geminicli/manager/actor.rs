@@ -1,22 +1,34 @@
 use super::{
     ops::CredentialOps,
     scheduler::{CredentialId, CredentialManager},
+    tier::TierPolicy,
 };
 use crate::config::GeminiCliResolvedConfig;
 use crate::db::GeminiCliPatch;
 use crate::error::{OauthError, PolluxError};
-use crate::model_catalog::MODEL_REGISTRY;
+use crate::model_catalog::{MODEL_REGISTRY, ModelMask};
+use crate::providers::clock::SharedClock;
 use crate::providers::geminicli::client::oauth::endpoints::GoogleTokenResponse;
 use crate::providers::geminicli::client::oauth::utils::attach_email_from_id_token;
+use crate::providers::geminicli::onboard_progress::{OnboardProgress, OnboardProgressTx};
 use crate::providers::geminicli::resource::GeminiCliResource;
 use crate::providers::geminicli::workers::{
     GeminiCliRefresherHandle, RefreshError, RefreshJob, RefreshResult, TaskType,
 };
 use crate::providers::geminicli::{SUPPORTED_MODEL_MASK, SUPPORTED_MODEL_NAMES};
 use crate::providers::manifest::{GeminiCliLease, GeminiCliProfile};
+use crate::providers::pool_status::CredentialStatus;
+use crate::providers::priority::LeasePriority;
+use chrono::{DateTime, Utc};
 use ractor::{Actor, ActorProcessingErr, ActorRef, RpcReplyPort};
+use serde::Serialize;
 use serde_json::json;
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
 #[derive(Debug, Clone)]
@@ -36,8 +48,10 @@ impl GeminiCliRefreshTokenSeed {
 
 /// Public messages handled by the Gemini CLI actor.
 pub enum GeminiCliActorMessage {
-    /// Request one available credential for the given model mask. Err if none available.
-    GetCredential(u64, RpcReplyPort<Option<GeminiCliLease>>),
+    /// Request one available credential for the given model mask. `None` if none is available;
+    /// `Err` if the credentials' quota tiers do not allow the model at all. The priority orders
+    /// leases waiting for a credential to free up.
+    GetCredential(u64, LeasePriority, LeaseReply),
     /// Report rate limiting for a model mask; start cooldown with lazy re-enqueue.
     ReportRateLimit {
         id: CredentialId,
@@ -48,13 +62,23 @@ pub enum GeminiCliActorMessage {
     ReportModelUnsupported { id: CredentialId, model_mask: u64 },
     /// Report invalid/expired access (e.g. 401/403); refresh then re-enqueue.
     ReportInvalid { id: CredentialId },
-    /// Report a credential as banned/unusable; remove from queues and storage.
+    /// Report a credential as banned/unusable; remove from queues and storage, or park it for
+    /// a later probe when `ban_probe_after` is configured.
     ReportBaned { id: CredentialId },
+    /// Snapshot every credential's availability for the admin pool view.
+    PoolStatus(RpcReplyPort<Vec<CredentialStatus>>),
+    /// Look up the refresh token of an active credential.
+    GetRefreshToken(CredentialId, RpcReplyPort<Option<String>>),
+    /// Remove a credential from queues and mark it disabled in storage; replies once persisted.
+    DisableCredential(CredentialId, RpcReplyPort<Result<(), PolluxError>>),
+    /// Refresh a credential's access token now; replies with the new expiry once persisted.
+    ForceRefresh(CredentialId, ForceRefreshReply),
 
-    /// Submit a batch of credentials and trigger one refresh pass for each.
-    SubmitCredentials(Vec<GeminiCliProfile>),
+    /// Submit a batch of credentials and trigger one refresh pass for each accepted one;
+    /// replies with one outcome per submitted credential, in order.
+    SubmitCredentials(Vec<GeminiCliProfile>, RpcReplyPort<Vec<SubmitOutcome>>),
     /// Submit a trusted OAuth token response to the actor for onboarding + persistence.
-    SubmitTrustedOauth(GoogleTokenResponse),
+    SubmitTrustedOauth(GoogleTokenResponse, Option<OnboardProgressTx>),
     /// Submit refresh tokens as 0-trust seeds. The actor will refresh, onboard, then persist+activate.
     SubmitUntrustedSeeds(Vec<GeminiCliRefreshTokenSeed>),
 
@@ -66,8 +90,23 @@ pub enum GeminiCliActorMessage {
         id: CredentialId,
         credential: GeminiCliResource,
     },
+    /// A rate-limit cooldown has run out; hand the credential to a waiting lease.
+    CooldownElapsed { id: CredentialId },
+}
+
+/// Result for one credential passed to [`GeminiCliActorHandle::submit_credentials`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum SubmitOutcome {
+    /// Queued for onboarding; later failures are only logged.
+    Accepted,
+    /// Not queued.
+    Rejected { reason: String },
 }
 
+type ForceRefreshReply = RpcReplyPort<Result<DateTime<Utc>, PolluxError>>;
+type LeaseReply = RpcReplyPort<Result<Option<GeminiCliLease>, PolluxError>>;
+
 /// Handle for interacting with the Gemini CLI actor.
 #[derive(Clone)]
 pub struct GeminiCliActorHandle {
@@ -80,8 +119,23 @@ impl GeminiCliActorHandle {
         &self,
         model_mask: u64,
     ) -> Result<Option<GeminiCliLease>, PolluxError> {
-        ractor::call!(self.actor, GeminiCliActorMessage::GetCredential, model_mask)
-            .map_err(|e| PolluxError::RactorError(format!("GetCredential RPC failed:: {e}")))
+        self.get_credential_with_priority(model_mask, LeasePriority::Normal)
+            .await
+    }
+
+    /// Like `get_credential`, served ahead of lower-priority leases waiting for a credential.
+    pub async fn get_credential_with_priority(
+        &self,
+        model_mask: u64,
+        priority: LeasePriority,
+    ) -> Result<Option<GeminiCliLease>, PolluxError> {
+        ractor::call!(
+            self.actor,
+            GeminiCliActorMessage::GetCredential,
+            model_mask,
+            priority
+        )
+        .map_err(|e| PolluxError::RactorError(format!("GetCredential RPC failed:: {e}")))?
     }
 
     /// Report rate limit; the actor will cool down this credential before reuse.
@@ -114,19 +168,73 @@ impl GeminiCliActorHandle {
         let _ = ractor::cast!(self.actor, GeminiCliActorMessage::ReportBaned { id });
     }
 
-    /// Submit new credentials to the actor and trigger refresh for each.
-    pub async fn submit_credentials(&self, creds: Vec<GeminiCliProfile>) {
-        let _ = ractor::cast!(self.actor, GeminiCliActorMessage::SubmitCredentials(creds));
+    /// Availability of every credential currently held by the actor.
+    pub async fn pool_status(&self) -> Result<Vec<CredentialStatus>, PolluxError> {
+        ractor::call!(self.actor, GeminiCliActorMessage::PoolStatus)
+            .map_err(|e| PolluxError::RactorError(format!("PoolStatus RPC failed: {e}")))
+    }
+
+    /// Refresh token of an active credential, or `None` if the id is unknown or disabled.
+    pub(crate) async fn refresh_token_of(
+        &self,
+        id: CredentialId,
+    ) -> Result<Option<String>, PolluxError> {
+        ractor::call!(self.actor, GeminiCliActorMessage::GetRefreshToken, id)
+            .map_err(|e| PolluxError::RactorError(format!("GetRefreshToken RPC failed: {e}")))
+    }
+
+    /// Remove a credential from rotation and persist `status = false`.
+    pub(crate) async fn disable_credential(&self, id: CredentialId) -> Result<(), PolluxError> {
+        ractor::call!(self.actor, GeminiCliActorMessage::DisableCredential, id)
+            .map_err(|e| PolluxError::RactorError(format!("DisableCredential RPC failed: {e}")))?
+    }
+
+    /// Refresh a credential's access token immediately and return its new expiry.
+    pub(crate) async fn force_refresh(
+        &self,
+        id: CredentialId,
+    ) -> Result<DateTime<Utc>, PolluxError> {
+        ractor::call!(self.actor, GeminiCliActorMessage::ForceRefresh, id)
+            .map_err(|e| PolluxError::RactorError(format!("ForceRefresh RPC failed: {e}")))?
+    }
+
+    /// Submit new credentials to the actor and trigger refresh for each accepted one.
+    ///
+    /// Returns one outcome per credential, in submission order.
+    pub async fn submit_credentials(
+        &self,
+        creds: Vec<GeminiCliProfile>,
+    ) -> Result<Vec<SubmitOutcome>, PolluxError> {
+        ractor::call!(self.actor, GeminiCliActorMessage::SubmitCredentials, creds)
+            .map_err(|e| PolluxError::RactorError(format!("SubmitCredentials RPC failed: {e}")))
     }
 
     /// Submit a trusted OAuth token response to the actor for persistence + activation.
     pub(crate) async fn submit_trusted_oauth(&self, token_response: GoogleTokenResponse) {
         let _ = ractor::cast!(
             self.actor,
-            GeminiCliActorMessage::SubmitTrustedOauth(token_response)
+            GeminiCliActorMessage::SubmitTrustedOauth(token_response, None)
         );
     }
 
+    /// [`Self::submit_trusted_oauth`], returning a feed of onboarding steps that ends with
+    /// [`OnboardProgress::Stored`] or [`OnboardProgress::Failed`].
+    pub(crate) async fn submit_trusted_oauth_with_progress(
+        &self,
+        token_response: GoogleTokenResponse,
+    ) -> mpsc::UnboundedReceiver<OnboardProgress> {
+        let (progress, rx) = OnboardProgressTx::channel();
+        if let Err(e) = ractor::cast!(
+            self.actor,
+            GeminiCliActorMessage::SubmitTrustedOauth(token_response, Some(progress.clone()))
+        ) {
+            progress.send(OnboardProgress::Failed {
+                message: format!("Gemini CLI actor unavailable: {e}"),
+            });
+        }
+        rx
+    }
+
     /// Submit refresh tokens as 0-trust seeds. The actor will refresh, onboard, then persist+activate.
     pub(crate) async fn submit_refresh_tokens(&self, refresh_tokens: Vec<String>) {
         let seeds: Vec<GeminiCliRefreshTokenSeed> = refresh_tokens
@@ -161,11 +269,27 @@ struct GeminiCliActorState {
     ops: CredentialOps,
     manager: CredentialManager,
     model_caps_all: u64,
+    tier_policy: TierPolicy,
     refresh_handle: GeminiCliRefresherHandle,
+    /// Admin callers waiting on an in-flight refresh, keyed by credential.
+    refresh_waiters: HashMap<CredentialId, Vec<ForceRefreshReply>>,
+    /// Hold a lease for an in-flight refresh when no valid credential is queued.
+    refresh_on_lease: bool,
+    /// Leases that found no free credential while one for their model was refreshing, keyed
+    /// by priority then arrival, with the model mask they asked for.
+    lease_waiters: BTreeMap<(LeasePriority, u64), (u64, LeaseReply)>,
+    /// Arrival counter for `lease_waiters`.
+    next_lease_seq: u64,
+    /// Grace period before a banned credential is re-probed; `None` keeps bans permanent.
+    ban_probe_after: Option<Duration>,
+    /// Refuse onboarded credentials whose project already has an active one.
+    reject_duplicate_projects: bool,
 }
 
 /// ractor-based Gemini CLI actor.
-struct GeminiCliActor;
+struct GeminiCliActor {
+    clock: SharedClock,
+}
 
 #[ractor::async_trait]
 impl Actor for GeminiCliActor {
@@ -190,7 +314,12 @@ impl Actor for GeminiCliActor {
         let model_count = MODEL_REGISTRY.len();
         let model_caps_all = *SUPPORTED_MODEL_MASK;
 
-        let mut manager = CredentialManager::new(model_count);
+        let mut manager = CredentialManager::new(model_count)
+            .with_clock(self.clock.clone())
+            .with_expiry_skew(cfg.expiry_skew);
+        if let Some(cap) = cfg.max_active_credentials {
+            manager = manager.with_hot_cap(cap);
+        }
 
         let model_names = (*SUPPORTED_MODEL_NAMES).clone();
         info!(
@@ -203,13 +332,16 @@ impl Actor for GeminiCliActor {
             .await
             .map_err(|e| ActorProcessingErr::from(format!("DB load active creds failed: {}", e)))?;
 
+        let tier_policy = TierPolicy::from_config(&cfg.tier_models);
         for (id, cred) in rows {
-            manager.add_credential(id, cred, model_caps_all);
+            let caps = tier_policy.caps_for(cred.quota_tier(), model_caps_all);
+            manager.add_credential(id, cred, caps);
         }
 
         info!(
-            "GeminiCliActor started from DB: {} active creds loaded into {} queues",
+            "GeminiCliActor started from DB: {} active creds loaded ({} queued) into {} queues",
             manager.total_creds(),
+            manager.hot_len(),
             model_count
         );
 
@@ -224,7 +356,14 @@ impl Actor for GeminiCliActor {
             ops,
             manager,
             model_caps_all,
+            tier_policy,
             refresh_handle,
+            refresh_waiters: HashMap::new(),
+            refresh_on_lease: cfg.refresh_on_lease,
+            lease_waiters: BTreeMap::new(),
+            next_lease_seq: 0,
+            ban_probe_after: cfg.ban_probe_after,
+            reject_duplicate_projects: cfg.reject_duplicate_projects,
         })
     }
 
@@ -235,8 +374,8 @@ impl Actor for GeminiCliActor {
         state: &mut Self::State,
     ) -> Result<(), ActorProcessingErr> {
         match message {
-            GeminiCliActorMessage::GetCredential(model_mask, rp) => {
-                self.handle_get_credential(myself.clone(), state, rp, model_mask)
+            GeminiCliActorMessage::GetCredential(model_mask, priority, rp) => {
+                self.handle_get_credential(myself.clone(), state, rp, model_mask, priority, true)
                     .await;
             }
 
@@ -245,7 +384,7 @@ impl Actor for GeminiCliActor {
                 cooldown,
                 model_mask,
             } => {
-                self.handle_report_rate_limit(state, id, cooldown, model_mask);
+                self.handle_report_rate_limit(&myself, state, id, cooldown, model_mask);
             }
             GeminiCliActorMessage::ReportModelUnsupported { id, model_mask } => {
                 self.handle_report_model_unsupported(state, id, model_mask);
@@ -258,11 +397,50 @@ impl Actor for GeminiCliActor {
             GeminiCliActorMessage::ReportBaned { id } => {
                 self.handle_report_baned(state, id).await;
             }
-            GeminiCliActorMessage::SubmitCredentials(creds_vec) => {
-                self.handle_submit_credentials(state, creds_vec).await;
+            GeminiCliActorMessage::PoolStatus(reply_port) => {
+                let _ = reply_port.send(state.manager.pool_status());
+            }
+            GeminiCliActorMessage::GetRefreshToken(id, reply_port) => {
+                let refresh_token = state
+                    .manager
+                    .get_full_credential_copy(id)
+                    .map(|cred| cred.refresh_token().to_string());
+                let _ = reply_port.send(refresh_token);
             }
-            GeminiCliActorMessage::SubmitTrustedOauth(token_response) => {
-                self.handle_submit_trusted_oauth(state, token_response)
+            GeminiCliActorMessage::DisableCredential(id, reply_port) => {
+                state.manager.delete_credential(id);
+                let ops = state.ops.clone();
+                tokio::spawn(async move {
+                    let result = ops.set_status(id, false).await;
+                    if result.is_ok() {
+                        info!("ID: {id} disabled by admin");
+                    }
+                    let _ = reply_port.send(result);
+                });
+            }
+            GeminiCliActorMessage::ForceRefresh(id, reply_port) => {
+                if !state.manager.contains(id) {
+                    let _ = reply_port.send(Err(PolluxError::NotFound(format!(
+                        "no active credential with id {id}"
+                    ))));
+                } else {
+                    info!("ID: {id} refresh requested by admin");
+                    state
+                        .refresh_waiters
+                        .entry(id)
+                        .or_default()
+                        .push(reply_port);
+                    // Joins the in-flight refresh if there is one.
+                    self.handle_report_invalid(myself.clone(), state, vec![id])
+                        .await;
+                }
+            }
+            GeminiCliActorMessage::SubmitCredentials(creds_vec, reply_port) => {
+                let outcomes = self.handle_submit_credentials(state, creds_vec);
+                let _ = reply_port.send(outcomes);
+            }
+            GeminiCliActorMessage::SubmitTrustedOauth(token_response, progress) => {
+                self.handle_submit_trusted_oauth(state, token_response, progress)
                     .await;
             }
             GeminiCliActorMessage::SubmitUntrustedSeeds(seeds) => {
@@ -274,10 +452,17 @@ impl Actor for GeminiCliActor {
             }
             GeminiCliActorMessage::ActivateCredential { id, credential } => {
                 let project = credential.project_id().to_string();
-                state
-                    .manager
-                    .add_credential(id, credential, state.model_caps_all);
+                let caps = state
+                    .tier_policy
+                    .caps_for(credential.quota_tier(), state.model_caps_all);
+                state.manager.add_credential(id, credential, caps);
                 info!("ID: {id}, Project: {project}, submitted and activated");
+                self.serve_lease_waiters(myself.clone(), state, Some(id))
+                    .await;
+            }
+            GeminiCliActorMessage::CooldownElapsed { id } => {
+                self.serve_lease_waiters(myself.clone(), state, Some(id))
+                    .await;
             }
         }
         Ok(())
@@ -291,7 +476,7 @@ impl GeminiCliActor {
         id: CredentialId,
         model_mask: u64,
     ) {
-        if model_mask == 0 || !state.manager.contains(id) {
+        if ModelMask::from_bits(model_mask).is_empty() || !state.manager.contains(id) {
             return;
         }
 
@@ -327,10 +512,15 @@ impl GeminiCliActor {
         &self,
         myself: ActorRef<GeminiCliActorMessage>,
         state: &mut GeminiCliActorState,
-        reply_port: RpcReplyPort<Option<GeminiCliLease>>,
+        reply_port: LeaseReply,
         model_mask: u64,
+        priority: LeasePriority,
+        wait_for_refresh: bool,
     ) {
+        self.start_ban_probes(&myself, state);
+
         let assignment = state.manager.get_assigned(model_mask);
+        let refresh_target = assignment.refresh_ids.first().copied();
 
         if !assignment.refresh_ids.is_empty() {
             self.handle_report_invalid(myself, state, assignment.refresh_ids)
@@ -345,7 +535,36 @@ impl GeminiCliActor {
                 model_mask,
                 state.manager.queue_len(model_mask)
             );
-            let _ = reply_port.send(Some(assigned));
+            let _ = reply_port.send(Ok(Some(assigned)));
+            return;
+        }
+
+        // Only expired credentials left: wait in line for a credential to free up.
+        if wait_for_refresh
+            && state.refresh_on_lease
+            && let Some(id) = refresh_target.or_else(|| state.manager.refreshing_for(model_mask))
+            && state.manager.is_refreshing(id)
+        {
+            debug!(
+                "ID: {id} {priority:?}-priority lease for model_mask=0x{model_mask:016x} waiting on refresh"
+            );
+            let seq = state.next_lease_seq;
+            state.next_lease_seq += 1;
+            state
+                .lease_waiters
+                .insert((priority, seq), (model_mask, reply_port));
+            return;
+        }
+
+        if !state.manager.supports_any(model_mask)
+            && let Some(reason) = state.tier_policy.rejection(
+                state.manager.quota_tiers(),
+                model_mask,
+                &crate::model_catalog::format_model_mask(model_mask),
+            )
+        {
+            warn!("Credential lease refused: {reason}");
+            let _ = reply_port.send(Err(PolluxError::TierNotAllowed(reason)));
             return;
         }
 
@@ -356,11 +575,12 @@ impl GeminiCliActor {
             state.manager.cooldown_len(),
             state.manager.refreshing_len()
         );
-        let _ = reply_port.send(None);
+        let _ = reply_port.send(Ok(None));
     }
 
     fn handle_report_rate_limit(
         &self,
+        myself: &ActorRef<GeminiCliActorMessage>,
         state: &mut GeminiCliActorState,
         id: CredentialId,
         cooldown: Duration,
@@ -369,13 +589,19 @@ impl GeminiCliActor {
         if !state.manager.contains(id) {
             return;
         }
-        state.manager.report_rate_limit(id, model_mask, cooldown);
+        let promoted = state.manager.report_rate_limit(id, model_mask, cooldown);
 
         info!(
             "ID: {id}, Credential starting cooldown for model_mask=0x{:016x}, lazy re-enqueue after {} secs",
             model_mask,
             cooldown.as_secs(),
         );
+        if let Some(promoted) = promoted {
+            info!("ID: {id} rotated out of the active set; ID: {promoted} rotated in");
+        }
+        myself.send_after(cooldown, move || GeminiCliActorMessage::CooldownElapsed {
+            id,
+        });
     }
 
     // handle_report_invalid, handle_report_baned, handle_submit_credentials
@@ -412,6 +638,7 @@ impl GeminiCliActor {
             let task = RefreshJob {
                 cred,
                 r#type: TaskType::Refresh(id),
+                progress: None,
             };
             if let Err(e) = refresh_handle.submit_refresh(task.clone()) {
                 warn!("ID: {id} Batch refresh enqueue failed. Rolling back.");
@@ -437,7 +664,12 @@ impl GeminiCliActor {
             .unwrap_or_else(|| "-".to_string());
         let removed_cred = state.manager.contains(id);
 
-        state.manager.delete_credential(id);
+        let parked = state
+            .ban_probe_after
+            .is_some_and(|grace| state.manager.park_banned(id, grace));
+        if !parked {
+            state.manager.delete_credential(id);
+        }
 
         let ops = state.ops.clone();
         let project_for_db = project.clone();
@@ -450,50 +682,101 @@ impl GeminiCliActor {
             }
         });
         info!(
-            "ID: {id}, Project: {project}, banned. removed_from_mem={}",
-            removed_cred
+            "ID: {id}, Project: {project}, banned. removed_from_mem={}, parked_for_probe={}",
+            removed_cred, parked
         );
     }
 
-    async fn handle_submit_credentials(
+    /// Send every banned credential whose grace period is over to the refresher for a probe.
+    fn start_ban_probes(
         &self,
+        myself: &ActorRef<GeminiCliActorMessage>,
         state: &mut GeminiCliActorState,
-        creds_vec: Vec<GeminiCliProfile>,
     ) {
+        for (id, cred) in state.manager.take_due_probes() {
+            info!(
+                "ID: {id}, Project: {}, re-probing banned credential",
+                cred.project_id()
+            );
+            let task = RefreshJob {
+                cred,
+                r#type: TaskType::Probe(id),
+                progress: None,
+            };
+            if let Err(e) = state.refresh_handle.submit_refresh(task.clone()) {
+                let _ = myself.cast(GeminiCliActorMessage::RefreshComplete {
+                    result: Err(RefreshError {
+                        original_job: task,
+                        error: PolluxError::RactorError(format!(
+                            "Failed to enqueue probe job: {e}"
+                        )),
+                    }),
+                });
+            }
+        }
+    }
+
+    fn handle_submit_credentials(
+        &self,
+        state: &mut GeminiCliActorState,
+        creds_vec: Vec<GeminiCliProfile>,
+    ) -> Vec<SubmitOutcome> {
         let count = creds_vec.len();
         info!(count, "Batch submit received, dispatching...");
-        let refresh_handle = state.refresh_handle.clone();
-        tokio::spawn(async move {
-            for profile in creds_vec {
-                let pid = profile.project_id.to_string();
-                let cred = GeminiCliResource::from(profile);
+        let mut seen_projects = HashSet::new();
+        creds_vec
+            .into_iter()
+            .map(|profile| {
+                let pid = profile.project_id.trim().to_string();
+                let rejected = |reason: String| {
+                    warn!("Project: {pid}, submit rejected: {reason}");
+                    SubmitOutcome::Rejected { reason }
+                };
+                if profile.refresh_token.trim().is_empty() {
+                    return rejected("missing refresh_token".to_string());
+                }
+                if pid.is_empty() {
+                    return rejected("missing project_id".to_string());
+                }
+                if state.manager.contains_project(&pid) {
+                    return rejected(format!("project {pid} already has an active credential"));
+                }
+                if !seen_projects.insert(pid.clone()) {
+                    return rejected(format!("project {pid} submitted more than once"));
+                }
+
                 let job = RefreshJob {
-                    cred,
+                    cred: GeminiCliResource::from(profile),
                     r#type: TaskType::Onboard,
+                    progress: None,
                 };
-                if let Err(e) = refresh_handle.submit_onboard(job) {
-                    warn!(
-                        "Project: {pid}, failed to enqueue onboarding refresh: {}",
-                        e
-                    );
-                    break;
+                match state.refresh_handle.submit_onboard(job) {
+                    Ok(()) => SubmitOutcome::Accepted,
+                    Err(e) => rejected(format!("failed to enqueue onboarding: {e}")),
                 }
-            }
-        });
+            })
+            .collect()
     }
 
     async fn handle_submit_trusted_oauth(
         &self,
         state: &mut GeminiCliActorState,
         token_response: GoogleTokenResponse,
+        progress: Option<OnboardProgressTx>,
     ) {
         info!("Trusted OAuth submit received, dispatching onboarding...");
         let refresh_handle = state.refresh_handle.clone();
         tokio::spawn(async move {
+            let fail = |message: String| {
+                if let Some(progress) = &progress {
+                    progress.send(OnboardProgress::Failed { message });
+                }
+            };
             let mut token_value = match serde_json::to_value(&token_response) {
                 Ok(v) => v,
                 Err(e) => {
                     warn!("Trusted OAuth submit ignored: token JSON encode failed: {e}");
+                    fail(format!("token JSON encode failed: {e}"));
                     return;
                 }
             };
@@ -502,14 +785,17 @@ impl GeminiCliActor {
             let mut cred = GeminiCliResource::default();
             if let Err(e) = cred.update_credential(token_value) {
                 warn!("Trusted OAuth submit ignored: token JSON error: {e}");
+                fail(format!("token JSON error: {e}"));
                 return;
             }
             let job = RefreshJob {
                 cred,
                 r#type: TaskType::Onboard,
+                progress: progress.clone(),
             };
             if let Err(e) = refresh_handle.submit_onboard(job) {
                 warn!("Trusted OAuth submit enqueue failed: {}", e);
+                fail(format!("failed to enqueue onboarding: {e}"));
             }
         });
     }
@@ -538,6 +824,7 @@ impl GeminiCliActor {
                 let job = RefreshJob {
                     cred,
                     r#type: TaskType::Onboard,
+                    progress: None,
                 };
                 if let Err(e) = refresh_handle.submit_onboard(job) {
                     warn!("0-trust seed enqueue failed: {}", e);
@@ -552,6 +839,61 @@ impl GeminiCliActor {
         myself: ActorRef<GeminiCliActorMessage>,
         state: &mut GeminiCliActorState,
         result: RefreshResult,
+    ) {
+        let (refreshed_id, freed) = match &result {
+            Ok(success) => (success.r#type.credential_id(), true),
+            Err(failed) => (failed.original_job.r#type.credential_id(), false),
+        };
+
+        self.apply_refresh_result(myself.clone(), state, result)
+            .await;
+
+        if refreshed_id.is_some() {
+            self.serve_lease_waiters(myself, state, refreshed_id.filter(|_| freed))
+                .await;
+        }
+    }
+
+    /// Hand the credential that just freed up to the first waiting lease it can serve, in
+    /// priority then arrival order. Other waiters keep waiting while a credential for their
+    /// model is still refreshing, and otherwise lease whatever is queued now.
+    async fn serve_lease_waiters(
+        &self,
+        myself: ActorRef<GeminiCliActorMessage>,
+        state: &mut GeminiCliActorState,
+        mut freed: Option<CredentialId>,
+    ) {
+        if state.lease_waiters.is_empty() {
+            return;
+        }
+        for ((priority, seq), (model_mask, reply_port)) in std::mem::take(&mut state.lease_waiters)
+        {
+            if let Some(id) = freed.filter(|id| state.manager.can_serve(*id, model_mask)) {
+                state.manager.lease_next(id, model_mask);
+                freed = None;
+            } else if state.manager.refreshing_for(model_mask).is_some() {
+                state
+                    .lease_waiters
+                    .insert((priority, seq), (model_mask, reply_port));
+                continue;
+            }
+            self.handle_get_credential(
+                myself.clone(),
+                state,
+                reply_port,
+                model_mask,
+                priority,
+                false,
+            )
+            .await;
+        }
+    }
+
+    async fn apply_refresh_result(
+        &self,
+        myself: ActorRef<GeminiCliActorMessage>,
+        state: &mut GeminiCliActorState,
+        result: RefreshResult,
     ) {
         // If the result is for a refresh task, check if the credential is still in refreshing state.
         if let Some(id) = match &result {
@@ -562,6 +904,11 @@ impl GeminiCliActor {
             && !state.manager.is_refreshing(id)
         {
             debug!("ID: {id} Refresh completed/failed after removal; skipping.");
+            for waiter in state.refresh_waiters.remove(&id).unwrap_or_default() {
+                let _ = waiter.send(Err(PolluxError::NotFound(format!(
+                    "credential {id} was removed during refresh"
+                ))));
+            }
             return;
         }
 
@@ -571,41 +918,88 @@ impl GeminiCliActor {
                 let pid = success.cred.project_id().to_string();
                 let cred = success.cred;
                 match success.r#type {
-                    TaskType::Refresh(id) => {
+                    TaskType::Refresh(id) | TaskType::Probe(id) => {
+                        let restored = matches!(success.r#type, TaskType::Probe(_));
+                        if restored {
+                            info!("ID: {id}, Project: {pid}, probe succeeded; restored.");
+                        }
                         state
                             .manager
                             .add_credential(id, cred.clone(), state.model_caps_all);
                         let ops = state.ops.clone();
+                        let waiters = state.refresh_waiters.remove(&id).unwrap_or_default();
                         tokio::spawn(async move {
+                            let expiry = cred.expiry();
                             let patch = GeminiCliPatch {
                                 email: cred.email().map(ToString::to_string),
+                                // Carries a rotated refresh token if the refresh returned one.
+                                refresh_token: Some(cred.refresh_token().to_string()),
                                 access_token: cred.access_token().map(ToString::to_string),
                                 expiry: Some(cred.expiry()),
                                 ..Default::default()
                             };
-                            if let Err(e) = ops.update_by_id(id, patch).await {
+                            let mut persisted = ops.update_by_id(id, patch).await;
+                            if restored && persisted.is_ok() {
+                                persisted = ops.set_status(id, true).await;
+                            }
+                            if let Err(e) = &persisted {
                                 warn!("ID: {id} DB update failed: {}", e);
                             }
+                            for waiter in waiters {
+                                let reply = match &persisted {
+                                    Ok(()) => Ok(expiry),
+                                    Err(e) => Err(PolluxError::UnexpectedError(format!(
+                                        "refreshed token not persisted: {e}"
+                                    ))),
+                                };
+                                let _ = waiter.send(reply);
+                            }
                         });
                     }
                     TaskType::Onboard => {
                         info!("Project: {pid} Onboard success. Inserting to DB.");
+                        let mut cred = cred;
+                        state
+                            .tier_policy
+                            .stamp_onboarded(&mut cred, state.model_caps_all);
                         let ops = state.ops.clone();
                         let myself = myself.clone();
+                        let progress = success.progress;
+                        let reject_duplicate = state.reject_duplicate_projects;
                         tokio::spawn(async move {
                             let cred_for_db = cred.clone();
-                            match ops.upsert(cred_for_db).await {
+                            let stored = if reject_duplicate {
+                                ops.insert_new(cred_for_db).await
+                            } else {
+                                ops.upsert(cred_for_db).await
+                            };
+                            let outcome = match stored {
                                 Ok(new_id) => {
-                                    if let Err(e) =
-                                        myself.cast(GeminiCliActorMessage::ActivateCredential {
-                                            id: new_id,
-                                            credential: cred,
-                                        })
-                                    {
-                                        warn!("Project: {pid} ActivateCredential failed: {}", e);
+                                    match myself.cast(GeminiCliActorMessage::ActivateCredential {
+                                        id: new_id,
+                                        credential: cred,
+                                    }) {
+                                        Ok(()) => OnboardProgress::Stored { project_id: pid },
+                                        Err(e) => {
+                                            warn!(
+                                                "Project: {pid} ActivateCredential failed: {}",
+                                                e
+                                            );
+                                            OnboardProgress::Failed {
+                                                message: format!("activation failed: {e}"),
+                                            }
+                                        }
+                                    }
+                                }
+                                Err(e) => {
+                                    warn!("Project: {pid} DB upsert failed: {}", e);
+                                    OnboardProgress::Failed {
+                                        message: format!("credential not stored: {e}"),
                                     }
                                 }
-                                Err(e) => warn!("Project: {pid} DB upsert failed: {}", e),
+                            };
+                            if let Some(progress) = progress {
+                                progress.send(outcome);
                             }
                         });
                     }
@@ -616,6 +1010,13 @@ impl GeminiCliActor {
                 let err = failed.error;
                 let pid = job.cred.project_id().to_string();
                 warn!("RefreshTask failed for project {}: {}", pid, err);
+                if let Some(id) = job.r#type.credential_id() {
+                    for waiter in state.refresh_waiters.remove(&id).unwrap_or_default() {
+                        let _ = waiter.send(Err(PolluxError::UpstreamUnavailable(format!(
+                            "token refresh failed: {err}"
+                        ))));
+                    }
+                }
                 match job.r#type {
                     TaskType::Refresh(id) => match err {
                         PolluxError::Oauth(OauthError::ServerResponse { .. }) => {
@@ -639,12 +1040,26 @@ impl GeminiCliActor {
                                 .add_credential(id, job.cred, state.model_caps_all);
                         }
                     },
+                    TaskType::Probe(id) => {
+                        let grace = state.ban_probe_after.unwrap_or_default();
+                        warn!(
+                            "ID: {id} probe failed: {}. Re-probing after {} secs.",
+                            err,
+                            grace.as_secs()
+                        );
+                        state.manager.park_banned(id, grace);
+                    }
                     TaskType::Onboard => {
                         warn!(
                             "Project: {} Onboard failed: {}. Discarding.",
                             job.cred.project_id(),
                             err
                         );
+                        if let Some(progress) = &job.progress {
+                            progress.send(OnboardProgress::Failed {
+                                message: err.to_string(),
+                            });
+                        }
                     }
                 }
             }
@@ -656,12 +1071,13 @@ impl GeminiCliActor {
 pub(in crate::providers) async fn spawn(
     db: crate::db::DbActorHandle,
     gemini_cfg: Arc<GeminiCliResolvedConfig>,
+    clock: SharedClock,
 ) -> GeminiCliActorHandle {
     let ops = CredentialOps::new(db);
 
     let (actor, _jh) = Actor::spawn(
         Some("GeminiCliMain".to_string()),
-        GeminiCliActor,
+        GeminiCliActor { clock },
         (ops, gemini_cfg),
     )
     .await
@@ -1,7 +1,8 @@
 mod actor;
 mod ops;
 mod scheduler;
+mod tier;
 
-pub use actor::GeminiCliActorHandle;
 pub(in crate::providers) use actor::spawn;
+pub use actor::{GeminiCliActorHandle, SubmitOutcome};
 pub use scheduler::CredentialId;
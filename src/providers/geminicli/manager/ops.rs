@@ -38,6 +38,19 @@ impl CredentialOps {
             .map_err(|_| PolluxError::UnexpectedError(format!("Invalid credential id {}", id)))
     }
 
+    /// [`Self::upsert`] that refuses to replace an active credential for the same project.
+    pub async fn insert_new(&self, cred: GeminiCliResource) -> Result<CredentialId, PolluxError> {
+        if cred.sub().is_empty() {
+            return Err(PolluxError::UnexpectedError(
+                "GeminiCli credential missing sub (id_token claims)".to_string(),
+            ));
+        }
+        let id = self.db.create_new_geminicli(cred.into()).await?;
+
+        u64::try_from(id)
+            .map_err(|_| PolluxError::UnexpectedError(format!("Invalid credential id {}", id)))
+    }
+
     pub async fn update_by_id(
         &self,
         id: CredentialId,
@@ -1,6 +1,8 @@
-use crate::model_catalog::ModelCapabilities;
+use crate::model_catalog::{ModelCapabilities, ModelMask};
+use crate::providers::clock::{SharedClock, system_clock};
 use crate::providers::geminicli::resource::GeminiCliResource;
 use crate::providers::manifest::GeminiCliLease;
+use crate::providers::pool_status::{self, CredentialStatus};
 use std::{
     cmp::Reverse,
     collections::{BinaryHeap, HashMap, HashSet, VecDeque},
@@ -26,10 +28,10 @@ impl RuntimeCredential {
         }
     }
 
-    /// Proxy: check expiration via the inner credential.
+    /// Proxy: check expiration via the inner credential as of `now`.
     #[inline(always)]
-    pub fn is_expired(&self) -> bool {
-        self.inner.is_expired()
+    pub fn is_expired_at(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.inner.is_expired_at(now)
     }
 }
 
@@ -52,6 +54,17 @@ pub struct CredentialManager {
     waiting_room: BinaryHeap<CooldownTicket>,
     cooldown_map: HashMap<(CredentialId, ModelIndex), Instant>,
     refreshing: HashSet<CredentialId>,
+    /// Banned credentials kept out of rotation until their re-probe deadline.
+    banned: HashMap<CredentialId, (Instant, RuntimeCredential)>,
+    /// Most credentials queued for leasing at once; `None` queues every credential.
+    hot_cap: Option<usize>,
+    /// Credentials currently queued for leasing, when `hot_cap` is set.
+    hot: HashSet<CredentialId>,
+    /// Known credentials waiting for a hot slot, oldest first.
+    reserve: VecDeque<CredentialId>,
+    /// Added to "now" before token expiry checks; see `providers.defaults.expiry_skew_secs`.
+    expiry_skew: chrono::Duration,
+    clock: SharedClock,
 }
 
 impl Default for CredentialManager {
@@ -68,9 +81,34 @@ impl CredentialManager {
             waiting_room: BinaryHeap::new(),
             cooldown_map: HashMap::new(),
             refreshing: HashSet::new(),
+            banned: HashMap::new(),
+            hot_cap: None,
+            hot: HashSet::new(),
+            reserve: VecDeque::new(),
+            expiry_skew: chrono::Duration::zero(),
+            clock: system_clock(),
         }
     }
 
+    /// Use `clock` for cooldown deadlines and token expiry instead of the system clock.
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Treat access tokens as expiring `skew` earlier than their recorded expiry.
+    pub fn with_expiry_skew(mut self, skew: Duration) -> Self {
+        self.expiry_skew = chrono::Duration::from_std(skew).unwrap_or(chrono::TimeDelta::MAX);
+        self
+    }
+
+    /// Queue at most `cap` credentials for leasing; the rest wait in a reserve and rotate in
+    /// when a hot credential is rate limited or removed.
+    pub fn with_hot_cap(mut self, cap: usize) -> Self {
+        self.hot_cap = Some(cap.max(1));
+        self
+    }
+
     pub fn add_credential(
         &mut self,
         id: CredentialId,
@@ -87,8 +125,27 @@ impl CredentialManager {
         self.creds.insert(id, RuntimeCredential::new(cred, caps));
         self.refreshing.remove(&id);
 
+        if let Some(cap) = self.hot_cap
+            && !self.hot.contains(&id)
+        {
+            if self.hot.len() >= cap {
+                if !self.reserve.contains(&id) {
+                    self.reserve.push_back(id);
+                }
+                return;
+            }
+            self.reserve.retain(|reserved| *reserved != id);
+            self.hot.insert(id);
+        }
+        self.enqueue(id);
+    }
+
+    fn enqueue(&mut self, id: CredentialId) {
+        let Some(cred) = self.creds.get(&id) else {
+            return;
+        };
         for (index, queue) in self.queues.iter_mut().enumerate() {
-            if !caps.supports(index) {
+            if !cred.caps.supports(index) {
                 continue;
             }
 
@@ -98,11 +155,34 @@ impl CredentialManager {
         }
     }
 
-    fn index_from_mask(&self, model_mask: u64) -> Option<ModelIndex> {
-        if model_mask == 0 || (model_mask & (model_mask - 1)) != 0 {
+    fn is_hot(&self, id: CredentialId) -> bool {
+        self.hot_cap.is_none() || self.hot.contains(&id)
+    }
+
+    /// Give up `id`'s hot slot (parking it at the back of the reserve when `keep`) and fill
+    /// the slot from the front of the reserve. Returns the promoted credential, if any.
+    fn rotate_out(&mut self, id: CredentialId, keep: bool) -> Option<CredentialId> {
+        self.hot_cap?;
+        self.reserve.retain(|reserved| *reserved != id);
+        if !self.hot.remove(&id) {
             return None;
         }
-        let index = model_mask.trailing_zeros() as usize;
+        let promoted = loop {
+            let next = self.reserve.pop_front()?;
+            if self.creds.contains_key(&next) {
+                break next;
+            }
+        };
+        if keep {
+            self.reserve.push_back(id);
+        }
+        self.hot.insert(promoted);
+        self.enqueue(promoted);
+        Some(promoted)
+    }
+
+    fn index_from_mask(&self, model_mask: u64) -> Option<ModelIndex> {
+        let index = ModelMask::from_bits(model_mask).single_index()?;
         if index >= self.queues.len() {
             return None;
         }
@@ -130,20 +210,66 @@ impl CredentialManager {
     }
 
     pub fn delete_credential(&mut self, id: CredentialId) {
+        self.rotate_out(id, false);
         self.creds.remove(&id);
+        self.banned.remove(&id);
         self.refreshing.remove(&id);
         self.clear_cooldowns_for(id);
     }
 
-    pub fn report_rate_limit(&mut self, id: CredentialId, model_mask: u64, cooldown: Duration) {
-        let Some(model_index) = self.index_from_mask(model_mask) else {
-            return;
+    /// Take a credential out of rotation for `grace`, after which [`Self::take_due_probes`]
+    /// hands it back for a probe. Returns `false` if the credential is unknown.
+    pub fn park_banned(&mut self, id: CredentialId, grace: Duration) -> bool {
+        if !self.creds.contains_key(&id) {
+            return false;
+        }
+        self.rotate_out(id, false);
+        let Some(cred) = self.creds.remove(&id) else {
+            return false;
         };
-        let deadline = Instant::now() + cooldown;
+        self.refreshing.remove(&id);
+        self.clear_cooldowns_for(id);
+        self.banned.insert(id, (self.clock.now() + grace, cred));
+        true
+    }
+
+    /// Banned credentials whose grace period is over. Each is restored as refreshing, so it
+    /// is not leased until the probe result re-adds it.
+    pub fn take_due_probes(&mut self) -> Vec<(CredentialId, GeminiCliResource)> {
+        let now = self.clock.now();
+        let due: Vec<CredentialId> = self
+            .banned
+            .iter()
+            .filter(|(_, (probe_at, _))| *probe_at <= now)
+            .map(|(id, _)| *id)
+            .collect();
+
+        due.into_iter()
+            .filter_map(|id| {
+                let (_, cred) = self.banned.remove(&id)?;
+                let inner = cred.inner.clone();
+                self.creds.insert(id, cred);
+                self.refreshing.insert(id);
+                Some((id, inner))
+            })
+            .collect()
+    }
+
+    /// Cool `id` down for the model. With a hot cap, it also hands its slot to the next
+    /// reserve credential, which is returned.
+    pub fn report_rate_limit(
+        &mut self,
+        id: CredentialId,
+        model_mask: u64,
+        cooldown: Duration,
+    ) -> Option<CredentialId> {
+        let model_index = self.index_from_mask(model_mask)?;
+        let deadline = self.clock.now() + cooldown;
 
         self.cooldown_map.insert((id, model_index), deadline);
         self.waiting_room
             .push(CooldownTicket(Reverse(deadline), id, model_index));
+        self.rotate_out(id, true)
     }
 
     pub fn get_full_credential_copy(&self, id: CredentialId) -> Option<GeminiCliResource> {
@@ -160,6 +286,13 @@ impl CredentialManager {
         self.creds.contains_key(&id)
     }
 
+    /// Whether an active (not banned) credential already serves `project_id`.
+    pub fn contains_project(&self, project_id: &str) -> bool {
+        self.creds
+            .values()
+            .any(|cred| cred.inner.project_id() == project_id)
+    }
+
     pub fn get_assigned(&mut self, model_mask: u64) -> AssignmentResult {
         self.process_waiting_room();
 
@@ -169,12 +302,13 @@ impl CredentialManager {
             return result;
         };
 
+        let now = self.clock.utc_now() + self.expiry_skew;
         while let Some(id) = self.queues.get_mut(model_index).and_then(|q| q.pop_front()) {
             let Some(cred) = self.creds.get(&id) else {
                 continue;
             };
 
-            if !cred.caps.supports(model_index) {
+            if !cred.caps.supports(model_index) || !self.is_hot(id) {
                 continue;
             }
 
@@ -185,7 +319,7 @@ impl CredentialManager {
             let Some(token) = cred
                 .inner
                 .access_token()
-                .filter(|_| !cred.is_expired())
+                .filter(|_| !cred.is_expired_at(now))
                 .map(str::to_owned)
             else {
                 result.refresh_ids.push(id);
@@ -207,7 +341,7 @@ impl CredentialManager {
     }
 
     fn process_waiting_room(&mut self) {
-        let now = Instant::now();
+        let now = self.clock.now();
 
         while self.waiting_room.peek().is_some_and(|t| (t.0).0 <= now) {
             let CooldownTicket(Reverse(ticket_deadline), credential_id, model_index) =
@@ -218,6 +352,10 @@ impl CredentialManager {
                     if ticket_deadline >= *entry.get() =>
                 {
                     let ((reclaimed_cred_id, reclaimed_model_index), _) = entry.remove_entry();
+                    // A credential rotated out is queued again when it is promoted.
+                    if !self.is_hot(reclaimed_cred_id) {
+                        continue;
+                    }
                     if let Some(target_queue) = self.queues.get_mut(reclaimed_model_index) {
                         target_queue.push_back(reclaimed_cred_id);
                     }
@@ -227,6 +365,18 @@ impl CredentialManager {
         }
     }
 
+    /// Whether any known credential (queued, cooling down or refreshing) may serve the model.
+    pub fn supports_any(&self, model_mask: u64) -> bool {
+        self.creds
+            .values()
+            .any(|cred| ModelMask::from_bits(cred.caps.bits()).intersects(model_mask.into()))
+    }
+
+    /// Quota tier of every known credential.
+    pub fn quota_tiers(&self) -> impl Iterator<Item = Option<&str>> {
+        self.creds.values().map(|cred| cred.inner.quota_tier())
+    }
+
     pub fn queue_len(&self, model_mask: u64) -> usize {
         self.index_from_mask(model_mask)
             .and_then(|model_index| self.queues.get(model_index).map(|q| q.len()))
@@ -237,6 +387,14 @@ impl CredentialManager {
         self.creds.len()
     }
 
+    /// Credentials queued for leasing (all of them without a hot cap).
+    pub fn hot_len(&self) -> usize {
+        match self.hot_cap {
+            Some(_) => self.hot.len(),
+            None => self.creds.len(),
+        }
+    }
+
     pub fn refreshing_len(&self) -> usize {
         self.refreshing.len()
     }
@@ -245,13 +403,62 @@ impl CredentialManager {
         self.refreshing.contains(&id)
     }
 
+    /// Whether credential `id` may serve `model_mask`.
+    pub fn can_serve(&self, id: CredentialId, model_mask: u64) -> bool {
+        self.index_from_mask(model_mask).is_some_and(|model_index| {
+            self.creds
+                .get(&id)
+                .is_some_and(|cred| cred.caps.supports(model_index))
+        })
+    }
+
+    /// Move `id` to the front of the `model_mask` queue, so the next lease for that model
+    /// tries it first.
+    pub fn lease_next(&mut self, id: CredentialId, model_mask: u64) {
+        let Some(model_index) = self.index_from_mask(model_mask) else {
+            return;
+        };
+        if let Some(queue) = self.queues.get_mut(model_index)
+            && let Some(pos) = queue.iter().position(|queued| *queued == id)
+        {
+            queue.remove(pos);
+            queue.push_front(id);
+        }
+    }
+
+    /// A credential able to serve `model_mask` whose refresh is in flight, if any.
+    pub fn refreshing_for(&self, model_mask: u64) -> Option<CredentialId> {
+        let model_index = self.index_from_mask(model_mask)?;
+        self.refreshing.iter().copied().find(|id| {
+            self.creds
+                .get(id)
+                .is_some_and(|cred| cred.caps.supports(model_index))
+        })
+    }
+
     pub fn cooldown_len(&self) -> usize {
         self.cooldown_map.len()
     }
 
+    /// Per-credential availability for the admin pool view.
+    pub fn pool_status(&self) -> Vec<CredentialStatus> {
+        let now = self.clock.utc_now() + self.expiry_skew;
+        pool_status::collect(
+            self.creds.iter().map(|(id, cred)| {
+                (
+                    *id,
+                    cred.inner.access_token().is_some() && !cred.is_expired_at(now),
+                )
+            }),
+            |id| self.refreshing.contains(&id),
+            &self.cooldown_map,
+            self.clock.as_ref(),
+        )
+    }
+
     fn is_model_cooling(&self, id: CredentialId, model_index: ModelIndex) -> bool {
         match self.cooldown_map.get(&(id, model_index)) {
-            Some(deadline) => Instant::now() < *deadline,
+            Some(deadline) => self.clock.now() < *deadline,
             None => false,
         }
     }
@@ -264,6 +471,8 @@ impl CredentialManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::providers::clock::ManualClock;
+    use crate::providers::pool_status::CredentialState;
     use chrono::{Duration, Utc};
     use serde_json::json;
 
@@ -328,18 +537,22 @@ mod tests {
 
     #[test]
     fn cooldown_blocks_and_requeues() {
-        let mut manager = CredentialManager::new(1);
+        let clock = ManualClock::new();
+        let mut manager = CredentialManager::new(1).with_clock(clock.shared());
 
         let mut caps = ModelCapabilities::none();
         caps.enable(0);
         manager.add_credential(1, make_credential("p1"), caps.bits());
 
-        manager.report_rate_limit(1, mask(0), std::time::Duration::from_millis(10));
+        manager.report_rate_limit(1, mask(0), std::time::Duration::from_secs(60));
 
         let assigned_during_cooldown = manager.get_assigned(mask(0)).assigned;
         assert!(assigned_during_cooldown.is_none());
 
-        std::thread::sleep(std::time::Duration::from_millis(20));
+        clock.advance(std::time::Duration::from_secs(59));
+        assert!(manager.get_assigned(mask(0)).assigned.is_none());
+
+        clock.advance(std::time::Duration::from_secs(1));
 
         let assigned_after = manager
             .get_assigned(mask(0))
@@ -348,6 +561,25 @@ mod tests {
         assert_eq!(assigned_after.project_id, "p1");
     }
 
+    #[test]
+    fn expiry_skew_refreshes_tokens_expiring_within_the_window() {
+        // The token expires in 10 minutes: outside the 5-minute buffer alone.
+        let mut manager = CredentialManager::new(1);
+        manager.add_credential(1, make_credential("p1"), mask(0));
+        let result = manager.get_assigned(mask(0));
+        assert!(result.assigned.is_some());
+        assert!(result.refresh_ids.is_empty());
+
+        // A 6-minute skew puts it inside the window: it is refreshed instead of leased.
+        let mut manager =
+            CredentialManager::new(1).with_expiry_skew(std::time::Duration::from_secs(6 * 60));
+        manager.add_credential(1, make_credential("p1"), mask(0));
+        assert_eq!(manager.pool_status()[0].state, CredentialState::Expired);
+        let result = manager.get_assigned(mask(0));
+        assert!(result.assigned.is_none());
+        assert_eq!(result.refresh_ids, vec![1]);
+    }
+
     #[test]
     fn expired_token_triggers_refresh_request() {
         let mut manager = CredentialManager::new(1);
@@ -361,6 +593,23 @@ mod tests {
         assert_eq!(result.refresh_ids, vec![1]);
     }
 
+    #[test]
+    fn token_expires_as_the_clock_advances() {
+        let clock = ManualClock::new();
+        let mut manager = CredentialManager::new(1).with_clock(clock.shared());
+        let mut caps = ModelCapabilities::none();
+        caps.enable(0);
+
+        // Valid for 10 minutes, treated as expired within the last 5.
+        manager.add_credential(1, make_credential("p1"), caps.bits());
+        assert!(manager.get_assigned(mask(0)).assigned.is_some());
+
+        clock.advance(std::time::Duration::from_secs(6 * 60));
+        let result = manager.get_assigned(mask(0));
+        assert!(result.assigned.is_none());
+        assert_eq!(result.refresh_ids, vec![1]);
+    }
+
     #[test]
     fn refreshing_credential_is_skipped() {
         let mut manager = CredentialManager::new(1);
@@ -379,6 +628,23 @@ mod tests {
         assert_eq!(assigned.id, 2);
     }
 
+    #[test]
+    fn refreshing_for_matches_model_capabilities() {
+        let mut manager = CredentialManager::new(2);
+        let mut caps = ModelCapabilities::none();
+        caps.enable(0);
+
+        manager.add_credential(1, make_expired_credential("p1"), caps.bits());
+        assert_eq!(manager.refreshing_for(mask(0)), None);
+
+        manager.mark_refreshing(1);
+        assert_eq!(manager.refreshing_for(mask(0)), Some(1));
+        assert_eq!(manager.refreshing_for(mask(1)), None);
+
+        manager.add_credential(1, make_credential("p1"), caps.bits());
+        assert_eq!(manager.refreshing_for(mask(0)), None);
+    }
+
     #[test]
     fn readd_after_refresh_preserves_disabled_caps() {
         let mut manager = CredentialManager::new(2);
@@ -398,6 +664,74 @@ mod tests {
         assert_eq!(assigned_allowed.id, 1);
     }
 
+    #[test]
+    fn banned_credential_returns_after_grace_and_probe() {
+        let clock = ManualClock::new();
+        let mut manager = CredentialManager::new(1).with_clock(clock.shared());
+        let mut caps = ModelCapabilities::none();
+        caps.enable(0);
+        manager.add_credential(1, make_credential("p1"), caps.bits());
+
+        assert!(manager.park_banned(1, std::time::Duration::from_secs(60)));
+        assert!(!manager.contains(1));
+        assert!(manager.get_assigned(mask(0)).assigned.is_none());
+
+        clock.advance(std::time::Duration::from_secs(59));
+        assert!(manager.take_due_probes().is_empty());
+
+        clock.advance(std::time::Duration::from_secs(1));
+        let probes = manager.take_due_probes();
+        assert_eq!(probes.len(), 1);
+        assert_eq!(probes[0].0, 1);
+        assert!(manager.take_due_probes().is_empty());
+
+        // Not leased while the probe is in flight.
+        assert!(manager.is_refreshing(1));
+        assert!(manager.get_assigned(mask(0)).assigned.is_none());
+
+        // A successful probe re-adds the credential with its original capabilities.
+        manager.add_credential(1, probes[0].1.clone(), ModelCapabilities::all().bits());
+        let assigned = manager
+            .get_assigned(mask(0))
+            .assigned
+            .expect("assigned after probe");
+        assert_eq!(assigned.id, 1);
+    }
+
+    #[test]
+    fn hot_cap_limits_leased_credentials_until_rotation() {
+        let mut manager = CredentialManager::new(1).with_hot_cap(2);
+        let mut caps = ModelCapabilities::none();
+        caps.enable(0);
+        for id in 1..=5 {
+            manager.add_credential(id, make_credential(&format!("p{id}")), caps.bits());
+        }
+        assert_eq!((manager.total_creds(), manager.hot_len()), (5, 2));
+
+        let leased: HashSet<_> = (0..6)
+            .map(|_| manager.get_assigned(mask(0)).assigned.expect("leased").id)
+            .collect();
+        assert_eq!(leased, HashSet::from([1, 2]));
+
+        // Rate limiting a hot credential hands its slot to the oldest reserve one.
+        assert_eq!(
+            manager.report_rate_limit(1, mask(0), std::time::Duration::from_secs(60)),
+            Some(3)
+        );
+        let leased: HashSet<_> = (0..6)
+            .map(|_| manager.get_assigned(mask(0)).assigned.expect("leased").id)
+            .collect();
+        assert_eq!(leased, HashSet::from([2, 3]));
+
+        // Removing a hot credential promotes the next one; the rotated-out one waits its turn.
+        manager.delete_credential(2);
+        let leased: HashSet<_> = (0..6)
+            .map(|_| manager.get_assigned(mask(0)).assigned.expect("leased").id)
+            .collect();
+        assert_eq!(leased, HashSet::from([3, 4]));
+        assert_eq!(manager.hot_len(), 2);
+    }
+
     #[test]
     fn multiple_credentials_rotate_in_queue() {
         let mut manager = CredentialManager::new(1);
@@ -419,4 +753,20 @@ mod tests {
         assert_eq!(first.id, 1);
         assert_eq!(second.id, 2);
     }
+
+    #[test]
+    fn lease_next_moves_a_credential_to_the_front() {
+        let mut manager = CredentialManager::new(1);
+        let mut caps = ModelCapabilities::none();
+        caps.enable(0);
+
+        manager.add_credential(1, make_credential("p1"), caps.bits());
+        manager.add_credential(2, make_credential("p2"), caps.bits());
+        manager.lease_next(2, mask(0));
+
+        let leased = manager.get_assigned(mask(0)).assigned.expect("assignment");
+        assert_eq!(leased.id, 2);
+        assert!(manager.can_serve(1, mask(0)));
+        assert!(!manager.can_serve(3, mask(0)));
+    }
 }
@@ -0,0 +1,174 @@
+use crate::model_catalog::ModelMask;
+use crate::providers::geminicli::resource::GeminiCliResource;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use tracing::warn;
+
+/// Which models each Code Assist quota tier may be leased for.
+///
+/// Tiers absent from the policy (and credentials with no known tier) are unrestricted.
+#[derive(Debug, Default, Clone)]
+pub struct TierPolicy {
+    allowed: HashMap<String, u64>,
+}
+
+impl TierPolicy {
+    /// Build from `providers.geminicli.tier_models`, resolving names via the model catalog.
+    pub fn from_config(tier_models: &BTreeMap<String, Vec<String>>) -> Self {
+        Self::from_model_masks(tier_models, crate::model_catalog::mask)
+    }
+
+    fn from_model_masks(
+        tier_models: &BTreeMap<String, Vec<String>>,
+        resolve: impl Fn(&str) -> Option<u64>,
+    ) -> Self {
+        let allowed = tier_models
+            .iter()
+            .map(|(tier, models)| {
+                let mask = models
+                    .iter()
+                    .fold(0u64, |mask, model| match resolve(model) {
+                        Some(bit) => mask | bit,
+                        None => {
+                            warn!(tier, model, "tier_models lists an unknown model; ignoring");
+                            mask
+                        }
+                    });
+                (tier.clone(), mask)
+            })
+            .collect();
+        Self { allowed }
+    }
+
+    /// Capability bits a credential of `tier` starts with.
+    pub fn caps_for(&self, tier: Option<&str>, all_caps: u64) -> u64 {
+        match tier.and_then(|tier| self.allowed.get(tier)) {
+            Some(mask) => all_caps & mask,
+            None => all_caps,
+        }
+    }
+
+    /// Record on a freshly onboarded credential which models its quota tier may serve.
+    pub fn stamp_onboarded(&self, cred: &mut GeminiCliResource, all_caps: u64) {
+        cred.set_supported_models(self.caps_for(cred.quota_tier(), all_caps));
+    }
+
+    pub fn allows(&self, tier: Option<&str>, model_mask: u64) -> bool {
+        ModelMask::from_bits(self.caps_for(tier, model_mask)).intersects(model_mask.into())
+    }
+
+    /// Explain why no credential can serve `model_mask` when the tiers of the given
+    /// credentials exclude it; `None` if tier policy is not the reason.
+    pub fn rejection<'a>(
+        &self,
+        tiers: impl Iterator<Item = Option<&'a str>>,
+        model_mask: u64,
+        model_name: &str,
+    ) -> Option<String> {
+        let blocked: BTreeSet<&str> = tiers
+            .filter(|tier| !self.allows(*tier, model_mask))
+            .flatten()
+            .collect();
+        if blocked.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "model {model_name} is not allowed for quota tier(s) {}",
+            blocked.into_iter().collect::<Vec<_>>().join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::GeminiCliCreate;
+    use crate::providers::geminicli::manager::scheduler::CredentialManager;
+    use chrono::{Duration, Utc};
+    use serde_json::json;
+
+    const FLASH: u64 = 1 << 0;
+    const PRO: u64 = 1 << 1;
+    const ALL: u64 = FLASH | PRO;
+
+    fn policy() -> TierPolicy {
+        let tier_models = BTreeMap::from([(
+            "free-tier".to_string(),
+            vec!["flash".to_string(), "not-a-model".to_string()],
+        )]);
+        TierPolicy::from_model_masks(&tier_models, |name| match name {
+            "flash" => Some(FLASH),
+            "pro" => Some(PRO),
+            _ => None,
+        })
+    }
+
+    fn credential(tier: &str) -> GeminiCliResource {
+        GeminiCliResource::from_payload(json!({
+            "project_id": "p1",
+            "refresh_token": "refresh",
+            "access_token": "token",
+            "expiry": Utc::now() + Duration::minutes(10),
+            "quota_tier": tier,
+        }))
+        .expect("valid resource payload")
+    }
+
+    #[test]
+    fn free_tier_credential_is_refused_for_pro_model() {
+        let policy = policy();
+        let cred = credential("free-tier");
+        let caps = policy.caps_for(cred.quota_tier(), ALL);
+
+        let mut manager = CredentialManager::new(2);
+        manager.add_credential(1, cred, caps);
+
+        assert!(!policy.allows(Some("free-tier"), PRO));
+        assert!(manager.get_assigned(PRO).assigned.is_none());
+
+        assert!(policy.allows(Some("free-tier"), FLASH));
+        let lease = manager.get_assigned(FLASH).assigned.expect("flash lease");
+        assert_eq!(lease.id, 1);
+    }
+
+    #[test]
+    fn onboarded_credential_records_its_tier_models() {
+        let policy = policy();
+
+        let mut free = credential("free-tier");
+        policy.stamp_onboarded(&mut free, ALL);
+        assert_eq!(free.supported_models(), Some(FLASH));
+
+        let mut standard = credential("standard-tier");
+        policy.stamp_onboarded(&mut standard, ALL);
+        assert_eq!(standard.supported_models(), Some(ALL));
+
+        let stored = GeminiCliCreate::from(free);
+        assert_eq!(stored.supported_models, Some(FLASH as i64));
+    }
+
+    #[test]
+    fn rejection_names_the_blocking_tier() {
+        let policy = policy();
+
+        let message = policy
+            .rejection([Some("free-tier")].into_iter(), PRO, "pro")
+            .expect("free tier blocks pro");
+        assert_eq!(
+            message,
+            "model pro is not allowed for quota tier(s) free-tier"
+        );
+
+        assert_eq!(
+            policy.rejection([Some("free-tier")].into_iter(), FLASH, "flash"),
+            None
+        );
+        assert_eq!(policy.rejection([None].into_iter(), PRO, "pro"), None);
+    }
+
+    #[test]
+    fn unlisted_or_unknown_tier_is_unrestricted() {
+        let policy = policy();
+        assert_eq!(policy.caps_for(Some("standard-tier"), ALL), ALL);
+        assert_eq!(policy.caps_for(None, ALL), ALL);
+    }
+}
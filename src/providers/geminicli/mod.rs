@@ -2,15 +2,17 @@ pub mod client;
 mod context;
 mod manager;
 mod model_mask;
+mod onboard_progress;
 mod resource;
 mod thoughtsig;
 mod workers;
 
 pub use context::GeminiContext;
-pub use manager::GeminiCliActorHandle;
 pub(in crate::providers) use manager::spawn;
+pub use manager::{GeminiCliActorHandle, SubmitOutcome};
 pub(crate) use model_mask::{SUPPORTED_MODEL_MASK, SUPPORTED_MODEL_NAMES, model_mask};
-pub use thoughtsig::GeminiThoughtSigService;
+pub use onboard_progress::OnboardProgress;
+pub use thoughtsig::{FillStatsSnapshot, GeminiThoughtSigService};
 
 use crate::config::CONFIG;
 use oauth2::{RedirectUrl, Scope};
@@ -22,6 +24,7 @@ pub(crate) const GEMINICLI_USER_AGENT: &str = "GeminiCLI/0.26.0/gemini-3-pro-pre
 /// Fixed Google OAuth endpoints used by Gemini CLI.
 const GOOGLE_AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
 const GOOGLE_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+const GOOGLE_DEVICE_AUTH_URL: &str = "https://oauth2.googleapis.com/device/code";
 
 /// Fixed Cloud Code Gemini endpoints used by Gemini CLI.
 const LOAD_CODE_ASSIST_URL: &str = "https://cloudcode-pa.googleapis.com/v1internal:loadCodeAssist";
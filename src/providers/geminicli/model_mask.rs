@@ -1,5 +1,5 @@
 use crate::config::CONFIG;
-use crate::model_catalog;
+use crate::model_catalog::{self, ModelMask};
 use std::collections::HashSet;
 use std::sync::LazyLock;
 
@@ -24,9 +24,9 @@ pub(crate) static SUPPORTED_MODEL_MASK: LazyLock<u64> = LazyLock::new(|| {
 });
 
 pub(crate) fn model_mask(name: &str) -> Option<u64> {
-    let bit = model_catalog::mask(name)?;
-    if (*SUPPORTED_MODEL_MASK & bit) != 0 {
-        Some(bit)
+    let bit = ModelMask::named(name)?;
+    if ModelMask::from_bits(*SUPPORTED_MODEL_MASK).contains(bit) {
+        Some(bit.bits())
     } else {
         None
     }
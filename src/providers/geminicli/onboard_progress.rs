@@ -0,0 +1,49 @@
+use std::fmt;
+use tokio::sync::mpsc;
+
+/// A step of onboarding a freshly authorized account, reported to the OAuth callback so the
+/// browser shows what is happening while the companion project is provisioned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OnboardProgress {
+    /// Asking `loadCodeAssist` for the account's tier and existing project.
+    LoadingCodeAssist,
+    /// Waiting on an `onboardUser` operation.
+    Provisioning { attempt: usize, max_attempts: usize },
+    /// The credential is stored and active. Always the last event on success.
+    Stored { project_id: String },
+    /// Onboarding gave up. Always the last event on failure.
+    Failed { message: String },
+}
+
+impl fmt::Display for OnboardProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LoadingCodeAssist => write!(f, "Checking Code Assist eligibility..."),
+            Self::Provisioning {
+                attempt,
+                max_attempts,
+            } => write!(
+                f,
+                "Provisioning project (attempt {attempt}/{max_attempts})..."
+            ),
+            Self::Stored { project_id } => write!(f, "Success: project {project_id} is ready."),
+            Self::Failed { message } => write!(f, "Failed: {message}"),
+        }
+    }
+}
+
+/// Sending half of an onboarding progress feed. A dropped receiver is ignored: the client
+/// going away must not abort onboarding.
+#[derive(Debug, Clone)]
+pub(crate) struct OnboardProgressTx(mpsc::UnboundedSender<OnboardProgress>);
+
+impl OnboardProgressTx {
+    pub(crate) fn channel() -> (Self, mpsc::UnboundedReceiver<OnboardProgress>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (Self(tx), rx)
+    }
+
+    pub(crate) fn send(&self, progress: OnboardProgress) {
+        let _ = self.0.send(progress);
+    }
+}
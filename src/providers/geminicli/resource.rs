@@ -13,6 +13,10 @@ pub(super) struct GeminiCliResource {
     refresh_token: String,
     access_token: Option<String>,
     expiry: DateTime<Utc>,
+    #[serde(default)]
+    quota_tier: Option<String>,
+    #[serde(default)]
+    supported_models: Option<u64>,
 }
 
 impl Default for GeminiCliResource {
@@ -24,6 +28,8 @@ impl Default for GeminiCliResource {
             refresh_token: String::new(),
             access_token: None,
             expiry: Utc::now(),
+            quota_tier: None,
+            supported_models: None,
         }
     }
 }
@@ -31,8 +37,14 @@ impl Default for GeminiCliResource {
 impl GeminiCliResource {
     /// Return true if current time is within 5 minutes of expiry (inclusive).
     /// This early-expiry buffer avoids edge cases during requests.
+    #[allow(dead_code)]
     pub fn is_expired(&self) -> bool {
-        Utc::now() + Duration::minutes(5) >= self.expiry
+        self.is_expired_at(Utc::now())
+    }
+
+    /// [`Self::is_expired`] as of `now`.
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
+        now + Duration::minutes(5) >= self.expiry
     }
 
     pub fn project_id(&self) -> &str {
@@ -57,6 +69,25 @@ impl GeminiCliResource {
         self.sub = sub;
     }
 
+    /// Code Assist quota tier (e.g. `free-tier`), known once onboarding ran.
+    pub fn quota_tier(&self) -> Option<&str> {
+        self.quota_tier.as_deref()
+    }
+
+    pub fn set_quota_tier(&mut self, quota_tier: String) {
+        self.quota_tier = Some(quota_tier);
+    }
+
+    /// Model mask the quota tier allowed at onboarding, if recorded.
+    #[allow(dead_code)]
+    pub fn supported_models(&self) -> Option<u64> {
+        self.supported_models
+    }
+
+    pub fn set_supported_models(&mut self, model_mask: u64) {
+        self.supported_models = Some(model_mask);
+    }
+
     pub fn refresh_token(&self) -> &str {
         &self.refresh_token
     }
@@ -85,6 +116,7 @@ impl GeminiCliResource {
             access_token: Option<String>,
             expiry: Option<DateTime<Utc>>,
             expires_in: Option<i64>,
+            quota_tier: Option<String>,
         }
 
         let value = serde_json::to_value(payload)?;
@@ -110,6 +142,7 @@ impl GeminiCliResource {
         set_plain!(project_id);
         set_plain!(refresh_token);
         set_opt!(access_token);
+        set_opt!(quota_tier);
 
         if let Some(secs) = patch.expires_in {
             self.expiry = Utc::now() + Duration::seconds(secs);
@@ -154,6 +187,8 @@ impl From<DbGeminiCliResource> for GeminiCliResource {
             refresh_token: d.refresh_token,
             access_token: d.access_token,
             expiry: d.expiry,
+            quota_tier: d.quota_tier,
+            supported_models: d.supported_models.map(|bits| bits as u64),
         }
     }
 }
@@ -167,6 +202,8 @@ impl From<GeminiCliResource> for GeminiCliCreate {
             refresh_token: cred.refresh_token,
             access_token: cred.access_token,
             expiry: cred.expiry,
+            quota_tier: cred.quota_tier,
+            supported_models: cred.supported_models.map(|bits| bits as i64),
         }
     }
 }
@@ -1,13 +1,15 @@
 use pollux_schema::gemini::{GeminiGenerateContentRequest, Part};
 use pollux_thoughtsig_core::{
-    PatchEvent, PatchOutcome, ThoughtSigPatchable, ThoughtSignatureEngine,
+    AsyncSignatureStore, IncrementalFill, PatchEvent, PatchOutcome, PatchStats,
+    ThoughtSigPatchable, ThoughtSignatureEngine, patch_all_async, patch_all_for_model,
 };
 use tracing::debug;
 
 // Minimal wrapper for `Part` due to orphan rule:
 // we cannot implement `ThoughtSigPatchable` directly on schema types
 // from another crate.
-struct GeminiPartPatch<'a>(&'a mut Part);
+// The second field is the index of the owning content, grouping sibling parts.
+struct GeminiPartPatch<'a>(&'a mut Part, usize);
 
 impl GeminiPartPatch<'_> {
     fn signature_preview(&self) -> String {
@@ -22,8 +24,8 @@ impl GeminiPartPatch<'_> {
 impl ThoughtSigPatchable for GeminiPartPatch<'_> {
     fn data(&self) -> PatchEvent<'_> {
         // Priority: functionCall first, then thought text.
-        // A thought part without text is still patchable and falls back
-        // to dummy signature through empty-text key generation.
+        // A thought part without text is still patchable; it yields no cache key, so the
+        // policy's `UnkeyedParts` decides between the dummy and leaving it as sent.
         if let Some(function_call) = self.0.function_call.as_ref() {
             return PatchEvent::FunctionCall(function_call);
         }
@@ -41,37 +43,157 @@ impl ThoughtSigPatchable for GeminiPartPatch<'_> {
     fn thought_signature_mut(&mut self) -> &mut Option<String> {
         self.0.thought_signature_mut()
     }
+
+    fn existing_signature(&self) -> Option<&str> {
+        self.0.thought_signature.as_deref()
+    }
+
+    fn group(&self) -> Option<usize> {
+        Some(self.1)
+    }
 }
 
 pub(super) fn patch_request(
     request: &mut GeminiGenerateContentRequest,
     engine: &ThoughtSignatureEngine,
+    model: Option<&str>,
+    parallel_threshold: usize,
+) -> PatchStats {
+    fill_model_parts(request, engine, model, |parts| {
+        patch_all_for_model(parts, engine, model, parallel_threshold)
+    })
+}
+
+/// Like [`patch_request`], reusing `incremental`'s decisions for the parts `conversation`
+/// already sent on its previous turn.
+pub(super) fn patch_request_incremental(
+    request: &mut GeminiGenerateContentRequest,
+    engine: &ThoughtSignatureEngine,
+    model: Option<&str>,
+    parallel_threshold: usize,
+    incremental: &IncrementalFill,
+    conversation: &str,
+) -> PatchStats {
+    fill_model_parts(request, engine, model, |parts| {
+        incremental.patch(conversation, parts, engine, model, parallel_threshold)
+    })
+}
+
+/// Like [`patch_request`], looking signatures up in `store` (e.g. one shared between
+/// instances) instead of the engine's own cache.
+pub(super) async fn patch_request_async<S: AsyncSignatureStore>(
+    request: &mut GeminiGenerateContentRequest,
+    engine: &ThoughtSignatureEngine,
+    store: &S,
+    model: Option<&str>,
+) -> PatchStats {
+    let (positions, mut parts) = model_parts(request, engine, model);
+    let (outcomes, stats) = patch_all_async(&mut parts, engine, store, model).await;
+    log_fill(&positions, &parts, outcomes, stats)
+}
+
+fn fill_model_parts(
+    request: &mut GeminiGenerateContentRequest,
+    engine: &ThoughtSignatureEngine,
+    model: Option<&str>,
+    fill: impl FnOnce(&mut [GeminiPartPatch<'_>]) -> (Vec<PatchOutcome>, PatchStats),
+) -> PatchStats {
+    let (positions, mut parts) = model_parts(request, engine, model);
+    let (outcomes, stats) = fill(&mut parts);
+    log_fill(&positions, &parts, outcomes, stats)
+}
+
+/// Model-turn parts to fill, with their `(content, part)` positions.
+fn model_parts<'a>(
+    request: &'a mut GeminiGenerateContentRequest,
+    engine: &ThoughtSignatureEngine,
+    model: Option<&str>,
+) -> (Vec<(usize, usize)>, Vec<GeminiPartPatch<'a>>) {
+    if engine.strips_thoughts(model) {
+        strip_thought_parts(request, engine);
+    }
+
+    // Two-phase patch flow over model parts only:
+    // decide every part (in parallel for large histories), then write back in order.
+    request
+        .contents
+        .iter_mut()
+        .enumerate()
+        .filter(|(_, content)| engine.is_model_role(content.role.as_deref()))
+        .flat_map(|(content_idx, content)| {
+            content
+                .parts
+                .iter_mut()
+                .enumerate()
+                .map(move |(part_idx, part)| {
+                    ((content_idx, part_idx), GeminiPartPatch(part, content_idx))
+                })
+        })
+        .unzip()
+}
+
+fn log_fill(
+    positions: &[(usize, usize)],
+    parts: &[GeminiPartPatch<'_>],
+    outcomes: Vec<PatchOutcome>,
+    stats: PatchStats,
+) -> PatchStats {
+    for (((content_idx, part_idx), part_patch), applied) in
+        positions.iter().zip(parts).zip(outcomes)
+    {
+        let key = match applied {
+            PatchOutcome::Skipped => continue,
+            PatchOutcome::Patched { cache_key }
+            | PatchOutcome::Kept { cache_key }
+            | PatchOutcome::Unfilled { cache_key } => cache_key,
+        };
+
+        debug!(
+            channel = "geminicli",
+            thoughtsig.phase = "fill",
+            content_idx = content_idx,
+            part_idx = part_idx,
+            key = ?key,
+            signature = %part_patch.signature_preview(),
+            "Thought signature decision"
+        );
+    }
+    debug!(
+        channel = "geminicli",
+        thoughtsig.phase = "fill",
+        cache_hits = stats.cache_hits,
+        fallbacks = stats.fallbacks,
+        kept = stats.kept,
+        unfilled = stats.unfilled,
+        borrowed = stats.borrowed,
+        skipped = stats.skipped,
+        reused = stats.reused,
+        "Thought signature fill summary"
+    );
+    stats
+}
+
+/// Remove pure thought parts from model turns; thought parts carrying a function call stay.
+fn strip_thought_parts(
+    request: &mut GeminiGenerateContentRequest,
+    engine: &ThoughtSignatureEngine,
 ) {
-    // Single-pass patch flow:
-    // request.contents(model only) -> content.parts -> patch each part.
-    // No pre-scan stage is needed.
     for (content_idx, content) in request.contents.iter_mut().enumerate() {
-        if content.role.as_deref() != Some("model") {
+        if !engine.is_model_role(content.role.as_deref()) {
             continue;
         }
-
-        for (part_idx, part) in content.parts.iter_mut().enumerate() {
-            let mut part_patch = GeminiPartPatch(part);
-            let applied = part_patch.patch_thought_signature(engine);
-
-            let key = match applied {
-                PatchOutcome::Skipped => continue,
-                PatchOutcome::Patched { cache_key } => cache_key,
-            };
-
+        let before = content.parts.len();
+        content
+            .parts
+            .retain(|part| part.thought != Some(true) || part.function_call.is_some());
+        let dropped = before - content.parts.len();
+        if dropped > 0 {
             debug!(
                 channel = "geminicli",
-                thoughtsig.phase = "fill",
+                thoughtsig.phase = "strip",
                 content_idx = content_idx,
-                part_idx = part_idx,
-                key = ?key,
-                signature = %part_patch.signature_preview(),
-                "Thought signature decision"
+                dropped = dropped,
+                "Stripped replayed thought parts"
             );
         }
     }
@@ -88,7 +210,9 @@ fn preview_signature(signature: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use pollux_thoughtsig_core::CacheKeyGenerator;
+    use pollux_thoughtsig_core::{
+        CacheKeyGenerator, DEFAULT_PARALLEL_FILL_THRESHOLD, EnginePolicy,
+    };
     use serde_json::json;
     use std::sync::Arc;
 
@@ -96,6 +220,34 @@ mod tests {
         serde_json::from_value(value).expect("request json must parse")
     }
 
+    #[tokio::test]
+    async fn async_patch_reads_the_given_store() {
+        use pollux_thoughtsig_core::{MokaSignatureStore, SignatureStore};
+
+        let engine = ThoughtSignatureEngine::new(3600, 1024);
+        let shared = MokaSignatureStore::new(3600, 1024);
+        let key = CacheKeyGenerator::generate_text("shared thought").expect("text key");
+        shared
+            .put(key, Arc::from("sig_from_peer"))
+            .expect("put must succeed");
+        let mut request = parse_request(json!({
+            "contents": [{"role": "model", "parts": [
+                {"thought": true, "text": "shared thought"},
+                {"thought": true, "text": "unknown thought"}
+            ]}]
+        }));
+
+        let stats = patch_request_async(&mut request, &engine, &shared, None).await;
+
+        let parts = &request.contents[0].parts;
+        assert_eq!(parts[0].thought_signature.as_deref(), Some("sig_from_peer"));
+        assert_eq!(
+            parts[1].thought_signature.as_deref(),
+            Some("skip_thought_signature_validator")
+        );
+        assert_eq!((stats.cache_hits, stats.fallbacks), (1, 1));
+    }
+
     #[test]
     fn patch_request_updates_only_model_content_parts() {
         let engine = ThoughtSignatureEngine::new(3600, 1024);
@@ -122,7 +274,7 @@ mod tests {
             ]
         }));
 
-        patch_request(&mut request, &engine);
+        patch_request(&mut request, &engine, None, DEFAULT_PARALLEL_FILL_THRESHOLD);
 
         assert!(request.contents[0].parts[0].thought_signature.is_none());
         assert_eq!(
@@ -131,6 +283,176 @@ mod tests {
         );
     }
 
+    #[test]
+    fn assistant_role_is_patched_when_aliased() {
+        let history = json!({
+            "contents": [
+                {
+                    "role": "assistant",
+                    "parts": [{"thought": true, "text": "assistant thought"}]
+                }
+            ]
+        });
+
+        let plain = ThoughtSignatureEngine::new(3600, 1024);
+        let mut request = parse_request(history.clone());
+        patch_request(&mut request, &plain, None, DEFAULT_PARALLEL_FILL_THRESHOLD);
+        assert!(request.contents[0].parts[0].thought_signature.is_none());
+
+        let aliased = ThoughtSignatureEngine::new(3600, 1024)
+            .with_policy(EnginePolicy::default().with_model_role_alias("assistant"));
+        let mut request = parse_request(history);
+        patch_request(
+            &mut request,
+            &aliased,
+            None,
+            DEFAULT_PARALLEL_FILL_THRESHOLD,
+        );
+        assert_eq!(
+            request.contents[0].parts[0].thought_signature.as_deref(),
+            Some("skip_thought_signature_validator")
+        );
+    }
+
+    #[test]
+    fn thought_miss_borrows_sibling_function_call_signature() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024)
+            .with_policy(EnginePolicy::default().with_sibling_signatures());
+        let function_call = json!({"name": "get_weather", "args": {}});
+        let key = CacheKeyGenerator::generate_json(&function_call).expect("json key must exist");
+        engine.put_signature(key, Arc::from("sig_call"));
+
+        let mut request = parse_request(json!({
+            "contents": [
+                {
+                    "role": "model",
+                    "parts": [
+                        {"thought": true, "text": "uncached plan"},
+                        {"functionCall": function_call}
+                    ]
+                },
+                {
+                    "role": "model",
+                    "parts": [{"thought": true, "text": "other turn"}]
+                }
+            ]
+        }));
+        patch_request(&mut request, &engine, None, DEFAULT_PARALLEL_FILL_THRESHOLD);
+
+        assert_eq!(
+            request.contents[0].parts[0].thought_signature.as_deref(),
+            Some("sig_call")
+        );
+        // A function call in another content is not a sibling.
+        assert_eq!(
+            request.contents[1].parts[0].thought_signature.as_deref(),
+            Some("skip_thought_signature_validator")
+        );
+    }
+
+    #[test]
+    fn strip_mode_removes_thoughts_and_signs_function_calls() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024)
+            .with_policy(EnginePolicy::default().with_thoughts_stripped("gemini-3-pro-preview"));
+        let history = json!({
+            "contents": [
+                {
+                    "role": "model",
+                    "parts": [
+                        {"thought": true, "text": "plan the call"},
+                        {"functionCall": {"name": "get_weather", "args": {}}},
+                        {"text": "done"}
+                    ]
+                }
+            ]
+        });
+
+        let mut stripped = parse_request(history.clone());
+        patch_request(
+            &mut stripped,
+            &engine,
+            Some("gemini-3-pro-preview"),
+            DEFAULT_PARALLEL_FILL_THRESHOLD,
+        );
+        let parts = &stripped.contents[0].parts;
+        assert_eq!(parts.len(), 2);
+        assert!(parts.iter().all(|part| part.thought != Some(true)));
+        assert!(parts[0].function_call.is_some());
+        assert_eq!(
+            parts[0].thought_signature.as_deref(),
+            Some("skip_thought_signature_validator")
+        );
+        assert!(parts[1].thought_signature.is_none());
+
+        // Other models keep and sign their thought parts.
+        let mut kept = parse_request(history);
+        patch_request(
+            &mut kept,
+            &engine,
+            Some("gemini-2.5-pro"),
+            DEFAULT_PARALLEL_FILL_THRESHOLD,
+        );
+        assert_eq!(kept.contents[0].parts.len(), 3);
+        assert_eq!(
+            kept.contents[0].parts[0].thought_signature.as_deref(),
+            Some("skip_thought_signature_validator")
+        );
+    }
+
+    #[test]
+    fn mixed_parts_each_keep_their_own_signature() {
+        let engine = ThoughtSignatureEngine::new(3600, 1024);
+        let calls: Vec<_> = (0..4)
+            .map(|i| json!({"name": format!("tool_{i}"), "args": {"i": i}}))
+            .collect();
+        for (i, call) in calls.iter().enumerate() {
+            let key = CacheKeyGenerator::generate_json(call).expect("function call key must exist");
+            engine.put_signature(key, Arc::from(format!("sig_call_{i}")));
+            let key = CacheKeyGenerator::generate_text(format!("thought {i}"))
+                .expect("text key must exist");
+            engine.put_signature(key, Arc::from(format!("sig_thought_{i}")));
+        }
+
+        let contents: Vec<_> = (0..4)
+            .flat_map(|i| {
+                [
+                    json!({"role": "user", "parts": [{"text": format!("question {i}")}]}),
+                    json!({
+                        "role": "model",
+                        "parts": [
+                            {"thought": true, "text": format!("thought {i}")},
+                            {"text": "visible"},
+                            {"functionCall": calls[i]}
+                        ]
+                    }),
+                ]
+            })
+            .collect();
+
+        // Both the sequential and the threaded decision paths must pair every part correctly.
+        for threshold in [usize::MAX, 1] {
+            let mut request = parse_request(json!({ "contents": contents }));
+            patch_request(&mut request, &engine, None, threshold);
+
+            for (i, content) in request.contents.iter().skip(1).step_by(2).enumerate() {
+                let sigs: Vec<_> = content
+                    .parts
+                    .iter()
+                    .map(|part| part.thought_signature.clone())
+                    .collect();
+                assert_eq!(
+                    sigs,
+                    vec![
+                        Some(format!("sig_thought_{i}")),
+                        None,
+                        Some(format!("sig_call_{i}")),
+                    ],
+                    "threshold {threshold}, model turn {i}"
+                );
+            }
+        }
+    }
+
     #[test]
     fn patch_request_uses_cached_signature_for_function_call() {
         let engine = ThoughtSignatureEngine::new(3600, 1024);
@@ -164,7 +486,7 @@ mod tests {
             ]
         }));
 
-        patch_request(&mut request, &engine);
+        patch_request(&mut request, &engine, None, DEFAULT_PARALLEL_FILL_THRESHOLD);
 
         assert_eq!(
             request.contents[0].parts[0].thought_signature.as_deref(),
@@ -188,7 +510,7 @@ mod tests {
             ]
         }));
 
-        patch_request(&mut request, &engine);
+        patch_request(&mut request, &engine, None, DEFAULT_PARALLEL_FILL_THRESHOLD);
         assert!(request.contents[0].parts[0].thought_signature.is_none());
     }
 }
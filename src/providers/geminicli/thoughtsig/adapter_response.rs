@@ -3,16 +3,43 @@ use pollux_thoughtsig_core::{SniffEvent, Sniffable};
 
 pub(super) struct GeminiResponseAdapter<'a>(pub &'a GeminiResponseBody);
 
-impl Sniffable for GeminiResponseAdapter<'_> {
+impl<'a> GeminiResponseAdapter<'a> {
+    /// Every content part of every candidate, in order. A candidate without parts still yields
+    /// one empty item, so its finish reason reaches the sniffer.
+    pub(super) fn parts(&self) -> impl Iterator<Item = CandidatePart<'a>> {
+        self.0.candidates.iter().flat_map(|candidate| {
+            let parts = candidate
+                .content
+                .as_ref()
+                .map(|content| content.parts.as_slice())
+                .unwrap_or_default();
+            let last = parts.len().saturating_sub(1);
+            let finished = candidate.finish_reason.is_some();
+            parts
+                .iter()
+                .map(Some)
+                .chain(parts.is_empty().then_some(None))
+                .enumerate()
+                .map(move |(position, part)| CandidatePart {
+                    index: candidate.index,
+                    part,
+                    finished: finished && position == last,
+                })
+        })
+    }
+}
+
+/// One part of a response candidate.
+pub(super) struct CandidatePart<'a> {
+    index: Option<u32>,
+    part: Option<&'a Part>,
+    /// Last part of a candidate that reported a finish reason.
+    finished: bool,
+}
+
+impl Sniffable for CandidatePart<'_> {
     fn data(&self) -> SniffEvent<'_> {
-        let part = self
-            .0
-            .candidates
-            .first()
-            .and_then(|candidate| candidate.content.as_ref())
-            .and_then(|content| content.parts.first());
-
-        let Some(part) = part else {
+        let Some(part) = self.part else {
             return SniffEvent::None;
         };
 
@@ -31,26 +58,14 @@ impl Sniffable for GeminiResponseAdapter<'_> {
     }
 
     fn thought_signature(&self) -> Option<&str> {
-        self.0
-            .candidates
-            .first()
-            .and_then(|candidate| candidate.content.as_ref())
-            .and_then(|content| content.parts.first())
-            .and_then(|part| part.thought_signature.as_deref())
+        self.part.and_then(|part| part.thought_signature.as_deref())
     }
 
     fn index(&self) -> Option<u32> {
-        self.0
-            .candidates
-            .first()
-            .and_then(|candidate| candidate.index)
+        self.index
     }
 
     fn is_finished(&self) -> bool {
-        self.0
-            .candidates
-            .first()
-            .and_then(|candidate| candidate.finish_reason.as_ref())
-            .is_some()
+        self.finished
     }
 }
@@ -2,4 +2,4 @@ mod adapter_request;
 mod adapter_response;
 mod service;
 
-pub use service::GeminiThoughtSigService;
+pub use service::{FillStatsSnapshot, GeminiThoughtSigService};
@@ -1,15 +1,92 @@
-use super::adapter_request::patch_request;
+use super::adapter_request::{patch_request, patch_request_async, patch_request_incremental};
 use super::adapter_response::GeminiResponseAdapter;
+use crate::db::DbActorHandle;
+use crate::providers::shared_signature_store::SharedSignatureStore;
+use crate::providers::signature_store::SqliteSignatureStore;
+use crate::providers::thoughtsig_selftest::{SELF_TEST_MODEL, SelfTestExchange};
 use pollux_schema::gemini::{GeminiGenerateContentRequest, GeminiResponseBody};
-use pollux_thoughtsig_core::{SignatureSniffer, ThoughtSignatureEngine};
+use pollux_thoughtsig_core::{
+    AsyncSignatureStore, CacheKey, EnginePolicy, IncrementalFill, MokaSignatureStore, PatchStats,
+    SignatureExpiry, SignatureSniffer, SignatureStore, StoreError, StoreFootprint,
+    ThoughtSignature, ThoughtSignatureEngine,
+};
+use serde::Serialize;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::warn;
 
 const DEFAULT_TTL_SECS: u64 = 60 * 60;
 const DEFAULT_MAX_CAPACITY: u64 = 200_000;
+const INCREMENTAL_MAX_CONVERSATIONS: u64 = 10_000;
+const INCREMENTAL_IDLE: Duration = Duration::from_secs(30 * 60);
 
 #[derive(Clone)]
 pub struct GeminiThoughtSigService {
     engine: Arc<ThoughtSignatureEngine>,
+    incremental: Arc<IncrementalFill>,
+    fill_counters: Arc<FillCounters>,
+    /// Store shared with other instances (`basic.thoughtsig_redis_url`): requests are filled
+    /// from it and sniffed signatures are copied into it.
+    shared: Option<Arc<SharedSignatureStore>>,
+}
+
+/// Request-patching totals since the service was built (see
+/// [`GeminiThoughtSigService::stats_snapshot`]).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FillStatsSnapshot {
+    /// Thoughts and function calls that needed a signature decision, whatever it was.
+    pub total_considered: u64,
+    /// Filled with the signature the cache recorded for them.
+    pub cache_hits: u64,
+    /// Filled with a dummy signature after a cache miss.
+    pub dummy_filled: u64,
+    /// Dummy fills whose miss the negative cache already knew (see
+    /// `basic.thoughtsig_negative_cache_secs`). Also counted in `dummy_filled`.
+    pub negative_cache_hits: u64,
+    /// Left with the signature the client sent.
+    pub kept_existing: u64,
+}
+
+#[derive(Debug, Default)]
+struct FillCounters {
+    total_considered: AtomicU64,
+    cache_hits: AtomicU64,
+    dummy_filled: AtomicU64,
+    negative_cache_hits: AtomicU64,
+    kept_existing: AtomicU64,
+}
+
+impl FillCounters {
+    fn record(&self, stats: PatchStats) -> PatchStats {
+        let considered = stats.cache_hits
+            + stats.fallbacks
+            + stats.kept
+            + stats.unfilled
+            + stats.borrowed
+            + stats.reused;
+        self.total_considered
+            .fetch_add(considered as u64, Ordering::Relaxed);
+        self.cache_hits
+            .fetch_add(stats.cache_hits as u64, Ordering::Relaxed);
+        self.dummy_filled
+            .fetch_add(stats.fallbacks as u64, Ordering::Relaxed);
+        self.negative_cache_hits
+            .fetch_add(stats.negative_cache_hits as u64, Ordering::Relaxed);
+        self.kept_existing
+            .fetch_add(stats.kept as u64, Ordering::Relaxed);
+        stats
+    }
+
+    fn snapshot(&self) -> FillStatsSnapshot {
+        FillStatsSnapshot {
+            total_considered: self.total_considered.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            dummy_filled: self.dummy_filled.load(Ordering::Relaxed),
+            negative_cache_hits: self.negative_cache_hits.load(Ordering::Relaxed),
+            kept_existing: self.kept_existing.load(Ordering::Relaxed),
+        }
+    }
 }
 
 impl Default for GeminiThoughtSigService {
@@ -20,30 +97,231 @@ impl Default for GeminiThoughtSigService {
 
 impl GeminiThoughtSigService {
     pub fn new() -> Self {
-        let engine = ThoughtSignatureEngine::new(DEFAULT_TTL_SECS, DEFAULT_MAX_CAPACITY);
+        Self::with_policy(EnginePolicy::default())
+    }
+
+    /// Service whose cache misses fall back to the dummy signatures in `policy`.
+    pub fn with_policy(policy: EnginePolicy) -> Self {
+        Self::with_policy_and_expiry(policy, SignatureExpiry::ttl(DEFAULT_TTL_SECS))
+    }
+
+    /// Like `with_policy`, with cache entries expiring per `expiry`.
+    pub fn with_policy_and_expiry(policy: EnginePolicy, expiry: SignatureExpiry) -> Self {
+        let store = MokaSignatureStore::with_expiry(expiry, DEFAULT_MAX_CAPACITY);
+        Self::with_policy_and_store(policy, Box::new(store))
+    }
+
+    /// Like `with_policy_and_expiry`, with signatures also kept in the database and the
+    /// unexpired ones reloaded from it.
+    pub async fn persisted(
+        policy: EnginePolicy,
+        expiry: SignatureExpiry,
+        db: DbActorHandle,
+    ) -> Self {
+        let store = SqliteSignatureStore::open(
+            db,
+            "geminicli",
+            expiry,
+            DEFAULT_MAX_CAPACITY,
+            Duration::from_secs(DEFAULT_TTL_SECS),
+        )
+        .await;
+        Self::with_policy_and_store(policy, Box::new(store))
+    }
+
+    /// Cache keys are namespaced by model: a signature is only replayed to the model it came from.
+    fn with_policy_and_store(policy: EnginePolicy, store: Box<dyn SignatureStore>) -> Self {
+        let engine = ThoughtSignatureEngine::with_store(store)
+            .with_policy(policy.with_model_namespaced_keys());
 
         Self {
             engine: Arc::new(engine),
+            incremental: Arc::new(IncrementalFill::new(
+                INCREMENTAL_MAX_CONVERSATIONS,
+                INCREMENTAL_IDLE,
+            )),
+            fill_counters: Arc::default(),
+            shared: None,
         }
     }
 
-    pub fn patch_request(&self, request: &mut GeminiGenerateContentRequest) {
-        patch_request(request, self.engine.as_ref())
+    /// Fill requests from `store` and copy every sniffed signature into it, so instances
+    /// sharing the store replay each other's signatures. The local cache still records them.
+    pub fn with_shared_store(mut self, store: SharedSignatureStore) -> Self {
+        self.shared = Some(Arc::new(store));
+        self
+    }
+
+    /// Maximum number of signatures the cache holds before evicting.
+    pub fn max_capacity(&self) -> u64 {
+        DEFAULT_MAX_CAPACITY
+    }
+
+    /// Lifetime totals of every `patch_request*` call on this service and its clones.
+    pub fn stats_snapshot(&self) -> FillStatsSnapshot {
+        self.fill_counters.snapshot()
+    }
+
+    pub fn patch_request(&self, request: &mut GeminiGenerateContentRequest) -> PatchStats {
+        self.fill_counters.record(patch_request(
+            request,
+            self.engine.as_ref(),
+            None,
+            self.engine.parallel_fill_threshold(),
+        ))
+    }
+
+    /// Like `patch_request`, but cache misses get `model`'s dummy signature if one is configured.
+    pub fn patch_request_for_model(
+        &self,
+        model: &str,
+        request: &mut GeminiGenerateContentRequest,
+    ) -> PatchStats {
+        self.fill_counters.record(patch_request(
+            request,
+            self.engine.as_ref(),
+            Some(model),
+            self.engine.parallel_fill_threshold(),
+        ))
+    }
+
+    /// Like `patch_request_for_model`, but only parts appended since `conversation`'s previous
+    /// turn are decided; earlier parts get the signatures they were given then.
+    pub fn patch_request_in_conversation(
+        &self,
+        model: &str,
+        conversation: &str,
+        request: &mut GeminiGenerateContentRequest,
+    ) -> PatchStats {
+        self.fill_counters.record(patch_request_incremental(
+            request,
+            self.engine.as_ref(),
+            Some(model),
+            self.engine.parallel_fill_threshold(),
+            self.incremental.as_ref(),
+            conversation,
+        ))
+    }
+
+    /// Patch a request to `model` the way the request routes do: from the shared store when
+    /// one is configured, else reusing `conversation`'s earlier decisions when given.
+    ///
+    /// Remembered decisions are per instance, so they are not used with a shared store.
+    pub async fn patch_request_async(
+        &self,
+        model: &str,
+        conversation: Option<&str>,
+        request: &mut GeminiGenerateContentRequest,
+    ) -> PatchStats {
+        match (&self.shared, conversation) {
+            (Some(shared), _) => self.fill_counters.record(
+                patch_request_async(request, self.engine.as_ref(), shared.as_ref(), Some(model))
+                    .await,
+            ),
+            (None, Some(conversation)) => {
+                self.patch_request_in_conversation(model, conversation, request)
+            }
+            (None, None) => self.patch_request_for_model(model, request),
+        }
     }
 
     pub fn build_sniffer(&self) -> SignatureSniffer {
-        SignatureSniffer::new(self.engine.clone())
+        let sniffer = SignatureSniffer::new(self.engine.clone())
+            .with_duplicate_policy(self.engine.duplicate_policy());
+        match &self.shared {
+            Some(shared) => {
+                let shared = shared.clone();
+                sniffer.with_record_hook(Arc::new(move |key, signature| {
+                    let shared = shared.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = shared.put_signature(key, signature).await {
+                            warn!(key, error = %err, "Shared signature store write failed");
+                        }
+                    });
+                }))
+            }
+            None => sniffer,
+        }
+    }
+
+    /// Sniffer for a response from `model`, whose signatures `patch_request_for_model` finds.
+    pub fn build_sniffer_for_model(&self, model: &str) -> SignatureSniffer {
+        self.build_sniffer().with_model(model)
     }
 
     pub fn sniff_response(&self, response: &GeminiResponseBody, sniffer: &mut SignatureSniffer) {
-        let adapter = GeminiResponseAdapter(response);
-        sniffer.inspect(&adapter);
+        for part in GeminiResponseAdapter(response).parts() {
+            sniffer.inspect(&part);
+        }
+    }
+
+    pub fn snapshot(&self) -> Result<Vec<(CacheKey, ThoughtSignature)>, StoreError> {
+        self.engine.snapshot()
+    }
+
+    pub fn footprint(&self) -> Result<StoreFootprint, StoreError> {
+        self.engine.footprint()
+    }
+
+    pub fn put_many(
+        &self,
+        entries: Vec<(CacheKey, ThoughtSignature)>,
+    ) -> Result<usize, StoreError> {
+        self.engine.put_many(entries)
+    }
+
+    /// Drop the signature cached under `key` (e.g. one upstream rejected as stale), so the next
+    /// request dummy-fills that part instead of replaying it.
+    pub fn forget(&self, key: CacheKey) -> Result<(), StoreError> {
+        self.engine.invalidate_signature(&key)?;
+        self.incremental.forget(key);
+        if let Some(shared) = self.shared.clone()
+            && let Ok(runtime) = tokio::runtime::Handle::try_current()
+        {
+            runtime.spawn(async move {
+                if let Err(err) = shared.invalidate_signature(&key).await {
+                    warn!(key, error = %err, "Shared signature store delete failed");
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Drop every cached signature, e.g. to recover from a poisoned cache without a restart.
+    /// A shared store keeps its entries until they expire.
+    pub fn clear(&self) -> Result<(), StoreError> {
+        self.engine.invalidate_all()?;
+        self.incremental.clear();
+        Ok(())
+    }
+
+    /// Record a synthetic signature through the sniffer, then patch a request replaying it and
+    /// report whether the fill found it. The synthetic entry is dropped afterwards and the fill
+    /// counters are left alone.
+    pub fn self_test(&self) -> bool {
+        let mut exchange = SelfTestExchange::new();
+        let mut sniffer = self.build_sniffer_for_model(SELF_TEST_MODEL);
+        self.sniff_response(&exchange.response, &mut sniffer);
+        patch_request(
+            &mut exchange.request,
+            self.engine.as_ref(),
+            Some(SELF_TEST_MODEL),
+            self.engine.parallel_fill_threshold(),
+        );
+        if let Some(key) = self
+            .engine
+            .json_key(Some(SELF_TEST_MODEL), &exchange.function_call)
+        {
+            let _ = self.engine.invalidate_signature(&key);
+        }
+        exchange.hit()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pollux_thoughtsig_core::{CacheKeyGenerator, DuplicatePolicy};
     use serde_json::json;
 
     #[test]
@@ -71,6 +349,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn patch_request_for_model_uses_configured_dummy() {
+        let service = GeminiThoughtSigService::with_policy(
+            EnginePolicy::default()
+                .with_model_dummy("gemini-3-pro-preview", "dummy_pro")
+                .with_model_dummy("gemini-3-flash-preview", "dummy_flash")
+                .without_model_dummy("gemini-2.0-flash"),
+        );
+        let fill = |model: &str| {
+            let mut req: GeminiGenerateContentRequest = serde_json::from_value(json!({
+                "contents": [{
+                    "role": "model",
+                    "parts": [{"functionCall": {"name": "lookup", "args": {}}}]
+                }]
+            }))
+            .expect("request json must parse");
+            service.patch_request_for_model(model, &mut req);
+            req.contents[0].parts[0].thought_signature.clone()
+        };
+
+        assert_eq!(fill("gemini-3-pro-preview").as_deref(), Some("dummy_pro"));
+        assert_eq!(
+            fill("gemini-3-flash-preview").as_deref(),
+            Some("dummy_flash")
+        );
+        assert_eq!(
+            fill("gemini-2.5-pro").as_deref(),
+            Some("skip_thought_signature_validator")
+        );
+        assert_eq!(fill("gemini-2.0-flash"), None);
+    }
+
+    #[test]
+    fn conversation_turn_keeps_earlier_fills() {
+        let service = GeminiThoughtSigService::with_policy(
+            EnginePolicy::default().with_model_dummy("gemini-3-pro-preview", "dummy_pro"),
+        );
+        let call = |name: &str| json!({"role": "model", "parts": [{"functionCall": {"name": name, "args": {}}}]});
+        let mut first: GeminiGenerateContentRequest =
+            serde_json::from_value(json!({"contents": [call("lookup")]}))
+                .expect("request json must parse");
+        service.patch_request_in_conversation("gemini-3-pro-preview", "conv", &mut first);
+
+        let mut second: GeminiGenerateContentRequest =
+            serde_json::from_value(json!({"contents": [call("lookup"), call("search")]}))
+                .expect("request json must parse");
+        service.patch_request_in_conversation("gemini-3-pro-preview", "conv", &mut second);
+
+        assert_eq!(
+            second.contents[0].parts[0].thought_signature,
+            first.contents[0].parts[0].thought_signature
+        );
+        assert_eq!(
+            second.contents[1].parts[0].thought_signature.as_deref(),
+            Some("dummy_pro")
+        );
+    }
+
     #[test]
     fn record_then_patch_hits_cache() {
         let service = GeminiThoughtSigService::new();
@@ -119,6 +455,189 @@ mod tests {
         );
     }
 
+    #[test]
+    fn signatures_are_only_replayed_to_the_model_that_produced_them() {
+        let service = GeminiThoughtSigService::new();
+        let response: GeminiResponseBody = serde_json::from_value(json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [{
+                        "thought": true,
+                        "text": "shared reasoning",
+                        "thoughtSignature": "pro_signature"
+                    }]
+                },
+                "finishReason": "STOP"
+            }]
+        }))
+        .expect("response json must parse");
+        let mut sniffer = service.build_sniffer_for_model("gemini-3-pro-preview");
+        service.sniff_response(&response, &mut sniffer);
+
+        let patched = |model: &str| {
+            let mut req: GeminiGenerateContentRequest = serde_json::from_value(json!({
+                "contents": [{
+                    "role": "model",
+                    "parts": [{"thought": true, "text": "shared reasoning"}]
+                }]
+            }))
+            .expect("request json must parse");
+            service.patch_request_for_model(model, &mut req);
+            req.contents[0].parts[0].thought_signature.clone()
+        };
+        assert_eq!(
+            patched("gemini-3-pro-preview").as_deref(),
+            Some("pro_signature")
+        );
+        assert_eq!(
+            patched("gemini-2.5-pro").as_deref(),
+            Some("skip_thought_signature_validator")
+        );
+    }
+
+    #[test]
+    fn forgotten_signatures_fall_back_to_the_dummy() {
+        let service = GeminiThoughtSigService::new();
+        service
+            .put_many(
+                ["first", "second"]
+                    .map(|text| {
+                        let key = CacheKeyGenerator::generate_text(text).expect("key");
+                        (key, Arc::from(format!("sig_{text}")))
+                    })
+                    .into(),
+            )
+            .expect("put signatures");
+        let patched = |text: &str| {
+            let mut req: GeminiGenerateContentRequest = serde_json::from_value(json!({
+                "contents": [{"role": "model", "parts": [{"thought": true, "text": text}]}]
+            }))
+            .expect("request json must parse");
+            service.patch_request(&mut req);
+            req.contents[0].parts[0].thought_signature.clone()
+        };
+        assert_eq!(patched("first").as_deref(), Some("sig_first"));
+
+        service
+            .forget(CacheKeyGenerator::generate_text("first").expect("key"))
+            .expect("forget");
+        assert_eq!(
+            patched("first").as_deref(),
+            Some("skip_thought_signature_validator")
+        );
+        assert_eq!(patched("second").as_deref(), Some("sig_second"));
+
+        service.clear().expect("clear");
+        assert_eq!(
+            patched("second").as_deref(),
+            Some("skip_thought_signature_validator")
+        );
+    }
+
+    #[test]
+    fn forgotten_signatures_are_not_replayed_within_a_conversation() {
+        let model = "gemini-3-pro-preview";
+        let service = GeminiThoughtSigService::new();
+        let key = service
+            .engine
+            .text_key(Some(model), "plan")
+            .expect("text key must exist");
+        service
+            .put_many(vec![(key, Arc::from("sig_plan"))])
+            .expect("put signatures");
+        let patched = |texts: &[&str]| {
+            let parts: Vec<_> = texts
+                .iter()
+                .map(|text| json!({"thought": true, "text": text}))
+                .collect();
+            let mut req: GeminiGenerateContentRequest = serde_json::from_value(json!({
+                "contents": [{"role": "model", "parts": parts}]
+            }))
+            .expect("request json must parse");
+            service.patch_request_in_conversation(model, "conv", &mut req);
+            req.contents[0].parts[0].thought_signature.clone()
+        };
+        assert_eq!(patched(&["plan"]).as_deref(), Some("sig_plan"));
+
+        service.forget(key).expect("forget");
+        assert_eq!(
+            patched(&["plan", "next"]).as_deref(),
+            Some("skip_thought_signature_validator")
+        );
+    }
+
+    #[test]
+    fn stats_snapshot_totals_every_patched_request() {
+        let service = GeminiThoughtSigService::new();
+        let response: GeminiResponseBody = serde_json::from_value(json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [{"thought": true, "text": "cached", "thoughtSignature": "sig"}]
+                },
+                "finishReason": "STOP"
+            }]
+        }))
+        .expect("response json must parse");
+        let mut sniffer = service.build_sniffer();
+        service.sniff_response(&response, &mut sniffer);
+
+        let mut req: GeminiGenerateContentRequest = serde_json::from_value(json!({
+            "contents": [{
+                "role": "model",
+                "parts": [
+                    {"thought": true, "text": "cached"},
+                    {"thought": true, "text": "never seen"},
+                    {"text": "plain answer"}
+                ]
+            }]
+        }))
+        .expect("request json must parse");
+        // Clones share the counters.
+        service.clone().patch_request(&mut req.clone());
+        service.patch_request(&mut req);
+
+        assert_eq!(
+            service.stats_snapshot(),
+            FillStatsSnapshot {
+                total_considered: 4,
+                cache_hits: 2,
+                dummy_filled: 2,
+                negative_cache_hits: 0,
+                kept_existing: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn self_test_hits_and_leaves_no_trace() {
+        let service = GeminiThoughtSigService::new();
+        assert!(service.self_test());
+        assert!(service.snapshot().expect("snapshot").is_empty());
+        assert_eq!(service.stats_snapshot(), FillStatsSnapshot::default());
+    }
+
+    #[test]
+    fn repeated_misses_are_counted_as_negative_cache_hits() {
+        let service = GeminiThoughtSigService::with_policy(
+            EnginePolicy::default().with_negative_cache(std::time::Duration::from_secs(60)),
+        );
+        let req: GeminiGenerateContentRequest = serde_json::from_value(json!({
+            "contents": [{
+                "role": "model",
+                "parts": [{"thought": true, "text": "retried reasoning"}]
+            }]
+        }))
+        .expect("request json must parse");
+
+        let first = service.patch_request(&mut req.clone());
+        let second = service.patch_request(&mut req.clone());
+        assert_eq!((first.fallbacks, first.negative_cache_hits), (1, 0));
+        assert_eq!((second.fallbacks, second.negative_cache_hits), (1, 1));
+        assert_eq!(service.stats_snapshot().negative_cache_hits, 1);
+    }
+
     #[test]
     fn record_then_patch_hits_cache_for_function_call_hash() {
         let service = GeminiThoughtSigService::new();
@@ -241,4 +760,90 @@ mod tests {
             Some("stream_sig_001")
         );
     }
+
+    #[test]
+    fn every_candidate_of_a_response_is_recorded() {
+        let service = GeminiThoughtSigService::new();
+        let chunks = [
+            json!({"candidates": [
+                {"index": 0, "content": {"parts": [{"thought": true, "text": "zero "}]}},
+                {"index": 1, "content": {"parts": [{"thought": true, "text": "one "}]}}
+            ]}),
+            json!({"candidates": [
+                {"index": 1, "finishReason": "STOP", "content": {"parts": [
+                    {"thought": true, "text": "plan"},
+                    {"functionCall": {"name": "first", "args": {}}, "thoughtSignature": "sig_one"},
+                    {"functionCall": {"name": "second", "args": {}}}
+                ]}},
+                {"index": 0, "finishReason": "STOP", "content": {"parts": [
+                    {"thought": true, "text": "plan", "thoughtSignature": "sig_zero"}
+                ]}}
+            ]}),
+        ];
+
+        let mut sniffer = service.build_sniffer();
+        for chunk in chunks {
+            let chunk: GeminiResponseBody =
+                serde_json::from_value(chunk).expect("chunk json must parse");
+            service.sniff_response(&chunk, &mut sniffer);
+        }
+
+        let mut req: GeminiGenerateContentRequest = serde_json::from_value(json!({
+            "contents": [
+                {"role": "model", "parts": [{"thought": true, "text": "zero plan"}]},
+                {"role": "model", "parts": [{"thought": true, "text": "one plan"}]},
+                {"role": "model", "parts": [
+                    {"functionCall": {"name": "first", "args": {}}},
+                    {"functionCall": {"name": "second", "args": {}}}
+                ]}
+            ]
+        }))
+        .expect("request json must parse");
+
+        service.patch_request(&mut req);
+        let signature = |content: usize, part: usize| {
+            req.contents[content].parts[part]
+                .thought_signature
+                .as_deref()
+        };
+        assert_eq!(signature(0, 0), Some("sig_zero"));
+        assert_eq!(signature(1, 0), Some("sig_one"));
+        assert_eq!(signature(2, 0), Some("sig_one"));
+        // Only the signed call is cached; its unsigned sibling is not keyed with it.
+        assert_eq!(signature(2, 1), Some("skip_thought_signature_validator"));
+    }
+
+    #[test]
+    fn sniffers_follow_the_policy_for_duplicate_signatures() {
+        let response: GeminiResponseBody = serde_json::from_value(json!({"candidates": [
+            {"index": 0, "finishReason": "STOP", "content": {"parts": [
+                {"thought": true, "text": "same plan", "thoughtSignature": "sig_first"}
+            ]}},
+            {"index": 1, "finishReason": "STOP", "content": {"parts": [
+                {"thought": true, "text": "same plan", "thoughtSignature": "sig_second"}
+            ]}}
+        ]}))
+        .expect("response json must parse");
+
+        for (duplicates, expected) in [
+            (DuplicatePolicy::LastWins, "sig_second"),
+            (DuplicatePolicy::FirstWins, "sig_first"),
+        ] {
+            let service = GeminiThoughtSigService::with_policy(
+                EnginePolicy::default().with_duplicate_policy(duplicates),
+            );
+            let mut sniffer = service.build_sniffer();
+            service.sniff_response(&response, &mut sniffer);
+
+            let mut req: GeminiGenerateContentRequest = serde_json::from_value(json!({
+                "contents": [{"role": "model", "parts": [{"thought": true, "text": "same plan"}]}]
+            }))
+            .expect("request json must parse");
+            service.patch_request(&mut req);
+            assert_eq!(
+                req.contents[0].parts[0].thought_signature.as_deref(),
+                Some(expected)
+            );
+        }
+    }
 }
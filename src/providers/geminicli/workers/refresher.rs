@@ -1,4 +1,5 @@
 use super::super::{
+    OnboardProgress,
     client::oauth::{
         OAUTH_RETRY_POLICY,
         endpoints::GoogleOauthEndpoints,
@@ -7,11 +8,13 @@ use super::super::{
         utils::attach_email_from_id_token,
     },
     manager::{CredentialId, GeminiCliActorHandle},
+    onboard_progress::OnboardProgressTx,
     resource::GeminiCliResource,
 };
 use crate::config::GeminiCliResolvedConfig;
 use crate::error::{IsRetryable, OauthError, PolluxError};
 use backon::{ExponentialBuilder, Retryable};
+use chrono::Utc;
 use futures::stream::StreamExt;
 use governor::{Quota, RateLimiter};
 use ractor::{Actor, ActorProcessingErr, ActorRef};
@@ -27,14 +30,20 @@ use tracing::{debug, error, info, warn};
 pub(in crate::providers::geminicli) struct RefreshJob {
     pub cred: GeminiCliResource,
     pub r#type: TaskType,
+    /// Where an onboarding job reports its steps, when a client is watching.
+    pub progress: Option<OnboardProgressTx>,
 }
 
 impl RefreshJob {
-    async fn execute(mut self, client: reqwest::Client) -> Result<RefreshJob, RefreshError> {
+    async fn execute(
+        mut self,
+        cfg: Arc<GeminiCliResolvedConfig>,
+        client: reqwest::Client,
+    ) -> Result<RefreshJob, RefreshError> {
         match self.r#type {
             TaskType::Refresh(_) => {
                 if let Err(e) =
-                    refresh_inner(client, *OAUTH_RETRY_POLICY, &mut self.cred, false).await
+                    refresh_inner(client, *OAUTH_RETRY_POLICY, &cfg, &mut self.cred, false).await
                 {
                     return Err(RefreshError {
                         original_job: self,
@@ -42,13 +51,26 @@ impl RefreshJob {
                     });
                 }
             }
+            TaskType::Probe(_) => {
+                if let Err(e) = probe_code_assist(client, &cfg, &mut self.cred).await {
+                    return Err(RefreshError {
+                        original_job: self,
+                        error: e,
+                    });
+                }
+            }
             TaskType::Onboard => {
                 if (self.cred.access_token().is_none()
-                    || self.cred.is_expired()
+                    || self.cred.is_expired_at(Utc::now() + cfg.expiry_skew)
                     || self.cred.sub().is_empty())
-                    && let Err(e) =
-                        refresh_inner(client.clone(), *OAUTH_RETRY_POLICY, &mut self.cred, true)
-                            .await
+                    && let Err(e) = refresh_inner(
+                        client.clone(),
+                        *OAUTH_RETRY_POLICY,
+                        &cfg,
+                        &mut self.cred,
+                        true,
+                    )
+                    .await
                 {
                     return Err(RefreshError {
                         original_job: self,
@@ -72,9 +94,12 @@ impl RefreshJob {
                     });
                 };
 
-                match ensure_companion_project(token_str, client).await {
-                    Ok(project_id) => {
+                match ensure_companion_project(token_str, &cfg, client, self.progress.as_ref())
+                    .await
+                {
+                    Ok((project_id, tier)) => {
                         self.cred.set_project_id(project_id);
+                        self.cred.set_quota_tier(tier.as_str().to_string());
                     }
                     Err(e) => {
                         return Err(RefreshError {
@@ -92,13 +117,15 @@ impl RefreshJob {
 #[derive(Clone, Debug)]
 pub enum TaskType {
     Refresh(CredentialId),
+    /// Refresh a banned credential's token and check `loadCodeAssist` still accepts it.
+    Probe(CredentialId),
     Onboard,
 }
 
 impl TaskType {
     pub fn credential_id(&self) -> Option<CredentialId> {
         match self {
-            TaskType::Refresh(id) => Some(*id),
+            TaskType::Refresh(id) | TaskType::Probe(id) => Some(*id),
             TaskType::Onboard => None,
         }
     }
@@ -156,10 +183,30 @@ impl GeminiCliRefresherHandle {
     }
 }
 
+/// Refresh `cred` and confirm the account is still eligible for Code Assist.
+async fn probe_code_assist(
+    client: reqwest::Client,
+    cfg: &GeminiCliResolvedConfig,
+    cred: &mut GeminiCliResource,
+) -> Result<(), PolluxError> {
+    refresh_inner(client.clone(), *OAUTH_RETRY_POLICY, cfg, cred, false).await?;
+    let access_token = cred.access_token().ok_or(PolluxError::MissingAccessToken)?;
+
+    let load_json = GoogleOauthOps::load_code_assist_with_retry(access_token, client).await?;
+    let load_resp: LoadCodeAssistResponse =
+        serde_json::from_value(load_json.clone()).map_err(PolluxError::JsonError)?;
+    load_resp.ensure_eligible(load_json)
+}
+
 async fn ensure_companion_project(
     access_token: &str,
+    cfg: &GeminiCliResolvedConfig,
     client: reqwest::Client,
-) -> Result<String, PolluxError> {
+    progress: Option<&OnboardProgressTx>,
+) -> Result<(String, UserTier), PolluxError> {
+    if let Some(progress) = progress {
+        progress.send(OnboardProgress::LoadingCodeAssist);
+    }
     let load_json =
         GoogleOauthOps::load_code_assist_with_retry(access_token, client.clone()).await?;
     debug!(body = %load_json, "loadCodeAssist upstream body");
@@ -171,45 +218,104 @@ async fn ensure_companion_project(
 
     let tier = load_resp.resolve_effective_tier();
 
-    if let Some(existing_project_id) = load_resp.cloudaicompanion_project {
+    if let Some(existing_project_id) = load_resp.cloudaicompanion_project.clone() {
         info!(
             project_id = %existing_project_id,
             tier = %tier.as_str(),
             "loadCodeAssist resolved companion project id"
         );
-        return Ok(existing_project_id);
+        return Ok((existing_project_id, tier));
     }
 
+    if let Some(project_id) = fallback_project_id(&load_resp, cfg.default_project_id.as_deref()) {
+        info!(
+            project_id = %project_id,
+            tier = %tier.as_str(),
+            "loadCodeAssist returned no companion project; applying configured default_project_id"
+        );
+        return Ok((project_id, tier));
+    }
+
+    let requested_tier = onboard_tier(cfg.onboard_tier.as_deref(), tier);
     info!(
-        tier = %tier.as_str(),
+        tier = %requested_tier.as_str(),
         "No existing companion project found; starting onboarding"
     );
-    let new_project_id = perform_onboarding(access_token, tier, client).await?;
+    let (new_project_id, quota_tier) =
+        perform_onboarding(access_token, requested_tier.clone(), client, progress).await?;
+    let quota_tier = quota_tier.unwrap_or(requested_tier);
 
     info!(
         project_id = %new_project_id,
+        quota_tier = %quota_tier.as_str(),
         "Companion project provisioning completed"
     );
-    Ok(new_project_id)
+    Ok((new_project_id, quota_tier))
 }
 
+/// Tier to request in `onboardUser`: the configured one, else the effective tier from
+/// `loadCodeAssist`.
+fn onboard_tier(configured: Option<&str>, effective: UserTier) -> UserTier {
+    configured
+        .map(|tier| UserTier::from(tier.to_string()))
+        .unwrap_or(effective)
+}
+
+/// Configured project to use when `loadCodeAssist` did not return one.
+fn fallback_project_id(
+    load_resp: &LoadCodeAssistResponse,
+    default_project_id: Option<&str>,
+) -> Option<String> {
+    if load_resp.cloudaicompanion_project.is_some() {
+        return None;
+    }
+    default_project_id.map(str::to_string)
+}
+
+/// Provision a companion project; returns its id and the quota tier the operation reports.
 async fn perform_onboarding(
     access_token: &str,
     tier: UserTier,
     client: reqwest::Client,
-) -> Result<String, PolluxError> {
-    const MAX_ATTEMPTS: usize = 5;
+    progress: Option<&OnboardProgressTx>,
+) -> Result<(String, Option<UserTier>), PolluxError> {
     const RETRY_DELAY: Duration = Duration::from_secs(5);
+    poll_onboarding(
+        || {
+            GoogleOauthOps::onboard_code_assist_with_retry(
+                access_token,
+                tier.clone(),
+                None,
+                client.clone(),
+            )
+        },
+        RETRY_DELAY,
+        progress,
+    )
+    .await
+}
+
+/// Call `onboard` until the operation reports `done`, up to five times `retry_delay` apart.
+async fn poll_onboarding<F, Fut>(
+    mut onboard: F,
+    retry_delay: Duration,
+    progress: Option<&OnboardProgressTx>,
+) -> Result<(String, Option<UserTier>), PolluxError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Value, OauthError>>,
+{
+    const MAX_ATTEMPTS: usize = 5;
     let mut last_resp: Option<serde_json::Value> = None;
 
     for attempt in 1..=MAX_ATTEMPTS {
-        let resp_json = GoogleOauthOps::onboard_code_assist_with_retry(
-            access_token,
-            tier.clone(),
-            None,
-            client.clone(),
-        )
-        .await?;
+        if let Some(progress) = progress {
+            progress.send(OnboardProgress::Provisioning {
+                attempt,
+                max_attempts: MAX_ATTEMPTS,
+            });
+        }
+        let resp_json = onboard().await?;
         debug!(body = %resp_json, "onboardCodeAssist upstream body");
 
         last_resp = Some(resp_json.clone());
@@ -217,10 +323,11 @@ async fn perform_onboarding(
             serde_json::from_value(resp_json.clone()).map_err(PolluxError::JsonError)?;
 
         if op_resp.done {
+            let quota_tier = op_resp.quota_tier();
             return op_resp
                 .response
                 .and_then(|r| r.project_details)
-                .map(|p| p.id)
+                .map(|p| (p.id, quota_tier))
                 .ok_or_else(|| {
                     OauthError::Flow {
                         code: "ONBOARD_FAILED".to_string(),
@@ -234,9 +341,9 @@ async fn perform_onboarding(
         if attempt < MAX_ATTEMPTS {
             info!(
                 "onboardCodeAssist pending (attempt {}/{}), retrying in {:?}...",
-                attempt, MAX_ATTEMPTS, RETRY_DELAY
+                attempt, MAX_ATTEMPTS, retry_delay
             );
-            sleep(RETRY_DELAY).await;
+            sleep(retry_delay).await;
         }
     }
 
@@ -300,6 +407,7 @@ impl Actor for GeminiCliRefresherActor {
 
         let (job_tx, job_rx) = mpsc::channel::<RefreshJob>(1000);
         let pipeline_handle = handle.clone();
+        let job_cfg = cfg.clone();
 
         // Spawn background refresh worker using buffer_unordered semantics.
         let buffer_unordered = oauth_tps.saturating_mul(2).max(1);
@@ -313,9 +421,10 @@ impl Actor for GeminiCliRefresherActor {
                 .map(|task| {
                     let lim = limiter.clone();
                     let http = client.clone();
+                    let cfg = job_cfg.clone();
                     async move {
                         lim.until_ready().await;
-                        task.execute(http).await
+                        task.execute(cfg, http).await
                     }
                 })
                 .buffer_unordered(buffer_unordered);
@@ -398,11 +507,17 @@ impl Actor for GeminiCliRefresherActor {
 pub async fn refresh_inner(
     client: reqwest::Client,
     retry_policy: ExponentialBuilder,
+    cfg: &GeminiCliResolvedConfig,
     creds: &mut GeminiCliResource,
     attach_email: bool,
 ) -> Result<(), PolluxError> {
     let payload = (|| async {
-        GoogleOauthEndpoints::refresh_access_token(creds.refresh_token(), client.clone()).await
+        GoogleOauthEndpoints::refresh_access_token(
+            &cfg.oauth_token_url,
+            creds.refresh_token(),
+            client.clone(),
+        )
+        .await
     })
     .retry(retry_policy)
     .when(|e: &OauthError| e.is_retryable())
@@ -485,6 +600,34 @@ mod tests {
         assert_eq!(cred.project_id(), "project-a");
     }
 
+    #[test]
+    fn missing_project_uses_configured_default() {
+        let load_resp: LoadCodeAssistResponse = serde_json::from_value(json!({
+            "currentTier": { "id": "standard-tier" },
+            "allowedTiers": [{ "id": "standard-tier", "isDefault": true }]
+        }))
+        .expect("valid loadCodeAssist body");
+
+        assert_eq!(
+            fallback_project_id(&load_resp, Some("manual-project")).as_deref(),
+            Some("manual-project")
+        );
+        assert_eq!(fallback_project_id(&load_resp, None), None);
+    }
+
+    #[test]
+    fn upstream_project_wins_over_default() {
+        let load_resp: LoadCodeAssistResponse = serde_json::from_value(json!({
+            "cloudaicompanionProject": "upstream-project"
+        }))
+        .expect("valid loadCodeAssist body");
+
+        assert_eq!(
+            fallback_project_id(&load_resp, Some("manual-project")),
+            None
+        );
+    }
+
     #[test]
     fn refresh_payload_preserves_email_without_id_token() {
         let mut cred = GeminiCliResource::from_payload(json!({
@@ -505,4 +648,103 @@ mod tests {
         assert_eq!(cred.email(), Some("old@example.com"));
         assert_eq!(cred.access_token(), Some("new-token"));
     }
+
+    #[test]
+    fn refresh_payload_takes_rotated_refresh_token() {
+        let mut cred = make_expired_credential();
+
+        let payload = json!({
+            "access_token": "new-token",
+            "expires_in": 3600,
+            "token_type": "bearer",
+        });
+        apply_refresh_payload(&mut cred, payload, false).expect("refresh payload applied");
+        assert_eq!(cred.refresh_token(), "refresh-token");
+
+        let payload = json!({
+            "access_token": "newer-token",
+            "refresh_token": "rotated-refresh-token",
+            "expires_in": 3600,
+            "token_type": "bearer",
+        });
+        apply_refresh_payload(&mut cred, payload, false).expect("refresh payload applied");
+        assert_eq!(cred.refresh_token(), "rotated-refresh-token");
+    }
+
+    #[test]
+    fn configured_onboard_tier_is_sent_in_onboard_request() {
+        use crate::providers::geminicli::client::oauth::endpoints::OnboardRequest;
+
+        let tier = onboard_tier(Some("free-tier"), UserTier::Standard);
+        let body = serde_json::to_value(OnboardRequest::new(tier, None)).expect("serialize");
+        assert_eq!(body["tierId"], "free-tier");
+
+        let tier = onboard_tier(None, UserTier::Standard);
+        let body = serde_json::to_value(OnboardRequest::new(tier, None)).expect("serialize");
+        assert_eq!(body["tierId"], "standard-tier");
+    }
+
+    #[test]
+    fn onboard_operation_surfaces_quota_tier() {
+        let op: OnboardOperationResponse = serde_json::from_value(json!({
+            "name": "operations/1",
+            "done": true,
+            "response": {
+                "cloudaicompanionProject": { "id": "project-new" },
+                "currentTier": { "id": "free-tier", "quotaTier": "free-tier" }
+            }
+        }))
+        .expect("valid onboard operation");
+        assert_eq!(op.quota_tier(), Some(UserTier::Free));
+
+        let op: OnboardOperationResponse = serde_json::from_value(json!({
+            "name": "operations/2",
+            "done": true,
+            "response": { "cloudaicompanionProject": { "id": "project-new" } }
+        }))
+        .expect("valid onboard operation");
+        assert_eq!(op.quota_tier(), None);
+    }
+
+    #[tokio::test]
+    async fn onboarding_reports_each_poll_before_the_project() {
+        let (progress, mut rx) = OnboardProgressTx::channel();
+        let mut polls = 0;
+        let result = poll_onboarding(
+            || {
+                polls += 1;
+                let done = polls == 3;
+                async move {
+                    Ok(json!({
+                        "name": "operations/1",
+                        "done": done,
+                        "response": { "cloudaicompanionProject": { "id": "project-new" } }
+                    }))
+                }
+            },
+            std::time::Duration::ZERO,
+            Some(&progress),
+        )
+        .await
+        .expect("onboarding completes");
+        assert_eq!(result.0, "project-new");
+
+        drop(progress);
+        let mut steps = Vec::new();
+        while let Some(step) = rx.recv().await {
+            steps.push(step);
+        }
+        let provisioning = |attempt| OnboardProgress::Provisioning {
+            attempt,
+            max_attempts: 5,
+        };
+        assert_eq!(
+            steps,
+            vec![provisioning(1), provisioning(2), provisioning(3)]
+        );
+        assert_eq!(
+            steps[0].to_string(),
+            "Provisioning project (attempt 1/5)..."
+        );
+    }
 }
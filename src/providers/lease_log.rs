@@ -0,0 +1,102 @@
+use crate::db::{DbActorHandle, DbLeaseLogEntry, LeaseLogCreate, LeaseLogFilter};
+use crate::error::PolluxError;
+use crate::providers::ActionForError;
+use tracing::warn;
+
+/// Audit trail of which credential served which request (see `basic.lease_log`).
+#[derive(Clone)]
+pub struct LeaseLog {
+    db: DbActorHandle,
+}
+
+impl LeaseLog {
+    pub fn new(db: DbActorHandle) -> Self {
+        Self { db }
+    }
+
+    /// Rows matching `filter`, newest first.
+    pub async fn query(&self, filter: LeaseLogFilter) -> Result<Vec<DbLeaseLogEntry>, PolluxError> {
+        self.db.list_lease_log(filter).await
+    }
+}
+
+/// How one upstream attempt made with a leased credential ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaseOutcome {
+    Ok,
+    /// Upstream answered 200 with nothing in it.
+    Empty,
+    RateLimited,
+    Banned,
+    ModelUnsupported,
+    Invalid,
+    /// Any other failure, including transport errors.
+    Error,
+}
+
+impl LeaseOutcome {
+    /// Outcome of a non-success upstream status classified as `action`.
+    pub fn for_action(action: &ActionForError) -> Self {
+        match action {
+            ActionForError::RateLimit(_) => Self::RateLimited,
+            ActionForError::Ban => Self::Banned,
+            ActionForError::ModelUnsupported => Self::ModelUnsupported,
+            ActionForError::Invalid => Self::Invalid,
+            ActionForError::None => Self::Error,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Empty => "empty",
+            Self::RateLimited => "rate_limited",
+            Self::Banned => "banned",
+            Self::ModelUnsupported => "model_unsupported",
+            Self::Invalid => "invalid",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// Records the leases of one client request; a no-op when the lease log is off.
+#[derive(Clone, Default)]
+pub(crate) struct LeaseRecorder {
+    log: Option<LeaseLog>,
+    provider: &'static str,
+    request_id: Option<String>,
+}
+
+impl LeaseRecorder {
+    pub(crate) fn new(
+        log: Option<LeaseLog>,
+        provider: &'static str,
+        request_id: Option<String>,
+    ) -> Self {
+        Self {
+            log,
+            provider,
+            request_id,
+        }
+    }
+
+    /// Append a row without waiting for it to be written.
+    pub(crate) fn record(&self, credential_id: u64, model: &str, outcome: LeaseOutcome) {
+        let Some(log) = &self.log else {
+            return;
+        };
+        let Ok(credential_id) = i64::try_from(credential_id) else {
+            return;
+        };
+        let entry = LeaseLogCreate {
+            request_id: self.request_id.clone(),
+            provider: self.provider.to_string(),
+            credential_id,
+            model: model.to_string(),
+            outcome: outcome.as_str().to_string(),
+        };
+        if let Err(e) = log.db.record_lease(entry) {
+            warn!("ID: {credential_id} lease not logged: {e}");
+        }
+    }
+}
@@ -1,12 +1,28 @@
 pub mod antigravity;
+pub mod clock;
 pub mod codex;
+pub mod conversation;
 pub mod geminicli;
+pub mod lease_log;
 pub mod manifest;
+pub mod pool_status;
+pub mod priority;
+#[cfg(feature = "redis")]
+pub mod redis_signature_store;
+pub mod shared_signature_store;
+pub mod signature_store;
 
 mod bootstrap;
+mod circuit_breaker;
 mod policy;
 mod provider_endpoints;
+mod thoughtsig_selftest;
+mod upstream_client;
 mod upstream_retry;
 
 pub use bootstrap::Providers;
-pub use policy::{ActionForError, MappingAction, UPSTREAM_BODY_PREVIEW_CHARS};
+pub use policy::{
+    ActionForError, MappingAction, UPSTREAM_BODY_PREVIEW_CHARS, classify_upstream_error,
+    error_actions_by_provider,
+};
+pub use upstream_client::UpstreamClient;
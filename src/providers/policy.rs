@@ -1,10 +1,19 @@
 use crate::utils::logging::with_pretty_json_debug;
+use pollux_schema::{gemini::GeminiResponseBody, geminicli::GeminiCliResponseBody};
 use reqwest::StatusCode;
+use reqwest::header::{COOKIE, HOST, HeaderMap, HeaderName};
 use serde::{Serialize, de::DeserializeOwned};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{LazyLock, Mutex};
 use std::time::Duration;
 
 pub const UPSTREAM_BODY_PREVIEW_CHARS: usize = 300;
 
+/// How often each provider's upstream errors were classified as each action since startup.
+static ERROR_ACTIONS: LazyLock<Mutex<BTreeMap<(&'static str, &'static str), u64>>> =
+    LazyLock::new(Mutex::default);
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum ActionForError {
     RateLimit(Duration),
@@ -14,6 +23,94 @@ pub enum ActionForError {
     None,
 }
 
+impl ActionForError {
+    /// Stable name used in metrics.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ActionForError::RateLimit(_) => "rate_limit",
+            ActionForError::Ban => "ban",
+            ActionForError::Invalid => "invalid",
+            ActionForError::ModelUnsupported => "model_unsupported",
+            ActionForError::None => "none",
+        }
+    }
+}
+
+fn record_error_action(provider: &'static str, action: &ActionForError) {
+    let mut counts = ERROR_ACTIONS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    *counts.entry((provider, action.label())).or_default() += 1;
+}
+
+/// Error classification counts since startup: provider -> action label -> count.
+pub fn error_actions_by_provider() -> BTreeMap<String, BTreeMap<String, u64>> {
+    let counts = ERROR_ACTIONS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut report: BTreeMap<String, BTreeMap<String, u64>> = BTreeMap::new();
+    for ((provider, action), count) in counts.iter() {
+        report
+            .entry(provider.to_string())
+            .or_default()
+            .insert(action.to_string(), *count);
+    }
+    report
+}
+
+/// Allowlisted headers of an upstream error response, for its log line (see
+/// `providers.defaults.capture_response_headers`).
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct CapturedHeaders(Vec<(HeaderName, String)>);
+
+impl CapturedHeaders {
+    /// Every value of each header in `names` that `headers` carries, in `names` order.
+    pub(crate) fn capture(headers: &HeaderMap, names: &[HeaderName]) -> Self {
+        Self(
+            names
+                .iter()
+                .flat_map(|name| {
+                    headers.get_all(name).iter().map(move |value| {
+                        let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+                        (name.clone(), value)
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for CapturedHeaders {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (name, value)) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{name}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Headers that must never reach an upstream, whatever else a request is built from: the
+/// pollux key, client cookies and the client's `Host`.
+const INTERNAL_HEADERS: [HeaderName; 3] = [HeaderName::from_static("x-goog-api-key"), COOKIE, HOST];
+
+/// Drop [`INTERNAL_HEADERS`] and any `x-pollux-*` header from a set about to be sent upstream.
+pub(crate) fn strip_internal_headers(headers: &mut HeaderMap) {
+    for name in &INTERNAL_HEADERS {
+        headers.remove(name);
+    }
+    let pollux: Vec<HeaderName> = headers
+        .keys()
+        .filter(|name| name.as_str().starts_with("x-pollux-"))
+        .cloned()
+        .collect();
+    for name in pollux {
+        headers.remove(name);
+    }
+}
+
 pub trait MappingAction: std::fmt::Debug + DeserializeOwned + Serialize {
     fn try_match_rule(&self, status: StatusCode) -> Option<ActionForError>;
 
@@ -28,7 +125,22 @@ pub trait MappingAction: std::fmt::Debug + DeserializeOwned + Serialize {
     }
 }
 
+/// Classify a non-success upstream response and count the resulting action under `provider`.
 pub async fn classify_upstream_error<E, MappedError>(
+    provider: &'static str,
+    resp: reqwest::Response,
+    map_raw: impl FnOnce(E) -> MappedError,
+    map_status: impl FnOnce(StatusCode, String) -> MappedError,
+) -> (ActionForError, MappedError)
+where
+    E: MappingAction,
+{
+    let (action, error) = classify_response::<E, _>(resp, map_raw, map_status).await;
+    record_error_action(provider, &action);
+    (action, error)
+}
+
+async fn classify_response<E, MappedError>(
     resp: reqwest::Response,
     map_raw: impl FnOnce(E) -> MappedError,
     map_status: impl FnOnce(StatusCode, String) -> MappedError,
@@ -81,3 +193,116 @@ where
 
     (action, map_status(status, raw_body_owned))
 }
+
+/// Buffer a successful unary response and fail it if upstream sent nothing usable.
+///
+/// Upstream occasionally answers with a 200 whose envelope has no candidate content or
+/// finish reason. That is reported as `PolluxError::EmptyResponse` so the caller's retry loop
+/// can try again; any other body is handed back unchanged for the normal response path.
+pub async fn reject_empty_response(
+    resp: reqwest::Response,
+) -> Result<reqwest::Response, crate::PolluxError> {
+    let status = resp.status();
+    let version = resp.version();
+    let headers = resp.headers().clone();
+    let bytes = resp.bytes().await?;
+
+    if let Ok(envelope) = serde_json::from_slice::<GeminiCliResponseBody>(&bytes)
+        && GeminiResponseBody::from(envelope).is_empty()
+    {
+        return Err(crate::PolluxError::EmptyResponse);
+    }
+
+    let mut rebuilt = axum::http::Response::new(bytes);
+    *rebuilt.status_mut() = status;
+    *rebuilt.version_mut() = version;
+    *rebuilt.headers_mut() = headers;
+    Ok(reqwest::Response::from(rebuilt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::GeminiCliErrorBody;
+
+    fn response(status: StatusCode, body: &'static str) -> reqwest::Response {
+        let mut resp = axum::http::Response::new(body);
+        *resp.status_mut() = status;
+        reqwest::Response::from(resp)
+    }
+
+    #[test]
+    fn only_allowlisted_headers_are_captured() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-quota-remaining", "0".parse().unwrap());
+        headers.append("x-request-id", "a".parse().unwrap());
+        headers.append("x-request-id", "b".parse().unwrap());
+        headers.insert("set-cookie", "secret".parse().unwrap());
+        let names = [
+            HeaderName::from_static("x-request-id"),
+            HeaderName::from_static("x-quota-remaining"),
+            HeaderName::from_static("x-absent"),
+        ];
+
+        let captured = CapturedHeaders::capture(&headers, &names);
+        assert_eq!(
+            captured.to_string(),
+            "x-request-id=a, x-request-id=b, x-quota-remaining=0"
+        );
+        assert_eq!(CapturedHeaders::capture(&headers, &[]).to_string(), "");
+    }
+
+    #[test]
+    fn internal_headers_are_stripped() {
+        let mut headers = HeaderMap::new();
+        for name in [
+            "x-goog-api-key",
+            "cookie",
+            "host",
+            "x-pollux-priority",
+            "x-goog-api-client",
+        ] {
+            headers.insert(HeaderName::from_static(name), "v".parse().unwrap());
+        }
+
+        strip_internal_headers(&mut headers);
+        let left: Vec<_> = headers.keys().map(HeaderName::as_str).collect();
+        assert_eq!(left, ["x-goog-api-client"]);
+    }
+
+    fn count(provider: &str, action: &str) -> u64 {
+        error_actions_by_provider()
+            .get(provider)
+            .and_then(|actions| actions.get(action))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn ban_classification_increments_the_ban_counter() {
+        let provider = "policy-test-ban";
+
+        let (action, _) = classify_upstream_error::<GeminiCliErrorBody, _>(
+            provider,
+            response(
+                StatusCode::FORBIDDEN,
+                r#"{"error":{"code":403,"status":"PERMISSION_DENIED"}}"#,
+            ),
+            |_| (),
+            |_, _| (),
+        )
+        .await;
+        assert_eq!(action, ActionForError::Ban);
+        assert_eq!(count(provider, "ban"), 1);
+
+        classify_upstream_error::<GeminiCliErrorBody, _>(
+            provider,
+            response(StatusCode::TOO_MANY_REQUESTS, "slow down"),
+            |_| (),
+            |_, _| (),
+        )
+        .await;
+        assert_eq!(count(provider, "ban"), 1);
+        assert_eq!(count(provider, "rate_limit"), 1);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::providers::clock::Clock;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Live scheduling state of one credential, as held by its provider actor.
+///
+/// Banned and disabled credentials are dropped from the actor and do not appear.
+#[derive(Debug, Clone, Serialize)]
+pub struct CredentialStatus {
+    pub id: u64,
+    pub state: CredentialState,
+    /// Models this credential is cooling down for after a rate limit; empty when none.
+    pub cooldowns: Vec<ModelCooldown>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialState {
+    /// Can be handed out for every model it supports.
+    Available,
+    /// Cooling down for at least one model; see `cooldowns`.
+    RateLimited,
+    /// A token refresh is in flight.
+    Refreshing,
+    /// The access token is missing or expired and will be refreshed when next picked.
+    Expired,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelCooldown {
+    pub model: String,
+    pub until: DateTime<Utc>,
+    pub remaining_secs: u64,
+}
+
+/// Build the status list from a scheduler's bookkeeping. `ids` yields each known credential with
+/// whether its token is usable; `cooldowns` maps `(credential, model index)` to its deadline.
+pub(crate) fn collect(
+    ids: impl Iterator<Item = (u64, bool)>,
+    is_refreshing: impl Fn(u64) -> bool,
+    cooldowns: &HashMap<(u64, usize), Instant>,
+    clock: &dyn Clock,
+) -> Vec<CredentialStatus> {
+    let now = clock.now();
+    let wall_now = clock.utc_now();
+
+    let mut statuses: Vec<CredentialStatus> = ids
+        .map(|(id, token_usable)| {
+            let mut cooling: Vec<ModelCooldown> = cooldowns
+                .iter()
+                .filter(|((cred_id, _), deadline)| *cred_id == id && **deadline > now)
+                .map(|((_, model_index), deadline)| {
+                    let remaining = *deadline - now;
+                    ModelCooldown {
+                        model: crate::model_catalog::MODEL_REGISTRY
+                            .get_name(*model_index)
+                            .to_string(),
+                        until: wall_now + chrono::Duration::from_std(remaining).unwrap_or_default(),
+                        remaining_secs: remaining.as_secs_f64().ceil() as u64,
+                    }
+                })
+                .collect();
+            cooling.sort_by(|a, b| a.model.cmp(&b.model));
+
+            let state = if is_refreshing(id) {
+                CredentialState::Refreshing
+            } else if !token_usable {
+                CredentialState::Expired
+            } else if !cooling.is_empty() {
+                CredentialState::RateLimited
+            } else {
+                CredentialState::Available
+            };
+            CredentialStatus {
+                id,
+                state,
+                cooldowns: cooling,
+            }
+        })
+        .collect();
+    statuses.sort_by_key(|status| status.id);
+    statuses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::clock::ManualClock;
+    use std::time::Duration;
+
+    #[test]
+    fn states_and_cooldowns_are_reported() {
+        let clock = ManualClock::new();
+        let now = clock.now();
+        let cooldowns = HashMap::from([
+            ((1, 0), now + Duration::from_secs(90)),
+            // Lapsed cooldowns are not reported.
+            ((2, 0), now - Duration::from_secs(1)),
+        ]);
+
+        let statuses = collect(
+            [(4, true), (3, false), (2, true), (1, true)].into_iter(),
+            |id| id == 4,
+            &cooldowns,
+            &clock,
+        );
+
+        let states: Vec<_> = statuses.iter().map(|s| (s.id, s.state)).collect();
+        assert_eq!(
+            states,
+            vec![
+                (1, CredentialState::RateLimited),
+                (2, CredentialState::Available),
+                (3, CredentialState::Expired),
+                (4, CredentialState::Refreshing),
+            ]
+        );
+
+        let cooldown = &statuses[0].cooldowns[0];
+        assert_eq!(cooldown.remaining_secs, 90);
+        assert_eq!(
+            cooldown.until - clock.utc_now(),
+            chrono::Duration::seconds(90)
+        );
+    }
+}
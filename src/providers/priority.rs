@@ -0,0 +1,71 @@
+use axum::http::HeaderMap;
+
+/// Header a client sets to rank its request against others waiting for a credential.
+pub const PRIORITY_HEADER: &str = "x-pollux-priority";
+
+/// How urgently a request wants a credential: `high`, `normal` or `low`.
+///
+/// Only matters while leases wait, which today means Gemini CLI leases that found every
+/// credential for their model refreshing (see `providers.geminicli.refresh_on_lease`). Each
+/// credential that frees up goes to one waiter, highest priority first and in arrival order
+/// within a priority. Variants sort highest first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LeasePriority {
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+impl LeasePriority {
+    /// The priority named by [`PRIORITY_HEADER`], `Normal` without one, or an error naming an
+    /// unknown value.
+    pub fn from_headers(headers: &HeaderMap) -> Result<Self, String> {
+        let Some(value) = headers.get(PRIORITY_HEADER) else {
+            return Ok(Self::Normal);
+        };
+        let value = value.to_str().unwrap_or_default().trim();
+        match value.to_ascii_lowercase().as_str() {
+            "high" => Ok(Self::High),
+            "normal" => Ok(Self::Normal),
+            "low" => Ok(Self::Low),
+            _ => Err(format!(
+                "unsupported {PRIORITY_HEADER} `{value}`; expected `high`, `normal` or `low`"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(value: Option<&'static str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(value) = value {
+            headers.insert(PRIORITY_HEADER, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn header_names_the_priority() {
+        assert_eq!(
+            LeasePriority::from_headers(&headers(None)),
+            Ok(LeasePriority::Normal)
+        );
+        assert_eq!(
+            LeasePriority::from_headers(&headers(Some(" HIGH "))),
+            Ok(LeasePriority::High)
+        );
+        assert_eq!(
+            LeasePriority::from_headers(&headers(Some("low"))),
+            Ok(LeasePriority::Low)
+        );
+        let err = LeasePriority::from_headers(&headers(Some("urgent"))).unwrap_err();
+        assert!(err.contains("`urgent`"), "{err}");
+        assert!(LeasePriority::High < LeasePriority::Normal);
+        assert!(LeasePriority::Normal < LeasePriority::Low);
+    }
+}
@@ -0,0 +1,99 @@
+use pollux_thoughtsig_core::{
+    AsyncSignatureStore, CacheKey, SignatureExpiry, StoreError, ThoughtSignature,
+};
+use redis::AsyncCommands;
+use redis::aio::ConnectionManager;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Thought signatures kept in Redis, so instances behind one load balancer replay each
+/// other's signatures. Used with [`pollux_thoughtsig_core::patch_all_async`].
+///
+/// Each signature is a plain string under `pollux:thoughtsig:<provider>:<key>`, with the
+/// cache key in decimal. Redis cannot refresh an idle window on reads, so entries keep the
+/// TTL when there is one and only fall back to the idle window without it.
+pub struct RedisSignatureStore {
+    connection: ConnectionManager,
+    prefix: String,
+    lifetime: Option<Duration>,
+}
+
+impl RedisSignatureStore {
+    /// Connect to `url` (e.g. `redis://127.0.0.1/`) for `provider`'s signatures.
+    pub async fn connect(
+        url: &str,
+        provider: &'static str,
+        expiry: SignatureExpiry,
+    ) -> Result<Self, StoreError> {
+        let client = redis::Client::open(url).map_err(store_error)?;
+        let connection = ConnectionManager::new(client).await.map_err(store_error)?;
+        Ok(Self {
+            connection,
+            prefix: format!("pollux:thoughtsig:{provider}:"),
+            lifetime: expiry.ttl.or(expiry.idle),
+        })
+    }
+
+    fn redis_key(&self, key: &CacheKey) -> String {
+        redis_key(&self.prefix, key)
+    }
+}
+
+impl AsyncSignatureStore for RedisSignatureStore {
+    async fn get_signature(&self, key: &CacheKey) -> Result<Option<ThoughtSignature>, StoreError> {
+        let signature: Option<String> = self
+            .connection
+            .clone()
+            .get(self.redis_key(key))
+            .await
+            .map_err(store_error)?;
+        Ok(signature.map(Arc::from))
+    }
+
+    async fn put_signature(
+        &self,
+        key: CacheKey,
+        signature: ThoughtSignature,
+    ) -> Result<(), StoreError> {
+        let mut connection = self.connection.clone();
+        let key = self.redis_key(&key);
+        match self.lifetime {
+            Some(lifetime) => {
+                connection
+                    .set_ex::<_, _, ()>(key, signature.as_ref(), lifetime.as_secs().max(1))
+                    .await
+            }
+            None => connection.set::<_, _, ()>(key, signature.as_ref()).await,
+        }
+        .map_err(store_error)
+    }
+
+    async fn invalidate_signature(&self, key: &CacheKey) -> Result<(), StoreError> {
+        self.connection
+            .clone()
+            .del::<_, ()>(self.redis_key(key))
+            .await
+            .map_err(store_error)
+    }
+}
+
+fn redis_key(prefix: &str, key: &CacheKey) -> String {
+    format!("{prefix}{key}")
+}
+
+fn store_error(err: redis::RedisError) -> StoreError {
+    StoreError(format!("redis: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_prefixed_decimal() {
+        assert_eq!(
+            redis_key("pollux:thoughtsig:geminicli:", &u64::MAX),
+            "pollux:thoughtsig:geminicli:18446744073709551615"
+        );
+    }
+}
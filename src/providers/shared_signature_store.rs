@@ -0,0 +1,53 @@
+//! Thought-signature store shared between proxy instances (`basic.thoughtsig_redis_url`).
+//!
+//! Builds with the `redis` feature use [`RedisSignatureStore`]; without it the type has no
+//! values and connecting reports that the feature is missing, so callers need no `cfg`.
+
+#[cfg(feature = "redis")]
+pub use super::redis_signature_store::RedisSignatureStore as SharedSignatureStore;
+
+#[cfg(not(feature = "redis"))]
+pub use disabled::SharedSignatureStore;
+
+#[cfg(not(feature = "redis"))]
+mod disabled {
+    use pollux_thoughtsig_core::{
+        AsyncSignatureStore, CacheKey, SignatureExpiry, StoreError, ThoughtSignature,
+    };
+
+    /// Stand-in for builds without the `redis` feature; it can never be constructed.
+    pub enum SharedSignatureStore {}
+
+    impl SharedSignatureStore {
+        pub async fn connect(
+            _url: &str,
+            _provider: &'static str,
+            _expiry: SignatureExpiry,
+        ) -> Result<Self, StoreError> {
+            Err(StoreError(
+                "built without the `redis` feature; rebuild with --features redis".to_string(),
+            ))
+        }
+    }
+
+    impl AsyncSignatureStore for SharedSignatureStore {
+        async fn get_signature(
+            &self,
+            _key: &CacheKey,
+        ) -> Result<Option<ThoughtSignature>, StoreError> {
+            match *self {}
+        }
+
+        async fn put_signature(
+            &self,
+            _key: CacheKey,
+            _signature: ThoughtSignature,
+        ) -> Result<(), StoreError> {
+            match *self {}
+        }
+
+        async fn invalidate_signature(&self, _key: &CacheKey) -> Result<(), StoreError> {
+            match *self {}
+        }
+    }
+}
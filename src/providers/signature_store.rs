@@ -0,0 +1,157 @@
+use crate::db::{DbActorHandle, SignatureCacheWrite};
+use chrono::Utc;
+use pollux_thoughtsig_core::{
+    CacheKey, MokaSignatureStore, SignatureExpiry, SignatureStore, StoreError, StoreFootprint,
+    ThoughtSignature,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+/// Most signatures handed to the database in one write.
+const WRITE_BATCH: usize = 256;
+
+/// [`MokaSignatureStore`] mirrored into the `signature_cache` table (see
+/// `basic.thoughtsig_persist`), so recorded signatures survive a restart.
+///
+/// Lookups only touch memory. Writes land in memory at once and are queued for a background
+/// task that flushes them to the database in batches, so the request path never waits on disk.
+pub struct SqliteSignatureStore {
+    memory: MokaSignatureStore,
+    writes: mpsc::UnboundedSender<Write>,
+}
+
+/// A change waiting for the database, applied in the order it was made.
+enum Write {
+    Put(CacheKey, ThoughtSignature),
+    /// Delete one key, or every key with `None`.
+    Delete(Option<CacheKey>),
+}
+
+impl SqliteSignatureStore {
+    /// Load `provider`'s unexpired rows into a fresh in-memory cache, then start the writer and
+    /// a task that deletes expired rows every `prune_every`.
+    ///
+    /// Loaded entries start a new in-memory lifetime; the row keeps its original expiry.
+    pub async fn open(
+        db: DbActorHandle,
+        provider: &'static str,
+        expiry: SignatureExpiry,
+        max_capacity: u64,
+        prune_every: Duration,
+    ) -> Self {
+        let memory = MokaSignatureStore::with_expiry(expiry, max_capacity);
+        match db.load_signatures(provider, max_capacity).await {
+            Ok(rows) => {
+                let loaded = rows
+                    .into_iter()
+                    .map(|row| (row.key as CacheKey, Arc::from(row.signature)))
+                    .collect();
+                match memory.put_many(loaded) {
+                    Ok(count) => info!(provider, count, "Loaded persisted thought signatures"),
+                    Err(e) => warn!(provider, "Persisted thought signatures not loaded: {e}"),
+                }
+            }
+            Err(e) => warn!(provider, "Persisted thought signatures not loaded: {e}"),
+        }
+
+        let (writes, pending) = mpsc::unbounded_channel();
+        // The database cannot refresh an idle window on reads, so rows keep the TTL when there
+        // is one and only fall back to the idle window without it.
+        let lifetime = expiry
+            .ttl
+            .or(expiry.idle)
+            .and_then(|lifetime| chrono::Duration::from_std(lifetime).ok());
+        tokio::spawn(write_behind(db.clone(), provider, lifetime, pending));
+        tokio::spawn(prune_expired(db, provider, prune_every));
+
+        Self { memory, writes }
+    }
+}
+
+impl SignatureStore for SqliteSignatureStore {
+    fn get(&self, key: &CacheKey) -> Result<Option<ThoughtSignature>, StoreError> {
+        self.memory.get(key)
+    }
+
+    fn put(&self, key: CacheKey, signature: ThoughtSignature) -> Result<(), StoreError> {
+        self.memory.put(key, signature.clone())?;
+        // The writer only stops with the runtime; the entry is still cached in memory.
+        let _ = self.writes.send(Write::Put(key, signature));
+        Ok(())
+    }
+
+    fn snapshot(&self) -> Result<Vec<(CacheKey, ThoughtSignature)>, StoreError> {
+        self.memory.snapshot()
+    }
+
+    fn invalidate(&self, key: &CacheKey) -> Result<(), StoreError> {
+        self.memory.invalidate(key)?;
+        let _ = self.writes.send(Write::Delete(Some(*key)));
+        Ok(())
+    }
+
+    fn invalidate_all(&self) -> Result<(), StoreError> {
+        self.memory.invalidate_all()?;
+        let _ = self.writes.send(Write::Delete(None));
+        Ok(())
+    }
+
+    fn footprint(&self) -> Result<StoreFootprint, StoreError> {
+        self.memory.footprint()
+    }
+}
+
+/// Flush queued changes until the store is dropped, batching the puts that piled up meanwhile.
+///
+/// Puts and deletes reach the database actor in the order they were made, so a deleted
+/// signature cannot come back from a put queued before it.
+async fn write_behind(
+    db: DbActorHandle,
+    provider: &'static str,
+    lifetime: Option<chrono::Duration>,
+    mut pending: mpsc::UnboundedReceiver<Write>,
+) {
+    let mut batch = Vec::with_capacity(WRITE_BATCH);
+    let mut puts = Vec::with_capacity(WRITE_BATCH);
+    while pending.recv_many(&mut batch, WRITE_BATCH).await > 0 {
+        let expires_at = lifetime.map(|lifetime| Utc::now() + lifetime);
+        for write in batch.drain(..) {
+            match write {
+                Write::Put(key, signature) => puts.push(SignatureCacheWrite {
+                    key: key as i64,
+                    signature: signature.to_string(),
+                    expires_at,
+                }),
+                Write::Delete(key) => {
+                    flush_puts(&db, provider, &mut puts);
+                    if let Err(e) = db.delete_signatures(provider, key.map(|key| key as i64)) {
+                        warn!(provider, "Thought signatures not deleted: {e}");
+                    }
+                }
+            }
+        }
+        flush_puts(&db, provider, &mut puts);
+    }
+}
+
+fn flush_puts(db: &DbActorHandle, provider: &'static str, puts: &mut Vec<SignatureCacheWrite>) {
+    if puts.is_empty() {
+        return;
+    }
+    if let Err(e) = db.put_signatures(provider, std::mem::take(puts)) {
+        warn!(provider, "Thought signatures not persisted: {e}");
+    }
+}
+
+async fn prune_expired(db: DbActorHandle, provider: &'static str, every: Duration) {
+    let mut interval = tokio::time::interval(every);
+    loop {
+        interval.tick().await;
+        if let Err(e) = db.prune_signatures(provider) {
+            warn!(provider, "Stopped pruning expired thought signatures: {e}");
+            return;
+        }
+    }
+}
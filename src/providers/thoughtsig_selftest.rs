@@ -0,0 +1,54 @@
+use pollux_schema::gemini::{GeminiGenerateContentRequest, GeminiResponseBody};
+use serde_json::{Value, json};
+
+/// Model the synthetic exchange is recorded and replayed under.
+pub(crate) const SELF_TEST_MODEL: &str = "pollux-selftest";
+
+/// A synthetic upstream response carrying a signed function call, and the request that
+/// replays that call, for checking the record-then-fill loop end to end.
+pub(crate) struct SelfTestExchange {
+    pub function_call: Value,
+    pub signature: String,
+    pub response: GeminiResponseBody,
+    pub request: GeminiGenerateContentRequest,
+}
+
+impl SelfTestExchange {
+    /// A fresh exchange whose call and signature match no earlier one.
+    pub(crate) fn new() -> Self {
+        let nonce = uuid::Uuid::new_v4().to_string();
+        let function_call = json!({"name": "pollux_selftest", "args": {"nonce": nonce}});
+        let signature = format!("pollux-selftest-{nonce}");
+        let response = serde_json::from_value(json!({
+            "candidates": [{
+                "index": 0,
+                "content": {
+                    "role": "model",
+                    "parts": [{"functionCall": function_call, "thoughtSignature": signature}]
+                },
+                "finishReason": "STOP"
+            }]
+        }))
+        .expect("self-test response is a valid Gemini response");
+        let request = serde_json::from_value(json!({
+            "contents": [{"role": "model", "parts": [{"functionCall": function_call}]}]
+        }))
+        .expect("self-test request is a valid Gemini request");
+        Self {
+            function_call,
+            signature,
+            response,
+            request,
+        }
+    }
+
+    /// Whether the patched request got the recorded signature back.
+    pub(crate) fn hit(&self) -> bool {
+        self.request
+            .contents
+            .first()
+            .and_then(|content| content.parts.first())
+            .and_then(|part| part.thought_signature.as_deref())
+            == Some(self.signature.as_str())
+    }
+}
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use pollux_schema::gemini::GeminiGenerateContentRequest;
+
+/// A Gemini-shaped upstream: lease a credential from the provider actor, send the request with
+/// the provider's retry policy, and classify upstream errors.
+///
+/// Implemented by the Gemini CLI and Antigravity clients so routes can dispatch to either
+/// through one code path.
+#[async_trait]
+pub trait UpstreamClient: Send + Sync {
+    /// Provider actor that hands out credential leases.
+    type Handle: Send + Sync;
+    /// Per-request routing data (model, mask, stream flag).
+    type Context: Send + Sync;
+    type Error: Send;
+
+    async fn call(
+        &self,
+        handle: &Self::Handle,
+        ctx: &Self::Context,
+        body: &GeminiGenerateContentRequest,
+    ) -> Result<reqwest::Response, Self::Error>;
+}
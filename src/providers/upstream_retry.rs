@@ -4,7 +4,18 @@ use std::sync::LazyLock;
 use std::time::Duration;
 use url::Url;
 
+use crate::config::RetryBackoff;
+use crate::error::PolluxError;
 use crate::providers::UPSTREAM_BODY_PREVIEW_CHARS;
+use crate::providers::circuit_breaker::HostCircuitBreaker;
+
+/// Consecutive connection failures to one upstream host before its circuit opens.
+const CIRCUIT_FAILURE_THRESHOLD: u32 = 5;
+/// How long an open circuit fails fast before a half-open probe is allowed.
+const CIRCUIT_OPEN_FOR: Duration = Duration::from_secs(30);
+
+static UPSTREAM_CIRCUIT: LazyLock<HostCircuitBreaker> =
+    LazyLock::new(|| HostCircuitBreaker::new(CIRCUIT_FAILURE_THRESHOLD, CIRCUIT_OPEN_FOR));
 
 static NETWORK_RETRY_POLICY: LazyLock<ExponentialBuilder> = LazyLock::new(|| {
     ExponentialBuilder::default()
@@ -14,28 +25,81 @@ static NETWORK_RETRY_POLICY: LazyLock<ExponentialBuilder> = LazyLock::new(|| {
         .with_jitter()
 });
 
+/// Exponential backoff for a provider's upstream calls, built from its resolved retry config.
+pub(crate) fn provider_retry_policy(backoff: RetryBackoff, max_times: usize) -> ExponentialBuilder {
+    let policy = ExponentialBuilder::default()
+        .with_min_delay(backoff.min_delay)
+        .with_max_delay(backoff.max_delay)
+        .with_max_times(max_times);
+    if backoff.jitter {
+        policy.with_jitter()
+    } else {
+        policy
+    }
+}
+
+/// POST `body` as JSON, retrying transport errors and 5xx responses a couple of times.
+///
+/// Calls go through a per-host circuit breaker: once a host has failed to connect
+/// `CIRCUIT_FAILURE_THRESHOLD` times in a row, calls fail fast with
+/// `PolluxError::UpstreamUnavailable` until a probe gets through.
 pub(crate) async fn post_json_with_retry<T>(
     provider: &'static str,
     client: &reqwest::Client,
     url: &Url,
     headers: Option<HeaderMap>,
     body: &T,
-) -> Result<reqwest::Response, reqwest::Error>
+) -> Result<reqwest::Response, PolluxError>
 where
     T: serde::Serialize,
 {
+    post_json_with_retry_via(&UPSTREAM_CIRCUIT, provider, client, url, headers, body).await
+}
+
+async fn post_json_with_retry_via<T>(
+    circuit: &HostCircuitBreaker,
+    provider: &'static str,
+    client: &reqwest::Client,
+    url: &Url,
+    headers: Option<HeaderMap>,
+    body: &T,
+) -> Result<reqwest::Response, PolluxError>
+where
+    T: serde::Serialize,
+{
+    let host = url.origin().ascii_serialization();
+
     (|| {
         let client = client.clone();
         let url = url.clone();
         let headers = headers.clone();
+        let host = host.as_str();
 
         async move {
+            if let Err(retry_in) = circuit.check(host) {
+                return Err(PolluxError::UpstreamUnavailable(format!(
+                    "{host} is unreachable; circuit open for another {}s",
+                    retry_in.as_secs().max(1)
+                )));
+            }
+
             let mut request = client.post(url.clone());
             if let Some(headers) = &headers {
                 request = request.headers(headers.clone());
             }
 
-            let resp = request.json(body).send().await?;
+            let resp = match request.json(body).send().await {
+                Ok(resp) => {
+                    circuit.record_success(host);
+                    resp
+                }
+                Err(e) => {
+                    if e.is_connect() || e.is_timeout() {
+                        circuit.record_failure(host);
+                    }
+                    return Err(e.into());
+                }
+            };
 
             if resp.status().is_server_error() {
                 let status = resp.status();
@@ -57,12 +121,62 @@ where
                     "[{provider}] Upstream server error (will retry)"
                 );
 
-                return Err(err);
+                return Err(err.into());
             }
 
             Ok(resp)
         }
     })
     .retry(*NETWORK_RETRY_POLICY)
+    .when(|e| matches!(e, PolluxError::ReqwestError(_)))
     .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backon::BackoffBuilder;
+
+    #[test]
+    fn provider_retry_policy_applies_configured_delays() {
+        let backoff = RetryBackoff {
+            min_delay: Duration::from_millis(50),
+            max_delay: Duration::from_millis(120),
+            jitter: false,
+        };
+        // backon scales delays as floats, so compare whole milliseconds.
+        let delays: Vec<u128> = provider_retry_policy(backoff, 4)
+            .build()
+            .map(|delay| delay.as_millis())
+            .collect();
+        assert_eq!(delays, [50, 100, 120, 120]);
+    }
+
+    #[tokio::test]
+    async fn open_circuit_fails_fast_after_connection_failures() {
+        // Reserve a port, then free it so connections are refused.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        drop(listener);
+
+        let circuit = HostCircuitBreaker::new(3, Duration::from_secs(60));
+        let client = reqwest::Client::new();
+        let body = serde_json::json!({});
+
+        // One call makes three connection attempts (initial + two retries): enough to open.
+        let err = post_json_with_retry_via(&circuit, "Test", &client, &url, None, &body)
+            .await
+            .expect_err("nothing listens on the port");
+        assert!(matches!(err, PolluxError::ReqwestError(_)), "{err:?}");
+
+        let started = std::time::Instant::now();
+        let err = post_json_with_retry_via(&circuit, "Test", &client, &url, None, &body)
+            .await
+            .expect_err("circuit must be open");
+        assert!(
+            matches!(err, PolluxError::UpstreamUnavailable(_)),
+            "{err:?}"
+        );
+        assert!(started.elapsed() < Duration::from_millis(50));
+    }
+}
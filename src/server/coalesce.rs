@@ -0,0 +1,203 @@
+use axum::{
+    body::{Body, Bytes},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use futures::{
+    FutureExt,
+    future::{BoxFuture, Shared},
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, warn};
+
+/// Buffered response handed to every caller of a coalesced request.
+#[derive(Clone)]
+struct SharedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl IntoResponse for SharedResponse {
+    fn into_response(self) -> Response {
+        let mut resp = Response::new(Body::from(self.body));
+        *resp.status_mut() = self.status;
+        *resp.headers_mut() = self.headers;
+        resp
+    }
+}
+
+type InFlight = Shared<BoxFuture<'static, SharedResponse>>;
+
+/// Single-flight for identical non-streaming requests.
+///
+/// While a request is in flight, identical ones (same provider, model and body) wait for it
+/// instead of making their own upstream call, and all callers get the same status, headers and
+/// body. Nothing is cached once the call finishes.
+#[derive(Clone, Default)]
+pub struct RequestCoalescer {
+    in_flight: Arc<Mutex<HashMap<Vec<u8>, InFlight>>>,
+}
+
+impl RequestCoalescer {
+    /// Exact key for a request; the body is compared in full, so distinct requests never share.
+    pub(crate) fn key<B: Serialize>(provider: &str, model: &str, body: &B) -> Option<Vec<u8>> {
+        let mut key = Vec::with_capacity(provider.len() + model.len() + 2);
+        key.extend_from_slice(provider.as_bytes());
+        key.push(0);
+        key.extend_from_slice(model.as_bytes());
+        key.push(0);
+        serde_json::to_writer(&mut key, body).ok()?;
+        Some(key)
+    }
+
+    /// Run `call` for `key`, or join the identical call already in flight.
+    pub(crate) async fn run<F>(&self, key: Vec<u8>, call: F) -> Response
+    where
+        F: Future<Output = Response> + Send + 'static,
+    {
+        let shared = {
+            let mut in_flight = self.in_flight.lock().expect("coalescer mutex poisoned");
+            match in_flight.get(&key) {
+                Some(shared) => {
+                    debug!("Joining identical in-flight request");
+                    shared.clone()
+                }
+                None => {
+                    let shared = self.lead(key.clone(), call);
+                    in_flight.insert(key, shared.clone());
+                    shared
+                }
+            }
+        };
+        shared.await.into_response()
+    }
+
+    /// Start `call` on its own task, so it finishes and leaves `in_flight` even if every caller
+    /// waiting on it disconnects.
+    fn lead<F>(&self, key: Vec<u8>, call: F) -> InFlight
+    where
+        F: Future<Output = Response> + Send + 'static,
+    {
+        let in_flight = self.in_flight.clone();
+        let task = tokio::spawn(async move {
+            let (parts, body) = call.await.into_parts();
+            let response = match axum::body::to_bytes(body, usize::MAX).await {
+                Ok(body) => SharedResponse {
+                    status: parts.status,
+                    headers: parts.headers,
+                    body,
+                },
+                Err(e) => {
+                    warn!(error = %e, "Failed to buffer coalesced response body");
+                    bad_gateway()
+                }
+            };
+            in_flight
+                .lock()
+                .expect("coalescer mutex poisoned")
+                .remove(&key);
+            response
+        });
+        async move {
+            task.await.unwrap_or_else(|e| {
+                warn!(error = %e, "Coalesced request task failed");
+                bad_gateway()
+            })
+        }
+        .boxed()
+        .shared()
+    }
+}
+
+fn bad_gateway() -> SharedResponse {
+    SharedResponse {
+        status: StatusCode::BAD_GATEWAY,
+        headers: HeaderMap::new(),
+        body: Bytes::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Counts calls only once polled, like a real upstream request.
+    async fn upstream(calls: Arc<AtomicUsize>, answer: &'static str) -> Response {
+        calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        (StatusCode::OK, answer).into_response()
+    }
+
+    async fn body_of(resp: Response) -> (StatusCode, Bytes) {
+        let status = resp.status();
+        (
+            status,
+            axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn identical_concurrent_requests_share_one_upstream_call() {
+        let coalescer = RequestCoalescer::default();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let key = || RequestCoalescer::key("geminicli", "m", &json!({"contents": ["hi"]})).unwrap();
+
+        let (a, b) = tokio::join!(
+            coalescer.run(key(), upstream(calls.clone(), "first")),
+            coalescer.run(key(), upstream(calls.clone(), "second")),
+        );
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        let (a, b) = (body_of(a).await, body_of(b).await);
+        assert_eq!(a, (StatusCode::OK, Bytes::from_static(b"first")));
+        assert_eq!(a, b);
+        assert!(coalescer.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn different_or_sequential_requests_are_not_shared() {
+        let coalescer = RequestCoalescer::default();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let key = |model| RequestCoalescer::key("geminicli", model, &json!({})).unwrap();
+
+        let (a, b) = tokio::join!(
+            coalescer.run(key("a"), upstream(calls.clone(), "a")),
+            coalescer.run(key("b"), upstream(calls.clone(), "b")),
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_ne!(body_of(a).await, body_of(b).await);
+
+        // Completed calls are not cached.
+        coalescer.run(key("a"), upstream(calls.clone(), "a")).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn abandoned_leader_still_leaves_in_flight() {
+        let coalescer = RequestCoalescer::default();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let key = || RequestCoalescer::key("geminicli", "m", &json!({})).unwrap();
+
+        // The only caller disconnects while the upstream call is still running.
+        let leader = coalescer.run(key(), upstream(calls.clone(), "gone"));
+        assert!(
+            tokio::time::timeout(Duration::from_millis(10), leader)
+                .await
+                .is_err()
+        );
+        assert_eq!(coalescer.in_flight.lock().unwrap().len(), 1);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(coalescer.in_flight.lock().unwrap().is_empty());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::config::{BasicConfig, CookieSameSite};
+use axum_extra::extract::cookie::{Cookie, SameSite};
+use time::Duration;
+
+/// Attributes for the short-lived OAuth CSRF/PKCE cookies.
+#[derive(Debug, Clone)]
+pub struct OauthCookieConfig {
+    pub domain: Option<String>,
+    pub secure: bool,
+    pub same_site: SameSite,
+}
+
+impl OauthCookieConfig {
+    /// Host-only, `SameSite=Lax` cookies; `Secure` unless `insecure` is set.
+    pub fn new(insecure: bool) -> Self {
+        Self {
+            domain: None,
+            secure: !insecure,
+            same_site: SameSite::Lax,
+        }
+    }
+
+    /// Resolve from `basic.cookie_*`; an unset `cookie_secure` follows `insecure_cookie`.
+    pub fn from_basic(basic: &BasicConfig) -> Self {
+        Self {
+            domain: basic
+                .cookie_domain
+                .as_deref()
+                .map(str::trim)
+                .filter(|domain| !domain.is_empty())
+                .map(str::to_string),
+            secure: basic.cookie_secure.unwrap_or(!basic.insecure_cookie),
+            same_site: match basic.cookie_same_site {
+                CookieSameSite::Lax => SameSite::Lax,
+                CookieSameSite::Strict => SameSite::Strict,
+                CookieSameSite::None => SameSite::None,
+            },
+        }
+    }
+
+    pub fn build(&self, name: &'static str, value: String) -> Cookie<'static> {
+        let mut cookie = self.base(name, value);
+        cookie.set_max_age(Duration::minutes(15));
+        cookie
+    }
+
+    /// Cookie matching [`Self::build`]'s path/domain, for `PrivateCookieJar::remove`.
+    pub fn removal(&self, name: &'static str) -> Cookie<'static> {
+        self.base(name, String::new())
+    }
+
+    fn base(&self, name: &'static str, value: String) -> Cookie<'static> {
+        let mut builder = Cookie::build((name, value))
+            .path("/")
+            .http_only(true)
+            .secure(self.secure)
+            .same_site(self.same_site);
+        if let Some(domain) = &self.domain {
+            builder = builder.domain(domain.clone());
+        }
+        builder.build()
+    }
+}
+
+impl Default for OauthCookieConfig {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn configured_domain_and_secure_flag_are_issued() {
+        let basic = BasicConfig {
+            insecure_cookie: true,
+            cookie_domain: Some("example.com".to_string()),
+            cookie_secure: Some(true),
+            cookie_same_site: CookieSameSite::Strict,
+            ..Default::default()
+        };
+        let cookie = OauthCookieConfig::from_basic(&basic).build("oauth_csrf_token", "v".into());
+
+        assert_eq!(cookie.domain(), Some("example.com"));
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.same_site(), Some(SameSite::Strict));
+        let header = cookie.to_string();
+        assert!(header.contains("Domain=example.com"), "{header}");
+        assert!(header.contains("Secure"), "{header}");
+    }
+
+    #[test]
+    fn defaults_follow_insecure_cookie() {
+        let basic = BasicConfig {
+            insecure_cookie: true,
+            ..Default::default()
+        };
+        let cookie = OauthCookieConfig::from_basic(&basic).build("oauth_csrf_token", "v".into());
+
+        assert_eq!(cookie.domain(), None);
+        assert_eq!(cookie.secure(), Some(false));
+        assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+        assert_eq!(cookie.http_only(), Some(true));
+        assert_eq!(cookie.path(), Some("/"));
+    }
+
+    #[test]
+    fn removal_cookie_keeps_domain() {
+        let cfg = OauthCookieConfig {
+            domain: Some("example.com".to_string()),
+            ..Default::default()
+        };
+        let removal = cfg.removal("oauth_csrf_token");
+        assert_eq!(removal.domain(), Some("example.com"));
+        assert_eq!(removal.path(), Some("/"));
+    }
+}
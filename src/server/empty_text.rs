@@ -0,0 +1,100 @@
+use pollux_schema::gemini::{GeminiResponseBody, Part};
+
+/// Drop parts that are nothing but an empty `text` from a Gemini-format response (see
+/// `basic.strip_empty_text_parts`). Returns how many parts went.
+///
+/// Parts carrying anything besides the text, such as a function call or a thought signature,
+/// stay. Candidates stay too, so finish reasons still reach the client. Run it after the
+/// thought-signature sniffer, which needs every delta to rebuild the thought text.
+pub(crate) fn strip_empty_text(response: &mut GeminiResponseBody) -> usize {
+    let mut stripped = 0;
+    for content in response
+        .candidates
+        .iter_mut()
+        .filter_map(|candidate| candidate.content.as_mut())
+    {
+        let before = content.parts.len();
+        content.parts.retain(|part| !is_empty_text(part));
+        stripped += before - content.parts.len();
+    }
+    stripped
+}
+
+/// Whether a stream chunk has nothing left for the client: no parts, finish reasons, usage or
+/// prompt feedback.
+pub(crate) fn is_blank(response: &GeminiResponseBody) -> bool {
+    response.promptFeedback.is_none()
+        && response.usageMetadata.is_none()
+        && response.candidates.iter().all(|candidate| {
+            candidate.finish_reason.is_none()
+                && candidate
+                    .content
+                    .as_ref()
+                    .is_none_or(|content| content.parts.is_empty())
+        })
+}
+
+fn is_empty_text(part: &Part) -> bool {
+    part.text.as_deref() == Some("")
+        && part.thought_signature.is_none()
+        && part.part_metadata.is_none()
+        && part.inline_data.is_none()
+        && part.function_call.is_none()
+        && part.function_response.is_none()
+        && part.file_data.is_none()
+        && part.executable_code.is_none()
+        && part.code_execution_result.is_none()
+        && part.video_metadata.is_none()
+        && part.extra.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(parts: serde_json::Value, finish_reason: Option<&str>) -> GeminiResponseBody {
+        serde_json::from_value(json!({
+            "candidates": [{
+                "index": 0,
+                "content": {"role": "model", "parts": parts},
+                "finishReason": finish_reason
+            }]
+        }))
+        .expect("response json must parse")
+    }
+
+    #[test]
+    fn only_bare_empty_text_parts_are_dropped() {
+        let mut body = response(
+            json!([
+                {"text": ""},
+                {"text": "hello"},
+                {"text": "", "thoughtSignature": "sig"},
+                {"functionCall": {"name": "lookup", "args": {}}},
+                {"text": "", "thought": true}
+            ]),
+            None,
+        );
+
+        assert_eq!(strip_empty_text(&mut body), 2);
+        let parts = &body.candidates[0].content.as_ref().expect("content").parts;
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].text.as_deref(), Some("hello"));
+        assert_eq!(parts[1].thought_signature.as_deref(), Some("sig"));
+        assert!(parts[2].function_call.is_some());
+        assert!(!is_blank(&body));
+    }
+
+    #[test]
+    fn emptied_chunks_are_blank_unless_they_finish() {
+        let mut delta = response(json!([{"text": ""}]), None);
+        assert_eq!(strip_empty_text(&mut delta), 1);
+        assert!(is_blank(&delta));
+
+        let mut last = response(json!([{"text": ""}]), Some("STOP"));
+        assert_eq!(strip_empty_text(&mut last), 1);
+        assert!(!is_blank(&last));
+        assert_eq!(last.candidates[0].finish_reason.as_deref(), Some("STOP"));
+    }
+}
@@ -0,0 +1,70 @@
+use pollux_schema::gemini::GeminiResponseBody;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Finish reasons a Gemini-format response may end with and still count as a success.
+///
+/// Unrestricted by default. When restricted, a unary response whose candidate finished for
+/// another reason (e.g. `SAFETY`) is turned into a client error. Streams are not checked: their
+/// status is sent before the finish reason is known.
+#[derive(Debug, Clone, Default)]
+pub struct FinishReasonPolicy {
+    success: Option<Arc<HashSet<String>>>,
+}
+
+impl FinishReasonPolicy {
+    /// `None` accepts every finish reason. Names are matched case-insensitively.
+    pub fn new(success: Option<Vec<String>>) -> Self {
+        Self {
+            success: success.map(|reasons| {
+                Arc::new(
+                    reasons
+                        .iter()
+                        .map(|reason| reason.trim().to_ascii_uppercase())
+                        .collect(),
+                )
+            }),
+        }
+    }
+
+    /// First candidate finish reason outside the success set.
+    pub(crate) fn rejected<'a>(&self, body: &'a GeminiResponseBody) -> Option<&'a str> {
+        let success = self.success.as_ref()?;
+        body.candidates
+            .iter()
+            .filter_map(|candidate| candidate.finish_reason.as_deref())
+            .find(|reason| !success.contains(&reason.to_ascii_uppercase()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn finished(reason: &str) -> GeminiResponseBody {
+        serde_json::from_value(json!({
+            "candidates": [{"index": 0, "finishReason": reason}]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn default_accepts_every_finish_reason() {
+        let policy = FinishReasonPolicy::default();
+        assert_eq!(policy.rejected(&finished("SAFETY")), None);
+    }
+
+    #[test]
+    fn restricted_policy_rejects_reasons_outside_the_set() {
+        let policy = FinishReasonPolicy::new(Some(vec!["stop".into(), "MAX_TOKENS".into()]));
+
+        assert_eq!(policy.rejected(&finished("STOP")), None);
+        assert_eq!(policy.rejected(&finished("MAX_TOKENS")), None);
+        assert_eq!(policy.rejected(&finished("SAFETY")), Some("SAFETY"));
+        // Candidates still in progress carry no finish reason.
+        let unfinished: GeminiResponseBody =
+            serde_json::from_value(json!({"candidates": [{"index": 0}]})).unwrap();
+        assert_eq!(policy.rejected(&unfinished), None);
+    }
+}
@@ -0,0 +1,200 @@
+use axum::response::sse::Event;
+use futures::{Stream, StreamExt, stream};
+use pollux_schema::gemini::GeminiResponseBody;
+use serde::Serialize;
+use tracing::warn;
+
+/// SSE event name of the terminal event carrying the merged response.
+pub const COMPLETE_EVENT: &str = "complete";
+
+/// Largest serialization buffer kept between events; an oversized chunk's buffer is shrunk back
+/// so one huge event does not pin that memory for the rest of the stream.
+const MAX_RETAINED_BUFFER_BYTES: usize = 64 * 1024;
+
+/// Serialize streamed Gemini chunks as SSE data events.
+///
+/// With `emit_complete`, chunks are also merged (`GeminiResponseBody::merge_chunk`) and a clean
+/// end of stream is followed by one `event: complete` holding the assembled response. A stream
+/// that fails midway gets no terminal event.
+///
+/// Upstream may send `modelVersion` on only some chunks; once one has it, later chunks without
+/// it repeat the last value seen, so every event after the first reports a version.
+///
+/// Each chunk is serialized into one buffer reused for the whole stream and handed to the event
+/// in a single write, rather than streamed into the event piece by piece.
+pub fn gemini_events<S, E>(
+    chunks: S,
+    emit_complete: bool,
+) -> impl Stream<Item = Result<Event, E>> + Send + 'static
+where
+    S: Stream<Item = Result<GeminiResponseBody, E>> + Send + 'static,
+    E: Send + 'static,
+{
+    struct State<S> {
+        chunks: std::pin::Pin<Box<S>>,
+        merged: Option<GeminiResponseBody>,
+        model_version: Option<String>,
+        failed: bool,
+        buf: Vec<u8>,
+    }
+
+    let initial = State {
+        chunks: Box::pin(chunks),
+        merged: None,
+        model_version: None,
+        failed: false,
+        buf: Vec::new(),
+    };
+
+    stream::unfold(Some(initial), move |state| async move {
+        let mut state = state?;
+        loop {
+            match state.chunks.next().await {
+                Some(Ok(mut chunk)) => {
+                    match &chunk.modelVersion {
+                        Some(version) => state.model_version = Some(version.clone()),
+                        None => chunk.modelVersion = state.model_version.clone(),
+                    }
+                    let event = encode(&mut state.buf, Event::default(), &chunk);
+                    if emit_complete && !state.failed {
+                        match &mut state.merged {
+                            Some(merged) => merged.merge_chunk(chunk),
+                            None => state.merged = Some(chunk),
+                        }
+                    }
+                    match event {
+                        Ok(event) => return Some((Ok(event), Some(state))),
+                        Err(e) => warn!("Failed to serialize GeminiResponse: {}", e),
+                    }
+                }
+                Some(Err(e)) => {
+                    state.failed = true;
+                    return Some((Err(e), Some(state)));
+                }
+                None => {
+                    let merged = state.merged.take().filter(|_| !state.failed)?;
+                    let event = Event::default().event(COMPLETE_EVENT);
+                    return match encode(&mut state.buf, event, &merged) {
+                        Ok(event) => Some((Ok(event), None)),
+                        Err(e) => {
+                            warn!("Failed to serialize merged GeminiResponse: {}", e);
+                            None
+                        }
+                    };
+                }
+            }
+        }
+    })
+}
+
+/// Serialize `value` into `buf` and set it as the event's data.
+fn encode<T: Serialize>(buf: &mut Vec<u8>, event: Event, value: &T) -> serde_json::Result<Event> {
+    buf.clear();
+    serde_json::to_writer(&mut *buf, value)?;
+    let data = std::str::from_utf8(buf).expect("serde_json always writes UTF-8");
+    let event = event.data(data);
+    if buf.capacity() > MAX_RETAINED_BUFFER_BYTES {
+        buf.clear();
+        buf.shrink_to(MAX_RETAINED_BUFFER_BYTES);
+    }
+    Ok(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::{IntoResponse, sse::Sse};
+    use serde_json::{Value, json};
+
+    fn chunk(value: Value) -> Result<GeminiResponseBody, std::convert::Infallible> {
+        Ok(serde_json::from_value(value).unwrap())
+    }
+
+    async fn render(
+        events: impl Stream<Item = Result<Event, std::convert::Infallible>> + Send + 'static,
+    ) -> String {
+        let body = Sse::new(events).into_response().into_body();
+        let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    fn chunks() -> impl Stream<Item = Result<GeminiResponseBody, std::convert::Infallible>> {
+        stream::iter([
+            chunk(
+                json!({"candidates": [{"index": 0, "content": {"role": "model", "parts": [{"text": "Hel"}]}}]}),
+            ),
+            chunk(
+                json!({"candidates": [{"index": 0, "content": {"role": "model", "parts": [{"text": "lo"}]}, "finishReason": "STOP"}]}),
+            ),
+        ])
+    }
+
+    #[tokio::test]
+    async fn terminal_event_contains_fully_merged_content() {
+        let body = render(gemini_events(chunks(), true)).await;
+        let events: Vec<&str> = body.split("\n\n").filter(|e| !e.is_empty()).collect();
+        assert_eq!(events.len(), 3, "{body}");
+
+        let complete = events[2];
+        assert!(complete.starts_with("event: complete\n"), "{complete}");
+        let data = complete.strip_prefix("event: complete\ndata: ").unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(data).unwrap(),
+            json!({"candidates": [{"index": 0, "finishReason": "STOP", "content": {
+                "role": "model",
+                "parts": [{"text": "Hello"}]
+            }}]})
+        );
+    }
+
+    #[tokio::test]
+    async fn no_terminal_event_unless_enabled() {
+        let body = render(gemini_events(chunks(), false)).await;
+        assert!(!body.contains("event: complete"), "{body}");
+        assert_eq!(body.matches("data: ").count(), 2);
+    }
+
+    #[tokio::test]
+    async fn model_version_is_carried_to_later_chunks() {
+        let chunks = stream::iter([
+            chunk(
+                json!({"candidates": [{"index": 0, "content": {"role": "model", "parts": [{"text": "a"}]}}], "modelVersion": "gemini-2.5-pro"}),
+            ),
+            chunk(
+                json!({"candidates": [{"index": 0, "content": {"role": "model", "parts": [{"text": "b"}]}}]}),
+            ),
+            chunk(
+                json!({"candidates": [{"index": 0, "content": {"role": "model", "parts": [{"text": "c"}]}, "finishReason": "STOP"}]}),
+            ),
+        ]);
+        let body = render(gemini_events(chunks, true)).await;
+
+        let versions: Vec<Value> = body
+            .split("\n\n")
+            .filter_map(|event| event.split("data: ").nth(1))
+            .map(|data| serde_json::from_str::<Value>(data).unwrap()["modelVersion"].clone())
+            .collect();
+        assert_eq!(versions, vec![json!("gemini-2.5-pro"); 4], "{body}");
+    }
+
+    #[tokio::test]
+    async fn buffered_encoding_matches_json_data_byte_for_byte() {
+        let long_text = "x".repeat(MAX_RETAINED_BUFFER_BYTES * 2);
+        let values = [
+            json!({"candidates": [{"index": 0, "content": {"role": "model", "parts": [
+                {"text": "line one\nline two \u{2603} \"quoted\"", "thought": true, "thoughtSignature": "sig"}
+            ]}}], "usageMetadata": {"promptTokenCount": 3}, "modelVersion": "m", "responseId": "r"}),
+            json!({"candidates": [{"index": 0, "content": {"role": "model", "parts": [{"text": long_text}]}}], "modelVersion": "m"}),
+            json!({"candidates": [{"index": 0, "content": {"role": "model", "parts": [{"text": "end"}]}, "finishReason": "STOP"}], "modelVersion": "m"}),
+        ];
+
+        let expected = render(stream::iter(values.clone().map(|value| {
+            let body: GeminiResponseBody = serde_json::from_value(value).unwrap();
+            Ok(Event::default().json_data(&body).unwrap())
+        })))
+        .await;
+        let actual = render(gemini_events(stream::iter(values.map(chunk)), false)).await;
+
+        assert_eq!(actual, expected);
+    }
+}
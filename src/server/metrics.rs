@@ -0,0 +1,61 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{
+    Arc, RwLock,
+    atomic::{AtomicU64, Ordering},
+};
+
+/// Process-wide request counters, cheap to clone into every handler.
+#[derive(Debug, Clone, Default)]
+pub struct RequestMetrics {
+    by_model: Arc<RwLock<HashMap<String, AtomicU64>>>,
+}
+
+impl RequestMetrics {
+    /// Count one inbound request for `model`.
+    pub fn record_request(&self, model: &str) {
+        // Fast path: existing models only need the read lock.
+        if let Ok(map) = self.by_model.read()
+            && let Some(counter) = map.get(model)
+        {
+            counter.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
+        let mut map = self
+            .by_model
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        map.entry(model.to_string())
+            .or_default()
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Current request count per model, sorted by model name.
+    pub fn requests_by_model(&self) -> BTreeMap<String, u64> {
+        let map = self
+            .by_model
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        map.iter()
+            .map(|(model, count)| (model.clone(), count.load(Ordering::Relaxed)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_requests_per_model() {
+        let metrics = RequestMetrics::default();
+        metrics.record_request("gemini-2.5-pro");
+        metrics.record_request("gpt-5");
+        metrics.clone().record_request("gemini-2.5-pro");
+
+        assert_eq!(
+            metrics.requests_by_model(),
+            BTreeMap::from([("gemini-2.5-pro".to_string(), 2), ("gpt-5".to_string(), 1),])
+        );
+    }
+}
@@ -1,3 +1,15 @@
+pub mod coalesce;
+pub mod cookies;
+pub mod empty_text;
+pub mod finish_reason;
+pub mod gemini_sse;
 pub mod guards;
+pub mod metrics;
 pub mod router;
 pub mod routes;
+pub mod sse_buffer;
+pub mod sse_retry;
+pub mod sse_timeout;
+pub mod stream_limit;
+pub mod summary;
+pub mod tool_call_ids;
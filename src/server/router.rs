@@ -2,18 +2,25 @@ use crate::providers::Providers;
 use crate::providers::antigravity::ANTIGRAVITY_USER_AGENT;
 use crate::providers::codex::CODEX_USER_AGENT;
 use crate::providers::geminicli::GEMINICLI_USER_AGENT;
+use crate::server::coalesce::RequestCoalescer;
+use crate::server::cookies::OauthCookieConfig;
+use crate::server::finish_reason::FinishReasonPolicy;
 use crate::server::guards::auth::RequireKeyAuth;
+use crate::server::metrics::RequestMetrics;
 use crate::server::routes::antigravity::oauth::{
     antigravity_oauth_callback_root, antigravity_oauth_entry,
 };
 use crate::server::routes::codex::oauth::{codex_oauth_callback, codex_oauth_entry};
 use crate::server::routes::geminicli::oauth::{google_oauth_callback, google_oauth_entry};
-use crate::server::routes::{antigravity, codex, geminicli};
+use crate::server::routes::{admin, antigravity, codex, geminicli};
+use crate::server::sse_buffer::SseBufferConfig;
+use crate::server::sse_timeout::SseTimeouts;
+use crate::server::stream_limit::StreamLimiter;
 
 use axum::{
     Router,
-    extract::{FromRef, Request},
-    http::{HeaderName, StatusCode, Version, header::USER_AGENT},
+    extract::{FromRef, FromRequestParts, Request},
+    http::{HeaderName, StatusCode, Version, header::USER_AGENT, request::Parts},
     middleware::{self, Next},
     response::Response,
     routing::get,
@@ -24,6 +31,7 @@ use rand::RngCore;
 use reqwest::header::{CONNECTION, HeaderMap, HeaderValue};
 use std::time::Instant;
 use std::{sync::Arc, sync::LazyLock, time::Duration};
+use tower_http::compression::CompressionLayer;
 use tracing::{error, info, warn};
 
 /// Global cookie signing/encryption key for PrivateCookieJar.
@@ -39,6 +47,24 @@ fn generate_request_id() -> String {
     base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
 }
 
+/// The request's `x-request-id`: the client's own if it sent a usable one, otherwise the id
+/// [`access_log`] generated for it.
+pub struct RequestId(pub Option<String>);
+
+impl<S: Send + Sync> FromRequestParts<S> for RequestId {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(
+            parts
+                .headers
+                .get(X_REQUEST_ID)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string),
+        ))
+    }
+}
+
 fn format_http_version(version: Version) -> &'static str {
     match version {
         Version::HTTP_09 => "HTTP/0.9",
@@ -58,6 +84,18 @@ pub struct PolluxState {
     pub antigravity_client: reqwest::Client,
     pub pollux_key: Arc<str>,
     pub insecure_cookie: bool,
+    pub oauth_cookies: OauthCookieConfig,
+    pub sse_buffer: SseBufferConfig,
+    pub sse_complete_event: bool,
+    pub sse_retry: Option<Duration>,
+    pub sse_timeouts: SseTimeouts,
+    pub tool_call_ids: bool,
+    pub strip_empty_text: bool,
+    pub stream_limiter: StreamLimiter,
+    pub coalescer: Option<RequestCoalescer>,
+    pub compress_responses: bool,
+    pub finish_reasons: FinishReasonPolicy,
+    pub metrics: RequestMetrics,
 }
 
 impl PolluxState {
@@ -124,8 +162,87 @@ impl PolluxState {
             antigravity_client,
             pollux_key,
             insecure_cookie,
+            oauth_cookies: OauthCookieConfig::new(insecure_cookie),
+            sse_buffer: SseBufferConfig::default(),
+            sse_complete_event: false,
+            sse_retry: None,
+            sse_timeouts: SseTimeouts::default(),
+            tool_call_ids: false,
+            strip_empty_text: false,
+            stream_limiter: StreamLimiter::default(),
+            coalescer: None,
+            compress_responses: false,
+            finish_reasons: FinishReasonPolicy::default(),
+            metrics: RequestMetrics::default(),
         }
     }
+
+    /// Override the OAuth cookie attributes (see `basic.cookie_*`).
+    pub fn with_oauth_cookies(mut self, oauth_cookies: OauthCookieConfig) -> Self {
+        self.oauth_cookies = oauth_cookies;
+        self
+    }
+
+    /// Override the per-stream SSE buffer settings (see `basic.sse_buffer_capacity`).
+    pub fn with_sse_buffer(mut self, sse_buffer: SseBufferConfig) -> Self {
+        self.sse_buffer = sse_buffer;
+        self
+    }
+
+    /// Append a merged `event: complete` to Gemini-format streams (see `basic.sse_complete_event`).
+    pub fn with_sse_complete_event(mut self, enabled: bool) -> Self {
+        self.sse_complete_event = enabled;
+        self
+    }
+
+    /// Send an SSE `retry:` reconnect hint on every stream (see `basic.sse_retry_ms`).
+    pub fn with_sse_retry(mut self, retry: Option<Duration>) -> Self {
+        self.sse_retry = retry;
+        self
+    }
+
+    /// Upstream SSE first-event and idle timeouts (see `basic.sse_first_event_timeout_secs`).
+    pub fn with_sse_timeouts(mut self, sse_timeouts: SseTimeouts) -> Self {
+        self.sse_timeouts = sse_timeouts;
+        self
+    }
+
+    /// Give Gemini-format function calls deterministic ids (see `basic.tool_call_ids`).
+    pub fn with_tool_call_ids(mut self, enabled: bool) -> Self {
+        self.tool_call_ids = enabled;
+        self
+    }
+
+    /// Drop empty text parts from Gemini-format responses (see `basic.strip_empty_text_parts`).
+    pub fn with_empty_text_stripped(mut self, enabled: bool) -> Self {
+        self.strip_empty_text = enabled;
+        self
+    }
+
+    /// Cap concurrently open SSE streams (see `basic.max_concurrent_streams`).
+    pub fn with_max_concurrent_streams(mut self, max_streams: Option<usize>) -> Self {
+        self.stream_limiter = StreamLimiter::new(max_streams);
+        self
+    }
+
+    /// Share one upstream call between identical in-flight unary requests
+    /// (see `basic.coalesce_requests`).
+    pub fn with_request_coalescing(mut self, enabled: bool) -> Self {
+        self.coalescer = enabled.then(RequestCoalescer::default);
+        self
+    }
+
+    /// Negotiate gzip/brotli for non-streaming responses (see `basic.compress_responses`).
+    pub fn with_response_compression(mut self, enabled: bool) -> Self {
+        self.compress_responses = enabled;
+        self
+    }
+
+    /// Restrict which finish reasons count as success (see `basic.success_finish_reasons`).
+    pub fn with_success_finish_reasons(mut self, reasons: Option<Vec<String>>) -> Self {
+        self.finish_reasons = FinishReasonPolicy::new(reasons);
+        self
+    }
 }
 
 impl FromRef<PolluxState> for Key {
@@ -139,7 +256,7 @@ async fn not_found_handler() -> StatusCode {
     StatusCode::NOT_FOUND
 }
 
-async fn access_log(req: Request, next: Next) -> Response {
+async fn access_log(mut req: Request, next: Next) -> Response {
     // Capture request metadata before moving `req` into the handler stack.
     let method = req.method().clone();
     let uri = req.uri().clone();
@@ -153,6 +270,11 @@ async fn access_log(req: Request, next: Next) -> Response {
         .map(str::to_string)
         .unwrap_or_else(generate_request_id);
 
+    // Handlers read the id through `RequestId`, so replace a missing or unusable one.
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        req.headers_mut().insert(X_REQUEST_ID, value);
+    }
+
     let user_agent = req
         .headers()
         .get(USER_AGENT)
@@ -228,6 +350,10 @@ pub fn pollux_router(state: PolluxState) -> Router {
             state.clone(),
         ));
 
+    let admin = admin::router().layer(middleware::from_extractor_with_state::<RequireKeyAuth, _>(
+        state.clone(),
+    ));
+
     let oauth = Router::new()
         // Oauth Redirect path
         .route("/geminicli/auth", get(google_oauth_entry))
@@ -240,12 +366,22 @@ pub fn pollux_router(state: PolluxState) -> Router {
         // Antigravity callback path (guarded)
         .route("/", get(antigravity_oauth_callback_root));
 
-    Router::new()
+    let compress_responses = state.compress_responses;
+    let router = Router::new()
         .merge(oauth)
         .merge(gemini)
         .merge(codex)
         .merge(antigravity)
+        .merge(admin)
         .fallback(not_found_handler)
-        .with_state(state)
-        .layer(middleware::from_fn(access_log))
+        .with_state(state);
+
+    // The default predicate already skips `text/event-stream`, so streams stay uncompressed.
+    let router = if compress_responses {
+        router.layer(CompressionLayer::new().gzip(true).br(true))
+    } else {
+        router
+    };
+
+    router.layer(middleware::from_fn(access_log))
 }
@@ -0,0 +1,477 @@
+use crate::db::{DbLeaseLogEntry, LeaseLogFilter};
+use crate::error::{GeminiCliErrorBody, PolluxError};
+use crate::model_catalog::ModelInfo;
+use crate::providers::antigravity::AntigravityClient;
+use crate::providers::codex::client::CodexClient;
+use crate::providers::geminicli::FillStatsSnapshot;
+use crate::providers::geminicli::client::GeminiClient;
+use crate::providers::geminicli::client::oauth::endpoints::GoogleOauthEndpoints;
+use crate::providers::pool_status::CredentialStatus;
+use crate::providers::{ActionForError, classify_upstream_error};
+use crate::server::router::PolluxState;
+use axum::{
+    Json,
+    body::Body,
+    extract::{Path, Query, State},
+    http::{StatusCode, header::CONTENT_TYPE},
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+use pollux_schema::CodexErrorBody;
+use pollux_thoughtsig_core::{CacheKey, StoreError, StoreFootprint, ThoughtSignature};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PassthroughQuery {
+    /// Hit the provider's streaming endpoint instead of the unary one.
+    #[serde(default)]
+    pub stream: bool,
+}
+
+/// Forward a raw JSON body to the selected provider's upstream and return the
+/// upstream status and body untouched.
+///
+/// The top-level `model` field is only read to pick a credential queue; the body
+/// itself is sent as-is. No signature patching, schema conversion, retries or
+/// error mapping happen on either direction.
+pub async fn admin_passthrough_handler(
+    State(state): State<PolluxState>,
+    Path(provider): Path<String>,
+    Query(query): Query<PassthroughQuery>,
+    Json(payload): Json<Value>,
+) -> Result<Response, PolluxError> {
+    let model = payload
+        .get("model")
+        .and_then(Value::as_str)
+        .ok_or_else(|| PolluxError::BadRequest("missing top-level `model` field".to_string()))?;
+    let model_mask = crate::model_catalog::mask(model)
+        .ok_or_else(|| PolluxError::BadRequest(format!("unknown model `{model}`")))?;
+
+    let providers = &state.providers;
+    let upstream_resp = match provider.as_str() {
+        "geminicli" => {
+            GeminiClient::new(providers.geminicli_cfg.as_ref(), state.client.clone(), None)
+                .passthrough(&providers.geminicli, model_mask, query.stream, payload)
+                .await?
+        }
+        "codex" => {
+            CodexClient::new(
+                providers.codex_cfg.as_ref(),
+                state.codex_client.clone(),
+                None,
+            )
+            .passthrough(&providers.codex, model_mask, query.stream, &payload)
+            .await?
+        }
+        "antigravity" => {
+            AntigravityClient::new(
+                providers.antigravity_cfg.as_ref(),
+                state.antigravity_client.clone(),
+                Some(providers.antigravity_cfg.api_url.clone()),
+            )
+            .passthrough(&providers.antigravity, model_mask, query.stream, payload)
+            .await?
+        }
+        other => {
+            return Err(PolluxError::BadRequest(format!(
+                "unknown provider `{other}`; expected one of geminicli, codex, antigravity"
+            )));
+        }
+    };
+
+    let mut builder = Response::builder().status(upstream_resp.status());
+    if let Some(content_type) = upstream_resp.headers().get(CONTENT_TYPE) {
+        builder = builder.header(CONTENT_TYPE, content_type.clone());
+    }
+    builder
+        .body(Body::from_stream(upstream_resp.bytes_stream()))
+        .map_err(|e| PolluxError::UnexpectedError(e.to_string()))
+}
+
+/// Revoke a Gemini CLI credential's refresh token at Google, then disable the credential.
+///
+/// The credential stays active if Google rejects the revocation, so the call can be retried.
+pub async fn revoke_credential_handler(
+    State(state): State<PolluxState>,
+    Path(id): Path<u64>,
+) -> Result<StatusCode, PolluxError> {
+    let providers = &state.providers;
+    let refresh_token = providers
+        .geminicli
+        .refresh_token_of(id)
+        .await?
+        .ok_or_else(|| PolluxError::NotFound(format!("no active credential with id {id}")))?;
+
+    GoogleOauthEndpoints::revoke_token(
+        &providers.geminicli_cfg.oauth_revoke_url,
+        &refresh_token,
+        state.client.clone(),
+    )
+    .await?;
+    providers.geminicli.disable_credential(id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// New token lifetime of a force-refreshed credential.
+#[derive(Debug, Serialize)]
+pub struct RefreshReport {
+    pub id: u64,
+    pub expiry: DateTime<Utc>,
+}
+
+/// Refresh a Gemini CLI credential's access token now, bypassing expiry checks.
+pub async fn refresh_credential_handler(
+    State(state): State<PolluxState>,
+    Path(id): Path<u64>,
+) -> Result<Json<RefreshReport>, PolluxError> {
+    let expiry = state.providers.geminicli.force_refresh(id).await?;
+    Ok(Json(RefreshReport { id, expiry }))
+}
+
+/// Rows returned by `/admin/lease-log` when `limit` is not given.
+const DEFAULT_LEASE_LOG_LIMIT: u32 = 100;
+/// Upper bound on `limit` for `/admin/lease-log`.
+const MAX_LEASE_LOG_LIMIT: u32 = 1000;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct LeaseLogQuery {
+    pub provider: Option<String>,
+    pub credential_id: Option<i64>,
+    /// RFC3339; only rows at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// RFC3339; only rows at or before this time.
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<u32>,
+}
+
+/// Recorded credential leases, newest first; `404` unless `basic.lease_log` is on.
+pub async fn lease_log_handler(
+    State(state): State<PolluxState>,
+    Query(query): Query<LeaseLogQuery>,
+) -> Result<Json<Vec<DbLeaseLogEntry>>, PolluxError> {
+    let lease_log = state
+        .providers
+        .lease_log
+        .as_ref()
+        .ok_or_else(|| PolluxError::NotFound("lease log is disabled".to_string()))?;
+    let filter = LeaseLogFilter {
+        provider: query.provider,
+        credential_id: query.credential_id,
+        since: query.since,
+        until: query.until,
+        limit: query
+            .limit
+            .unwrap_or(DEFAULT_LEASE_LOG_LIMIT)
+            .min(MAX_LEASE_LOG_LIMIT),
+    };
+    Ok(Json(lease_log.query(filter).await?))
+}
+
+/// Request counters since process start.
+#[derive(Debug, Serialize)]
+pub struct MetricsReport {
+    pub requests_by_model: BTreeMap<String, u64>,
+    /// How upstream errors were classified, per provider then action
+    /// (`rate_limit`, `ban`, `invalid`, `model_unsupported`, `none`).
+    pub upstream_error_actions: BTreeMap<String, BTreeMap<String, u64>>,
+    /// How Gemini CLI requests got their thought signatures: from the cache or a dummy.
+    pub geminicli_thoughtsig_fill: FillStatsSnapshot,
+    /// Live thought-signature cache entries and their summed signature bytes, per provider.
+    /// A provider whose store cannot be read is left out.
+    pub thoughtsig_store: BTreeMap<&'static str, StoreFootprint>,
+}
+
+pub async fn metrics_handler(State(state): State<PolluxState>) -> Json<MetricsReport> {
+    let providers = &state.providers;
+    let footprints = [
+        ("geminicli", providers.geminicli_thoughtsig.footprint()),
+        ("antigravity", providers.antigravity_thoughtsig.footprint()),
+    ];
+    let thoughtsig_store = footprints
+        .into_iter()
+        .filter_map(|(provider, footprint)| match footprint {
+            Ok(footprint) => Some((provider, footprint)),
+            Err(e) => {
+                tracing::warn!(
+                    provider,
+                    "Thought-signature store footprint unavailable: {e}"
+                );
+                None
+            }
+        })
+        .collect();
+    Json(MetricsReport {
+        requests_by_model: state.metrics.requests_by_model(),
+        upstream_error_actions: crate::providers::error_actions_by_provider(),
+        geminicli_thoughtsig_fill: providers.geminicli_thoughtsig.stats_snapshot(),
+        thoughtsig_store,
+    })
+}
+
+/// Upstream error to run through a provider's classifier.
+#[derive(Debug, Deserialize)]
+pub struct SimulateErrorRequest {
+    pub provider: String,
+    pub status: u16,
+    /// Upstream body; a JSON string is sent as raw text, anything else as JSON.
+    #[serde(default)]
+    pub body: Value,
+}
+
+/// How a simulated upstream error was classified.
+#[derive(Debug, Serialize)]
+pub struct SimulatedAction {
+    /// Same labels as `upstream_error_actions` in `/admin/metrics`.
+    pub action: &'static str,
+    /// Cooldown for `rate_limit`.
+    pub retry_after_secs: Option<u64>,
+}
+
+/// Classify a made-up upstream error as the provider would. The result is counted in
+/// `/admin/metrics` like a real one.
+pub async fn simulate_error_handler(
+    Json(request): Json<SimulateErrorRequest>,
+) -> Result<Json<SimulatedAction>, PolluxError> {
+    let status = reqwest::StatusCode::from_u16(request.status)
+        .ok()
+        .filter(|status| !status.is_success())
+        .ok_or_else(|| {
+            PolluxError::BadRequest(format!("status {} is not an error status", request.status))
+        })?;
+    let body = match request.body {
+        Value::String(text) => text,
+        Value::Null => String::new(),
+        json => json.to_string(),
+    };
+    let mut resp = axum::http::Response::new(body);
+    *resp.status_mut() = status;
+    let resp = reqwest::Response::from(resp);
+
+    let (action, ()) = match request.provider.as_str() {
+        "geminicli" => {
+            classify_upstream_error::<GeminiCliErrorBody, _>("geminicli", resp, |_| (), |_, _| ())
+                .await
+        }
+        "antigravity" => {
+            classify_upstream_error::<GeminiCliErrorBody, _>("antigravity", resp, |_| (), |_, _| ())
+                .await
+        }
+        "codex" => {
+            classify_upstream_error::<CodexErrorBody, _>("codex", resp, |_| (), |_, _| ()).await
+        }
+        other => {
+            return Err(PolluxError::BadRequest(format!(
+                "unknown provider `{other}`; expected one of geminicli, codex, antigravity"
+            )));
+        }
+    };
+    let retry_after_secs = match action {
+        ActionForError::RateLimit(cooldown) => Some(cooldown.as_secs()),
+        _ => None,
+    };
+    Ok(Json(SimulatedAction {
+        action: action.label(),
+        retry_after_secs,
+    }))
+}
+
+/// How a model name resolves in the global catalog; `404` when no provider lists it.
+pub async fn model_info_handler(Path(model): Path<String>) -> Result<Json<ModelInfo>, PolluxError> {
+    crate::model_catalog::describe(&model)
+        .map(Json)
+        .ok_or_else(|| PolluxError::NotFound(format!("unknown model `{model}`")))
+}
+
+/// Credential availability per provider, ordered by credential id.
+#[derive(Debug, Serialize)]
+pub struct PoolReport {
+    pub geminicli: Vec<CredentialStatus>,
+    pub codex: Vec<CredentialStatus>,
+    pub antigravity: Vec<CredentialStatus>,
+}
+
+/// Live view of every credential the provider actors currently hold.
+pub async fn pool_handler(
+    State(state): State<PolluxState>,
+) -> Result<Json<PoolReport>, PolluxError> {
+    let providers = &state.providers;
+    let (geminicli, codex, antigravity) = tokio::try_join!(
+        providers.geminicli.pool_status(),
+        providers.codex.pool_status(),
+        providers.antigravity.pool_status(),
+    )?;
+    Ok(Json(PoolReport {
+        geminicli,
+        codex,
+        antigravity,
+    }))
+}
+
+/// Signature cache dump: per provider, hex cache key -> signature.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ThoughtSigDump {
+    #[serde(default)]
+    pub geminicli: BTreeMap<String, String>,
+    #[serde(default)]
+    pub antigravity: BTreeMap<String, String>,
+}
+
+/// Whether each provider's thought-signature cache returned a signature it had just recorded.
+#[derive(Debug, Serialize)]
+pub struct SelfTestReport {
+    /// Every provider hit.
+    pub ok: bool,
+    pub geminicli_cache_hit: bool,
+    pub antigravity_cache_hit: bool,
+}
+
+/// Run each thought-signature service's record-then-fill loop on a synthetic exchange.
+pub async fn selftest_handler(State(state): State<PolluxState>) -> Json<SelfTestReport> {
+    let geminicli_cache_hit = state.providers.geminicli_thoughtsig.self_test();
+    let antigravity_cache_hit = state.providers.antigravity_thoughtsig.self_test();
+    Json(SelfTestReport {
+        ok: geminicli_cache_hit && antigravity_cache_hit,
+        geminicli_cache_hit,
+        antigravity_cache_hit,
+    })
+}
+
+/// Number of signatures written per provider by an import.
+#[derive(Debug, Serialize)]
+pub struct ThoughtSigImported {
+    pub geminicli: usize,
+    pub antigravity: usize,
+}
+
+/// Export every cached thought signature so it can be imported elsewhere.
+pub async fn thoughtsig_export_handler(
+    State(state): State<PolluxState>,
+) -> Result<Json<ThoughtSigDump>, PolluxError> {
+    let providers = &state.providers;
+    Ok(Json(ThoughtSigDump {
+        geminicli: encode_entries(providers.geminicli_thoughtsig.snapshot())?,
+        antigravity: encode_entries(providers.antigravity_thoughtsig.snapshot())?,
+    }))
+}
+
+/// Load a dump produced by [`thoughtsig_export_handler`]. Existing keys are overwritten.
+pub async fn thoughtsig_import_handler(
+    State(state): State<PolluxState>,
+    Json(dump): Json<ThoughtSigDump>,
+) -> Result<Json<ThoughtSigImported>, PolluxError> {
+    // Decode both sections first so a bad key leaves the caches untouched.
+    let geminicli = decode_entries(dump.geminicli)?;
+    let antigravity = decode_entries(dump.antigravity)?;
+
+    let providers = &state.providers;
+    Ok(Json(ThoughtSigImported {
+        geminicli: providers
+            .geminicli_thoughtsig
+            .put_many(geminicli)
+            .map_err(store_error)?,
+        antigravity: providers
+            .antigravity_thoughtsig
+            .put_many(antigravity)
+            .map_err(store_error)?,
+    }))
+}
+
+fn encode_entries(
+    snapshot: Result<Vec<(CacheKey, ThoughtSignature)>, StoreError>,
+) -> Result<BTreeMap<String, String>, PolluxError> {
+    Ok(snapshot
+        .map_err(store_error)?
+        .into_iter()
+        .map(|(key, sig)| (format!("{key:016x}"), sig.to_string()))
+        .collect())
+}
+
+fn decode_entries(
+    entries: BTreeMap<String, String>,
+) -> Result<Vec<(CacheKey, ThoughtSignature)>, PolluxError> {
+    entries
+        .into_iter()
+        .map(|(key, sig)| {
+            let key = CacheKey::from_str_radix(&key, 16).map_err(|_| {
+                PolluxError::BadRequest(format!("invalid cache key `{key}`; expected hex u64"))
+            })?;
+            Ok((key, Arc::from(sig)))
+        })
+        .collect()
+}
+
+fn store_error(err: StoreError) -> PolluxError {
+    PolluxError::UnexpectedError(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::antigravity::AntigravityThoughtSigService;
+    use pollux_schema::gemini::{GeminiGenerateContentRequest, GeminiResponseBody};
+    use serde_json::json;
+
+    #[test]
+    fn export_import_round_trips_into_fresh_store() {
+        let source = AntigravityThoughtSigService::new();
+        for part in [
+            json!({"thought": true, "text": "plan", "thoughtSignature": "sig_text"}),
+            json!({"functionCall": {"name": "f", "args": {}}, "thoughtSignature": "sig_call"}),
+        ] {
+            let response: GeminiResponseBody = serde_json::from_value(json!({
+                "candidates": [{
+                    "content": {"role": "model", "parts": [part]},
+                    "finishReason": "STOP"
+                }]
+            }))
+            .expect("response json must parse");
+            let mut sniffer = source.build_sniffer();
+            source.sniff_response(&response, &mut sniffer);
+        }
+
+        let dump = ThoughtSigDump {
+            antigravity: encode_entries(source.snapshot()).expect("export"),
+            ..Default::default()
+        };
+        assert_eq!(dump.antigravity.len(), 2);
+        let wire = serde_json::to_string(&dump).expect("dump serializes");
+
+        let dump: ThoughtSigDump = serde_json::from_str(&wire).expect("dump parses");
+        let target = AntigravityThoughtSigService::new();
+        let written = target
+            .put_many(decode_entries(dump.antigravity).expect("import"))
+            .expect("put_many");
+        assert_eq!(written, 2);
+
+        let mut req: GeminiGenerateContentRequest = serde_json::from_value(json!({
+            "contents": [{
+                "role": "model",
+                "parts": [
+                    {"thought": true, "text": "plan"},
+                    {"functionCall": {"name": "f", "args": {}}}
+                ]
+            }]
+        }))
+        .expect("request json must parse");
+        target.patch_request(&mut req);
+
+        let sigs: Vec<_> = req.contents[0]
+            .parts
+            .iter()
+            .map(|p| p.thought_signature.as_deref())
+            .collect();
+        assert_eq!(sigs, vec![Some("sig_text"), Some("sig_call")]);
+    }
+
+    #[test]
+    fn import_rejects_non_hex_key() {
+        let entries = BTreeMap::from([("not-hex".to_string(), "sig".to_string())]);
+        assert!(matches!(
+            decode_entries(entries),
+            Err(PolluxError::BadRequest(_))
+        ));
+    }
+}
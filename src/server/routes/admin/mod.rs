@@ -0,0 +1,37 @@
+pub mod handlers;
+
+use crate::server::router::PolluxState;
+use axum::{
+    Router,
+    routing::{get, post},
+};
+
+use handlers::{
+    admin_passthrough_handler, lease_log_handler, metrics_handler, model_info_handler,
+    pool_handler, refresh_credential_handler, revoke_credential_handler, selftest_handler,
+    simulate_error_handler, thoughtsig_export_handler, thoughtsig_import_handler,
+};
+
+pub fn router() -> Router<PolluxState> {
+    Router::new()
+        .route(
+            "/admin/passthrough/{provider}",
+            post(admin_passthrough_handler),
+        )
+        .route(
+            "/admin/credentials/{id}/revoke",
+            post(revoke_credential_handler),
+        )
+        .route(
+            "/admin/credentials/{id}/refresh",
+            post(refresh_credential_handler),
+        )
+        .route("/admin/metrics", get(metrics_handler))
+        .route("/admin/simulate-error", post(simulate_error_handler))
+        .route("/admin/pool", get(pool_handler))
+        .route("/admin/lease-log", get(lease_log_handler))
+        .route("/admin/models/{model}", get(model_info_handler))
+        .route("/admin/thoughtsig/export", get(thoughtsig_export_handler))
+        .route("/admin/thoughtsig/import", post(thoughtsig_import_handler))
+        .route("/admin/selftest", post(selftest_handler))
+}
@@ -1,7 +1,13 @@
+use crate::config::AntigravityResolvedConfig;
 use crate::error::{GeminiCliError, GeminiErrorObject};
 use crate::providers::antigravity::AntigravityContext;
+use crate::providers::conversation::ConversationId;
 use crate::server::router::PolluxState;
+use crate::server::tool_call_ids;
+use crate::utils::body_limit::{DEFAULT_BODY_LIMIT_BYTES, declared_length_over};
+use crate::utils::content_type::json_content_type_error;
 use crate::utils::logging::with_pretty_json_debug;
+use crate::utils::stream_mode::stream_requested;
 use axum::{
     Json, RequestExt,
     extract::{FromRequest, Path, Request},
@@ -33,6 +39,9 @@ where
                 debug_message: Some(rejection.to_string()),
             })?;
 
+        // Clients sometimes append a trailing slash; it is not part of the model or rpc.
+        let path = path.trim_end_matches('/').to_string();
+
         // Determine model and optional rpc from the last path segment.
         let last_seg = path.split('/').next_back().map(|s| s.to_string());
         let Some(last_seg) = last_seg else {
@@ -46,62 +55,141 @@ where
                 debug_message: None,
             });
         };
-        let model = if let Some((m, _r)) = last_seg.split_once(':') {
+        let requested = if let Some((m, _r)) = last_seg.split_once(':') {
             m.to_string()
         } else {
             last_seg
         };
-
-        let state = state.borrow();
-        let is_allowed = state
+        // A path without a model (`models/:generateContent`) gets the configured default.
+        let requested = match state
+            .borrow()
             .providers
             .antigravity_cfg
-            .model_list
-            .iter()
-            .any(|m| m == &model);
-        if !is_allowed {
-            warn!(
-                "Rejected request for unsupported antigravity model: {}",
-                model
-            );
-            let body = GeminiErrorObject::for_status(
-                StatusCode::BAD_REQUEST,
-                "INVALID_ARGUMENT",
-                format!("unsupported model: {model}"),
-            );
+            .default_model
+            .as_deref()
+        {
+            Some(default) if requested.is_empty() => default.to_string(),
+            _ => requested,
+        };
+        // Catalog aliases are forwarded under the model's own name.
+        let requested =
+            crate::model_catalog::canonical_name(&requested).map_or(requested, str::to_string);
+
+        let state = state.borrow();
+        let cfg = state.providers.antigravity_cfg.as_ref();
+        let (model, model_mask) = match resolve_model(&requested, cfg, crate::model_catalog::mask) {
+            Ok(resolved) => resolved,
+            Err(rejection) => {
+                warn!(
+                    model_info = ?crate::model_catalog::describe(&requested),
+                    "Rejected antigravity request: {}",
+                    rejection
+                );
+                let body = GeminiErrorObject::for_status(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_ARGUMENT",
+                    rejection.to_string(),
+                );
+                return Err(GeminiCliError::RequestRejected {
+                    status: StatusCode::BAD_REQUEST,
+                    body,
+                    debug_message: None,
+                });
+            }
+        };
+        let model = model.to_string();
+
+        let stream = stream_requested(&path, req.uri().query()).map_err(|message| {
+            GeminiCliError::RequestRejected {
+                status: StatusCode::BAD_REQUEST,
+                body: GeminiErrorObject::for_status(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_ARGUMENT",
+                    message,
+                ),
+                debug_message: None,
+            }
+        })?;
+        if let Some(length) = declared_length_over(req.headers(), DEFAULT_BODY_LIMIT_BYTES) {
+            return Err(GeminiCliError::payload_too_large(
+                DEFAULT_BODY_LIMIT_BYTES,
+                Some(format!(
+                    "declared Content-Length {length} exceeds limit of {} bytes",
+                    DEFAULT_BODY_LIMIT_BYTES
+                )),
+            ));
+        }
+        if let Some(message) = json_content_type_error(req.headers()) {
+            return Err(GeminiCliError::invalid_content_type(message));
+        }
+        let headers = req.headers().clone();
+        let Json(mut body) = req
+            .extract::<Json<GeminiGenerateContentRequest>, _>()
+            .await
+            .map_err(|e| GeminiCliError::json_rejection(e, DEFAULT_BODY_LIMIT_BYTES))?;
+
+        let max_parts = state.providers.antigravity_cfg.max_parts_per_content;
+        if let Some((index, parts)) = body.oversized_content(max_parts) {
             return Err(GeminiCliError::RequestRejected {
                 status: StatusCode::BAD_REQUEST,
-                body,
+                body: GeminiErrorObject::for_status(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_ARGUMENT",
+                    format!("contents[{index}] has {parts} parts; at most {max_parts} allowed"),
+                ),
                 debug_message: None,
             });
         }
-
-        let Some(model_mask) = crate::model_catalog::mask(model.as_str()) else {
-            warn!(
-                "Rejected request for antigravity model not in global catalog: {}",
-                model
-            );
-            let body = GeminiErrorObject::for_status(
-                StatusCode::BAD_REQUEST,
-                "INVALID_ARGUMENT",
-                format!("unsupported model: {model}"),
-            );
+        if let Some(message) = body.response_schema_error() {
             return Err(GeminiCliError::RequestRejected {
                 status: StatusCode::BAD_REQUEST,
-                body,
+                body: GeminiErrorObject::for_status(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_ARGUMENT",
+                    message,
+                ),
                 debug_message: None,
             });
-        };
+        }
 
-        let stream = path.contains("streamGenerateContent");
-        let Json(mut body) = req
-            .extract::<Json<GeminiGenerateContentRequest>, _>()
-            .await?;
+        if let Err(e) = body.merge_system_instructions() {
+            return Err(GeminiCliError::RequestRejected {
+                status: StatusCode::BAD_REQUEST,
+                body: GeminiErrorObject::for_status(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_ARGUMENT",
+                    format!("invalid system_instruction: {e}"),
+                ),
+                debug_message: None,
+            });
+        }
+        // After merging, so a request of only `system` turns counts as empty too.
+        if body.contents.is_empty()
+            && !state
+                .providers
+                .antigravity_cfg
+                .empty_contents_models
+                .contains(&model)
+        {
+            return Err(GeminiCliError::RequestRejected {
+                status: StatusCode::BAD_REQUEST,
+                body: GeminiErrorObject::for_status(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_ARGUMENT",
+                    format!("contents must not be empty for model {model}"),
+                ),
+                debug_message: None,
+            });
+        }
+        let conversation = ConversationId::extract(&headers, &body);
+        if state.tool_call_ids {
+            tool_call_ids::strip_assigned(&mut body);
+        }
 
         state
             .providers
             .antigravity_thoughtsig
-            .patch_request(&mut body);
+            .patch_request_for_model(&model, &mut body);
 
         with_pretty_json_debug(&body, |pretty_body| {
             debug!(
@@ -119,7 +207,162 @@ where
             stream,
             path,
             model_mask,
+            conversation,
         };
         Ok(AntigravityPreprocess(body, ctx))
     }
 }
+
+/// Find `requested` in the allowlist, returning the configured spelling.
+/// Why a requested model cannot be served by Antigravity.
+#[derive(Debug, PartialEq, Eq)]
+enum ModelRejection {
+    /// Not in `providers.antigravity.model_list`.
+    NotListed(String),
+    /// Listed for Antigravity, but the global model catalog has no mask for it.
+    NotCataloged(String),
+}
+
+impl std::fmt::Display for ModelRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotListed(model) => {
+                write!(
+                    f,
+                    "unsupported model: {model} (not in the antigravity model list)"
+                )
+            }
+            Self::NotCataloged(model) => write!(
+                f,
+                "unsupported model: {model} (listed for antigravity but missing from the model catalog)"
+            ),
+        }
+    }
+}
+
+/// Match `requested` against the provider's `model_list` and look up its catalog mask.
+///
+/// With `trust_model_list`, a listed model the catalog lacks borrows the mask of the first
+/// `model_list` entry the catalog knows, so it is served from that model's credential queue.
+fn resolve_model<'a>(
+    requested: &str,
+    cfg: &'a AntigravityResolvedConfig,
+    mask: impl Fn(&str) -> Option<u64>,
+) -> Result<(&'a str, u64), ModelRejection> {
+    let model = allowed_model(requested, &cfg.model_list, cfg.case_insensitive_models)
+        .ok_or_else(|| ModelRejection::NotListed(requested.to_string()))?;
+    if let Some(model_mask) = mask(model) {
+        return Ok((model, model_mask));
+    }
+    let fallback = cfg
+        .trust_model_list
+        .then(|| cfg.model_list.iter().find_map(|listed| mask(listed)))
+        .flatten()
+        .ok_or_else(|| ModelRejection::NotCataloged(model.to_string()))?;
+    debug!(
+        model,
+        fallback_mask = %crate::model_catalog::format_model_mask(fallback),
+        "Antigravity model missing from catalog; using the default mask"
+    );
+    Ok((model, fallback))
+}
+
+fn allowed_model<'a>(
+    requested: &str,
+    model_list: &'a [String],
+    case_insensitive: bool,
+) -> Option<&'a str> {
+    model_list
+        .iter()
+        .find(|m| {
+            if case_insensitive {
+                m.eq_ignore_ascii_case(requested)
+            } else {
+                m.as_str() == requested
+            }
+        })
+        .map(String::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allowlist_is_exact_unless_case_insensitive() {
+        let list = vec!["gemini-3-pro-preview".to_string()];
+
+        assert_eq!(
+            allowed_model("gemini-3-pro-preview", &list, false),
+            Some("gemini-3-pro-preview")
+        );
+        assert_eq!(allowed_model("Gemini-3-Pro-Preview", &list, false), None);
+        assert_eq!(
+            allowed_model("Gemini-3-Pro-Preview", &list, true),
+            Some("gemini-3-pro-preview")
+        );
+        assert_eq!(allowed_model("gemini-2.5-pro", &list, true), None);
+    }
+
+    fn config(models: &[&str], trust_model_list: bool) -> AntigravityResolvedConfig {
+        let mut cfg = crate::config::AntigravityConfig {
+            model_list: models.iter().map(|m| m.to_string()).collect(),
+            ..Default::default()
+        }
+        .resolve(&crate::config::ProviderDefaults::default());
+        cfg.trust_model_list = trust_model_list;
+        cfg
+    }
+
+    /// A catalog that only knows `gemini-3-pro-preview`, at bit 2.
+    fn catalog(model: &str) -> Option<u64> {
+        (model == "gemini-3-pro-preview").then_some(1 << 2)
+    }
+
+    #[test]
+    fn model_missing_from_provider_list_is_reported_as_not_listed() {
+        let cfg = config(&["gemini-3-pro-preview"], true);
+        let rejection = resolve_model("claude-opus-4-5", &cfg, catalog).unwrap_err();
+        assert_eq!(
+            rejection,
+            ModelRejection::NotListed("claude-opus-4-5".to_string())
+        );
+        assert!(
+            rejection
+                .to_string()
+                .contains("not in the antigravity model list")
+        );
+    }
+
+    #[test]
+    fn model_missing_from_catalog_is_rejected_unless_list_is_trusted() {
+        let strict = config(&["gemini-3-pro-preview", "gemini-3-new"], false);
+        let rejection = resolve_model("gemini-3-new", &strict, catalog).unwrap_err();
+        assert_eq!(
+            rejection,
+            ModelRejection::NotCataloged("gemini-3-new".to_string())
+        );
+        assert!(
+            rejection
+                .to_string()
+                .contains("missing from the model catalog")
+        );
+
+        let trusted = config(&["gemini-3-new", "gemini-3-pro-preview"], true);
+        assert_eq!(
+            resolve_model("gemini-3-new", &trusted, catalog),
+            Ok(("gemini-3-new", 1 << 2))
+        );
+        assert_eq!(
+            resolve_model("gemini-3-pro-preview", &trusted, catalog),
+            Ok(("gemini-3-pro-preview", 1 << 2))
+        );
+
+        // Nothing in the list has a mask to borrow.
+        let uncataloged = config(&["gemini-3-new"], true);
+        assert_eq!(
+            resolve_model("gemini-3-new", &uncataloged, catalog),
+            Err(ModelRejection::NotCataloged("gemini-3-new".to_string()))
+        );
+    }
+}
@@ -3,36 +3,77 @@ use super::{
     respond::{build_json_response, build_stream_response},
 };
 use crate::error::GeminiCliError;
-use crate::providers::antigravity::AntigravityClient;
-use crate::server::router::PolluxState;
+use crate::providers::UpstreamClient;
+use crate::providers::antigravity::{AntigravityClient, AntigravityContext};
+use crate::providers::lease_log::LeaseRecorder;
+use crate::server::coalesce::RequestCoalescer;
+use crate::server::router::{PolluxState, RequestId};
 use axum::{
     Json,
     extract::State,
     response::{IntoResponse, Response},
 };
-use pollux_schema::gemini::GeminiModelList;
+use pollux_schema::gemini::{GeminiGenerateContentRequest, GeminiModelList};
 
 pub async fn antigravity_proxy_handler(
     State(state): State<PolluxState>,
+    RequestId(request_id): RequestId,
     AntigravityPreprocess(body, ctx): AntigravityPreprocess,
 ) -> Result<Response, GeminiCliError> {
+    state.metrics.record_request(&ctx.model);
+    let leases = LeaseRecorder::new(state.providers.lease_log.clone(), "antigravity", request_id);
+
+    // Reserve the stream slot before spending an upstream call on it.
+    let stream_permit = if ctx.stream {
+        let permit = state.stream_limiter.try_acquire();
+        Some(permit.ok_or_else(GeminiCliError::too_many_streams)?)
+    } else {
+        None
+    };
+
+    if let Some(permit) = stream_permit {
+        let upstream_resp = call_upstream(&state, &ctx, &body, leases).await?;
+        return Ok(build_stream_response(upstream_resp, state.clone(), permit).into_response());
+    }
+
+    if let Some(coalescer) = state.coalescer.clone()
+        && let Some(key) = RequestCoalescer::key("antigravity", &ctx.model, &body)
+    {
+        return Ok(coalescer.run(key, unary(state, ctx, body, leases)).await);
+    }
+    Ok(unary(state, ctx, body, leases).await)
+}
+
+async fn call_upstream(
+    state: &PolluxState,
+    ctx: &AntigravityContext,
+    body: &GeminiGenerateContentRequest,
+    leases: LeaseRecorder,
+) -> Result<reqwest::Response, GeminiCliError> {
     let caller = AntigravityClient::new(
         state.providers.antigravity_cfg.as_ref(),
         state.antigravity_client.clone(),
-        None,
-    );
+        Some(state.providers.antigravity_cfg.api_url.clone()),
+    )
+    .with_lease_recorder(leases);
 
-    let upstream_resp = caller
-        .call_antigravity(&state.providers.antigravity, &ctx, &body)
+    caller
+        .call(&state.providers.antigravity, ctx, body)
         .await
-        .map_err(map_antigravity_error)?;
+        .map_err(map_antigravity_error)
+}
 
-    if ctx.stream {
-        Ok(build_stream_response(upstream_resp, state.clone()).into_response())
-    } else {
-        Ok(build_json_response(upstream_resp, &state)
-            .await?
-            .into_response())
+async fn unary(
+    state: PolluxState,
+    ctx: AntigravityContext,
+    body: GeminiGenerateContentRequest,
+    leases: LeaseRecorder,
+) -> Response {
+    match call_upstream(&state, &ctx, &body, leases).await {
+        Ok(upstream_resp) => build_json_response(upstream_resp, &state)
+            .await
+            .into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
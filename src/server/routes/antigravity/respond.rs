@@ -1,17 +1,23 @@
 use crate::error::GeminiCliError;
+use crate::server::empty_text;
+use crate::server::gemini_sse;
 use crate::server::router::PolluxState;
+use crate::server::sse_buffer;
+use crate::server::sse_retry;
+use crate::server::sse_timeout;
+use crate::server::stream_limit::{self, StreamPermit};
+use crate::server::tool_call_ids::ToolCallIds;
 use axum::{
     Json,
-    http::StatusCode,
+    http::{StatusCode, header::CONTENT_TYPE},
     response::{
         IntoResponse,
-        sse::{Event, KeepAlive, Sse},
+        sse::{KeepAlive, Sse},
     },
 };
-use eventsource_stream::Eventsource;
-use futures::{Stream, TryStreamExt, future};
-use pollux_schema::{gemini::GeminiResponseBody, geminicli::GeminiCliResponseBody};
-use std::time::Duration;
+use eventsource_stream::{Event, EventStreamError, Eventsource};
+use futures::{Stream, TryStreamExt, future, future::Either, stream};
+use pollux_schema::{antigravity::AntigravityResponseBody, gemini::GeminiResponseBody};
 use tokio_stream::StreamExt;
 use tracing::{error, warn};
 
@@ -20,45 +26,97 @@ pub async fn build_json_response(
     state: &PolluxState,
 ) -> Result<(StatusCode, Json<GeminiResponseBody>), GeminiCliError> {
     let status = upstream_resp.status();
-    let response_body = transform_nostream(upstream_resp).await?;
+    let mut response_body = transform_nostream(upstream_resp).await?;
     let mut sniffer = state.providers.antigravity_thoughtsig.build_sniffer();
     state
         .providers
         .antigravity_thoughtsig
         .sniff_response(&response_body, &mut sniffer);
+    // After sniffing, so signatures are cached under the calls as upstream sent them and the
+    // sniffer still sees every text delta.
+    if state.strip_empty_text {
+        empty_text::strip_empty_text(&mut response_body);
+    }
+    if state.tool_call_ids {
+        ToolCallIds::default().assign(&mut response_body);
+    }
+    if let Some(reason) = state.finish_reasons.rejected(&response_body) {
+        return Err(GeminiCliError::finish_reason_rejected(reason));
+    }
     Ok((status, Json(response_body)))
 }
 
 pub fn build_stream_response(
     upstream_resp: reqwest::Response,
     state: PolluxState,
+    permit: StreamPermit,
 ) -> impl IntoResponse {
     let sniffer = state.providers.antigravity_thoughtsig.build_sniffer();
-    let raw_stream = upstream_resp.bytes_stream().eventsource();
-    let timed_stream = transform_stream(raw_stream, state.clone(), sniffer)
-        .timeout(Duration::from_secs(60))
-        .map(|item| match item {
+    let raw_stream = if is_json(&upstream_resp) {
+        Either::Left(unary_as_event(upstream_resp))
+    } else {
+        Either::Right(upstream_resp.bytes_stream().eventsource())
+    };
+    let record_stream = transform_stream(raw_stream, state.clone(), sniffer);
+    let timed_stream =
+        sse_timeout::with_timeouts(record_stream, state.sse_timeouts).map(|item| match item {
             Ok(Ok(event)) => Ok(event),
             Ok(Err(e)) => Err(GeminiCliError::StreamProtocolError(e.to_string())),
-            Err(_) => {
-                error!("Upstream SSE stream timed out (idle > 60s)");
-                Err(GeminiCliError::StreamProtocolError(
-                    "Stream idle timeout".to_string(),
-                ))
+            Err(timeout) => {
+                error!("Upstream SSE stream timed out ({timeout})");
+                Err(GeminiCliError::StreamProtocolError(format!(
+                    "Stream timeout: {timeout}"
+                )))
             }
         });
 
-    Sse::new(timed_stream).keep_alive(KeepAlive::default())
+    let events = gemini_sse::gemini_events(timed_stream, state.sse_complete_event);
+
+    let buffered = sse_buffer::bounded(events, state.sse_buffer, || {
+        GeminiCliError::StreamProtocolError("Client too slow; SSE buffer overflow".to_string())
+    });
+
+    let hinted = sse_retry::with_retry_hint(buffered, state.sse_retry);
+
+    Sse::new(stream_limit::hold(hinted, permit)).keep_alive(KeepAlive::default())
+}
+
+fn is_json(upstream_resp: &reqwest::Response) -> bool {
+    upstream_resp
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/json"))
+}
+
+/// Upstream occasionally answers a stream request with the unary JSON envelope; replay the
+/// whole body as a single SSE event.
+fn unary_as_event(
+    upstream_resp: reqwest::Response,
+) -> impl Stream<Item = Result<Event, EventStreamError<reqwest::Error>>> {
+    warn!("Upstream answered a stream request with a JSON body; sending it as one event");
+    stream::once(async move {
+        let data = upstream_resp
+            .text()
+            .await
+            .map_err(EventStreamError::Transport)?;
+        Ok(Event {
+            data,
+            ..Event::default()
+        })
+    })
 }
 
 fn transform_stream<I, E>(
     s: I,
     state: PolluxState,
     mut sniffer: pollux_thoughtsig_core::SignatureSniffer,
-) -> impl Stream<Item = Result<Event, E>>
+) -> impl Stream<Item = Result<GeminiResponseBody, E>>
 where
     I: Stream<Item = Result<eventsource_stream::Event, E>>,
 {
+    let mut tool_call_ids = state.tool_call_ids.then(ToolCallIds::default);
     s.try_filter_map(move |upstream_event| {
         let state = state.clone();
 
@@ -69,7 +127,7 @@ where
             {
                 Ok(None)
             } else {
-                let Some(gemini_resp) = parse_sse_payload(&upstream_event.data) else {
+                let Some(mut gemini_resp) = parse_sse_payload(&upstream_event.data) else {
                     return future::ready(Ok(None));
                 };
 
@@ -77,14 +135,17 @@ where
                     .providers
                     .antigravity_thoughtsig
                     .sniff_response(&gemini_resp, &mut sniffer);
-
-                match Event::default().json_data(gemini_resp) {
-                    Ok(ev) => Ok(Some(ev)),
-                    Err(e) => {
-                        warn!("Failed to serialize GeminiResponse: {}", e);
-                        Ok(None)
-                    }
+                if state.strip_empty_text
+                    && empty_text::strip_empty_text(&mut gemini_resp) > 0
+                    && empty_text::is_blank(&gemini_resp)
+                {
+                    return future::ready(Ok(None));
+                }
+                if let Some(ids) = tool_call_ids.as_mut() {
+                    ids.assign(&mut gemini_resp);
                 }
+
+                Ok(Some(gemini_resp))
             }
         };
 
@@ -93,17 +154,17 @@ where
 }
 
 fn parse_sse_payload(data: &str) -> Option<GeminiResponseBody> {
-    let Ok(cli_resp) = serde_json::from_str::<GeminiCliResponseBody>(data) else {
+    let Ok(envelope) = AntigravityResponseBody::from_sse_data(data) else {
         warn!("Skipping invalid SSE JSON data: {:.50}...", data);
         return None;
     };
 
-    Some(cli_resp.into())
+    Some(envelope.into())
 }
 
 async fn transform_nostream(
     upstream_resp: reqwest::Response,
 ) -> Result<GeminiResponseBody, GeminiCliError> {
-    let envelope = upstream_resp.json::<GeminiCliResponseBody>().await?;
+    let envelope = upstream_resp.json::<AntigravityResponseBody>().await?;
     Ok(envelope.into())
 }
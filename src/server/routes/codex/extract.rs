@@ -1,44 +1,90 @@
 use crate::error::CodexError;
 use crate::providers::codex::model_mask;
+use crate::server::router::PolluxState;
+use crate::utils::body_limit::declared_length_over;
+use crate::utils::content_type::json_content_type_error;
 use crate::utils::logging::with_pretty_json_debug;
+use crate::utils::spool::{SpooledBody, should_spool};
 use axum::{
     Json,
     extract::{FromRequest, Request},
     http::StatusCode,
 };
 use pollux_schema::OpenaiResponsesErrorObject;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use pollux_schema::OpenaiRequestBody;
 
-use super::CodexContext;
+use super::{CODEX_RESPONSES_BODY_LIMIT_BYTES, CodexContext};
 
 pub(crate) struct CodexPreprocess(pub(crate) OpenaiRequestBody, pub(crate) CodexContext);
 
 impl<S> FromRequest<S> for CodexPreprocess
 where
-    S: Send + Sync,
+    S: Send + Sync + std::borrow::Borrow<PolluxState>,
 {
     type Rejection = CodexError;
 
     /// Extract and validate a Codex `/codex/v1/responses` request.
     ///
     /// Responsibilities:
-    /// - Deserialize the HTTP JSON body into `OpenaiRequestBody`.
+    /// - Deserialize the HTTP JSON body into `OpenaiRequestBody`, spooling it to a temp file first
+    ///   when `providers.codex.body_spool_threshold` says it is too large to buffer in memory.
     /// - Compute `model_mask` (capability bit) used for credential selection/routing.
     ///
     /// Error handling:
     /// - JSON syntax/schema errors from the `axum::Json` extractor are converted into `CodexError`
-    ///   via `From<JsonRejection> for CodexError`, which emits our standardized OpenAI-style error
+    ///   via `CodexError::json_rejection`, which emits our standardized OpenAI-style error
     ///   response body and logs the underlying parser error to `debug_message`.
-    /// - Missing/empty `model` => `INVALID_MODEL`.
+    /// - A declared `Content-Length` over the route limit => `PAYLOAD_TOO_LARGE`, before the body
+    ///   is read (so `Expect: 100-continue` clients never upload it).
+    /// - A `Content-Type` other than JSON => `INVALID_CONTENT_TYPE`, naming the received type.
+    /// - Missing/empty `model` => `providers.codex.default_model`, or `INVALID_MODEL` when unset.
     /// - Model not present in this deployment's configured model set => `UNSUPPORTED_MODEL`.
     ///
     /// Notes:
-    /// - We intentionally do not `trim()` or otherwise normalize `model`; matching is exact.
-    async fn from_request(req: Request, _state: &S) -> Result<Self, Self::Rejection> {
-        let Json(body) = Json::<OpenaiRequestBody>::from_request(req, &()).await?;
+    /// - We intentionally do not `trim()` or otherwise normalize `model`; matching is exact. The
+    ///   only rewrite is a `basic.model_catalog` alias to its model name.
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(length) = declared_length_over(req.headers(), CODEX_RESPONSES_BODY_LIMIT_BYTES)
+        {
+            return Err(CodexError::payload_too_large(
+                CODEX_RESPONSES_BODY_LIMIT_BYTES,
+                Some(format!(
+                    "declared Content-Length {length} exceeds limit of {} bytes",
+                    CODEX_RESPONSES_BODY_LIMIT_BYTES
+                )),
+            ));
+        }
+        if let Some(message) = json_content_type_error(req.headers()) {
+            return Err(CodexError::invalid_content_type(message));
+        }
+        let threshold = state.borrow().providers.codex_cfg.body_spool_threshold;
+        let mut body = if should_spool(req.headers(), threshold) {
+            let spooled =
+                SpooledBody::write(req.into_body(), CODEX_RESPONSES_BODY_LIMIT_BYTES).await?;
+            debug!(
+                bytes = spooled.len(),
+                path = %spooled.path().display(),
+                "[Codex] Spooled request body to disk"
+            );
+            spooled.parse::<OpenaiRequestBody>().await?
+        } else {
+            let Json(body) = Json::<OpenaiRequestBody>::from_request(req, &())
+                .await
+                .map_err(|e| CodexError::json_rejection(e, CODEX_RESPONSES_BODY_LIMIT_BYTES))?;
+            body
+        };
 
+        if body.model.is_empty()
+            && let Some(default) = &state.borrow().providers.codex_cfg.default_model
+        {
+            body.model = default.clone();
+        }
+        // Catalog aliases are forwarded under the model's own name.
+        if let Some(canonical) = crate::model_catalog::canonical_name(&body.model) {
+            body.model = canonical.to_string();
+        }
         let model = body.model.as_str();
         if model.is_empty() {
             return Err(CodexError::RequestRejected {
@@ -56,6 +102,11 @@ where
         let stream = body.stream;
 
         let Some(model_mask) = model_mask(model) else {
+            warn!(
+                model_info = ?crate::model_catalog::describe(model),
+                "Rejected request for unsupported codex model: {}",
+                model
+            );
             return Err(CodexError::RequestRejected {
                 status: StatusCode::BAD_REQUEST,
                 body: OpenaiResponsesErrorObject {
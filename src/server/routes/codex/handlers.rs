@@ -1,7 +1,9 @@
-use super::{extract::CodexPreprocess, respond};
+use super::{CodexContext, extract::CodexPreprocess, respond};
 use crate::error::CodexError;
 use crate::providers::codex::client::CodexClient;
-use crate::server::router::PolluxState;
+use crate::providers::lease_log::LeaseRecorder;
+use crate::server::coalesce::RequestCoalescer;
+use crate::server::router::{PolluxState, RequestId};
 use axum::{
     Json,
     extract::State,
@@ -13,8 +15,20 @@ use tracing::debug;
 
 pub(super) async fn codex_response_handler(
     State(state): State<PolluxState>,
+    RequestId(request_id): RequestId,
     CodexPreprocess(body, ctx): CodexPreprocess,
 ) -> Result<Response, CodexError> {
+    state.metrics.record_request(&ctx.model);
+    let leases = LeaseRecorder::new(state.providers.lease_log.clone(), "codex", request_id);
+
+    // Reserve the stream slot before spending an upstream call on it.
+    let stream_permit = if ctx.stream {
+        let permit = state.stream_limiter.try_acquire();
+        Some(permit.ok_or_else(CodexError::too_many_streams)?)
+    } else {
+        None
+    };
+
     let codex_body: CodexRequestBody = body.into();
 
     debug!(
@@ -25,28 +39,63 @@ pub(super) async fn codex_response_handler(
         "Incoming Codex request"
     );
 
+    if let Some(permit) = stream_permit {
+        let upstream_resp = call_upstream(&state, &ctx, &codex_body, leases).await?;
+        return Ok(respond::build_stream_response(
+            upstream_resp,
+            state.sse_buffer,
+            state.sse_retry,
+            state.sse_timeouts,
+            permit,
+        )
+        .into_response());
+    }
+
+    if let Some(coalescer) = state.coalescer.clone()
+        && let Some(key) = RequestCoalescer::key("codex", &ctx.model, &codex_body)
+    {
+        return Ok(coalescer
+            .run(key, unary(state, ctx, codex_body, leases))
+            .await);
+    }
+    Ok(unary(state, ctx, codex_body, leases).await)
+}
+
+async fn call_upstream(
+    state: &PolluxState,
+    ctx: &CodexContext,
+    codex_body: &CodexRequestBody,
+    leases: LeaseRecorder,
+) -> Result<reqwest::Response, CodexError> {
     let caller = CodexClient::new(
         state.providers.codex_cfg.as_ref(),
         state.codex_client.clone(),
         None,
-    );
+    )
+    .with_lease_recorder(leases);
 
-    let upstream_resp = caller
+    caller
         .call_codex(
             &state.providers.codex,
             ctx.model.as_str(),
             ctx.model_mask,
             ctx.stream,
-            &codex_body,
+            codex_body,
         )
-        .await?;
+        .await
+}
 
-    if ctx.stream {
-        Ok(respond::build_stream_response(upstream_resp).into_response())
-    } else {
-        let (status, body) = respond::build_json_response_from_stream(upstream_resp).await?;
-        Ok((status, body).into_response())
-    }
+async fn unary(
+    state: PolluxState,
+    ctx: CodexContext,
+    codex_body: CodexRequestBody,
+    leases: LeaseRecorder,
+) -> Response {
+    let result = async {
+        let upstream_resp = call_upstream(&state, &ctx, &codex_body, leases).await?;
+        respond::build_json_response_from_stream(upstream_resp, state.sse_timeouts).await
+    };
+    result.await.into_response()
 }
 
 pub(super) async fn codex_models_handler() -> Result<Json<OpenaiModelList>, CodexError> {
@@ -19,7 +19,7 @@ use crate::providers::codex::SUPPORTED_MODEL_NAMES;
 use pollux_schema::openai::OpenaiModelList;
 use std::sync::LazyLock;
 
-const CODEX_RESPONSES_BODY_LIMIT_BYTES: usize = 100 * 1024 * 1024;
+pub(crate) const CODEX_RESPONSES_BODY_LIMIT_BYTES: usize = 100 * 1024 * 1024;
 
 pub static CODEX_MODEL_LIST: LazyLock<OpenaiModelList> = LazyLock::new(|| {
     OpenaiModelList::from_model_names(SUPPORTED_MODEL_NAMES.iter().cloned(), "codex".to_string())
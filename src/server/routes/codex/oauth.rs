@@ -8,10 +8,9 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Redirect},
 };
-use axum_extra::extract::cookie::{Cookie, PrivateCookieJar, SameSite};
+use axum_extra::extract::cookie::PrivateCookieJar;
 use oauth2::{AuthorizationCode, PkceCodeChallenge, PkceCodeVerifier, TokenResponse};
 use serde::Deserialize;
-use time::Duration;
 use tracing::{error, info};
 
 const CSRF_COOKIE: &str = "codex_oauth_csrf_token";
@@ -33,17 +32,10 @@ pub async fn codex_oauth_entry(
     let (challenge, verifier) = PkceCodeChallenge::new_random_sha256();
     let (auth_url, csrf_token) = CodexOauthEndpoints::build_authorize_url(challenge);
 
+    let cookies = &state.oauth_cookies;
     let jar = jar
-        .add(build_cookie(
-            CSRF_COOKIE,
-            csrf_token.secret().to_string(),
-            !state.insecure_cookie,
-        ))
-        .add(build_cookie(
-            PKCE_COOKIE,
-            verifier.secret().to_string(),
-            !state.insecure_cookie,
-        ));
+        .add(cookies.build(CSRF_COOKIE, csrf_token.secret().to_string()))
+        .add(cookies.build(PKCE_COOKIE, verifier.secret().to_string()));
 
     info!("Dispatching Codex OAuth redirect to: {}", auth_url);
     Ok((jar, Redirect::temporary(auth_url.as_ref())).into_response())
@@ -55,7 +47,7 @@ pub async fn codex_oauth_callback(
     Query(query): Query<AuthCallbackQuery>,
     jar: PrivateCookieJar,
 ) -> impl IntoResponse {
-    let (jar, session_data) = take_oauth_cookies(jar);
+    let (jar, session_data) = take_oauth_cookies(&state, jar);
 
     let result = process_oauth_exchange(&state, &query.code, &query.state, session_data).await;
     match result {
@@ -137,26 +129,19 @@ async fn process_oauth_exchange(
     Ok(token_response)
 }
 
-fn take_oauth_cookies(jar: PrivateCookieJar) -> (PrivateCookieJar, Option<(String, String)>) {
+fn take_oauth_cookies(
+    state: &PolluxState,
+    jar: PrivateCookieJar,
+) -> (PrivateCookieJar, Option<(String, String)>) {
     let csrf = jar.get(CSRF_COOKIE).map(|c| c.value().to_string());
     let pkce = jar.get(PKCE_COOKIE).map(|c| c.value().to_string());
 
     let jar = jar
-        .remove(Cookie::from(CSRF_COOKIE))
-        .remove(Cookie::from(PKCE_COOKIE));
+        .remove(state.oauth_cookies.removal(CSRF_COOKIE))
+        .remove(state.oauth_cookies.removal(PKCE_COOKIE));
 
     match (pkce, csrf) {
         (Some(p), Some(c)) => (jar, Some((p, c))),
         _ => (jar, None),
     }
 }
-
-fn build_cookie(name: &'static str, value: String, secure: bool) -> Cookie<'static> {
-    Cookie::build((name, value))
-        .path("/")
-        .http_only(true)
-        .secure(secure)
-        .same_site(SameSite::Lax)
-        .max_age(Duration::minutes(15))
-        .build()
-}
@@ -1,4 +1,8 @@
 use crate::error::CodexError;
+use crate::server::sse_buffer::{self, SseBufferConfig};
+use crate::server::sse_retry;
+use crate::server::sse_timeout::{self, SseTimeouts};
+use crate::server::stream_limit::{self, StreamPermit};
 use axum::{
     Json,
     body::Bytes,
@@ -15,26 +19,36 @@ use std::time::Duration;
 use tokio_stream::StreamExt;
 use tracing::error;
 
-const SSE_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
-
 /// Build SSE stream response.
-pub(super) fn build_stream_response(upstream_resp: reqwest::Response) -> impl IntoResponse {
+#[allow(clippy::result_large_err)]
+pub(super) fn build_stream_response(
+    upstream_resp: reqwest::Response,
+    sse_buffer: SseBufferConfig,
+    sse_retry: Option<Duration>,
+    sse_timeouts: SseTimeouts,
+    permit: StreamPermit,
+) -> impl IntoResponse {
     let raw_stream = upstream_resp.bytes_stream().eventsource();
-    let timed_stream =
-        transform_stream(raw_stream)
-            .timeout(SSE_IDLE_TIMEOUT)
-            .map(|item| match item {
-                Ok(Ok(event)) => Ok(event),
-                Ok(Err(e)) => Err(CodexError::StreamProtocolError(e.to_string())),
-                Err(_) => {
-                    error!("Upstream Codex SSE stream timed out (idle > 60s)");
-                    Err(CodexError::StreamProtocolError(
-                        "Stream idle timeout".to_string(),
-                    ))
-                }
-            });
-
-    Sse::new(timed_stream).keep_alive(KeepAlive::default())
+    let timed_stream = sse_timeout::with_timeouts(transform_stream(raw_stream), sse_timeouts).map(
+        |item| match item {
+            Ok(Ok(event)) => Ok(event),
+            Ok(Err(e)) => Err(CodexError::StreamProtocolError(e.to_string())),
+            Err(timeout) => {
+                error!("Upstream Codex SSE stream timed out ({timeout})");
+                Err(CodexError::StreamProtocolError(format!(
+                    "Stream timeout: {timeout}"
+                )))
+            }
+        },
+    );
+
+    let buffered = sse_buffer::bounded(timed_stream, sse_buffer, || {
+        CodexError::StreamProtocolError("Client too slow; SSE buffer overflow".to_string())
+    });
+
+    let hinted = sse_retry::with_retry_hint(buffered, sse_retry);
+
+    Sse::new(stream_limit::hold(hinted, permit)).keep_alive(KeepAlive::default())
 }
 
 /// Build JSON response from a streaming upstream response.
@@ -44,14 +58,18 @@ pub(super) fn build_stream_response(upstream_resp: reqwest::Response) -> impl In
 /// final `response.completed` event and return the embedded `response` as JSON.
 pub(super) async fn build_json_response_from_stream(
     upstream_resp: reqwest::Response,
+    sse_timeouts: SseTimeouts,
 ) -> Result<(StatusCode, Json<Value>), CodexError> {
     let status = upstream_resp.status();
 
-    let body = parse_upstream_sse_to_json(upstream_resp.bytes_stream()).await?;
+    let body = parse_upstream_sse_to_json(upstream_resp.bytes_stream(), sse_timeouts).await?;
     Ok((status, Json(body)))
 }
 
-async fn parse_upstream_sse_to_json<S, E>(stream: S) -> Result<Value, CodexError>
+async fn parse_upstream_sse_to_json<S, E>(
+    stream: S,
+    sse_timeouts: SseTimeouts,
+) -> Result<Value, CodexError>
 where
     S: Stream<Item = Result<Bytes, E>>,
     E: std::error::Error + Send + Sync + 'static,
@@ -61,18 +79,18 @@ where
     let mut last_json: Option<Value> = None;
 
     let raw_stream = stream.eventsource();
-    let timed_stream = raw_stream.timeout(SSE_IDLE_TIMEOUT);
+    let timed_stream = sse_timeout::with_timeouts(raw_stream, sse_timeouts);
     tokio::pin!(timed_stream);
 
     while let Some(item) = timed_stream.next().await {
         let upstream_event = match item {
             Ok(Ok(event)) => event,
             Ok(Err(e)) => return Err(CodexError::StreamProtocolError(e.to_string())),
-            Err(_) => {
-                error!("Upstream Codex stream timed out (idle > 60s)");
-                return Err(CodexError::StreamProtocolError(
-                    "Stream idle timeout".to_string(),
-                ));
+            Err(timeout) => {
+                error!("Upstream Codex stream timed out ({timeout})");
+                return Err(CodexError::StreamProtocolError(format!(
+                    "Stream timeout: {timeout}"
+                )));
             }
         };
 
@@ -140,7 +158,9 @@ mod tests {
         let stream = stream::iter([Ok::<_, std::convert::Infallible>(Bytes::from_static(
             sse_body.as_bytes(),
         ))]);
-        let body = parse_upstream_sse_to_json(stream).await.unwrap();
+        let body = parse_upstream_sse_to_json(stream, SseTimeouts::default())
+            .await
+            .unwrap();
         assert_eq!(body, json!({"id":"r1","object":"response"}));
     }
 
@@ -156,7 +176,9 @@ mod tests {
             Ok::<_, std::convert::Infallible>(Bytes::from_static(a.as_bytes())),
             Ok::<_, std::convert::Infallible>(Bytes::from_static(b.as_bytes())),
         ]);
-        let body = parse_upstream_sse_to_json(stream).await.unwrap();
+        let body = parse_upstream_sse_to_json(stream, SseTimeouts::default())
+            .await
+            .unwrap();
         assert_eq!(body, json!({"id":"r2"}));
     }
 }
@@ -1,6 +1,12 @@
+use crate::providers::conversation::ConversationId;
 use crate::providers::geminicli::{GeminiContext, model_mask};
+use crate::providers::priority::LeasePriority;
 use crate::server::router::PolluxState;
+use crate::server::tool_call_ids;
+use crate::utils::body_limit::{DEFAULT_BODY_LIMIT_BYTES, declared_length_over};
+use crate::utils::content_type::json_content_type_error;
 use crate::utils::logging::with_pretty_json_debug;
+use crate::utils::stream_mode::stream_requested;
 use crate::{error::GeminiCliError, error::GeminiErrorObject};
 use axum::{
     Json, RequestExt,
@@ -8,9 +14,14 @@ use axum::{
     http::StatusCode,
 };
 use pollux_schema::gemini::GeminiGenerateContentRequest;
+use pollux_thoughtsig_core::PatchStats;
 use tracing::{debug, warn};
 
-pub struct GeminiPreprocess(pub GeminiGenerateContentRequest, pub GeminiContext);
+pub struct GeminiPreprocess(
+    pub GeminiGenerateContentRequest,
+    pub GeminiContext,
+    pub PatchStats,
+);
 
 impl<S> FromRequest<S> for GeminiPreprocess
 where
@@ -50,9 +61,26 @@ where
         } else {
             last_seg
         };
+        // A path without a model (`models/:generateContent`) gets the configured default.
+        let model = match state
+            .borrow()
+            .providers
+            .geminicli_cfg
+            .default_model
+            .as_deref()
+        {
+            Some(default) if model.is_empty() => default.to_string(),
+            _ => model,
+        };
+        // Catalog aliases are forwarded under the model's own name.
+        let model = crate::model_catalog::canonical_name(&model).map_or(model, str::to_string);
 
         let Some(model_mask) = model_mask(model.as_str()) else {
-            warn!("Rejected request for unsupported model: {}", model);
+            warn!(
+                model_info = ?crate::model_catalog::describe(model.as_str()),
+                "Rejected request for unsupported model: {}",
+                model
+            );
             let body = GeminiErrorObject::for_status(
                 StatusCode::BAD_REQUEST,
                 "INVALID_ARGUMENT",
@@ -65,15 +93,112 @@ where
             });
         };
 
-        let stream = path.contains("streamGenerateContent");
+        let stream = stream_requested(&path, req.uri().query()).map_err(|message| {
+            GeminiCliError::RequestRejected {
+                status: StatusCode::BAD_REQUEST,
+                body: GeminiErrorObject::for_status(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_ARGUMENT",
+                    message,
+                ),
+                debug_message: None,
+            }
+        })?;
 
-        let Json(mut body) = Json::<GeminiGenerateContentRequest>::from_request(req, &()).await?;
+        if let Some(length) = declared_length_over(req.headers(), DEFAULT_BODY_LIMIT_BYTES) {
+            return Err(GeminiCliError::payload_too_large(
+                DEFAULT_BODY_LIMIT_BYTES,
+                Some(format!(
+                    "declared Content-Length {length} exceeds limit of {} bytes",
+                    DEFAULT_BODY_LIMIT_BYTES
+                )),
+            ));
+        }
+        if let Some(message) = json_content_type_error(req.headers()) {
+            return Err(GeminiCliError::invalid_content_type(message));
+        }
+        let priority = LeasePriority::from_headers(req.headers()).map_err(|message| {
+            GeminiCliError::RequestRejected {
+                status: StatusCode::BAD_REQUEST,
+                body: GeminiErrorObject::for_status(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_ARGUMENT",
+                    message,
+                ),
+                debug_message: None,
+            }
+        })?;
+        let headers = req.headers().clone();
+        let Json(mut body) = Json::<GeminiGenerateContentRequest>::from_request(req, &())
+            .await
+            .map_err(|e| GeminiCliError::json_rejection(e, DEFAULT_BODY_LIMIT_BYTES))?;
 
         let state = state.borrow();
-        state
-            .providers
-            .geminicli_thoughtsig
-            .patch_request(&mut body);
+        let max_parts = state.providers.geminicli_cfg.max_parts_per_content;
+        if let Some((index, parts)) = body.oversized_content(max_parts) {
+            return Err(GeminiCliError::RequestRejected {
+                status: StatusCode::BAD_REQUEST,
+                body: GeminiErrorObject::for_status(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_ARGUMENT",
+                    format!("contents[{index}] has {parts} parts; at most {max_parts} allowed"),
+                ),
+                debug_message: None,
+            });
+        }
+        if let Some(message) = body.response_schema_error() {
+            return Err(GeminiCliError::RequestRejected {
+                status: StatusCode::BAD_REQUEST,
+                body: GeminiErrorObject::for_status(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_ARGUMENT",
+                    message,
+                ),
+                debug_message: None,
+            });
+        }
+
+        if let Err(e) = body.merge_system_instructions() {
+            return Err(GeminiCliError::RequestRejected {
+                status: StatusCode::BAD_REQUEST,
+                body: GeminiErrorObject::for_status(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_ARGUMENT",
+                    format!("invalid system_instruction: {e}"),
+                ),
+                debug_message: None,
+            });
+        }
+        // After merging, so a request of only `system` turns counts as empty too.
+        if body.contents.is_empty()
+            && !state
+                .providers
+                .geminicli_cfg
+                .empty_contents_models
+                .contains(&model)
+        {
+            return Err(GeminiCliError::RequestRejected {
+                status: StatusCode::BAD_REQUEST,
+                body: GeminiErrorObject::for_status(
+                    StatusCode::BAD_REQUEST,
+                    "INVALID_ARGUMENT",
+                    format!("contents must not be empty for model {model}"),
+                ),
+                debug_message: None,
+            });
+        }
+
+        if state.tool_call_ids {
+            tool_call_ids::strip_assigned(&mut body);
+        }
+        let conversation = ConversationId::extract(&headers, &body);
+        let thoughtsig = &state.providers.geminicli_thoughtsig;
+        // Derived ids can collide between conversations, so only a client-named one may
+        // reuse earlier fill decisions.
+        let explicit = conversation.as_ref().filter(|c| c.explicit);
+        let fill_stats = thoughtsig
+            .patch_request_async(&model, explicit.map(|c| c.id.as_str()), &mut body)
+            .await;
 
         with_pretty_json_debug(&body, |pretty_body| {
             debug!(
@@ -91,7 +216,9 @@ where
             stream,
             path,
             model_mask,
+            conversation,
+            priority,
         };
-        Ok(GeminiPreprocess(body, ctx))
+        Ok(GeminiPreprocess(body, ctx, fill_stats))
     }
 }
@@ -3,36 +3,118 @@ use super::{
     respond::{build_json_response, build_stream_response},
 };
 use crate::error::GeminiCliError;
+use crate::providers::UpstreamClient;
+use crate::providers::geminicli::GeminiContext;
 use crate::providers::geminicli::client::GeminiClient;
-use crate::server::router::PolluxState;
+use crate::providers::lease_log::LeaseRecorder;
+use crate::server::coalesce::RequestCoalescer;
+use crate::server::router::{PolluxState, RequestId};
 use axum::{
     Json,
     extract::State,
+    http::HeaderValue,
     response::{IntoResponse, Response},
 };
-use pollux_schema::{gemini::GeminiModelList, openai::OpenaiModelList};
+use pollux_schema::{
+    gemini::{GeminiGenerateContentRequest, GeminiModelList},
+    openai::OpenaiModelList,
+};
+use pollux_thoughtsig_core::PatchStats;
+
+/// Summarizes the request's thought-signature fill, when `fill_stats_header` is enabled.
+const FILL_STATS_HEADER: &str = "x-pollux-fillstats";
 
 pub async fn gemini_cli_handler(
     State(state): State<PolluxState>,
-    GeminiPreprocess(body, ctx): GeminiPreprocess,
+    RequestId(request_id): RequestId,
+    GeminiPreprocess(body, ctx, fill_stats): GeminiPreprocess,
+) -> Response {
+    let with_stats = state.providers.geminicli_cfg.fill_stats_header;
+    let leases = LeaseRecorder::new(state.providers.lease_log.clone(), "geminicli", request_id);
+    let mut response = generate(state, ctx, body, leases).await.into_response();
+    if with_stats {
+        response.headers_mut().insert(
+            FILL_STATS_HEADER,
+            HeaderValue::from_str(&format_fill_stats(&fill_stats)).expect("fill stats are ASCII"),
+        );
+    }
+    response
+}
+
+/// `total=<n>;cache_hits=<n>;dummy=<n>`, where `total` counts every part that needed a
+/// signature and `dummy` those that got the fallback.
+fn format_fill_stats(stats: &PatchStats) -> String {
+    let total = stats.cache_hits
+        + stats.fallbacks
+        + stats.kept
+        + stats.unfilled
+        + stats.borrowed
+        + stats.reused;
+    format!(
+        "total={total};cache_hits={};dummy={}",
+        stats.cache_hits, stats.fallbacks
+    )
+}
+
+async fn generate(
+    state: PolluxState,
+    ctx: GeminiContext,
+    body: GeminiGenerateContentRequest,
+    leases: LeaseRecorder,
 ) -> Result<Response, GeminiCliError> {
+    state.metrics.record_request(&ctx.model);
+
+    // Reserve the stream slot before spending an upstream call on it.
+    let stream_permit = if ctx.stream {
+        let permit = state.stream_limiter.try_acquire();
+        Some(permit.ok_or_else(GeminiCliError::too_many_streams)?)
+    } else {
+        None
+    };
+
+    if let Some(permit) = stream_permit {
+        let upstream_resp = call_upstream(&state, &ctx, &body, leases).await?;
+        return Ok(
+            build_stream_response(upstream_resp, state.clone(), permit, &ctx.model).into_response(),
+        );
+    }
+
+    if let Some(coalescer) = state.coalescer.clone()
+        && let Some(key) = RequestCoalescer::key("geminicli", &ctx.model, &body)
+    {
+        return Ok(coalescer.run(key, unary(state, ctx, body, leases)).await);
+    }
+    Ok(unary(state, ctx, body, leases).await)
+}
+
+async fn call_upstream(
+    state: &PolluxState,
+    ctx: &GeminiContext,
+    body: &GeminiGenerateContentRequest,
+    leases: LeaseRecorder,
+) -> Result<reqwest::Response, GeminiCliError> {
     // Construct caller
     let caller = GeminiClient::new(
         state.providers.geminicli_cfg.as_ref(),
         state.client.clone(),
         None,
-    );
+    )
+    .with_lease_recorder(leases);
 
-    let upstream_resp = caller
-        .call_gemini_cli(&state.providers.geminicli, &ctx, &body)
-        .await?;
+    caller.call(&state.providers.geminicli, ctx, body).await
+}
 
-    if ctx.stream {
-        Ok(build_stream_response(upstream_resp, state.clone()).into_response())
-    } else {
-        Ok(build_json_response(upstream_resp, &state)
+async fn unary(
+    state: PolluxState,
+    ctx: GeminiContext,
+    body: GeminiGenerateContentRequest,
+    leases: LeaseRecorder,
+) -> Response {
+    match call_upstream(&state, &ctx, &body, leases).await {
+        Ok(upstream_resp) => build_json_response(upstream_resp, &state, &ctx.model)
             .await
-            .into_response())
+            .into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
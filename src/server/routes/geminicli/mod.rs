@@ -7,6 +7,7 @@ pub mod respond;
 use crate::providers::geminicli::SUPPORTED_MODEL_NAMES;
 use crate::server::router::PolluxState;
 use handlers::{gemini_cli_handler, gemini_models_handler, gemini_openai_models_handler};
+use oauth::google_device_oauth_entry;
 use pollux_schema::{gemini::GeminiModelList, openai::OpenaiModelList};
 use resource::geminicli_resource_add;
 
@@ -35,4 +36,5 @@ pub fn router() -> Router<PolluxState> {
         )
         .route("/geminicli/v1beta/models/{*path}", post(gemini_cli_handler))
         .route("/geminicli/resource:add", post(geminicli_resource_add))
+        .route("/geminicli/auth/device", post(google_device_oauth_entry))
 }
@@ -1,18 +1,33 @@
 use crate::server::router::PolluxState;
 use crate::{
-    PolluxError, error::OauthError, providers::geminicli::GeminiCliActorHandle,
-    providers::geminicli::client::oauth::endpoints::GoogleOauthEndpoints,
+    PolluxError,
+    error::OauthError,
+    providers::geminicli::client::oauth::endpoints::{
+        DeviceFlowUrls, GoogleOauthEndpoints, GoogleTokenResponse,
+    },
+    providers::geminicli::{GeminiCliActorHandle, OnboardProgress},
 };
 use axum::{
+    Json,
+    body::Body,
     extract::{Query, State},
-    http::StatusCode,
-    response::{IntoResponse, Redirect},
+    http::{
+        StatusCode,
+        header::{CACHE_CONTROL, CONTENT_TYPE, X_CONTENT_TYPE_OPTIONS},
+    },
+    response::{IntoResponse, Redirect, Response},
+};
+use axum_extra::extract::cookie::PrivateCookieJar;
+use futures::StreamExt;
+use oauth2::{
+    AuthorizationCode, PkceCodeChallenge, PkceCodeVerifier, StandardDeviceAuthorizationResponse,
+    TokenResponse,
 };
-use axum_extra::extract::cookie::{Cookie, PrivateCookieJar, SameSite};
-use oauth2::{AuthorizationCode, PkceCodeChallenge, PkceCodeVerifier, TokenResponse};
 use reqwest::Client;
-use serde::Deserialize;
-use time::Duration;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::{error, info};
 
 const CSRF_COOKIE: &str = "oauth_csrf_token";
@@ -31,17 +46,10 @@ pub async fn google_oauth_entry(
     let (challenge, verifier) = PkceCodeChallenge::new_random_sha256();
     let (auth_url, csrf_token) = GoogleOauthEndpoints::build_authorize_url(challenge);
 
+    let cookies = &state.oauth_cookies;
     let jar = jar
-        .add(build_cookie(
-            CSRF_COOKIE,
-            csrf_token.secret().to_string(),
-            !state.insecure_cookie,
-        ))
-        .add(build_cookie(
-            PKCE_COOKIE,
-            verifier.secret().to_string(),
-            !state.insecure_cookie,
-        ));
+        .add(cookies.build(CSRF_COOKIE, csrf_token.secret().to_string()))
+        .add(cookies.build(PKCE_COOKIE, verifier.secret().to_string()));
 
     info!("Dispatching OAuth redirect to: {}", auth_url);
 
@@ -49,12 +57,15 @@ pub async fn google_oauth_entry(
 }
 
 /// GET /oauth2callback
+///
+/// Streams onboarding progress as plain-text lines, so the browser shows "Provisioning
+/// project..." while Code Assist sets the account up; the last line says whether it worked.
 pub async fn google_oauth_callback(
     State(state): State<PolluxState>,
     Query(query): Query<AuthCallbackQuery>,
     jar: PrivateCookieJar,
 ) -> impl IntoResponse {
-    let (jar, session_data) = take_oauth_cookies(jar);
+    let (jar, session_data) = take_oauth_cookies(&state, jar);
 
     let result = process_oauth_exchange(
         &state.providers.geminicli,
@@ -66,7 +77,7 @@ pub async fn google_oauth_callback(
     .await;
 
     match result {
-        Ok(()) => (jar, (StatusCode::ACCEPTED, "Success")).into_response(),
+        Ok(progress) => (jar, progress_response(progress)).into_response(),
         Err(err) => {
             error!("OAuth failure: {:?}", err);
             (jar, err.into_response()).into_response()
@@ -74,13 +85,16 @@ pub async fn google_oauth_callback(
     }
 }
 
-fn take_oauth_cookies(jar: PrivateCookieJar) -> (PrivateCookieJar, Option<(String, String)>) {
+fn take_oauth_cookies(
+    state: &PolluxState,
+    jar: PrivateCookieJar,
+) -> (PrivateCookieJar, Option<(String, String)>) {
     let csrf = jar.get(CSRF_COOKIE).map(|c| c.value().to_string());
     let pkce = jar.get(PKCE_COOKIE).map(|c| c.value().to_string());
 
     let jar = jar
-        .remove(Cookie::from(CSRF_COOKIE))
-        .remove(Cookie::from(PKCE_COOKIE));
+        .remove(state.oauth_cookies.removal(CSRF_COOKIE))
+        .remove(state.oauth_cookies.removal(PKCE_COOKIE));
 
     match (pkce, csrf) {
         (Some(p), Some(c)) => (jar, Some((p, c))),
@@ -88,23 +102,13 @@ fn take_oauth_cookies(jar: PrivateCookieJar) -> (PrivateCookieJar, Option<(Strin
     }
 }
 
-fn build_cookie(name: &'static str, value: String, secure: bool) -> Cookie<'static> {
-    Cookie::build((name, value))
-        .path("/")
-        .http_only(true)
-        .secure(secure)
-        .same_site(SameSite::Lax)
-        .max_age(Duration::minutes(15))
-        .build()
-}
-
 pub async fn process_oauth_exchange(
     handle: &GeminiCliActorHandle,
     client: &Client,
     code: &str,
     state: &str,
     session_data: Option<(String, String)>,
-) -> Result<(), PolluxError> {
+) -> Result<mpsc::UnboundedReceiver<OnboardProgress>, PolluxError> {
     let (pkce_verifier, csrf_token) = session_data.ok_or_else(|| OauthError::Flow {
         code: "OAUTH_SESSION_MISSING".to_string(),
         message: "Missing OAuth session cookies".to_string(),
@@ -132,18 +136,185 @@ pub async fn process_oauth_exchange(
         details: None,
     })?;
 
-    let refresh_token = token_response
+    require_refresh_token(&token_response)?;
+    Ok(handle
+        .submit_trusted_oauth_with_progress(token_response)
+        .await)
+}
+
+/// One line per onboarding step; the body ends once onboarding has finished either way.
+fn progress_response(progress: mpsc::UnboundedReceiver<OnboardProgress>) -> Response {
+    let lines =
+        UnboundedReceiverStream::new(progress).map(|step| Ok::<_, Infallible>(format!("{step}\n")));
+    (
+        StatusCode::ACCEPTED,
+        [
+            (CONTENT_TYPE, "text/plain; charset=utf-8"),
+            (CACHE_CONTROL, "no-cache"),
+            // Without this, browsers buffer text/plain to sniff it and show nothing until the end.
+            (X_CONTENT_TYPE_OPTIONS, "nosniff"),
+        ],
+        Body::from_stream(lines),
+    )
+        .into_response()
+}
+
+fn require_refresh_token(token_response: &GoogleTokenResponse) -> Result<(), OauthError> {
+    let has_refresh_token = token_response
         .refresh_token()
-        .map(|t| t.secret().to_string())
-        .unwrap_or_default();
-    if refresh_token.is_empty() {
+        .is_some_and(|t| !t.secret().is_empty());
+    if !has_refresh_token {
         return Err(OauthError::Flow {
             code: "MISSING_REFRESH_TOKEN".to_string(),
             message: "Missing refresh_token (check access_type=offline)".to_string(),
             details: None,
-        }
-        .into());
+        });
     }
-    handle.submit_trusted_oauth(token_response).await;
     Ok(())
 }
+
+#[derive(Debug, Serialize)]
+pub struct DeviceAuthorization {
+    pub user_code: String,
+    pub verification_url: String,
+    pub expires_in: u64,
+    pub interval: u64,
+}
+
+/// POST /geminicli/auth/device
+///
+/// Device-code grant for headless servers: returns the code the user enters at the verification
+/// URL, then polls Google in the background and stores the credential once it is approved.
+pub async fn google_device_oauth_entry(
+    State(state): State<PolluxState>,
+) -> Result<Json<DeviceAuthorization>, PolluxError> {
+    let urls = DeviceFlowUrls::default();
+    let details = GoogleOauthEndpoints::request_device_code(&urls, state.client.clone()).await?;
+    let body = DeviceAuthorization {
+        user_code: details.user_code().secret().to_string(),
+        verification_url: details.verification_uri().to_string(),
+        expires_in: details.expires_in().as_secs(),
+        interval: details.interval().as_secs(),
+    };
+
+    let handle = state.providers.geminicli.clone();
+    let client = state.client.clone();
+    tokio::spawn(async move {
+        let store = |token| async move { handle.submit_trusted_oauth(token).await };
+        if let Err(err) = complete_device_flow(&client, &urls, details, store).await {
+            error!("OAuth device flow failure: {:?}", err);
+        }
+    });
+
+    info!("Issued OAuth device code, waiting for user approval");
+    Ok(Json(body))
+}
+
+/// Wait for the user to approve the device code, then hand the tokens to `store`.
+pub(crate) async fn complete_device_flow<F, Fut>(
+    client: &Client,
+    urls: &DeviceFlowUrls,
+    details: StandardDeviceAuthorizationResponse,
+    store: F,
+) -> Result<(), PolluxError>
+where
+    F: FnOnce(GoogleTokenResponse) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let token_response = GoogleOauthEndpoints::poll_device_token(urls, &details, client.clone())
+        .await
+        .map_err(|e| OauthError::Flow {
+            code: "DEVICE_TOKEN_FAILED".to_string(),
+            message: format!("Device code exchange failed: {}", e),
+            details: None,
+        })?;
+
+    require_refresh_token(&token_response)?;
+    store(token_response).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, extract::State as AxumState, routing::post};
+    use serde_json::{Value, json};
+    use std::sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    };
+    use tokio::net::TcpListener;
+
+    /// Token endpoint that reports `authorization_pending` until the user has "approved".
+    async fn token_handler(AxumState(polls): AxumState<Arc<AtomicUsize>>) -> impl IntoResponse {
+        if polls.fetch_add(1, Ordering::SeqCst) < 2 {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": "authorization_pending"})),
+            );
+        }
+        (
+            StatusCode::OK,
+            Json(json!({
+                "access_token": "device-access",
+                "refresh_token": "device-refresh",
+                "token_type": "Bearer",
+                "expires_in": 3599
+            })),
+        )
+    }
+
+    async fn device_code_handler() -> Json<Value> {
+        Json(json!({
+            "device_code": "dev-code",
+            "user_code": "ABCD-EFGH",
+            "verification_url": "https://www.google.com/device",
+            "expires_in": 30,
+            "interval": 0
+        }))
+    }
+
+    #[tokio::test]
+    async fn device_flow_stores_credential_after_approval() {
+        let polls = Arc::new(AtomicUsize::new(0));
+        let app = Router::new()
+            .route("/device/code", post(device_code_handler))
+            .route("/token", post(token_handler))
+            .with_state(polls.clone());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let urls = DeviceFlowUrls {
+            device_auth_url: format!("http://{addr}/device/code"),
+            token_url: format!("http://{addr}/token"),
+        };
+        let client = Client::new();
+
+        let details = GoogleOauthEndpoints::request_device_code(&urls, client.clone())
+            .await
+            .expect("device code");
+        assert_eq!(details.user_code().secret(), "ABCD-EFGH");
+        assert_eq!(
+            details.verification_uri().as_str(),
+            "https://www.google.com/device"
+        );
+
+        let stored: Arc<Mutex<Vec<GoogleTokenResponse>>> = Arc::default();
+        let sink = stored.clone();
+        complete_device_flow(&client, &urls, details, |token| async move {
+            sink.lock().unwrap().push(token);
+        })
+        .await
+        .expect("device flow completes");
+
+        assert_eq!(polls.load(Ordering::SeqCst), 3);
+        let stored = stored.lock().unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].access_token().secret(), "device-access");
+        assert_eq!(
+            stored[0].refresh_token().map(|t| t.secret().as_str()),
+            Some("device-refresh")
+        );
+    }
+}
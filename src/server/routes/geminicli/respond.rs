@@ -1,17 +1,23 @@
 use crate::error::GeminiCliError;
+use crate::server::empty_text;
+use crate::server::gemini_sse;
 use crate::server::router::PolluxState;
+use crate::server::sse_buffer;
+use crate::server::sse_retry;
+use crate::server::sse_timeout;
+use crate::server::stream_limit::{self, StreamPermit};
+use crate::server::tool_call_ids::ToolCallIds;
 use axum::{
     Json,
     http::StatusCode,
     response::{
         IntoResponse,
-        sse::{Event, KeepAlive, Sse},
+        sse::{KeepAlive, Sse},
     },
 };
 use eventsource_stream::Eventsource;
 use futures::{Stream, TryStreamExt, future};
 use pollux_schema::{gemini::GeminiResponseBody, geminicli::GeminiCliResponseBody};
-use std::time::Duration;
 use tokio_stream::StreamExt;
 use tracing::{error, warn};
 
@@ -19,14 +25,29 @@ use tracing::{error, warn};
 pub async fn build_json_response(
     upstream_resp: reqwest::Response,
     state: &PolluxState,
+    model: &str,
 ) -> Result<(StatusCode, Json<GeminiResponseBody>), GeminiCliError> {
     let status = upstream_resp.status();
-    let response_body = transform_nostream(upstream_resp).await?;
-    let mut sniffer = state.providers.geminicli_thoughtsig.build_sniffer();
+    let mut response_body = transform_nostream(upstream_resp).await?;
+    let mut sniffer = state
+        .providers
+        .geminicli_thoughtsig
+        .build_sniffer_for_model(model);
     state
         .providers
         .geminicli_thoughtsig
         .sniff_response(&response_body, &mut sniffer);
+    // After sniffing, so signatures are cached under the calls as upstream sent them and the
+    // sniffer still sees every text delta.
+    if state.strip_empty_text {
+        empty_text::strip_empty_text(&mut response_body);
+    }
+    if state.tool_call_ids {
+        ToolCallIds::default().assign(&mut response_body);
+    }
+    if let Some(reason) = state.finish_reasons.rejected(&response_body) {
+        return Err(GeminiCliError::finish_reason_rejected(reason));
+    }
     Ok((status, Json(response_body)))
 }
 
@@ -34,35 +55,48 @@ pub async fn build_json_response(
 pub fn build_stream_response(
     upstream_resp: reqwest::Response,
     state: PolluxState,
+    permit: StreamPermit,
+    model: &str,
 ) -> impl IntoResponse {
-    let sniffer = state.providers.geminicli_thoughtsig.build_sniffer();
+    let sniffer = state
+        .providers
+        .geminicli_thoughtsig
+        .build_sniffer_for_model(model);
     let raw_stream = upstream_resp.bytes_stream().eventsource();
     let record_stream = transform_stream(raw_stream, state.clone(), sniffer);
-    let timed_stream = record_stream
-        .timeout(Duration::from_secs(60))
-        .map(move |item| match item {
+    let timed_stream =
+        sse_timeout::with_timeouts(record_stream, state.sse_timeouts).map(move |item| match item {
             Ok(Ok(event)) => Ok(event),
             Ok(Err(e)) => Err(GeminiCliError::StreamProtocolError(e.to_string())),
-            Err(_) => {
-                error!("Upstream SSE stream timed out (idle > 60s)");
-                Err(GeminiCliError::StreamProtocolError(
-                    "Stream idle timeout".to_string(),
-                ))
+            Err(timeout) => {
+                error!("Upstream SSE stream timed out ({timeout})");
+                Err(GeminiCliError::StreamProtocolError(format!(
+                    "Stream timeout: {timeout}"
+                )))
             }
         });
 
-    Sse::new(timed_stream).keep_alive(KeepAlive::default())
+    let events = gemini_sse::gemini_events(timed_stream, state.sse_complete_event);
+
+    let buffered = sse_buffer::bounded(events, state.sse_buffer, || {
+        GeminiCliError::StreamProtocolError("Client too slow; SSE buffer overflow".to_string())
+    });
+
+    let hinted = sse_retry::with_retry_hint(buffered, state.sse_retry);
+
+    Sse::new(stream_limit::hold(hinted, permit)).keep_alive(KeepAlive::default())
 }
 
-/// Convert upstream SSE events into SSE `Event`s and record thought signatures.
+/// Parse upstream SSE events into Gemini responses and record thought signatures.
 fn transform_stream<I, E>(
     s: I,
     state: PolluxState,
     mut sniffer: pollux_thoughtsig_core::SignatureSniffer,
-) -> impl Stream<Item = Result<Event, E>>
+) -> impl Stream<Item = Result<GeminiResponseBody, E>>
 where
     I: Stream<Item = Result<eventsource_stream::Event, E>>,
 {
+    let mut tool_call_ids = state.tool_call_ids.then(ToolCallIds::default);
     s.try_filter_map(move |upstream_event| {
         let state = state.clone();
 
@@ -73,7 +107,7 @@ where
             {
                 Ok(None)
             } else {
-                let Some(gemini_resp) = parse_sse_payload(&upstream_event.data) else {
+                let Some(mut gemini_resp) = parse_sse_payload(&upstream_event.data) else {
                     return future::ready(Ok(None));
                 };
 
@@ -81,14 +115,17 @@ where
                     .providers
                     .geminicli_thoughtsig
                     .sniff_response(&gemini_resp, &mut sniffer);
-
-                match Event::default().json_data(gemini_resp) {
-                    Ok(ev) => Ok(Some(ev)),
-                    Err(e) => {
-                        warn!("Failed to serialize GeminiResponse: {}", e);
-                        Ok(None)
-                    }
+                if state.strip_empty_text
+                    && empty_text::strip_empty_text(&mut gemini_resp) > 0
+                    && empty_text::is_blank(&gemini_resp)
+                {
+                    return future::ready(Ok(None));
                 }
+                if let Some(ids) = tool_call_ids.as_mut() {
+                    ids.assign(&mut gemini_resp);
+                }
+
+                Ok(Some(gemini_resp))
             }
         };
 
@@ -1,3 +1,4 @@
+pub mod admin;
 pub mod antigravity;
 pub mod codex;
 pub mod geminicli;
@@ -0,0 +1,151 @@
+use crate::config::SseOverflowPolicy;
+use futures::{Stream, StreamExt, stream};
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// Bounded SSE buffer settings shared by all streaming routes.
+#[derive(Debug, Clone, Copy)]
+pub struct SseBufferConfig {
+    pub capacity: usize,
+    pub overflow: SseOverflowPolicy,
+}
+
+impl Default for SseBufferConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 64,
+            overflow: SseOverflowPolicy::default(),
+        }
+    }
+}
+
+/// Decouple upstream reads from the client through a bounded channel.
+///
+/// A background task drains `upstream` into a channel of `cfg.capacity` items, so at most that
+/// many events sit in memory per client. When the channel is full, the task either waits
+/// (backpressure on the upstream body) or gives up on upstream and the client stream ends with
+/// `on_overflow()` right away. Dropping the returned stream stops the task and the upstream read.
+pub(crate) fn bounded<S, T, E, F>(
+    upstream: S,
+    cfg: SseBufferConfig,
+    on_overflow: F,
+) -> impl Stream<Item = Result<T, E>> + Send + 'static
+where
+    S: Stream<Item = Result<T, E>> + Send + 'static,
+    T: Send + 'static,
+    E: Send + 'static,
+    F: Fn() -> E + Clone + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(cfg.capacity.max(1));
+    let overflowed = Arc::new(AtomicBool::new(false));
+
+    tokio::spawn({
+        let overflowed = overflowed.clone();
+        async move {
+            let mut upstream = std::pin::pin!(upstream);
+            while let Some(item) = upstream.next().await {
+                match cfg.overflow {
+                    SseOverflowPolicy::Backpressure => {
+                        if tx.send(item).await.is_err() {
+                            return;
+                        }
+                    }
+                    SseOverflowPolicy::Error => match tx.try_send(item) {
+                        Ok(()) => {}
+                        Err(mpsc::error::TrySendError::Full(_)) => {
+                            warn!(
+                                capacity = cfg.capacity,
+                                "SSE buffer overflow: client too slow, aborting upstream stream"
+                            );
+                            overflowed.store(true, Ordering::Release);
+                            return;
+                        }
+                        Err(mpsc::error::TrySendError::Closed(_)) => return,
+                    },
+                }
+            }
+        }
+    });
+
+    stream::unfold(Some((rx, overflowed)), move |state| {
+        let on_overflow = on_overflow.clone();
+        async move {
+            let (mut rx, overflowed) = state?;
+            if overflowed.load(Ordering::Acquire) {
+                return Some((Err(on_overflow()), None));
+            }
+            let item = rx.recv().await?;
+            Some((item, Some((rx, overflowed))))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Duration;
+
+    fn counted_upstream(
+        total: usize,
+        pulled: Arc<AtomicUsize>,
+    ) -> impl Stream<Item = Result<usize, &'static str>> + Send + 'static {
+        stream::iter(0..total).map(move |i| {
+            pulled.fetch_add(1, Ordering::SeqCst);
+            Ok(i)
+        })
+    }
+
+    #[tokio::test]
+    async fn slow_consumer_backpressures_upstream() {
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let cfg = SseBufferConfig {
+            capacity: 4,
+            overflow: SseOverflowPolicy::Backpressure,
+        };
+        let out = bounded(counted_upstream(100, pulled.clone()), cfg, || "overflow");
+        let mut out = std::pin::pin!(out);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        // Channel holds `capacity` items, plus one pulled item waiting for a free slot.
+        assert!(pulled.load(Ordering::SeqCst) <= cfg.capacity + 1);
+
+        let mut received = Vec::new();
+        while let Some(item) = out.next().await {
+            received.push(item.expect("backpressure mode must not error"));
+        }
+        assert_eq!(received, (0..100).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn slow_consumer_gets_error_when_configured() {
+        let pulled = Arc::new(AtomicUsize::new(0));
+        let cfg = SseBufferConfig {
+            capacity: 4,
+            overflow: SseOverflowPolicy::Error,
+        };
+        let out = bounded(counted_upstream(100, pulled.clone()), cfg, || "overflow");
+        let mut out = std::pin::pin!(out);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(pulled.load(Ordering::SeqCst), cfg.capacity + 1);
+
+        assert_eq!(out.next().await, Some(Err("overflow")));
+        assert_eq!(out.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn fast_consumer_sees_every_item_in_error_mode() {
+        let cfg = SseBufferConfig {
+            capacity: 4,
+            overflow: SseOverflowPolicy::Error,
+        };
+        let upstream = stream::iter(0..3).map(Ok::<_, &'static str>);
+        let received: Vec<_> = bounded(upstream, cfg, || "overflow").collect().await;
+        assert_eq!(received, vec![Ok(0), Ok(1), Ok(2)]);
+    }
+}
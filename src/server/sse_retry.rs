@@ -0,0 +1,66 @@
+use axum::response::sse::Event;
+use futures::{Stream, StreamExt, stream};
+use std::time::Duration;
+
+/// Tell clients how long to wait before reconnecting (SSE `retry:` field).
+///
+/// The hint is sent as the first event and again right before every stream error, since an
+/// error ends the stream and a client reconnects after it. `None` passes `events` through.
+pub(crate) fn with_retry_hint<S, E>(
+    events: S,
+    retry: Option<Duration>,
+) -> impl Stream<Item = Result<Event, E>> + Send + 'static
+where
+    S: Stream<Item = Result<Event, E>> + Send + 'static,
+    E: Send + 'static,
+{
+    let Some(retry) = retry else {
+        return events.left_stream();
+    };
+    let hint = move || Event::default().retry(retry);
+
+    let body = events.flat_map(move |item| match item {
+        Ok(event) => stream::iter(vec![Ok(event)]),
+        Err(e) => stream::iter(vec![Ok(hint()), Err(e)]),
+    });
+    stream::once(async move { Ok(hint()) })
+        .chain(body)
+        .right_stream()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::{IntoResponse, sse::Sse};
+
+    async fn render(
+        events: impl Stream<Item = Result<Event, &'static str>> + Send + 'static,
+    ) -> String {
+        // Stop at the first error, as the client would see it.
+        let events = events.take_while(|item| std::future::ready(item.is_ok()));
+        let body = Sse::new(events).into_response().into_body();
+        let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn hint_leads_the_stream_and_precedes_errors() {
+        let events = stream::iter([Ok(Event::default().data("a")), Err("boom")]);
+        let items: Vec<_> = with_retry_hint(events, Some(Duration::from_millis(1500)))
+            .collect()
+            .await;
+        assert_eq!(items.len(), 4);
+        assert!(matches!(items.last(), Some(Err("boom"))));
+
+        let events = stream::iter([Ok(Event::default().data("a")), Err("boom")]);
+        let body = render(with_retry_hint(events, Some(Duration::from_millis(1500)))).await;
+        assert_eq!(body, "retry: 1500\n\ndata: a\n\nretry: 1500\n\n");
+    }
+
+    #[tokio::test]
+    async fn no_hint_leaves_the_stream_untouched() {
+        let events = stream::iter([Ok(Event::default().data("a"))]);
+        let body = render(with_retry_hint(events, None)).await;
+        assert_eq!(body, "data: a\n\n");
+    }
+}
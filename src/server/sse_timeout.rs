@@ -0,0 +1,117 @@
+use futures::{Stream, StreamExt, stream};
+use std::time::Duration;
+
+/// How long an upstream SSE stream may go quiet before it is abandoned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SseTimeouts {
+    /// Wait for the first event, i.e. the upstream's time to first token.
+    pub first_event: Duration,
+    /// Wait between later events, i.e. a mid-stream stall.
+    pub idle: Duration,
+}
+
+impl Default for SseTimeouts {
+    fn default() -> Self {
+        Self {
+            first_event: Duration::from_secs(60),
+            idle: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Which of the [`SseTimeouts`] ran out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StreamTimeout {
+    FirstEvent(Duration),
+    Idle(Duration),
+}
+
+impl std::fmt::Display for StreamTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FirstEvent(limit) => write!(f, "no first event within {}s", limit.as_secs_f64()),
+            Self::Idle(limit) => write!(f, "idle for more than {}s", limit.as_secs_f64()),
+        }
+    }
+}
+
+/// Pass `upstream` through, ending it with a [`StreamTimeout`] if the first item takes longer
+/// than `timeouts.first_event` or any later one longer than `timeouts.idle`.
+pub(crate) fn with_timeouts<S>(
+    upstream: S,
+    timeouts: SseTimeouts,
+) -> impl Stream<Item = Result<S::Item, StreamTimeout>>
+where
+    S: Stream,
+{
+    let upstream = Box::pin(upstream);
+    stream::unfold(Some((upstream, true)), move |state| async move {
+        let (mut upstream, first) = state?;
+        let (limit, timeout) = if first {
+            (
+                timeouts.first_event,
+                StreamTimeout::FirstEvent(timeouts.first_event),
+            )
+        } else {
+            (timeouts.idle, StreamTimeout::Idle(timeouts.idle))
+        };
+        match tokio::time::timeout(limit, upstream.next()).await {
+            Ok(Some(item)) => Some((Ok(item), Some((upstream, false)))),
+            Ok(None) => None,
+            Err(_) => Some((Err(timeout), None)),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Yields `0, 1, ...` with each item arriving after the matching delay.
+    fn delayed(delays: &'static [u64]) -> impl Stream<Item = usize> {
+        stream::iter(delays.iter().enumerate()).then(|(index, ms)| async move {
+            tokio::time::sleep(Duration::from_millis(*ms)).await;
+            index
+        })
+    }
+
+    fn timeouts(first_event_ms: u64, idle_ms: u64) -> SseTimeouts {
+        SseTimeouts {
+            first_event: Duration::from_millis(first_event_ms),
+            idle: Duration::from_millis(idle_ms),
+        }
+    }
+
+    #[tokio::test]
+    async fn slow_first_event_trips_only_the_first_event_timeout() {
+        let items: Vec<_> = with_timeouts(delayed(&[150, 0]), timeouts(50, 1000))
+            .collect()
+            .await;
+        assert_eq!(
+            items,
+            vec![Err(StreamTimeout::FirstEvent(Duration::from_millis(50)))]
+        );
+
+        // The same slow start passes under a longer first-event window and a short idle one.
+        let items: Vec<_> = with_timeouts(delayed(&[150, 0]), timeouts(1000, 50))
+            .collect()
+            .await;
+        assert_eq!(items, vec![Ok(0), Ok(1)]);
+    }
+
+    #[tokio::test]
+    async fn mid_stream_stall_trips_only_the_idle_timeout() {
+        let items: Vec<_> = with_timeouts(delayed(&[0, 150, 0]), timeouts(1000, 50))
+            .collect()
+            .await;
+        assert_eq!(
+            items,
+            vec![Ok(0), Err(StreamTimeout::Idle(Duration::from_millis(50)))]
+        );
+
+        let items: Vec<_> = with_timeouts(delayed(&[0, 150]), timeouts(50, 1000))
+            .collect()
+            .await;
+        assert_eq!(items, vec![Ok(0), Ok(1)]);
+    }
+}
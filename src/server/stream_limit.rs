@@ -0,0 +1,80 @@
+use futures::{Stream, StreamExt};
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Server-wide cap on concurrently open SSE streams.
+///
+/// Streams are long-lived and each holds an upstream connection plus its SSE buffer, so a flood
+/// of them can exhaust memory. Unary requests are not counted.
+#[derive(Debug, Clone, Default)]
+pub struct StreamLimiter {
+    slots: Option<Arc<Semaphore>>,
+}
+
+/// A reserved stream slot, released when dropped.
+#[derive(Debug)]
+pub struct StreamPermit {
+    _slot: Option<OwnedSemaphorePermit>,
+}
+
+impl StreamLimiter {
+    /// `None` leaves streams unlimited.
+    pub fn new(max_streams: Option<usize>) -> Self {
+        Self {
+            slots: max_streams.map(|max| Arc::new(Semaphore::new(max))),
+        }
+    }
+
+    /// Reserve a slot for a new stream, or `None` when the limit is reached.
+    pub fn try_acquire(&self) -> Option<StreamPermit> {
+        let slot = match &self.slots {
+            Some(slots) => Some(slots.clone().try_acquire_owned().ok()?),
+            None => None,
+        };
+        Some(StreamPermit { _slot: slot })
+    }
+}
+
+/// Keep `permit` alive for as long as `stream` is, i.e. until the client disconnects or the
+/// stream ends.
+pub(crate) fn hold<S: Stream>(stream: S, permit: StreamPermit) -> impl Stream<Item = S::Item> {
+    stream.map(move |item| {
+        let _ = &permit;
+        item
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+
+    #[test]
+    fn rejects_once_every_slot_is_taken() {
+        let limiter = StreamLimiter::new(Some(2));
+
+        let first = limiter.try_acquire().expect("slot 1");
+        let _second = limiter.try_acquire().expect("slot 2");
+        assert!(limiter.try_acquire().is_none());
+
+        drop(first);
+        assert!(limiter.try_acquire().is_some());
+    }
+
+    #[test]
+    fn unlimited_by_default() {
+        let limiter = StreamLimiter::default();
+        let permits: Vec<_> = (0..1000).filter_map(|_| limiter.try_acquire()).collect();
+        assert_eq!(permits.len(), 1000);
+    }
+
+    #[tokio::test]
+    async fn held_stream_releases_its_slot_when_dropped() {
+        let limiter = StreamLimiter::new(Some(1));
+        let held = hold(stream::iter([1, 2]), limiter.try_acquire().expect("slot"));
+        assert!(limiter.try_acquire().is_none());
+
+        assert_eq!(held.collect::<Vec<_>>().await, vec![1, 2]);
+        assert!(limiter.try_acquire().is_some());
+    }
+}
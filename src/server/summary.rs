@@ -0,0 +1,100 @@
+use crate::config::Config;
+use crate::db::DbActorHandle;
+use crate::error::PolluxError;
+use crate::providers::Providers;
+use crate::server::cookies::OauthCookieConfig;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use tracing::info;
+
+/// Effective settings of one provider at startup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProviderSummary {
+    /// A provider with an empty model list never receives traffic.
+    pub enabled: bool,
+    pub models: Vec<String>,
+    /// Active credentials in the database.
+    pub credentials: usize,
+}
+
+/// What this process is serving with, logged once at boot to make deployments easy to check.
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupSummary {
+    pub bind: SocketAddr,
+    /// How API routes authenticate callers (`basic.pollux_key` via header or `?key=`).
+    pub auth_mode: &'static str,
+    pub oauth_cookie_secure: bool,
+    pub providers: BTreeMap<&'static str, ProviderSummary>,
+    /// Thought-signature cache capacity (entries) per provider.
+    pub thoughtsig_cache_capacity: BTreeMap<&'static str, u64>,
+    pub sse_buffer_capacity: usize,
+}
+
+impl StartupSummary {
+    pub async fn collect(
+        cfg: &Config,
+        providers: &Providers,
+        db: &DbActorHandle,
+    ) -> Result<Self, PolluxError> {
+        let provider = |models: &[String], credentials: usize| ProviderSummary {
+            enabled: !models.is_empty(),
+            models: models.to_vec(),
+            credentials,
+        };
+
+        let providers_summary = BTreeMap::from([
+            (
+                "geminicli",
+                provider(
+                    &providers.geminicli_cfg.model_list,
+                    db.list_active_geminicli().await?.len(),
+                ),
+            ),
+            (
+                "codex",
+                provider(
+                    &providers.codex_cfg.model_list,
+                    db.list_active_codex().await?.len(),
+                ),
+            ),
+            (
+                "antigravity",
+                provider(
+                    &providers.antigravity_cfg.model_list,
+                    db.list_active_antigravity().await?.len(),
+                ),
+            ),
+        ]);
+
+        Ok(Self {
+            bind: SocketAddr::from((cfg.basic.listen_addr, cfg.basic.listen_port)),
+            auth_mode: "pollux_key",
+            oauth_cookie_secure: OauthCookieConfig::from_basic(&cfg.basic).secure,
+            providers: providers_summary,
+            thoughtsig_cache_capacity: BTreeMap::from([
+                ("geminicli", providers.geminicli_thoughtsig.max_capacity()),
+                (
+                    "antigravity",
+                    providers.antigravity_thoughtsig.max_capacity(),
+                ),
+            ]),
+            sse_buffer_capacity: cfg.basic.sse_buffer_capacity,
+        })
+    }
+
+    /// Emit the summary as a single structured log line.
+    pub fn log(&self) {
+        let providers = serde_json::to_string(&self.providers).unwrap_or_default();
+        let caches = serde_json::to_string(&self.thoughtsig_cache_capacity).unwrap_or_default();
+        info!(
+            bind = %self.bind,
+            auth_mode = self.auth_mode,
+            oauth_cookie_secure = self.oauth_cookie_secure,
+            providers = %providers,
+            thoughtsig_cache_capacity = %caches,
+            sse_buffer_capacity = self.sse_buffer_capacity,
+            "Startup summary"
+        );
+    }
+}
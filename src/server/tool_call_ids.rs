@@ -0,0 +1,206 @@
+use pollux_schema::gemini::{GeminiGenerateContentRequest, GeminiResponseBody, Part};
+use pollux_thoughtsig_core::CacheKeyGenerator;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+const ID_PREFIX: &str = "pollux_call_";
+
+/// Gives id-less function calls in responses a deterministic `id`, for clients that correlate
+/// tool calls and results by id (see `basic.tool_call_ids`).
+///
+/// The id is derived from the call itself, so [`strip_assigned`] recognizes it on the next
+/// request without any stored state. One instance covers one response or stream, so identical
+/// calls within it get distinct ids.
+#[derive(Debug, Default)]
+pub(crate) struct ToolCallIds {
+    seen: HashMap<String, usize>,
+}
+
+impl ToolCallIds {
+    pub(crate) fn assign(&mut self, response: &mut GeminiResponseBody) {
+        let parts = response
+            .candidates
+            .iter_mut()
+            .filter_map(|candidate| candidate.content.as_mut())
+            .flat_map(|content| content.parts.iter_mut());
+        for part in parts {
+            let Some(Value::Object(call)) = part.function_call.as_mut() else {
+                continue;
+            };
+            if call.contains_key("id") {
+                continue;
+            }
+            let Some(base) = base_id(call) else {
+                continue;
+            };
+            let seen = self.seen.entry(base.clone()).or_default();
+            let id = match *seen {
+                0 => base,
+                n => format!("{base}_{n}"),
+            };
+            *seen += 1;
+            call.insert("id".to_string(), Value::String(id));
+        }
+    }
+}
+
+/// Remove the ids [`ToolCallIds`] assigned from a request's history, so upstream sees its
+/// function calls (and their thought-signature cache keys) exactly as it produced them.
+/// Function responses carrying one of those ids lose it too. Returns how many ids went.
+pub(crate) fn strip_assigned(request: &mut GeminiGenerateContentRequest) -> usize {
+    let mut assigned = HashSet::new();
+    for part in parts_mut(request) {
+        let Some(Value::Object(call)) = part.function_call.as_mut() else {
+            continue;
+        };
+        let Some(Value::String(id)) = call.get("id") else {
+            continue;
+        };
+        let id = id.clone();
+        let Some(base) = base_id(call) else {
+            continue;
+        };
+        let ours = id == base
+            || id
+                .strip_prefix(base.as_str())
+                .and_then(|rest| rest.strip_prefix('_'))
+                .is_some_and(|n| n.parse::<usize>().is_ok());
+        if ours {
+            call.remove("id");
+            assigned.insert(id);
+        }
+    }
+
+    let mut stripped = assigned.len();
+    for part in parts_mut(request) {
+        let Some(Value::Object(response)) = part.function_response.as_mut() else {
+            continue;
+        };
+        if let Some(Value::String(id)) = response.get("id")
+            && assigned.contains(id)
+        {
+            response.remove("id");
+            stripped += 1;
+        }
+    }
+    stripped
+}
+
+fn parts_mut(request: &mut GeminiGenerateContentRequest) -> impl Iterator<Item = &mut Part> {
+    request
+        .contents
+        .iter_mut()
+        .flat_map(|content| content.parts.iter_mut())
+}
+
+/// Id for `call` ignoring any `id` it already has.
+fn base_id(call: &Map<String, Value>) -> Option<String> {
+    let mut call = call.clone();
+    call.remove("id");
+    // The default seed keeps ids independent of `basic.thoughtsig_hash_seed`.
+    let key = CacheKeyGenerator::default().json_key(&call)?;
+    Some(format!("{ID_PREFIX}{key:016x}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn call(city: &str) -> Value {
+        json!({"name": "get_weather", "args": {"city": city}})
+    }
+
+    #[test]
+    fn ids_are_assigned_on_output_and_matched_on_the_next_request() {
+        let mut response: GeminiResponseBody = serde_json::from_value(json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [
+                        {"functionCall": call("Berlin")},
+                        {"functionCall": call("Berlin")},
+                        {"functionCall": {"name": "lookup", "args": {}, "id": "upstream-1"}}
+                    ]
+                }
+            }]
+        }))
+        .expect("response json must parse");
+        ToolCallIds::default().assign(&mut response);
+
+        let parts = &response.candidates[0]
+            .content
+            .as_ref()
+            .expect("content")
+            .parts;
+        let ids: Vec<_> = parts
+            .iter()
+            .map(|part| part.function_call.as_ref().expect("call")["id"].clone())
+            .collect();
+        let first = ids[0].as_str().expect("id is a string");
+        assert!(first.starts_with(ID_PREFIX));
+        assert_eq!(ids[1], json!(format!("{first}_1")));
+        assert_eq!(ids[2], json!("upstream-1"));
+
+        // The same call gets the same id in a later response.
+        let mut again: GeminiResponseBody = serde_json::from_value(json!({
+            "candidates": [{"content": {"role": "model", "parts": [{"functionCall": call("Berlin")}]}}]
+        }))
+        .expect("response json must parse");
+        ToolCallIds::default().assign(&mut again);
+        assert_eq!(
+            again.candidates[0].content.as_ref().expect("content").parts[0]
+                .function_call
+                .as_ref()
+                .expect("call")["id"],
+            ids[0]
+        );
+
+        // The client replays the calls and answers them by id.
+        let model_parts: Vec<_> = parts
+            .iter()
+            .map(|part| json!({"functionCall": part.function_call}))
+            .collect();
+        let mut request: GeminiGenerateContentRequest = serde_json::from_value(json!({
+            "contents": [
+                {"role": "model", "parts": model_parts},
+                {"role": "user", "parts": [
+                    {"functionResponse": {"name": "get_weather", "id": ids[0], "response": {}}},
+                    {"functionResponse": {"name": "get_weather", "id": ids[1], "response": {}}},
+                    {"functionResponse": {"name": "lookup", "id": "upstream-1", "response": {}}}
+                ]}
+            ]
+        }))
+        .expect("request json must parse");
+
+        assert_eq!(strip_assigned(&mut request), 4);
+        let calls = &request.contents[0].parts;
+        assert_eq!(calls[0].function_call, Some(call("Berlin")));
+        assert_eq!(calls[1].function_call, Some(call("Berlin")));
+        assert_eq!(
+            calls[2].function_call.as_ref().expect("call")["id"],
+            "upstream-1"
+        );
+        let responses = &request.contents[1].parts;
+        assert!(
+            responses[0]
+                .function_response
+                .as_ref()
+                .expect("response")
+                .get("id")
+                .is_none()
+        );
+        assert!(
+            responses[1]
+                .function_response
+                .as_ref()
+                .expect("response")
+                .get("id")
+                .is_none()
+        );
+        assert_eq!(
+            responses[2].function_response.as_ref().expect("response")["id"],
+            "upstream-1"
+        );
+    }
+}
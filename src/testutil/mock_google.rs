@@ -0,0 +1,232 @@
+use super::{Capture, CapturedRequest, spawn_test_server};
+use axum::{
+    Json, Router,
+    body::Bytes,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode, Uri, header},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+/// Code the mock authorize endpoint hands back to the redirect URI.
+pub const MOCK_AUTH_CODE: &str = "mock-auth-code";
+
+/// Canned `(status, JSON body)` answers for each [`MockGoogle`] endpoint.
+#[derive(Debug, Clone)]
+pub struct MockGoogleResponses {
+    /// `POST /token` with `grant_type=authorization_code`.
+    pub authorization_code: (StatusCode, Value),
+    /// `POST /token` with `grant_type=refresh_token`.
+    pub refresh_token: (StatusCode, Value),
+    /// `POST /v1internal:loadCodeAssist`.
+    pub load_code_assist: (StatusCode, Value),
+    /// `POST /v1internal:onboardUser`.
+    pub onboard_user: (StatusCode, Value),
+}
+
+impl Default for MockGoogleResponses {
+    fn default() -> Self {
+        Self {
+            authorization_code: (
+                StatusCode::OK,
+                json!({
+                    "access_token": "access-from-code",
+                    "token_type": "bearer",
+                    "expires_in": 3600,
+                    "refresh_token": "refresh-from-code"
+                }),
+            ),
+            refresh_token: (
+                StatusCode::OK,
+                json!({
+                    "access_token": "access-from-refresh",
+                    "token_type": "bearer",
+                    "expires_in": 3600
+                }),
+            ),
+            load_code_assist: (
+                StatusCode::OK,
+                json!({
+                    "cloudaicompanionProject": "project-1",
+                    "allowedTiers": [{ "id": "FREE", "isDefault": true }]
+                }),
+            ),
+            onboard_user: (
+                StatusCode::OK,
+                json!({
+                    "done": true,
+                    "response": { "cloudaicompanionProject": { "id": "project-1" } }
+                }),
+            ),
+        }
+    }
+}
+
+/// Fake Google OAuth and Code Assist upstream on a local port.
+///
+/// Serves `GET /authorize` (redirects to the `redirect_uri` with [`MOCK_AUTH_CODE`] and the
+/// given `state`), `POST /token`, `POST /v1internal:loadCodeAssist` and
+/// `POST /v1internal:onboardUser`. Every request is recorded in `requests`.
+#[derive(Clone)]
+pub struct MockGoogle {
+    pub base: Url,
+    pub requests: Capture<CapturedRequest>,
+    responses: Arc<Mutex<MockGoogleResponses>>,
+}
+
+#[derive(Clone)]
+struct MockState {
+    requests: Capture<CapturedRequest>,
+    responses: Arc<Mutex<MockGoogleResponses>>,
+}
+
+impl MockGoogle {
+    pub async fn spawn() -> Self {
+        Self::spawn_with(MockGoogleResponses::default()).await
+    }
+
+    pub async fn spawn_with(responses: MockGoogleResponses) -> Self {
+        let state = MockState {
+            requests: Capture::default(),
+            responses: Arc::new(Mutex::new(responses)),
+        };
+        let app = Router::new()
+            .route("/authorize", get(authorize))
+            .route("/token", post(token))
+            .route("/v1internal:loadCodeAssist", post(load_code_assist))
+            .route("/v1internal:onboardUser", post(onboard_user))
+            .with_state(state.clone());
+        let base = spawn_test_server(app).await;
+
+        Self {
+            base,
+            requests: state.requests,
+            responses: state.responses,
+        }
+    }
+
+    pub fn authorize_url(&self) -> Url {
+        self.base.join("/authorize").expect("valid authorize url")
+    }
+
+    pub fn token_url(&self) -> Url {
+        self.base.join("/token").expect("valid token url")
+    }
+
+    /// Base URL for Code Assist calls (`api_url` in provider config).
+    pub fn api_url(&self) -> Url {
+        self.base.clone()
+    }
+
+    /// Change the canned answers for subsequent requests.
+    pub fn set_responses(&self, update: impl FnOnce(&mut MockGoogleResponses)) {
+        update(&mut self.responses.lock().unwrap());
+    }
+
+    /// Recorded requests whose path is `path`.
+    pub fn requests_to(&self, path: &str) -> Vec<CapturedRequest> {
+        self.requests
+            .snapshot()
+            .into_iter()
+            .filter(|request| request.path == path)
+            .collect()
+    }
+
+    /// Form fields of every recorded `/token` request.
+    pub fn token_forms(&self) -> Vec<HashMap<String, String>> {
+        self.requests_to("/token")
+            .iter()
+            .map(|request| {
+                url::form_urlencoded::parse(&request.body)
+                    .into_owned()
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl MockState {
+    fn record(&self, uri: &Uri, headers: HeaderMap, body: &[u8]) {
+        self.requests.push(CapturedRequest {
+            path: uri.path().to_string(),
+            headers,
+            body: body.to_vec(),
+        });
+    }
+
+    fn reply(&self, pick: impl FnOnce(&MockGoogleResponses) -> &(StatusCode, Value)) -> Response {
+        let (status, body) = pick(&self.responses.lock().unwrap()).clone();
+        (status, Json(body)).into_response()
+    }
+}
+
+async fn authorize(
+    State(state): State<MockState>,
+    uri: Uri,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+) -> Response {
+    state.record(&uri, headers, &[]);
+    let Some(mut redirect) = params
+        .get("redirect_uri")
+        .and_then(|uri| Url::parse(uri).ok())
+    else {
+        return (StatusCode::BAD_REQUEST, "missing redirect_uri").into_response();
+    };
+    redirect
+        .query_pairs_mut()
+        .append_pair("code", MOCK_AUTH_CODE)
+        .append_pair("state", params.get("state").map_or("", String::as_str));
+    (
+        StatusCode::FOUND,
+        [(header::LOCATION, redirect.to_string())],
+    )
+        .into_response()
+}
+
+async fn token(
+    State(state): State<MockState>,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    state.record(&uri, headers, &body);
+    let form: HashMap<String, String> = url::form_urlencoded::parse(&body).into_owned().collect();
+
+    match form.get("grant_type").map(String::as_str) {
+        Some("authorization_code") => state.reply(|r| &r.authorization_code),
+        Some("refresh_token") => state.reply(|r| &r.refresh_token),
+        grant_type => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "unsupported_grant_type",
+                "grant_type": grant_type,
+            })),
+        )
+            .into_response(),
+    }
+}
+
+async fn load_code_assist(
+    State(state): State<MockState>,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    state.record(&uri, headers, &body);
+    state.reply(|r| &r.load_code_assist)
+}
+
+async fn onboard_user(
+    State(state): State<MockState>,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    state.record(&uri, headers, &body);
+    state.reply(|r| &r.onboard_user)
+}
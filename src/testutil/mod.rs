@@ -0,0 +1,172 @@
+//! Helpers shared by the integration tests in `tests/`.
+//!
+//! Compiled only with the `testutil` feature, which the crate's dev-dependency on itself
+//! enables for test builds. Everything here is safe to use from concurrent tests, but note
+//! that `db::spawn` registers a process-wide actor: keep one test per file that spawns it.
+
+use crate::config::Config;
+use crate::db::DbActorHandle;
+use crate::providers::Providers;
+use crate::server::router::{PolluxState, pollux_router};
+use axum::Router;
+use axum::http::{HeaderMap, header};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::net::TcpListener;
+use url::Url;
+
+mod mock_google;
+
+pub use mock_google::{MOCK_AUTH_CODE, MockGoogle, MockGoogleResponses};
+
+/// Temp-dir sqlite path unique to this process and call.
+pub fn unique_sqlite_path(prefix: &str) -> PathBuf {
+    static SEQ: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+
+    std::env::temp_dir().join(format!(
+        "pollux-{prefix}-{}-{nanos}-{}.sqlite",
+        std::process::id(),
+        SEQ.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Sqlite file for one test, removed on drop.
+pub struct TestDatabase {
+    pub path: PathBuf,
+    pub handle: DbActorHandle,
+}
+
+impl TestDatabase {
+    /// Spawn the DB actor on a fresh file named after `prefix`.
+    pub async fn spawn(prefix: &str) -> Self {
+        let path = unique_sqlite_path(prefix);
+        let handle = crate::db::spawn(&format!("sqlite:{}", path.display())).await;
+        Self { path, handle }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TestDatabase {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// `Config::default()` with `basic.pollux_key` set to `key`.
+pub fn test_config(key: &str) -> Config {
+    let mut cfg = Config::default();
+    cfg.basic.pollux_key = key.to_string();
+    cfg
+}
+
+/// Full pollux router over `providers`, authenticated with `cfg.basic.pollux_key`.
+pub fn test_app(providers: Providers, cfg: &Config) -> Router {
+    let state = PolluxState::new(
+        providers,
+        Arc::from(cfg.basic.pollux_key.as_str()),
+        cfg.basic.insecure_cookie,
+    );
+    pollux_router(state)
+}
+
+/// Serve `app` on an ephemeral localhost port and return its base URL.
+pub async fn spawn_test_server(app: Router) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind listener");
+    let addr = listener.local_addr().expect("local addr");
+    let base = Url::parse(&format!("http://{addr}")).expect("valid base url");
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("server run");
+    });
+
+    base
+}
+
+/// Items recorded by mock upstream handlers, shared between the handler state and the test.
+#[derive(Debug)]
+pub struct Capture<T> {
+    items: Arc<Mutex<Vec<T>>>,
+}
+
+impl<T> Clone for Capture<T> {
+    fn clone(&self) -> Self {
+        Self {
+            items: self.items.clone(),
+        }
+    }
+}
+
+impl<T> Default for Capture<T> {
+    fn default() -> Self {
+        Self {
+            items: Arc::default(),
+        }
+    }
+}
+
+impl<T> Capture<T> {
+    pub fn push(&self, item: T) {
+        self.lock().push(item);
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Remove and return everything recorded so far.
+    pub fn take(&self) -> Vec<T> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<T>> {
+        // A panicking handler must not hide what was captured before it.
+        self.items
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<T: Clone> Capture<T> {
+    pub fn snapshot(&self) -> Vec<T> {
+        self.lock().clone()
+    }
+}
+
+/// One request seen by a mock upstream.
+#[derive(Debug, Clone)]
+pub struct CapturedRequest {
+    pub path: String,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+/// `Cookie` header value replaying every `Set-Cookie` in `headers`.
+pub fn cookie_header_from_set_cookie_headers(headers: &HeaderMap) -> String {
+    headers
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| {
+            let value = value
+                .to_str()
+                .expect("set-cookie header was not valid utf-8");
+            let (name, value) = value.split(';').next()?.split_once('=')?;
+            (!name.trim().is_empty()).then(|| format!("{}={value}", name.trim()))
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
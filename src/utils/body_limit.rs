@@ -0,0 +1,38 @@
+use axum::http::{HeaderMap, header::CONTENT_LENGTH};
+
+/// Body limit axum's `DefaultBodyLimit` applies when a route does not override it.
+pub(crate) const DEFAULT_BODY_LIMIT_BYTES: usize = 2 * 1024 * 1024;
+
+/// Return the declared `Content-Length` when it is already larger than `limit`.
+///
+/// Checking the header before touching the body matters for `Expect: 100-continue`: hyper only
+/// sends `100 Continue` once the body is polled, so rejecting here answers 413 before the client
+/// starts uploading.
+pub(crate) fn declared_length_over(headers: &HeaderMap, limit: usize) -> Option<u64> {
+    headers
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .filter(|&length| length > limit as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn only_declared_lengths_over_the_limit_are_reported() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(declared_length_over(&headers, 10), None);
+
+        headers.insert(CONTENT_LENGTH, HeaderValue::from_static("10"));
+        assert_eq!(declared_length_over(&headers, 10), None);
+
+        headers.insert(CONTENT_LENGTH, HeaderValue::from_static("11"));
+        assert_eq!(declared_length_over(&headers, 10), Some(11));
+
+        headers.insert(CONTENT_LENGTH, HeaderValue::from_static("not-a-number"));
+        assert_eq!(declared_length_over(&headers, 10), None);
+    }
+}
@@ -0,0 +1,65 @@
+use axum::http::{HeaderMap, header::CONTENT_TYPE};
+
+/// Explain why `headers` do not declare a JSON body, or `None` if they do.
+///
+/// Accepts `application/json` and `application/*+json`, with any parameters, like `axum::Json`.
+pub(crate) fn json_content_type_error(headers: &HeaderMap) -> Option<String> {
+    let Some(value) = headers.get(CONTENT_TYPE) else {
+        return Some("missing Content-Type header; expected application/json".to_string());
+    };
+    let Ok(raw) = value.to_str() else {
+        return Some("unreadable Content-Type header; expected application/json".to_string());
+    };
+
+    let essence = raw.split(';').next().unwrap_or_default().trim();
+    let is_json = essence.split_once('/').is_some_and(|(kind, subtype)| {
+        kind.eq_ignore_ascii_case("application")
+            && (subtype.eq_ignore_ascii_case("json")
+                || subtype.to_ascii_lowercase().ends_with("+json"))
+    });
+    if is_json {
+        return None;
+    }
+    Some(format!(
+        "unsupported Content-Type `{raw}`; expected application/json"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(content_type: Option<&'static str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(value) = content_type {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn json_media_types_pass() {
+        for value in [
+            "application/json",
+            "application/json; charset=utf-8",
+            "Application/JSON",
+            "application/vnd.api+json",
+        ] {
+            assert_eq!(
+                json_content_type_error(&headers(Some(value))),
+                None,
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn other_media_types_name_the_received_type() {
+        let message = json_content_type_error(&headers(Some("text/plain"))).unwrap();
+        assert!(message.contains("`text/plain`"), "{message}");
+
+        let message = json_content_type_error(&headers(None)).unwrap();
+        assert!(message.contains("missing Content-Type"), "{message}");
+    }
+}
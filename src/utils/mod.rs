@@ -1,2 +1,6 @@
+pub(crate) mod body_limit;
+pub(crate) mod content_type;
 pub(crate) mod jwt;
 pub(crate) mod logging;
+pub(crate) mod spool;
+pub(crate) mod stream_mode;
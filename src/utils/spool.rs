@@ -0,0 +1,145 @@
+use axum::{
+    body::Body,
+    http::{HeaderMap, header::CONTENT_LENGTH},
+};
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use std::{
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+use thiserror::Error as ThisError;
+use tokio::io::AsyncWriteExt;
+
+#[derive(Debug, ThisError)]
+pub(crate) enum SpoolError {
+    #[error("request body exceeds limit of {limit} bytes")]
+    TooLarge { limit: usize },
+
+    #[error("failed to read request body: {0}")]
+    Body(#[from] axum::Error),
+
+    #[error("request body spool I/O failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Whether a request body should go to disk: its declared length is above `threshold`, or it has
+/// no `Content-Length` (chunked) and so might be.
+pub(crate) fn should_spool(headers: &HeaderMap, threshold: Option<usize>) -> bool {
+    let Some(threshold) = threshold else {
+        return false;
+    };
+    match headers
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+    {
+        Some(length) => length > threshold as u64,
+        None => true,
+    }
+}
+
+/// A request body written to a temp file; the file is removed on drop.
+#[derive(Debug)]
+pub(crate) struct SpooledBody {
+    path: PathBuf,
+    len: u64,
+}
+
+impl SpooledBody {
+    /// Stream `body` into a fresh file under the system temp dir, failing once more than
+    /// `limit` bytes arrive.
+    pub(crate) async fn write(body: Body, limit: usize) -> Result<Self, SpoolError> {
+        let path = std::env::temp_dir().join(format!("pollux-body-{}.json", uuid::Uuid::new_v4()));
+        let mut spooled = Self { path, len: 0 };
+        let mut file = tokio::fs::File::create(&spooled.path).await?;
+
+        let mut chunks = body.into_data_stream();
+        while let Some(chunk) = chunks.next().await {
+            let chunk = chunk?;
+            spooled.len += chunk.len() as u64;
+            if spooled.len > limit as u64 {
+                return Err(SpoolError::TooLarge { limit });
+            }
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+        Ok(spooled)
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Deserialize the spooled JSON straight from disk on a blocking thread.
+    pub(crate) async fn parse<T>(self) -> Result<T, SpoolError>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        tokio::task::spawn_blocking(move || {
+            let file = std::fs::File::open(&self.path)?;
+            Ok(serde_json::from_reader(BufReader::new(file))?)
+        })
+        .await
+        .map_err(|e| SpoolError::Io(std::io::Error::other(e)))?
+    }
+}
+
+impl Drop for SpooledBody {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use serde_json::{Value, json};
+
+    #[test]
+    fn spools_only_above_threshold_or_without_length() {
+        let mut headers = HeaderMap::new();
+        assert!(!should_spool(&headers, None));
+        assert!(should_spool(&headers, Some(10)));
+
+        headers.insert(CONTENT_LENGTH, HeaderValue::from_static("10"));
+        assert!(!should_spool(&headers, Some(10)));
+
+        headers.insert(CONTENT_LENGTH, HeaderValue::from_static("11"));
+        assert!(should_spool(&headers, Some(10)));
+        assert!(!should_spool(&headers, None));
+    }
+
+    #[tokio::test]
+    async fn spooled_body_is_on_disk_until_parsed() {
+        let payload = json!({"model": "gpt-4o-mini", "input": "x".repeat(4096)});
+        let raw = payload.to_string();
+
+        let spooled = SpooledBody::write(Body::from(raw.clone()), 1024 * 1024)
+            .await
+            .expect("spool body");
+        let path = spooled.path().to_path_buf();
+        assert_eq!(spooled.len(), raw.len() as u64);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), raw);
+
+        let parsed: Value = spooled.parse().await.expect("parse spooled body");
+        assert_eq!(parsed, payload);
+        assert!(!path.exists(), "spool file must be removed after parsing");
+    }
+
+    #[tokio::test]
+    async fn spooling_stops_at_the_limit() {
+        let err = SpooledBody::write(Body::from("x".repeat(64)), 16)
+            .await
+            .expect_err("over-limit body must fail");
+        assert!(matches!(err, SpoolError::TooLarge { limit: 16 }));
+    }
+}
@@ -0,0 +1,86 @@
+/// Whether a Gemini-style request asks for a streamed response.
+///
+/// `path` is the route's `models/...` tail and `query` the raw query string. Either the
+/// `streamGenerateContent` method or `alt=sse` selects streaming. `alt=json` leaves the method
+/// in charge, and any other `alt` is an error naming the value.
+pub(crate) fn stream_requested(path: &str, query: Option<&str>) -> Result<bool, String> {
+    let method = path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .and_then(|segment| segment.split_once(':'))
+        .map(|(_, method)| method);
+    let by_path = method.is_some_and(|method| method == "streamGenerateContent");
+
+    let mut by_query = false;
+    for (key, value) in url::form_urlencoded::parse(query.unwrap_or_default().as_bytes()) {
+        if key != "alt" {
+            continue;
+        }
+        if value.eq_ignore_ascii_case("sse") {
+            by_query = true;
+        } else if !value.eq_ignore_ascii_case("json") {
+            return Err(format!(
+                "unsupported alt `{value}`; expected `sse` or `json`"
+            ));
+        }
+    }
+    Ok(by_path || by_query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_stream_method_selects_streaming() {
+        assert_eq!(
+            stream_requested("gemini-2.5-pro:streamGenerateContent", None),
+            Ok(true)
+        );
+        assert_eq!(
+            stream_requested("gemini-2.5-pro:streamGenerateContent/", Some("")),
+            Ok(true)
+        );
+        assert_eq!(
+            stream_requested("gemini-2.5-pro:generateContent", None),
+            Ok(false)
+        );
+        // Only the method counts, not a model that happens to contain the word.
+        assert_eq!(
+            stream_requested("streamGenerateContent-model:generateContent", None),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn alt_sse_selects_streaming() {
+        assert_eq!(
+            stream_requested("gemini-2.5-pro:generateContent", Some("alt=sse")),
+            Ok(true)
+        );
+        assert_eq!(
+            stream_requested("gemini-2.5-pro:generateContent", Some("key=k&alt=SSE")),
+            Ok(true)
+        );
+        assert_eq!(
+            stream_requested("gemini-2.5-pro:generateContent", Some("alt=json")),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn path_and_query_combine() {
+        assert_eq!(
+            stream_requested("gemini-2.5-pro:streamGenerateContent", Some("alt=sse")),
+            Ok(true)
+        );
+        assert_eq!(
+            stream_requested("gemini-2.5-pro:streamGenerateContent", Some("alt=json")),
+            Ok(true)
+        );
+        let err = stream_requested("gemini-2.5-pro:streamGenerateContent", Some("alt=proto"))
+            .unwrap_err();
+        assert!(err.contains("`proto`"), "{err}");
+    }
+}
@@ -0,0 +1,178 @@
+use axum::{
+    Router,
+    body::{Body, Bytes, to_bytes},
+    extract::State,
+    http::{HeaderMap, Request, StatusCode, header},
+    response::IntoResponse,
+    routing::post,
+};
+use chrono::{Duration, Utc};
+use pollux::db::{AntigravityCreate, ProviderCreate};
+use pollux::testutil::{TestDatabase, spawn_test_server, test_app, test_config};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+
+#[derive(Clone, Default)]
+struct CaptureState {
+    reqs: Arc<Mutex<Vec<(HeaderMap, Value)>>>,
+}
+
+const RAW_OK_BODY: &str = r#"{"response":{"candidates":"not-an-array"},  "odd" : [1,2,3]}"#;
+const RAW_ERR_BODY: &str = "upstream says no\n";
+
+async fn generate_handler(
+    State(state): State<CaptureState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let json: Value = serde_json::from_slice(&body).expect("upstream body json");
+    let fail = json.get("fail").is_some();
+    state.reqs.lock().unwrap().push((headers, json));
+
+    if fail {
+        (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::CONTENT_TYPE, "text/plain")],
+            RAW_ERR_BODY,
+        )
+    } else {
+        (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/json")],
+            RAW_OK_BODY,
+        )
+    }
+}
+
+#[tokio::test]
+async fn admin_passthrough_returns_raw_upstream_response() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("admin-passthrough").await;
+    db.handle
+        .create(ProviderCreate::Antigravity(AntigravityCreate {
+            email: None,
+            project_id: "project-raw".to_string(),
+            sub: Some("sub-raw".to_string()),
+            refresh_token: "refresh-raw".to_string(),
+            access_token: Some("access-raw".to_string()),
+            expiry: Utc::now() + Duration::hours(1),
+        }))
+        .await
+        .expect("insert antigravity credential");
+
+    let captured = CaptureState::default();
+    let upstream = Router::new()
+        .route("/v1internal:generateContent", post(generate_handler))
+        .with_state(captured.clone());
+    let base = spawn_test_server(upstream).await;
+
+    let mut cfg = test_config("pwd");
+    let model = pollux::config::CONFIG
+        .antigravity()
+        .model_list
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "gemini-2.5-pro".to_string());
+    cfg.providers.antigravity.model_list = vec![model.clone()];
+    cfg.providers.antigravity.api_url = base;
+
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+    let app = test_app(providers, &cfg);
+
+    let raw_request = json!({
+        "model": model,
+        "request": {"contents": [{"role": "user", "parts": [{"text": "hi"}]}]},
+        "somethingUnusual": true
+    });
+
+    // 1) no key -> 401
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/admin/passthrough/antigravity")
+                .header("content-type", "application/json")
+                .body(Body::from(raw_request.to_string()))
+                .expect("failed to build request"),
+        )
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    // 2) unknown provider -> 400
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/admin/passthrough/nope")
+                .header("content-type", "application/json")
+                .header("x-goog-api-key", "pwd")
+                .body(Body::from(raw_request.to_string()))
+                .expect("failed to build request"),
+        )
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // 3) success: upstream body is returned byte-for-byte.
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/admin/passthrough/antigravity")
+                .header("content-type", "application/json")
+                .header("x-goog-api-key", "pwd")
+                .body(Body::from(raw_request.to_string()))
+                .expect("failed to build request"),
+        )
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    assert_eq!(body.as_ref(), RAW_OK_BODY.as_bytes());
+
+    // 4) upstream error status and body are passed through, not mapped.
+    let mut failing = raw_request.clone();
+    failing["fail"] = json!(true);
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/admin/passthrough/antigravity")
+                .header("content-type", "application/json")
+                .header("x-goog-api-key", "pwd")
+                .body(Body::from(failing.to_string()))
+                .expect("failed to build request"),
+        )
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(
+        resp.headers().get(header::CONTENT_TYPE).unwrap(),
+        "text/plain"
+    );
+    let body = to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    assert_eq!(body.as_ref(), RAW_ERR_BODY.as_bytes());
+
+    // The upstream saw the raw body (plus the leased project) and the lease token.
+    let reqs = captured.reqs.lock().unwrap().clone();
+    assert_eq!(reqs.len(), 2, "passthrough must not retry");
+    let (headers, first) = &reqs[0];
+    assert_eq!(
+        headers.get(header::AUTHORIZATION).unwrap(),
+        "Bearer access-raw"
+    );
+    let mut expected = raw_request.clone();
+    expected["project"] = json!("project-raw");
+    assert_eq!(first, &expected);
+}
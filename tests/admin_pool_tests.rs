@@ -0,0 +1,114 @@
+use axum::{
+    body::{Body, to_bytes},
+    http::{Request, StatusCode},
+};
+use chrono::{Duration, Utc};
+use pollux::db::{GeminiCliCreate, ProviderCreate};
+use serde_json::Value;
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn rate_limited_credential_is_listed_with_its_cooldown() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+
+    let mut temp_path = std::env::temp_dir();
+    temp_path.push(format!(
+        "pollux-admin-pool-{}-{}.sqlite",
+        std::process::id(),
+        nanos
+    ));
+    let database_url = format!("sqlite:{}", temp_path.display());
+    let db = pollux::db::spawn(&database_url).await;
+
+    let mut ids = Vec::new();
+    for n in 0..2 {
+        let id = db
+            .create(ProviderCreate::GeminiCli(GeminiCliCreate {
+                email: None,
+                project_id: format!("project-pool-{n}"),
+                sub: format!("sub-pool-{n}"),
+                refresh_token: format!("refresh-pool-{n}"),
+                access_token: Some(format!("access-pool-{n}")),
+                expiry: Utc::now() + Duration::hours(1),
+                quota_tier: None,
+                supported_models: None,
+            }))
+            .await
+            .expect("insert geminicli credential");
+        ids.push(id as u64);
+    }
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.basic.pollux_key = "pwd".to_string();
+    let model = pollux::config::CONFIG
+        .geminicli()
+        .model_list
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "gemini-2.5-pro".to_string());
+    let model_mask = pollux::model_catalog::mask(&model).expect("model is registered");
+
+    let providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+    providers
+        .geminicli
+        .report_rate_limit(ids[0], model_mask, std::time::Duration::from_secs(120))
+        .await;
+
+    let pollux_key: Arc<str> = Arc::from(cfg.basic.pollux_key.clone());
+    let state = pollux::server::router::PolluxState::new(
+        providers,
+        pollux_key.clone(),
+        cfg.basic.insecure_cookie,
+    );
+    let app = pollux::server::router::pollux_router(state);
+
+    let resp = app
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/admin/pool")
+                .header("x-goog-api-key", pollux_key.as_ref())
+                .body(Body::empty())
+                .expect("failed to build request"),
+        )
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body = to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    let report: Value = serde_json::from_slice(&body).expect("pool report is JSON");
+    let geminicli = report["geminicli"].as_array().expect("geminicli list");
+    assert_eq!(geminicli.len(), 2, "{report}");
+
+    let limited = &geminicli[0];
+    assert_eq!(limited["id"], ids[0]);
+    assert_eq!(limited["state"], "rate_limited");
+    let cooldown = &limited["cooldowns"][0];
+    assert_eq!(cooldown["model"], model.as_str());
+    let remaining = cooldown["remaining_secs"].as_u64().expect("remaining_secs");
+    assert!((115..=120).contains(&remaining), "{cooldown}");
+    let until: chrono::DateTime<Utc> = cooldown["until"]
+        .as_str()
+        .expect("until")
+        .parse()
+        .expect("until is RFC 3339");
+    assert!(until > Utc::now() + Duration::seconds(110));
+
+    let idle = &geminicli[1];
+    assert_eq!(idle["id"], ids[1]);
+    assert_eq!(idle["state"], "available");
+    assert_eq!(idle["cooldowns"], Value::Array(Vec::new()));
+
+    let _ = tokio::fs::remove_file(&temp_path).await;
+}
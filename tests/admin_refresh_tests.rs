@@ -0,0 +1,133 @@
+use axum::{
+    Json, Router,
+    body::{Body, to_bytes},
+    http::{Request, StatusCode},
+    routing::post,
+};
+use chrono::{DateTime, Duration, Utc};
+use pollux::db::{GeminiCliCreate, ProviderCreate};
+use serde_json::{Value, json};
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::net::TcpListener;
+use tower::ServiceExt;
+use url::Url;
+
+async fn spawn_test_server(app: Router) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind listener");
+    let addr = listener.local_addr().expect("local addr");
+    let base = Url::parse(&format!("http://{}", addr)).expect("valid base url");
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("server run");
+    });
+
+    base
+}
+
+async fn token_handler() -> Json<Value> {
+    Json(json!({
+        "access_token": "access-fresh",
+        "token_type": "Bearer",
+        "expires_in": 7200
+    }))
+}
+
+fn refresh_request(id: i64, key: &str) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri(format!("/admin/credentials/{id}/refresh"))
+        .header("x-goog-api-key", key)
+        .body(Body::empty())
+        .expect("failed to build request")
+}
+
+#[tokio::test]
+async fn admin_refresh_updates_stored_token_and_expiry() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+
+    let mut temp_path = std::env::temp_dir();
+    temp_path.push(format!(
+        "pollux-admin-refresh-{}-{}.sqlite",
+        std::process::id(),
+        nanos
+    ));
+    let database_url = format!("sqlite:{}", temp_path.display());
+    let db = pollux::db::spawn(&database_url).await;
+
+    // Still valid for a while, so nothing but the admin call would refresh it.
+    let id = db
+        .create(ProviderCreate::GeminiCli(GeminiCliCreate {
+            email: None,
+            project_id: "project-refresh".to_string(),
+            sub: "sub-refresh".to_string(),
+            refresh_token: "refresh-original".to_string(),
+            access_token: Some("access-stale".to_string()),
+            expiry: Utc::now() + Duration::minutes(30),
+            quota_tier: None,
+            supported_models: None,
+        }))
+        .await
+        .expect("insert geminicli credential");
+
+    let upstream = Router::new().route("/token", post(token_handler));
+    let base = spawn_test_server(upstream).await;
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.basic.pollux_key = "pwd".to_string();
+    cfg.providers.geminicli.oauth_token_url = base.join("/token").unwrap();
+
+    let providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+    let pollux_key: Arc<str> = Arc::from(cfg.basic.pollux_key.clone());
+    let state = pollux::server::router::PolluxState::new(
+        providers,
+        pollux_key.clone(),
+        cfg.basic.insecure_cookie,
+    );
+    let app = pollux::server::router::pollux_router(state);
+
+    // 1) unknown credential -> 404.
+    let resp = app
+        .clone()
+        .oneshot(refresh_request(id + 100, &pollux_key))
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    // 2) refresh: the response carries the new expiry, and it is already stored.
+    let before = Utc::now();
+    let resp = app
+        .clone()
+        .oneshot(refresh_request(id, &pollux_key))
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let report: Value = serde_json::from_slice(&body).expect("json body");
+    assert_eq!(report["id"], json!(id));
+    let expiry: DateTime<Utc> =
+        serde_json::from_value(report["expiry"].clone()).expect("expiry timestamp");
+    assert!(expiry >= before + Duration::minutes(110));
+
+    let row = db
+        .list_active_geminicli()
+        .await
+        .expect("list active credentials")
+        .into_iter()
+        .find(|row| row.id == id)
+        .expect("credential still active");
+    assert_eq!(row.access_token.as_deref(), Some("access-fresh"));
+    assert!((row.expiry - expiry).num_seconds().abs() <= 1);
+    assert_eq!(row.refresh_token, "refresh-original");
+
+    let _ = tokio::fs::remove_file(&temp_path).await;
+}
@@ -0,0 +1,148 @@
+use axum::{
+    Form, Router,
+    body::Body,
+    extract::State,
+    http::{Request, StatusCode},
+    routing::post,
+};
+use chrono::{Duration, Utc};
+use pollux::db::{GeminiCliCreate, ProviderCreate};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::net::TcpListener;
+use tower::ServiceExt;
+use url::Url;
+
+#[derive(Clone, Default)]
+struct RevokeCapture {
+    tokens: Arc<Mutex<Vec<String>>>,
+}
+
+async fn spawn_test_server(app: Router) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind listener");
+    let addr = listener.local_addr().expect("local addr");
+    let base = Url::parse(&format!("http://{}", addr)).expect("valid base url");
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("server run");
+    });
+
+    base
+}
+
+async fn revoke_handler(
+    State(state): State<RevokeCapture>,
+    Form(form): Form<HashMap<String, String>>,
+) -> StatusCode {
+    state
+        .tokens
+        .lock()
+        .unwrap()
+        .push(form.get("token").cloned().unwrap_or_default());
+    StatusCode::OK
+}
+
+fn revoke_request(id: i64, key: &str) -> Request<Body> {
+    Request::builder()
+        .method("POST")
+        .uri(format!("/admin/credentials/{id}/revoke"))
+        .header("x-goog-api-key", key)
+        .body(Body::empty())
+        .expect("failed to build request")
+}
+
+#[tokio::test]
+async fn admin_revoke_calls_google_and_disables_credential() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+
+    let mut temp_path = std::env::temp_dir();
+    temp_path.push(format!(
+        "pollux-admin-revoke-{}-{}.sqlite",
+        std::process::id(),
+        nanos
+    ));
+    let database_url = format!("sqlite:{}", temp_path.display());
+    let db = pollux::db::spawn(&database_url).await;
+
+    let id = db
+        .create(ProviderCreate::GeminiCli(GeminiCliCreate {
+            email: None,
+            project_id: "project-revoke".to_string(),
+            sub: "sub-revoke".to_string(),
+            refresh_token: "refresh-revoke".to_string(),
+            access_token: Some("access-revoke".to_string()),
+            expiry: Utc::now() + Duration::hours(1),
+            quota_tier: None,
+            supported_models: None,
+        }))
+        .await
+        .expect("insert geminicli credential");
+
+    let captured = RevokeCapture::default();
+    let upstream = Router::new()
+        .route("/revoke", post(revoke_handler))
+        .with_state(captured.clone());
+    let base = spawn_test_server(upstream).await;
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.basic.pollux_key = "pwd".to_string();
+
+    let mut providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+    let mut geminicli_cfg = (*providers.geminicli_cfg).clone();
+    geminicli_cfg.oauth_revoke_url = base.join("/revoke").unwrap();
+    providers.geminicli_cfg = Arc::new(geminicli_cfg);
+
+    let pollux_key: Arc<str> = Arc::from(cfg.basic.pollux_key.clone());
+    let state = pollux::server::router::PolluxState::new(
+        providers,
+        pollux_key.clone(),
+        cfg.basic.insecure_cookie,
+    );
+    let app = pollux::server::router::pollux_router(state);
+
+    // 1) unknown credential -> 404, nothing revoked.
+    let resp = app
+        .clone()
+        .oneshot(revoke_request(id + 100, &pollux_key))
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert!(captured.tokens.lock().unwrap().is_empty());
+
+    // 2) revoke: Google sees the refresh token and the row is disabled.
+    let resp = app
+        .clone()
+        .oneshot(revoke_request(id, &pollux_key))
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        captured.tokens.lock().unwrap().as_slice(),
+        ["refresh-revoke".to_string()]
+    );
+    let active = db
+        .list_active_geminicli()
+        .await
+        .expect("list active credentials");
+    assert!(active.iter().all(|row| row.id != id));
+
+    // 3) a second revoke finds nothing active.
+    let resp = app
+        .clone()
+        .oneshot(revoke_request(id, &pollux_key))
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let _ = tokio::fs::remove_file(&temp_path).await;
+}
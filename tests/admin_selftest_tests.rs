@@ -0,0 +1,50 @@
+use pollux::testutil::{TestDatabase, spawn_test_server, test_app, test_config};
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn selftest_reports_a_cache_hit_on_a_healthy_service() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("admin-selftest").await;
+    let cfg = test_config("pwd");
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+    let base = spawn_test_server(test_app(providers, &cfg)).await;
+    let client = reqwest::Client::new();
+    let url = base.join("/admin/selftest").expect("valid admin url");
+
+    let resp = client
+        .post(url.clone())
+        .send()
+        .await
+        .expect("admin request failed");
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = client
+        .post(url)
+        .header("x-goog-api-key", "pwd")
+        .send()
+        .await
+        .expect("admin request failed");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let report: Value = resp.json().await.expect("self-test json");
+    assert_eq!(
+        report,
+        json!({"ok": true, "geminicli_cache_hit": true, "antigravity_cache_hit": true})
+    );
+
+    // The synthetic signatures are not left in the cache.
+    let dump: Value = client
+        .get(
+            base.join("/admin/thoughtsig/export")
+                .expect("valid admin url"),
+        )
+        .header("x-goog-api-key", "pwd")
+        .send()
+        .await
+        .expect("export request failed")
+        .json()
+        .await
+        .expect("export json");
+    assert_eq!(dump, json!({"geminicli": {}, "antigravity": {}}));
+}
@@ -0,0 +1,92 @@
+use pollux::testutil::{TestDatabase, spawn_test_server, test_app, test_config};
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+
+#[tokio::test]
+async fn simulated_quota_error_is_classified_as_a_rate_limit() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("admin-simulate-error").await;
+    let cfg = test_config("pwd");
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+    let base = spawn_test_server(test_app(providers, &cfg)).await;
+    let client = reqwest::Client::new();
+    let url = base.join("/admin/simulate-error").expect("valid admin url");
+
+    let quota_body = json!({
+        "error": {
+            "code": 429,
+            "message": "You have exhausted your capacity on this model.",
+            "status": "RESOURCE_EXHAUSTED",
+            "details": [{
+                "@type": "type.googleapis.com/google.rpc.ErrorInfo",
+                "reason": "MODEL_CAPACITY_EXHAUSTED"
+            }]
+        }
+    });
+    let resp = client
+        .post(url.clone())
+        .header("x-goog-api-key", "pwd")
+        .json(&json!({"provider": "geminicli", "status": 429, "body": quota_body}))
+        .send()
+        .await
+        .expect("admin request failed");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let action: Value = resp.json().await.expect("action json");
+    assert_eq!(
+        action,
+        json!({"action": "rate_limit", "retry_after_secs": 600})
+    );
+
+    // The simulated error is counted like a real one.
+    let metrics: Value = client
+        .get(base.join("/admin/metrics").expect("valid admin url"))
+        .header("x-goog-api-key", "pwd")
+        .send()
+        .await
+        .expect("metrics request failed")
+        .json()
+        .await
+        .expect("metrics json");
+    assert_eq!(
+        metrics["upstream_error_actions"]["geminicli"]["rate_limit"],
+        1
+    );
+    assert_eq!(
+        metrics["thoughtsig_store"]["geminicli"],
+        json!({"entries": 0, "signature_bytes": 0})
+    );
+
+    // An unstructured 429 falls back to the status default.
+    let action: Value = client
+        .post(url.clone())
+        .header("x-goog-api-key", "pwd")
+        .json(&json!({"provider": "geminicli", "status": 429, "body": "slow down"}))
+        .send()
+        .await
+        .expect("admin request failed")
+        .json()
+        .await
+        .expect("action json");
+    assert_eq!(
+        action,
+        json!({"action": "rate_limit", "retry_after_secs": 60})
+    );
+
+    let resp = client
+        .post(url.clone())
+        .header("x-goog-api-key", "pwd")
+        .json(&json!({"provider": "nope", "status": 429}))
+        .send()
+        .await
+        .expect("admin request failed");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = client
+        .post(url)
+        .json(&json!({"provider": "geminicli", "status": 429}))
+        .send()
+        .await
+        .expect("admin request failed");
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+}
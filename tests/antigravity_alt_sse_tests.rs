@@ -0,0 +1,84 @@
+use axum::{Json, Router, routing::post};
+use chrono::{Duration, Utc};
+use pollux::db::{AntigravityCreate, ProviderCreate};
+use pollux::testutil::{TestDatabase, spawn_test_server, test_app, test_config};
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+
+async fn stream_handler() -> Json<Value> {
+    Json(json!({
+        "response": {
+            "candidates": [{
+                "index": 0,
+                "content": {"role": "model", "parts": [{"text": "streamed"}]},
+                "finishReason": "STOP"
+            }]
+        }
+    }))
+}
+
+#[tokio::test]
+async fn alt_sse_on_generate_content_streams() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("alt-sse").await;
+    db.handle
+        .create(ProviderCreate::Antigravity(AntigravityCreate {
+            email: None,
+            sub: Some("sub-alt-sse".to_string()),
+            project_id: "project-alt-sse".to_string(),
+            refresh_token: "refresh-alt-sse".to_string(),
+            access_token: Some("access-alt-sse".to_string()),
+            expiry: Utc::now() + Duration::hours(1),
+        }))
+        .await
+        .expect("insert antigravity credential");
+
+    // Only the stream endpoint exists upstream, so a unary call would fail.
+    let upstream = Router::new().route("/v1internal:streamGenerateContent", post(stream_handler));
+    let upstream_base = spawn_test_server(upstream).await;
+
+    let mut cfg = test_config("pwd");
+    cfg.providers.antigravity.model_list = vec!["gemini-2.5-pro".to_string()];
+    cfg.providers.antigravity.api_url = upstream_base;
+
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+    let base = spawn_test_server(test_app(providers, &cfg)).await;
+    let client = reqwest::Client::new();
+    let request = json!({"contents": [{"role": "user", "parts": [{"text": "hi"}]}]});
+
+    let resp = client
+        .post(
+            base.join("/antigravity/v1beta/models/gemini-2.5-pro:generateContent?alt=sse")
+                .expect("valid route url"),
+        )
+        .header("x-goog-api-key", "pwd")
+        .json(&request)
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok()),
+        Some("text/event-stream")
+    );
+    let body = resp.text().await.expect("response body");
+    assert!(body.contains("data: "), "{body}");
+    assert!(body.contains("streamed"), "{body}");
+
+    let resp = client
+        .post(
+            base.join("/antigravity/v1beta/models/gemini-2.5-pro:generateContent?alt=proto")
+                .expect("valid route url"),
+        )
+        .header("x-goog-api-key", "pwd")
+        .json(&request)
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: Value = resp.json().await.expect("error json");
+    assert_eq!(body["error"]["status"], "INVALID_ARGUMENT", "{body}");
+}
@@ -0,0 +1,82 @@
+use axum::{Json, Router, extract::State, routing::post};
+use chrono::{Duration, Utc};
+use pollux::db::{AntigravityCreate, ProviderCreate};
+use pollux::testutil::{TestDatabase, spawn_test_server, test_app, test_config};
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+
+type Captured = Arc<Mutex<Vec<Value>>>;
+
+async fn generate_handler(
+    State(captured): State<Captured>,
+    Json(body): Json<Value>,
+) -> Json<Value> {
+    captured.lock().unwrap().push(body);
+    Json(json!({
+        "response": {
+            "candidates": [{
+                "index": 0,
+                "content": {"role": "model", "parts": [{"text": "hello"}]},
+                "finishReason": "STOP"
+            }]
+        }
+    }))
+}
+
+#[tokio::test]
+async fn request_without_a_model_uses_the_configured_default() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("antigravity-default-model").await;
+    db.handle
+        .create(ProviderCreate::Antigravity(AntigravityCreate {
+            email: None,
+            sub: Some("sub-default-model".to_string()),
+            project_id: "project-default-model".to_string(),
+            refresh_token: "refresh-default-model".to_string(),
+            access_token: Some("access-default-model".to_string()),
+            expiry: Utc::now() + Duration::hours(1),
+        }))
+        .await
+        .expect("insert antigravity credential");
+
+    let captured = Captured::default();
+    let upstream = Router::new()
+        .route("/v1internal:generateContent", post(generate_handler))
+        .with_state(captured.clone());
+    let upstream_base = spawn_test_server(upstream).await;
+
+    let mut cfg = test_config("pwd");
+    cfg.providers.antigravity.model_list =
+        vec!["gemini-2.5-pro".to_string(), "gemini-3-flash".to_string()];
+    cfg.providers.antigravity.default_model = Some("gemini-3-flash".to_string());
+    cfg.providers.antigravity.api_url = upstream_base;
+
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+    let base = spawn_test_server(test_app(providers, &cfg)).await;
+    let client = reqwest::Client::new();
+    let body = json!({"contents": [{"role": "user", "parts": [{"text": "hi"}]}]});
+
+    for (path, expected) in [
+        (
+            "/antigravity/v1beta/models/:generateContent",
+            "gemini-3-flash",
+        ),
+        (
+            "/antigravity/v1beta/models/gemini-2.5-pro:generateContent",
+            "gemini-2.5-pro",
+        ),
+    ] {
+        let resp = client
+            .post(base.join(path).expect("valid route url"))
+            .header("x-goog-api-key", "pwd")
+            .json(&body)
+            .send()
+            .await
+            .expect("request failed");
+        assert_eq!(resp.status(), StatusCode::OK, "{path}");
+        let upstream_body = captured.lock().unwrap().pop().expect("upstream was called");
+        assert_eq!(upstream_body["model"], expected, "{path}");
+    }
+}
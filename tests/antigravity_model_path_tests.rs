@@ -0,0 +1,91 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use std::{
+    fs,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn antigravity_accepts_trailing_slash_and_other_casing_when_normalizing() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+
+    let mut temp_path = std::env::temp_dir();
+    temp_path.push(format!(
+        "pollux-antigravity-model-path-{}-{}.sqlite",
+        std::process::id(),
+        nanos
+    ));
+
+    let database_url = format!("sqlite:{}", temp_path.display());
+    let db = pollux::db::spawn(&database_url).await;
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.basic.pollux_key = "pwd".to_string();
+    cfg.providers.antigravity.model_list = vec!["gemini-2.5-pro".to_string()];
+    cfg.providers.antigravity.case_insensitive_models = true;
+
+    // No credentials: a request that passes the allowlist ends in 503, a rejected one in 400.
+    let providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+    let pollux_key: Arc<str> = Arc::from(cfg.basic.pollux_key.clone());
+    let state = pollux::server::router::PolluxState::new(
+        providers,
+        pollux_key.clone(),
+        cfg.basic.insecure_cookie,
+    );
+    let app = pollux::server::router::pollux_router(state);
+
+    let valid_body = r#"{"contents":[{"role":"user","parts":[{"text":"hi"}]}]}"#;
+    let send = |uri: &'static str| {
+        let app = app.clone();
+        let key = pollux_key.clone();
+        async move {
+            app.oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .header("x-goog-api-key", key.as_ref())
+                    .body(Body::from(valid_body))
+                    .expect("failed to build request"),
+            )
+            .await
+            .expect("request failed")
+            .status()
+        }
+    };
+
+    // 1) trailing slash
+    assert_eq!(
+        send("/antigravity/v1beta/models/gemini-2.5-pro:generateContent/").await,
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+
+    // 2) different casing
+    assert_eq!(
+        send("/antigravity/v1beta/models/Gemini-2.5-Pro:generateContent").await,
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+
+    // 3) both at once
+    assert_eq!(
+        send("/antigravity/v1beta/models/GEMINI-2.5-PRO:streamGenerateContent/").await,
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+
+    // 4) a model outside the allowlist is still rejected
+    assert_eq!(
+        send("/antigravity/v1beta/models/Gemini-3-Flash:generateContent").await,
+        StatusCode::BAD_REQUEST
+    );
+
+    let _ = fs::remove_file(&temp_path);
+}
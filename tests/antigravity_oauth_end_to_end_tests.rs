@@ -0,0 +1,121 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use pollux::testutil::{
+    MOCK_AUTH_CODE, MockGoogle, TestDatabase, cookie_header_from_set_cookie_headers, test_app,
+    test_config,
+};
+use std::time::Duration;
+use tower::ServiceExt;
+use url::Url;
+
+#[tokio::test]
+async fn antigravity_oauth_flow_against_mock_google_stores_a_credential() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let google = MockGoogle::spawn().await;
+    let db = TestDatabase::spawn("antigravity-oauth-e2e").await;
+
+    let mut cfg = test_config("pwd");
+    cfg.providers.antigravity.api_url = google.api_url();
+
+    // The refresh worker onboards the new credential, so it must see the mock endpoints too.
+    let mut antigravity_cfg = cfg.antigravity();
+    antigravity_cfg.oauth_auth_url = google.authorize_url();
+    antigravity_cfg.oauth_token_url = google.token_url();
+    antigravity_cfg.oauth_redirect_url =
+        Url::parse("http://localhost:8188").expect("valid redirect url");
+    let providers = pollux::providers::Providers::spawn_with_antigravity(
+        db.handle.clone(),
+        &cfg,
+        antigravity_cfg,
+    )
+    .await;
+    let app = test_app(providers, &cfg);
+
+    // 1) Entry redirects to the mock authorize endpoint and sets the session cookies.
+    let entry = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/antigravity/auth")
+                .body(Body::empty())
+                .expect("build request"),
+        )
+        .await
+        .expect("request failed");
+    assert!(entry.status().is_redirection());
+    let cookies = cookie_header_from_set_cookie_headers(entry.headers());
+    let authorize = entry
+        .headers()
+        .get(header::LOCATION)
+        .and_then(|value| value.to_str().ok())
+        .expect("entry location header")
+        .to_string();
+
+    // 2) "User consents": the mock authorize endpoint redirects back with a code.
+    let consent = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .expect("http client")
+        .get(&authorize)
+        .send()
+        .await
+        .expect("authorize request failed");
+    assert_eq!(consent.status(), StatusCode::FOUND);
+    let callback = Url::parse(
+        consent
+            .headers()
+            .get(header::LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .expect("authorize location header"),
+    )
+    .expect("callback url");
+    assert!(
+        callback
+            .query_pairs()
+            .any(|(k, v)| k == "code" && v == MOCK_AUTH_CODE)
+    );
+
+    // 3) Callback exchanges the code and hands the tokens to the provider.
+    let resp = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri(format!("/?{}", callback.query().unwrap_or_default()))
+                .header(header::COOKIE, cookies)
+                .body(Body::empty())
+                .expect("build request"),
+        )
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::ACCEPTED);
+
+    // 4) Onboarding runs in the background; wait for the credential to land.
+    let mut stored = Vec::new();
+    for _ in 0..100 {
+        stored = db
+            .handle
+            .list_active_antigravity()
+            .await
+            .expect("list credentials");
+        if !stored.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(stored.len(), 1, "credential was not stored");
+    assert_eq!(stored[0].refresh_token, "refresh-from-code");
+    assert_eq!(stored[0].project_id, "project-1");
+
+    let forms = google.token_forms();
+    assert!(
+        forms.iter().any(|form| {
+            form.get("grant_type").map(String::as_str) == Some("authorization_code")
+                && form.get("code").map(String::as_str) == Some(MOCK_AUTH_CODE)
+        }),
+        "{forms:?}"
+    );
+    assert_eq!(google.requests_to("/authorize").len(), 1);
+}
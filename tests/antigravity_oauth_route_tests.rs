@@ -2,37 +2,17 @@ use axum::{
     body::{Body, to_bytes},
     http::{Request, StatusCode, header},
 };
-use std::{
-    fs,
-    sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
+use pollux::testutil::{
+    TestDatabase, cookie_header_from_set_cookie_headers, test_app, test_config,
 };
+use std::sync::Arc;
 use tower::ServiceExt;
 
-fn unique_sqlite_path(prefix: &str) -> std::path::PathBuf {
-    let nanos = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("system time before UNIX_EPOCH")
-        .as_nanos();
+async fn build_app_for_oauth_tests() -> (axum::Router, TestDatabase) {
+    let db = TestDatabase::spawn("antigravity-oauth").await;
+    let cfg = test_config("pwd");
 
-    let mut temp_path = std::env::temp_dir();
-    temp_path.push(format!(
-        "pollux-{prefix}-{}-{}.sqlite",
-        std::process::id(),
-        nanos
-    ));
-    temp_path
-}
-
-async fn build_app_for_oauth_tests() -> (axum::Router, std::path::PathBuf) {
-    let temp_path = unique_sqlite_path("antigravity-oauth");
-    let database_url = format!("sqlite:{}", temp_path.display());
-    let db = pollux::db::spawn(&database_url).await;
-
-    let mut cfg = pollux::config::Config::default();
-    cfg.basic.pollux_key = "pwd".to_string();
-
-    let mut providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+    let mut providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
 
     // Keep OAuth endpoints deterministic and off the public internet for tests.
     let antigravity_cfg = Arc::make_mut(&mut providers.antigravity_cfg);
@@ -42,33 +22,14 @@ async fn build_app_for_oauth_tests() -> (axum::Router, std::path::PathBuf) {
         url::Url::parse("http://oauth.test/token").expect("valid token url");
     antigravity_cfg.oauth_redirect_url =
         url::Url::parse("http://localhost:8188").expect("valid redirect url");
-    let pollux_key: Arc<str> = Arc::from(cfg.basic.pollux_key.clone());
-    let state =
-        pollux::server::router::PolluxState::new(providers, pollux_key, cfg.basic.insecure_cookie);
-    let app = pollux::server::router::pollux_router(state);
-    (app, temp_path)
-}
-
-fn cookie_header_from_set_cookie_headers(headers: &axum::http::HeaderMap) -> String {
-    let mut pairs: Vec<String> = Vec::new();
-    for v in headers.get_all(header::SET_COOKIE).iter() {
-        let s = v.to_str().expect("set-cookie header was not valid utf-8");
-        let first = s.split(';').next().unwrap_or("");
-        let mut parts = first.splitn(2, '=');
-        let name = parts.next().unwrap_or("");
-        let value = parts.next().unwrap_or("");
-        if !name.trim().is_empty() {
-            pairs.push(format!("{}={}", name.trim(), value));
-        }
-    }
-    pairs.join("; ")
+    (test_app(providers, &cfg), db)
 }
 
 #[tokio::test]
 async fn antigravity_oauth_routes_set_cookies_and_return_explicit_flow_errors() {
     // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
     // Keep all OAuth route assertions in one test to avoid multiple spawns in this test binary.
-    let (app, temp_path) = build_app_for_oauth_tests().await;
+    let (app, _db) = build_app_for_oauth_tests().await;
 
     // 1) GET /antigravity/auth returns redirect and sets provider-specific cookies.
     let entry_resp = app
@@ -155,6 +116,4 @@ async fn antigravity_oauth_routes_set_cookies_and_return_explicit_flow_errors()
         .expect("failed to read response body");
     let body_str = std::str::from_utf8(&body).expect("response body was not utf-8");
     assert!(body_str.contains("\"code\":\"CSRF_MISMATCH\""));
-
-    let _ = fs::remove_file(&temp_path);
 }
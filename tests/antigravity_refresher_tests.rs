@@ -5,7 +5,7 @@ use axum::{
     routing::post,
 };
 use base64::Engine as _;
-use pollux::config::AntigravityResolvedConfig;
+use pollux::config::{AntigravityResolvedConfig, RetryBackoff};
 use pollux::providers::antigravity::client::oauth::{
     endpoints::AntigravityOauthEndpoints, ops::AntigravityOauthOps,
 };
@@ -13,6 +13,7 @@ use serde_json::{Value, json};
 use std::{
     collections::HashMap,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 use tokio::net::TcpListener;
 use url::Url;
@@ -49,8 +50,23 @@ fn make_cfg(token_url: Url, api_url: Url) -> AntigravityResolvedConfig {
         proxy: None,
         oauth_tps: 5,
         model_list: vec!["gemini-2.5-pro".to_string()],
+        default_model: None,
         enable_multiplexing: true,
         retry_max_times: 3,
+        expiry_skew: Duration::ZERO,
+        retry_backoff: RetryBackoff {
+            min_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(300),
+            jitter: true,
+        },
+        retry_empty_responses: true,
+        capture_response_headers: Vec::new(),
+        telemetry_headers: Default::default(),
+        empty_contents_models: Vec::new(),
+        max_parts_per_content: 4096,
+        preamble_marker: "proactiveness".to_string(),
+        case_insensitive_models: false,
+        trust_model_list: false,
         oauth_auth_url: Url::parse("http://oauth.test/authorize").unwrap(),
         oauth_token_url: token_url,
         oauth_redirect_url: Url::parse("http://localhost:8188").unwrap(),
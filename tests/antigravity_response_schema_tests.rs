@@ -0,0 +1,167 @@
+use axum::{
+    Json, Router,
+    body::{Body, to_bytes},
+    extract::State,
+    http::{Request, StatusCode},
+    routing::post,
+};
+use chrono::{Duration, Utc};
+use pollux::db::{AntigravityCreate, ProviderCreate};
+use serde_json::{Value, json};
+use std::{
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::net::TcpListener;
+use tower::ServiceExt;
+use url::Url;
+
+#[derive(Clone, Default)]
+struct UpstreamCapture {
+    bodies: Arc<Mutex<Vec<Value>>>,
+}
+
+async fn spawn_test_server(app: Router) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind listener");
+    let addr = listener.local_addr().expect("local addr");
+    let base = Url::parse(&format!("http://{}", addr)).expect("valid base url");
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("server run");
+    });
+
+    base
+}
+
+async fn generate_handler(
+    State(capture): State<UpstreamCapture>,
+    Json(body): Json<Value>,
+) -> Json<Value> {
+    capture.bodies.lock().unwrap().push(body);
+    Json(json!({
+        "response": {
+            "candidates": [{
+                "index": 0,
+                "content": {"role": "model", "parts": [{"text": "{\"name\":\"pollux\"}"}]},
+                "finishReason": "STOP"
+            }]
+        }
+    }))
+}
+
+#[tokio::test]
+async fn json_mode_passes_through_and_malformed_schema_is_rejected() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+
+    let mut temp_path = std::env::temp_dir();
+    temp_path.push(format!(
+        "pollux-antigravity-response-schema-{}-{}.sqlite",
+        std::process::id(),
+        nanos
+    ));
+    let database_url = format!("sqlite:{}", temp_path.display());
+    let db = pollux::db::spawn(&database_url).await;
+
+    db.create(ProviderCreate::Antigravity(AntigravityCreate {
+        email: None,
+        sub: Some("sub-schema".to_string()),
+        project_id: "project-schema".to_string(),
+        refresh_token: "refresh-schema".to_string(),
+        access_token: Some("access-schema".to_string()),
+        expiry: Utc::now() + Duration::hours(1),
+    }))
+    .await
+    .expect("insert antigravity credential");
+
+    let capture = UpstreamCapture::default();
+    let upstream = Router::new()
+        .route("/v1internal:generateContent", post(generate_handler))
+        .with_state(capture.clone());
+    let base = spawn_test_server(upstream).await;
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.basic.pollux_key = "pwd".to_string();
+    cfg.providers.antigravity.model_list = vec!["gemini-2.5-pro".to_string()];
+    cfg.providers.antigravity.api_url = base;
+
+    let providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+    let pollux_key: Arc<str> = Arc::from(cfg.basic.pollux_key.clone());
+    let state = pollux::server::router::PolluxState::new(
+        providers,
+        pollux_key.clone(),
+        cfg.basic.insecure_cookie,
+    );
+    let app = pollux::server::router::pollux_router(state);
+
+    let send = |generation_config: Value| {
+        let app = app.clone();
+        let payload = json!({
+            "contents": [{"role": "user", "parts": [{"text": "name this proxy"}]}],
+            "generationConfig": generation_config,
+        });
+        let request = Request::builder()
+            .method("POST")
+            .uri("/antigravity/v1beta/models/gemini-2.5-pro:generateContent")
+            .header("content-type", "application/json")
+            .header("x-goog-api-key", pollux_key.as_ref())
+            .body(Body::from(payload.to_string()))
+            .expect("failed to build request");
+        async move {
+            let resp = app.oneshot(request).await.expect("request failed");
+            let status = resp.status();
+            let body = to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .expect("failed to read response body");
+            (
+                status,
+                String::from_utf8(body.to_vec()).expect("utf-8 body"),
+            )
+        }
+    };
+
+    // 1) malformed schema -> 400 from the preprocess layer, upstream never called.
+    let (status, body) = send(json!({
+        "responseMimeType": "application/json",
+        "responseSchema": {"type": "object", "properties": ["name"]}
+    }))
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    assert!(body.contains(r#""status":"INVALID_ARGUMENT""#), "{body}");
+    assert!(
+        body.contains("generationConfig.responseSchema.properties must be an object"),
+        "{body}"
+    );
+    assert!(capture.bodies.lock().unwrap().is_empty());
+
+    // 2) valid JSON mode -> forwarded upstream untouched.
+    let schema = json!({
+        "type": "OBJECT",
+        "properties": {"name": {"type": "STRING"}},
+        "required": ["name"]
+    });
+    let (status, body) = send(json!({
+        "responseMimeType": "application/json",
+        "responseSchema": schema.clone()
+    }))
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let forwarded = capture
+        .bodies
+        .lock()
+        .unwrap()
+        .pop()
+        .expect("upstream called");
+    let generation_config = &forwarded["request"]["generationConfig"];
+    assert_eq!(generation_config["responseMimeType"], "application/json");
+    assert_eq!(generation_config["responseSchema"], schema);
+
+    let _ = tokio::fs::remove_file(&temp_path).await;
+}
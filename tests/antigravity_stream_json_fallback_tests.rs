@@ -0,0 +1,86 @@
+use axum::{Json, Router, routing::post};
+use chrono::{Duration, Utc};
+use pollux::db::{AntigravityCreate, ProviderCreate};
+use pollux::testutil::{TestDatabase, spawn_test_server, test_app, test_config};
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+
+/// Answers the stream endpoint with the unary JSON envelope instead of SSE.
+async fn unary_stream_handler() -> Json<Value> {
+    Json(json!({
+        "response": {
+            "candidates": [{
+                "index": 0,
+                "content": {"role": "model", "parts": [{"text": "hello from json"}]},
+                "finishReason": "STOP"
+            }],
+            "modelVersion": "gemini-2.5-pro"
+        },
+        "traceId": "trace-1"
+    }))
+}
+
+#[tokio::test]
+async fn unary_json_body_on_stream_endpoint_becomes_one_sse_event() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("stream-json-fallback").await;
+    db.handle
+        .create(ProviderCreate::Antigravity(AntigravityCreate {
+            email: None,
+            sub: Some("sub-json-fallback".to_string()),
+            project_id: "project-json-fallback".to_string(),
+            refresh_token: "refresh-json-fallback".to_string(),
+            access_token: Some("access-json-fallback".to_string()),
+            expiry: Utc::now() + Duration::hours(1),
+        }))
+        .await
+        .expect("insert antigravity credential");
+
+    let upstream = Router::new().route(
+        "/v1internal:streamGenerateContent",
+        post(unary_stream_handler),
+    );
+    let upstream_base = spawn_test_server(upstream).await;
+
+    let mut cfg = test_config("pwd");
+    cfg.providers.antigravity.model_list = vec!["gemini-2.5-pro".to_string()];
+    cfg.providers.antigravity.api_url = upstream_base;
+
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+    let base = spawn_test_server(test_app(providers, &cfg)).await;
+
+    let resp = reqwest::Client::new()
+        .post(
+            base.join("/antigravity/v1beta/models/gemini-2.5-pro:streamGenerateContent")
+                .expect("valid route url"),
+        )
+        .header("x-goog-api-key", "pwd")
+        .json(&json!({"contents": [{"role": "user", "parts": [{"text": "hi"}]}]}))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok()),
+        Some("text/event-stream")
+    );
+
+    let body = resp.text().await.expect("response body");
+    let events: Vec<Value> = body
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .map(|data| serde_json::from_str(data).expect("event data is JSON"))
+        .collect();
+    assert_eq!(events.len(), 1, "{body}");
+    assert_eq!(
+        events[0]["candidates"][0]["content"]["parts"][0]["text"],
+        "hello from json"
+    );
+    assert_eq!(events[0]["modelVersion"], "gemini-2.5-pro");
+    // The Antigravity envelope is unwrapped, as for real SSE chunks.
+    assert!(events[0].get("response").is_none(), "{body}");
+    assert!(events[0].get("traceId").is_none(), "{body}");
+}
@@ -0,0 +1,78 @@
+use axum::{Json, Router, http::HeaderMap, routing::post};
+use chrono::{Duration, Utc};
+use pollux::db::{AntigravityCreate, ProviderCreate};
+use pollux::testutil::{TestDatabase, spawn_test_server, test_app, test_config};
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+
+/// Echoes the telemetry headers it received back in the response text.
+async fn generate_handler(headers: HeaderMap) -> Json<Value> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("<missing>")
+            .to_string()
+    };
+    let text = format!(
+        "{}|{}",
+        header("x-goog-api-client"),
+        header("client-metadata")
+    );
+    Json(json!({
+        "response": {
+            "candidates": [{
+                "index": 0,
+                "content": {"role": "model", "parts": [{"text": text}]},
+                "finishReason": "STOP"
+            }]
+        }
+    }))
+}
+
+#[tokio::test]
+async fn configured_telemetry_headers_reach_upstream() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("telemetry-headers").await;
+    db.handle
+        .create(ProviderCreate::Antigravity(AntigravityCreate {
+            email: None,
+            sub: Some("sub-telemetry".to_string()),
+            project_id: "project-telemetry".to_string(),
+            refresh_token: "refresh-telemetry".to_string(),
+            access_token: Some("access-telemetry".to_string()),
+            expiry: Utc::now() + Duration::hours(1),
+        }))
+        .await
+        .expect("insert antigravity credential");
+
+    let upstream = Router::new().route("/v1internal:generateContent", post(generate_handler));
+    let upstream_base = spawn_test_server(upstream).await;
+
+    let mut cfg = test_config("pwd");
+    cfg.providers.antigravity.model_list = vec!["gemini-2.5-pro".to_string()];
+    cfg.providers.antigravity.api_url = upstream_base;
+    cfg.providers.antigravity.api_client_header = Some("pollux-test/1.0".to_string());
+    cfg.providers.antigravity.client_metadata_header = Some("pluginType=TEST".to_string());
+
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+    let base = spawn_test_server(test_app(providers, &cfg)).await;
+
+    let resp = reqwest::Client::new()
+        .post(
+            base.join("/antigravity/v1beta/models/gemini-2.5-pro:generateContent")
+                .expect("valid route url"),
+        )
+        .header("x-goog-api-key", "pwd")
+        .json(&json!({"contents": [{"role": "user", "parts": [{"text": "hi"}]}]}))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: Value = resp.json().await.expect("response json");
+    assert_eq!(
+        body["candidates"][0]["content"]["parts"][0]["text"],
+        "pollux-test/1.0|pluginType=TEST"
+    );
+}
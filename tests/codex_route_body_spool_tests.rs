@@ -0,0 +1,98 @@
+use axum::{
+    body::{Body, to_bytes},
+    http::{Request, StatusCode},
+};
+use std::{
+    fs,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn codex_route_parses_bodies_spooled_above_threshold() {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+
+    let mut temp_path = std::env::temp_dir();
+    temp_path.push(format!(
+        "pollux-codex-body-spool-{}-{}.sqlite",
+        std::process::id(),
+        nanos
+    ));
+
+    let database_url = format!("sqlite:{}", temp_path.display());
+    let db = pollux::db::spawn(&database_url).await;
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.basic.pollux_key = "pwd".to_string();
+    // Keep test behavior stable regardless of the repo's runtime `config.toml`.
+    let model = pollux::config::CONFIG
+        .codex()
+        .model_list
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "gpt-4o-mini".to_string());
+    cfg.providers.codex.model_list = vec![model.clone()];
+    cfg.providers.codex.body_spool_threshold = Some(1024);
+
+    // No Codex keys inserted => a parsed, valid request yields 503 (NO_CREDENTIAL).
+    let providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+    let pollux_key: Arc<str> = Arc::from(cfg.basic.pollux_key.clone());
+    let state = pollux::server::router::PolluxState::new(
+        providers,
+        pollux_key.clone(),
+        cfg.basic.insecure_cookie,
+    );
+    let app = pollux::server::router::pollux_router(state);
+
+    let post = |payload: String| {
+        Request::builder()
+            .method("POST")
+            .uri("/codex/v1/responses")
+            .header("content-type", "application/json")
+            .header("content-length", payload.len())
+            .header("x-goog-api-key", pollux_key.as_ref())
+            .body(Body::from(payload))
+            .expect("failed to build request")
+    };
+
+    // 1) above the threshold: spooled to disk, still parsed into a valid request.
+    let input = "a".repeat(64 * 1024);
+    let resp = app
+        .clone()
+        .oneshot(post(format!(r#"{{"model":"{model}","input":"{input}"}}"#)))
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body = to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    let body_str = std::str::from_utf8(&body).expect("response body was not utf-8");
+    assert!(body_str.contains(r#""code":"NO_CREDENTIAL""#), "{body_str}");
+
+    // 2) above the threshold but malformed: rejected like the in-memory path.
+    let resp = app
+        .clone()
+        .oneshot(post(format!(r#"{{"model":"{model}","input":"{input}""#)))
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body = to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    let body_str = std::str::from_utf8(&body).expect("response body was not utf-8");
+    assert!(body_str.contains(r#""code":"INVALID_JSON""#), "{body_str}");
+
+    // 3) below the threshold: buffered in memory as before.
+    let resp = app
+        .clone()
+        .oneshot(post(format!(r#"{{"model":"{model}"}}"#)))
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let _ = fs::remove_file(&temp_path);
+}
@@ -0,0 +1,95 @@
+use std::{
+    fs,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+#[tokio::test]
+async fn codex_route_rejects_oversized_expect_continue_before_upload() {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+
+    let mut temp_path = std::env::temp_dir();
+    temp_path.push(format!(
+        "pollux-codex-expect-continue-{}-{}.sqlite",
+        std::process::id(),
+        nanos
+    ));
+
+    let database_url = format!("sqlite:{}", temp_path.display());
+    let db = pollux::db::spawn(&database_url).await;
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.basic.pollux_key = "pwd".to_string();
+
+    let providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+    let pollux_key: Arc<str> = Arc::from(cfg.basic.pollux_key.clone());
+    let state = pollux::server::router::PolluxState::new(
+        providers,
+        pollux_key.clone(),
+        cfg.basic.insecure_cookie,
+    );
+    let app = pollux::server::router::pollux_router(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind listener");
+    let addr = listener.local_addr().expect("local addr");
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("server run");
+    });
+
+    // Announce a 200 MiB body (over the 100 MiB Codex limit) but never send it: the server has
+    // to answer from the headers alone instead of asking for the body with `100 Continue`.
+    let mut stream = TcpStream::connect(addr).await.expect("connect");
+    let head = format!(
+        "POST /codex/v1/responses HTTP/1.1\r\n\
+         Host: {addr}\r\n\
+         Authorization: Bearer {key}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {len}\r\n\
+         Expect: 100-continue\r\n\
+         Connection: close\r\n\
+         \r\n",
+        key = pollux_key,
+        len = 200 * 1024 * 1024,
+    );
+    stream
+        .write_all(head.as_bytes())
+        .await
+        .expect("write request head");
+
+    let mut received = Vec::new();
+    let mut buf = [0u8; 4096];
+    let response = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let n = stream.read(&mut buf).await.expect("read response");
+            received.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&received);
+            if n == 0 || text.contains("PAYLOAD_TOO_LARGE") {
+                return text.into_owned();
+            }
+        }
+    })
+    .await
+    .expect("server must answer without waiting for the body");
+
+    assert!(
+        response.starts_with("HTTP/1.1 413"),
+        "expected an immediate 413, got: {response}"
+    );
+    assert!(!response.contains("100 Continue"), "{response}");
+    assert!(response.contains("PAYLOAD_TOO_LARGE"), "{response}");
+    assert!(
+        response.contains("request body too large; limit is 104857600 bytes"),
+        "{response}"
+    );
+
+    let _ = fs::remove_file(&temp_path);
+}
@@ -39,6 +39,8 @@ async fn test_geminicli_db_actor_baseline() {
         refresh_token: refresh_token.clone(),
         access_token: access_token.clone(),
         expiry,
+        quota_tier: Some("free-tier".to_string()),
+        supported_models: Some(0b101),
     };
     let provider_create = ProviderCreate::GeminiCli(create_data);
 
@@ -61,6 +63,8 @@ async fn test_geminicli_db_actor_baseline() {
     assert_eq!(credential.email, email);
     assert_eq!(credential.access_token, access_token);
     assert_eq!(credential.expiry.timestamp(), expiry.timestamp()); // Compare timestamps for equality
+    assert_eq!(credential.quota_tier.as_deref(), Some("free-tier"));
+    assert_eq!(credential.supported_models, Some(0b101));
     assert!(credential.status);
 
     // 4. Patch access_token while status remains active
@@ -0,0 +1,91 @@
+use axum::{Json, Router, routing::post};
+use chrono::{Duration, Utc};
+use pollux::db::{AntigravityCreate, ProviderCreate};
+use pollux::testutil::{TestDatabase, spawn_test_server, test_app, test_config};
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+
+async fn generate_handler() -> Json<Value> {
+    Json(json!({
+        "response": {
+            "candidates": [{
+                "index": 0,
+                "content": {"role": "model", "parts": [{"text": "generated"}]},
+                "finishReason": "STOP"
+            }]
+        }
+    }))
+}
+
+#[tokio::test]
+async fn empty_contents_are_rejected_unless_the_model_allows_them() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("empty-contents").await;
+    db.handle
+        .create(ProviderCreate::Antigravity(AntigravityCreate {
+            email: None,
+            sub: Some("sub-empty".to_string()),
+            project_id: "project-empty".to_string(),
+            refresh_token: "refresh-empty".to_string(),
+            access_token: Some("access-empty".to_string()),
+            expiry: Utc::now() + Duration::hours(1),
+        }))
+        .await
+        .expect("insert antigravity credential");
+
+    let upstream = Router::new().route("/v1internal:generateContent", post(generate_handler));
+    let upstream_base = spawn_test_server(upstream).await;
+
+    let mut cfg = test_config("pwd");
+    cfg.providers.antigravity.model_list =
+        vec!["gemini-2.5-pro".to_string(), "gemini-3-flash".to_string()];
+    cfg.providers.antigravity.empty_contents_models = vec!["gemini-3-flash".to_string()];
+    cfg.providers.antigravity.api_url = upstream_base;
+
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+    let base = spawn_test_server(test_app(providers, &cfg)).await;
+    let client = reqwest::Client::new();
+    let generate = |model: &str, body: Value| {
+        client
+            .post(
+                base.join(&format!(
+                    "/antigravity/v1beta/models/{model}:generateContent"
+                ))
+                .expect("valid route url"),
+            )
+            .header("x-goog-api-key", "pwd")
+            .json(&body)
+            .send()
+    };
+
+    // Rejected by default, including when only system turns were sent.
+    for body in [
+        json!({"contents": []}),
+        json!({"contents": [{"role": "system", "parts": [{"text": "be brief"}]}]}),
+    ] {
+        let resp = generate("gemini-2.5-pro", body)
+            .await
+            .expect("request failed");
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let error: Value = resp.json().await.expect("error json");
+        assert_eq!(error["error"]["status"], "INVALID_ARGUMENT");
+        assert!(
+            error["error"]["message"]
+                .as_str()
+                .is_some_and(|message| message.contains("contents must not be empty")),
+            "{error}"
+        );
+    }
+
+    // Allowed for a configured model.
+    let resp = generate("gemini-3-flash", json!({"contents": []}))
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: Value = resp.json().await.expect("response json");
+    assert_eq!(
+        body["candidates"][0]["content"]["parts"][0]["text"],
+        "generated"
+    );
+}
@@ -0,0 +1,125 @@
+use axum::{
+    Json, Router,
+    body::{Body, to_bytes},
+    extract::State,
+    http::{Request, StatusCode},
+    routing::post,
+};
+use chrono::{Duration, Utc};
+use pollux::db::{AntigravityCreate, ProviderCreate};
+use serde_json::{Value, json};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::net::TcpListener;
+use tower::ServiceExt;
+use url::Url;
+
+async fn spawn_test_server(app: Router) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind listener");
+    let addr = listener.local_addr().expect("local addr");
+    let base = Url::parse(&format!("http://{}", addr)).expect("valid base url");
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("server run");
+    });
+
+    base
+}
+
+/// First call answers 200 with no candidates; later calls carry content.
+async fn flaky_handler(State(calls): State<Arc<AtomicUsize>>) -> Json<Value> {
+    if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+        return Json(json!({"response": {"candidates": []}}));
+    }
+    Json(json!({
+        "response": {
+            "candidates": [{
+                "index": 0,
+                "content": {"role": "model", "parts": [{"text": "second time lucky"}]},
+                "finishReason": "STOP"
+            }]
+        }
+    }))
+}
+
+#[tokio::test]
+async fn empty_200_is_retried_until_content_arrives() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+
+    let mut temp_path = std::env::temp_dir();
+    temp_path.push(format!(
+        "pollux-empty-response-{}-{}.sqlite",
+        std::process::id(),
+        nanos
+    ));
+    let database_url = format!("sqlite:{}", temp_path.display());
+    let db = pollux::db::spawn(&database_url).await;
+
+    db.create(ProviderCreate::Antigravity(AntigravityCreate {
+        email: None,
+        sub: Some("sub-empty".to_string()),
+        project_id: "project-empty".to_string(),
+        refresh_token: "refresh-empty".to_string(),
+        access_token: Some("access-empty".to_string()),
+        expiry: Utc::now() + Duration::hours(1),
+    }))
+    .await
+    .expect("insert antigravity credential");
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let upstream = Router::new()
+        .route("/v1internal:generateContent", post(flaky_handler))
+        .with_state(calls.clone());
+    let base = spawn_test_server(upstream).await;
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.basic.pollux_key = "pwd".to_string();
+    cfg.providers.antigravity.model_list = vec!["gemini-2.5-pro".to_string()];
+    cfg.providers.antigravity.api_url = base;
+
+    let providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+    let pollux_key: Arc<str> = Arc::from(cfg.basic.pollux_key.clone());
+    let state = pollux::server::router::PolluxState::new(
+        providers,
+        pollux_key.clone(),
+        cfg.basic.insecure_cookie,
+    );
+
+    let resp = pollux::server::router::pollux_router(state)
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/antigravity/v1beta/models/gemini-2.5-pro:generateContent")
+                .header("content-type", "application/json")
+                .header("x-goog-api-key", pollux_key.as_ref())
+                .body(Body::from(
+                    r#"{"contents":[{"role":"user","parts":[{"text":"hi"}]}]}"#,
+                ))
+                .expect("failed to build request"),
+        )
+        .await
+        .expect("request failed");
+    let status = resp.status();
+    let body = to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    let body = String::from_utf8(body.to_vec()).expect("utf-8 body");
+
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert!(body.contains("second time lucky"), "{body}");
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    let _ = tokio::fs::remove_file(&temp_path).await;
+}
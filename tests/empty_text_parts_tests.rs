@@ -0,0 +1,98 @@
+use axum::{Router, http::header::CONTENT_TYPE, response::IntoResponse, routing::post};
+use chrono::{Duration, Utc};
+use pollux::db::{AntigravityCreate, ProviderCreate};
+use pollux::server::router::{PolluxState, pollux_router};
+use pollux::testutil::{TestDatabase, spawn_test_server, test_config};
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+fn chunk(parts: Value, finish_reason: Option<&str>) -> String {
+    let body = json!({
+        "response": {
+            "candidates": [{
+                "index": 0,
+                "content": {"role": "model", "parts": parts},
+                "finishReason": finish_reason
+            }]
+        }
+    });
+    format!("data: {body}\n\n")
+}
+
+async fn stream_handler() -> impl IntoResponse {
+    let body = [
+        chunk(json!([{"text": ""}]), None),
+        chunk(json!([{"text": "hello"}]), None),
+        chunk(
+            json!([{"text": ""}, {"functionCall": {"name": "lookup", "args": {}}}]),
+            None,
+        ),
+        chunk(json!([{"text": ""}]), Some("STOP")),
+    ]
+    .concat();
+    ([(CONTENT_TYPE, "text/event-stream")], body)
+}
+
+#[tokio::test]
+async fn empty_text_parts_are_removed_from_client_output() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("empty-text-parts").await;
+    db.handle
+        .create(ProviderCreate::Antigravity(AntigravityCreate {
+            email: None,
+            sub: Some("sub-empty-text".to_string()),
+            project_id: "project-empty-text".to_string(),
+            refresh_token: "refresh-empty-text".to_string(),
+            access_token: Some("access-empty-text".to_string()),
+            expiry: Utc::now() + Duration::hours(1),
+        }))
+        .await
+        .expect("insert antigravity credential");
+
+    let upstream = Router::new().route("/v1internal:streamGenerateContent", post(stream_handler));
+    let upstream_base = spawn_test_server(upstream).await;
+
+    let mut cfg = test_config("pwd");
+    cfg.providers.antigravity.model_list = vec!["gemini-2.5-pro".to_string()];
+    cfg.providers.antigravity.api_url = upstream_base;
+
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+    let state = PolluxState::new(providers, Arc::from("pwd"), false).with_empty_text_stripped(true);
+    let base = spawn_test_server(pollux_router(state)).await;
+
+    let resp = reqwest::Client::new()
+        .post(
+            base.join("/antigravity/v1beta/models/gemini-2.5-pro:streamGenerateContent?alt=sse")
+                .expect("valid route url"),
+        )
+        .header("x-goog-api-key", "pwd")
+        .json(&json!({"contents": [{"role": "user", "parts": [{"text": "hi"}]}]}))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = resp.text().await.expect("response body");
+
+    let chunks: Vec<Value> = body
+        .lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .map(|data| serde_json::from_str(data).expect("chunk json"))
+        .collect();
+    // The leading empty delta is gone; the rest still carry text, a call or the finish.
+    assert_eq!(chunks.len(), 3, "{body}");
+    let parts: Vec<&Value> = chunks
+        .iter()
+        .flat_map(|chunk| {
+            chunk["candidates"][0]["content"]["parts"]
+                .as_array()
+                .into_iter()
+                .flatten()
+        })
+        .collect();
+    assert_eq!(parts.len(), 2, "{body}");
+    assert_eq!(parts[0]["text"], "hello");
+    assert_eq!(parts[1]["functionCall"]["name"], "lookup");
+    assert_eq!(chunks[2]["candidates"][0]["finishReason"], "STOP");
+}
@@ -0,0 +1,126 @@
+use axum::{
+    Json, Router,
+    body::{Body, to_bytes},
+    http::{Request, StatusCode},
+    routing::post,
+};
+use chrono::{Duration, Utc};
+use pollux::db::{AntigravityCreate, ProviderCreate};
+use serde_json::{Value, json};
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::net::TcpListener;
+use tower::ServiceExt;
+use url::Url;
+
+async fn spawn_test_server(app: Router) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind listener");
+    let addr = listener.local_addr().expect("local addr");
+    let base = Url::parse(&format!("http://{}", addr)).expect("valid base url");
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("server run");
+    });
+
+    base
+}
+
+async fn safety_handler() -> Json<Value> {
+    Json(json!({
+        "response": {
+            "candidates": [{"index": 0, "finishReason": "SAFETY"}]
+        }
+    }))
+}
+
+#[tokio::test]
+async fn safety_finish_is_an_error_only_under_a_strict_allowlist() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+
+    let mut temp_path = std::env::temp_dir();
+    temp_path.push(format!(
+        "pollux-finish-reason-{}-{}.sqlite",
+        std::process::id(),
+        nanos
+    ));
+    let database_url = format!("sqlite:{}", temp_path.display());
+    let db = pollux::db::spawn(&database_url).await;
+
+    db.create(ProviderCreate::Antigravity(AntigravityCreate {
+        email: None,
+        sub: Some("sub-finish".to_string()),
+        project_id: "project-finish".to_string(),
+        refresh_token: "refresh-finish".to_string(),
+        access_token: Some("access-finish".to_string()),
+        expiry: Utc::now() + Duration::hours(1),
+    }))
+    .await
+    .expect("insert antigravity credential");
+
+    let upstream = Router::new().route("/v1internal:generateContent", post(safety_handler));
+    let base = spawn_test_server(upstream).await;
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.basic.pollux_key = "pwd".to_string();
+    cfg.providers.antigravity.model_list = vec!["gemini-2.5-pro".to_string()];
+    cfg.providers.antigravity.api_url = base;
+
+    let providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+    let pollux_key: Arc<str> = Arc::from(cfg.basic.pollux_key.clone());
+    let default_state = pollux::server::router::PolluxState::new(
+        providers,
+        pollux_key.clone(),
+        cfg.basic.insecure_cookie,
+    );
+    let strict_state = default_state
+        .clone()
+        .with_success_finish_reasons(Some(vec!["STOP".to_string(), "MAX_TOKENS".to_string()]));
+
+    let send = |state: pollux::server::router::PolluxState| {
+        let request = Request::builder()
+            .method("POST")
+            .uri("/antigravity/v1beta/models/gemini-2.5-pro:generateContent")
+            .header("content-type", "application/json")
+            .header("x-goog-api-key", pollux_key.as_ref())
+            .body(Body::from(
+                r#"{"contents":[{"role":"user","parts":[{"text":"hi"}]}]}"#,
+            ))
+            .expect("failed to build request");
+        async move {
+            let resp = pollux::server::router::pollux_router(state)
+                .oneshot(request)
+                .await
+                .expect("request failed");
+            let status = resp.status();
+            let body = to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .expect("failed to read response body");
+            (
+                status,
+                String::from_utf8(body.to_vec()).expect("utf-8 body"),
+            )
+        }
+    };
+
+    // 1) default: SAFETY is passed through as a normal completion.
+    let (status, body) = send(default_state).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert!(body.contains(r#""finishReason":"SAFETY""#), "{body}");
+
+    // 2) strict allowlist: SAFETY becomes a client error.
+    let (status, body) = send(strict_state).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+    assert!(body.contains(r#""status":"FAILED_PRECONDITION""#), "{body}");
+    assert!(body.contains("finished with SAFETY"), "{body}");
+
+    let _ = tokio::fs::remove_file(&temp_path).await;
+}
@@ -0,0 +1,64 @@
+use axum::response::IntoResponse;
+use chrono::{Duration, Utc};
+use pollux::PolluxError;
+use pollux::db::{GeminiCliCreate, GeminiCliPatch, ProviderCreate, ProviderPatch};
+use pollux::testutil::TestDatabase;
+use reqwest::StatusCode;
+
+fn reauth(refresh_token: &str) -> GeminiCliCreate {
+    GeminiCliCreate {
+        email: Some("dup@example.com".to_string()),
+        sub: "sub-dup".to_string(),
+        project_id: "project-dup".to_string(),
+        refresh_token: refresh_token.to_string(),
+        access_token: Some("access".to_string()),
+        expiry: Utc::now() + Duration::hours(1),
+        quota_tier: None,
+        supported_models: None,
+    }
+}
+
+#[tokio::test]
+async fn reauth_for_an_active_project_conflicts_instead_of_overwriting() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("duplicate-project").await;
+    let id = db
+        .handle
+        .create(ProviderCreate::GeminiCli(reauth("refresh-working")))
+        .await
+        .expect("insert credential");
+
+    let err = db
+        .handle
+        .create_new_geminicli(reauth("refresh-reauth"))
+        .await
+        .expect_err("duplicate project must be rejected");
+    assert!(matches!(err, PolluxError::Conflict(_)), "{err:?}");
+    assert_eq!(err.into_response().status(), StatusCode::CONFLICT);
+
+    let rows = db.handle.list_active_geminicli().await.expect("list");
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].id, id);
+    assert_eq!(rows[0].refresh_token, "refresh-working");
+
+    // A disabled credential is not protected; re-auth replaces and reactivates it.
+    db.handle
+        .patch(ProviderPatch::GeminiCli {
+            id: u64::try_from(id).expect("positive id"),
+            patch: GeminiCliPatch {
+                status: Some(false),
+                ..Default::default()
+            },
+        })
+        .await
+        .expect("disable credential");
+    let reauthed = db
+        .handle
+        .create_new_geminicli(reauth("refresh-reauth"))
+        .await
+        .expect("disabled project can be re-authed");
+    assert_eq!(reauthed, id);
+    let rows = db.handle.list_active_geminicli().await.expect("list");
+    assert_eq!(rows[0].refresh_token, "refresh-reauth");
+}
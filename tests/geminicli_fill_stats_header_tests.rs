@@ -0,0 +1,49 @@
+use pollux::testutil::{TestDatabase, spawn_test_server, test_app, test_config};
+use serde_json::json;
+
+#[tokio::test]
+async fn fill_stats_header_summarizes_the_request_fill() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("geminicli-fill-stats").await;
+
+    let mut cfg = test_config("pwd");
+    cfg.providers.geminicli.model_list = vec!["gemini-2.5-pro".to_string()];
+    cfg.providers.geminicli.fill_stats_header = true;
+
+    // No credentials: the request is filled, then answered with 503.
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+    let base = spawn_test_server(test_app(providers, &cfg)).await;
+
+    let url = base
+        .join("/geminicli/v1beta/models/gemini-2.5-pro:generateContent")
+        .expect("valid route url");
+    let resp = reqwest::Client::new()
+        .post(url)
+        .header("x-goog-api-key", "pwd")
+        .json(&json!({
+            "contents": [
+                {"role": "user", "parts": [{"text": "hi"}]},
+                {
+                    "role": "model",
+                    "parts": [
+                        {"thought": true, "text": "plan"},
+                        {"functionCall": {"name": "lookup", "args": {}}},
+                        {"text": "answer"}
+                    ]
+                },
+                {"role": "user", "parts": [{"text": "again"}]}
+            ]
+        }))
+        .send()
+        .await
+        .expect("request failed");
+
+    let header = resp
+        .headers()
+        .get("x-pollux-fillstats")
+        .expect("fill stats header present")
+        .to_str()
+        .expect("ascii header");
+    assert_eq!(header, "total=2;cache_hits=0;dummy=2");
+}
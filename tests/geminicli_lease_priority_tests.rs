@@ -0,0 +1,72 @@
+use axum::{Json, Router, routing::post};
+use chrono::{Duration, Utc};
+use pollux::db::{GeminiCliCreate, ProviderCreate};
+use pollux::providers::priority::LeasePriority;
+use pollux::testutil::{TestDatabase, spawn_test_server};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+
+/// Slow token endpoint, so both leases are parked before the refresh finishes.
+async fn token_handler() -> Json<Value> {
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    Json(json!({
+        "access_token": "access-fresh",
+        "token_type": "Bearer",
+        "expires_in": 3600
+    }))
+}
+
+#[tokio::test]
+async fn high_priority_lease_is_served_before_an_earlier_low_one() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("lease-priority").await;
+    db.handle
+        .create(ProviderCreate::GeminiCli(GeminiCliCreate {
+            email: None,
+            project_id: "project-priority".to_string(),
+            sub: "sub-priority".to_string(),
+            refresh_token: "refresh-priority".to_string(),
+            access_token: Some("access-expired".to_string()),
+            expiry: Utc::now() - Duration::hours(1),
+            quota_tier: None,
+            supported_models: None,
+        }))
+        .await
+        .expect("insert geminicli credential");
+
+    let upstream = Router::new().route("/token", post(token_handler));
+    let base = spawn_test_server(upstream).await;
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.providers.geminicli.oauth_token_url = base.join("/token").unwrap();
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+    let model_mask = pollux::model_catalog::mask("gemini-2.5-pro").expect("model is registered");
+
+    let served = Arc::new(Mutex::new(Vec::new()));
+    let lease = |priority: LeasePriority| {
+        let handle = providers.geminicli.clone();
+        let served = served.clone();
+        tokio::spawn(async move {
+            let lease = handle
+                .get_credential_with_priority(model_mask, priority)
+                .await
+                .expect("lease RPC")
+                .expect("refreshed credential leased");
+            assert_eq!(lease.access_token, "access-fresh");
+            served.lock().unwrap().push(priority);
+        })
+    };
+
+    // The low-priority lease starts the refresh and waits first.
+    let low = lease(LeasePriority::Low);
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let high = lease(LeasePriority::High);
+    low.await.expect("low lease task");
+    high.await.expect("high lease task");
+
+    assert_eq!(
+        *served.lock().unwrap(),
+        vec![LeasePriority::High, LeasePriority::Low]
+    );
+}
@@ -0,0 +1,91 @@
+use axum::{Form, Json, Router, extract::State, routing::post};
+use chrono::{Duration, Utc};
+use pollux::db::{GeminiCliCreate, ProviderCreate};
+use pollux::providers::priority::LeasePriority;
+use pollux::testutil::{TestDatabase, spawn_test_server};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+/// Refreshes `refresh-slow` only once the test releases it, so that credential stays busy.
+async fn token_handler(
+    State(release): State<Arc<Notify>>,
+    Form(form): Form<HashMap<String, String>>,
+) -> Json<Value> {
+    let refresh_token = form.get("refresh_token").cloned().unwrap_or_default();
+    if refresh_token == "refresh-slow" {
+        release.notified().await;
+    } else {
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    }
+    Json(json!({
+        "access_token": refresh_token.replace("refresh", "access"),
+        "token_type": "Bearer",
+        "expires_in": 3600
+    }))
+}
+
+fn expired_credential(name: &str) -> ProviderCreate {
+    ProviderCreate::GeminiCli(GeminiCliCreate {
+        email: None,
+        project_id: format!("project-{name}"),
+        sub: format!("sub-{name}"),
+        refresh_token: format!("refresh-{name}"),
+        access_token: Some("access-expired".to_string()),
+        expiry: Utc::now() - Duration::hours(1),
+        quota_tier: None,
+        supported_models: None,
+    })
+}
+
+#[tokio::test]
+async fn freed_credential_goes_to_a_later_high_priority_lease_first() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("lease-queue").await;
+    for name in ["fast", "slow"] {
+        db.handle
+            .create(expired_credential(name))
+            .await
+            .expect("insert geminicli credential");
+    }
+
+    let release = Arc::new(Notify::new());
+    let upstream = Router::new()
+        .route("/token", post(token_handler))
+        .with_state(release.clone());
+    let base = spawn_test_server(upstream).await;
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.providers.geminicli.oauth_token_url = base.join("/token").unwrap();
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+    let model_mask = pollux::model_catalog::mask("gemini-2.5-pro").expect("model is registered");
+
+    let lease = |priority: LeasePriority| {
+        let handle = providers.geminicli.clone();
+        tokio::spawn(async move {
+            handle
+                .get_credential_with_priority(model_mask, priority)
+                .await
+                .expect("lease RPC")
+                .expect("refreshed credential leased")
+                .access_token
+        })
+    };
+
+    // The low-priority lease starts both refreshes and waits first.
+    let low = lease(LeasePriority::Low);
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let high = lease(LeasePriority::High);
+
+    assert_eq!(high.await.expect("high lease task"), "access-fast");
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert!(
+        !low.is_finished(),
+        "low lease must keep waiting for the next credential"
+    );
+
+    release.notify_one();
+    assert_eq!(low.await.expect("low lease task"), "access-slow");
+}
@@ -0,0 +1,98 @@
+use axum::{Json, Router, extract::State, routing::post};
+use chrono::{Duration, Utc};
+use pollux::db::{GeminiCliCreate, ProviderCreate};
+use serde_json::{Value, json};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::net::TcpListener;
+use url::Url;
+
+async fn spawn_test_server(app: Router) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind listener");
+    let addr = listener.local_addr().expect("local addr");
+    let base = Url::parse(&format!("http://{}", addr)).expect("valid base url");
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("server run");
+    });
+
+    base
+}
+
+/// Slow token endpoint, so concurrent leases overlap with the refresh.
+async fn token_handler(State(calls): State<Arc<AtomicUsize>>) -> Json<Value> {
+    calls.fetch_add(1, Ordering::SeqCst);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    Json(json!({
+        "access_token": "access-fresh",
+        "token_type": "Bearer",
+        "expires_in": 3600
+    }))
+}
+
+#[tokio::test]
+async fn expired_credential_is_refreshed_inline_on_lease() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+
+    let mut temp_path = std::env::temp_dir();
+    temp_path.push(format!(
+        "pollux-geminicli-lease-refresh-{}-{}.sqlite",
+        std::process::id(),
+        nanos
+    ));
+    let database_url = format!("sqlite:{}", temp_path.display());
+    let db = pollux::db::spawn(&database_url).await;
+
+    db.create(ProviderCreate::GeminiCli(GeminiCliCreate {
+        email: None,
+        project_id: "project-expired".to_string(),
+        sub: "sub-expired".to_string(),
+        refresh_token: "refresh-expired".to_string(),
+        access_token: Some("access-expired".to_string()),
+        expiry: Utc::now() - Duration::hours(1),
+        quota_tier: None,
+        supported_models: None,
+    }))
+    .await
+    .expect("insert geminicli credential");
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let upstream = Router::new()
+        .route("/token", post(token_handler))
+        .with_state(calls.clone());
+    let base = spawn_test_server(upstream).await;
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.providers.geminicli.oauth_token_url = base.join("/token").unwrap();
+    let providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+
+    let model_mask = pollux::model_catalog::mask("gemini-2.5-pro").expect("model is registered");
+    let (first, second) = tokio::join!(
+        providers.geminicli.get_credential(model_mask),
+        providers.geminicli.get_credential(model_mask),
+    );
+
+    for lease in [first, second] {
+        let lease = lease
+            .expect("lease RPC")
+            .expect("refreshed credential leased");
+        assert_eq!(lease.project_id, "project-expired");
+        assert_eq!(lease.access_token, "access-fresh");
+    }
+    // Both leases shared one refresh.
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    let _ = tokio::fs::remove_file(&temp_path).await;
+}
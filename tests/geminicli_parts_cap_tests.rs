@@ -0,0 +1,94 @@
+use axum::{
+    body::{Body, to_bytes},
+    http::{Request, StatusCode},
+};
+use serde_json::json;
+use std::{
+    fs,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn geminicli_route_rejects_contents_over_the_parts_cap() {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+
+    let mut temp_path = std::env::temp_dir();
+    temp_path.push(format!(
+        "pollux-geminicli-parts-cap-{}-{}.sqlite",
+        std::process::id(),
+        nanos
+    ));
+
+    let database_url = format!("sqlite:{}", temp_path.display());
+    let db = pollux::db::spawn(&database_url).await;
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.basic.pollux_key = "pwd".to_string();
+    // Keep test behavior stable regardless of the repo's runtime `config.toml`.
+    let model = pollux::config::CONFIG
+        .geminicli()
+        .model_list
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "gemini-2.5-pro".to_string());
+    cfg.providers.geminicli.model_list = vec![model.clone()];
+    cfg.providers.geminicli.max_parts_per_content = 8;
+
+    // No credentials inserted: a request that gets past preprocessing yields 503.
+    let providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+    let pollux_key: Arc<str> = Arc::from(cfg.basic.pollux_key.clone());
+    let state = pollux::server::router::PolluxState::new(
+        providers,
+        pollux_key.clone(),
+        cfg.basic.insecure_cookie,
+    );
+    let app = pollux::server::router::pollux_router(state);
+    let uri = format!("/geminicli/v1beta/models/{model}:generateContent");
+
+    let request_with_parts = |count: usize| {
+        let parts: Vec<_> = (0..count).map(|i| json!({"text": i.to_string()})).collect();
+        let payload = json!({"contents": [{"role": "user", "parts": parts}]});
+        Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("content-type", "application/json")
+            .header("x-goog-api-key", pollux_key.as_ref())
+            .body(Body::from(payload.to_string()))
+            .expect("failed to build request")
+    };
+
+    // 1) over the cap -> 400 from the preprocess layer.
+    let resp = app
+        .clone()
+        .oneshot(request_with_parts(9))
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body = to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    let body_str = std::str::from_utf8(&body).expect("response body was not utf-8");
+    assert!(
+        body_str.contains(r#""status":"INVALID_ARGUMENT""#),
+        "{body_str}"
+    );
+    assert!(
+        body_str.contains("contents[0] has 9 parts; at most 8 allowed"),
+        "{body_str}"
+    );
+
+    // 2) at the cap -> accepted, fails later for lack of credentials.
+    let resp = app
+        .clone()
+        .oneshot(request_with_parts(8))
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let _ = fs::remove_file(&temp_path);
+}
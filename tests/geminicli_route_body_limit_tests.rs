@@ -72,7 +72,10 @@ async fn geminicli_response_route_returns_413_for_oversized_body() {
     let body_str = std::str::from_utf8(&body).expect("response body was not utf-8");
     assert!(body_str.contains(r#""code":413"#));
     assert!(body_str.contains(r#""status":"PAYLOAD_TOO_LARGE""#));
-    assert!(body_str.contains(r#""message":"request body too large""#));
+    assert!(
+        body_str.contains(r#""message":"request body too large; limit is 2097152 bytes""#),
+        "{body_str}"
+    );
 
     let _ = fs::remove_file(&temp_path);
 }
@@ -0,0 +1,61 @@
+use pollux::testutil::{Capture, TestDatabase, spawn_test_server, test_app, test_config};
+use reqwest::StatusCode;
+use serde_json::json;
+
+#[tokio::test]
+async fn geminicli_route_answers_concurrent_requests_over_a_real_socket() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("geminicli-harness").await;
+
+    let mut cfg = test_config("pwd");
+    // Keep test behavior stable regardless of the repo's runtime `config.toml`.
+    let model = pollux::config::CONFIG
+        .geminicli()
+        .model_list
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "gemini-2.5-pro".to_string());
+    cfg.providers.geminicli.model_list = vec![model.clone()];
+
+    // No credentials inserted: requests that pass validation yield 503.
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+    let base = spawn_test_server(test_app(providers, &cfg)).await;
+
+    let client = reqwest::Client::new();
+    let payload = json!({"contents": [{"role": "user", "parts": [{"text": "hi"}]}]});
+    let cases = [
+        ("no_credentials", model.as_str(), Some("pwd")),
+        ("unsupported_model", "not-a-model", Some("pwd")),
+        ("bad_key", model.as_str(), Some("wrong")),
+        ("no_key", model.as_str(), None),
+    ];
+
+    let statuses: Capture<(&str, StatusCode)> = Capture::default();
+    let requests = (0..4).flat_map(|_| cases).map(|(label, model, key)| {
+        let url = base
+            .join(&format!("/geminicli/v1beta/models/{model}:generateContent"))
+            .expect("valid route url");
+        let mut request = client.post(url).json(&payload);
+        if let Some(key) = key {
+            request = request.header("x-goog-api-key", key);
+        }
+        let statuses = statuses.clone();
+        async move {
+            let resp = request.send().await.expect("request failed");
+            statuses.push((label, resp.status()));
+        }
+    });
+    futures::future::join_all(requests).await;
+
+    let statuses = statuses.take();
+    assert_eq!(statuses.len(), 16);
+    for (label, status) in statuses {
+        let expected = match label {
+            "no_credentials" => StatusCode::SERVICE_UNAVAILABLE,
+            "unsupported_model" => StatusCode::BAD_REQUEST,
+            _ => StatusCode::UNAUTHORIZED,
+        };
+        assert_eq!(status, expected, "{label}");
+    }
+}
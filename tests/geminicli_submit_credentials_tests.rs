@@ -0,0 +1,61 @@
+use chrono::{Duration, Utc};
+use pollux::db::{GeminiCliCreate, ProviderCreate};
+use pollux::providers::geminicli::SubmitOutcome;
+use pollux::providers::manifest::GeminiCliProfile;
+use pollux::testutil::{MockGoogle, TestDatabase};
+
+fn profile(project_id: &str) -> GeminiCliProfile {
+    GeminiCliProfile {
+        refresh_token: format!("refresh-{project_id}"),
+        project_id: project_id.to_string(),
+        access_token: None,
+    }
+}
+
+#[tokio::test]
+async fn submit_credentials_reports_each_outcome() {
+    // `db::spawn` registers a process-wide actor: keep this file to a single test.
+    let db = TestDatabase::spawn("geminicli-submit").await;
+    db.handle
+        .create(ProviderCreate::GeminiCli(GeminiCliCreate {
+            email: None,
+            project_id: "project-active".to_string(),
+            sub: "sub-active".to_string(),
+            refresh_token: "refresh-active".to_string(),
+            access_token: Some("access-active".to_string()),
+            expiry: Utc::now() + Duration::hours(1),
+            quota_tier: None,
+            supported_models: None,
+        }))
+        .await
+        .expect("insert geminicli credential");
+
+    // Keeps the accepted credential's onboarding refresh off the network.
+    let google = MockGoogle::spawn().await;
+    let mut cfg = pollux::config::Config::default();
+    cfg.providers.geminicli.oauth_token_url = google.token_url();
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+
+    let outcomes = providers
+        .geminicli
+        .submit_credentials(vec![
+            profile("project-new"),
+            profile("project-active"),
+            profile("project-new"),
+        ])
+        .await
+        .expect("submit RPC");
+
+    assert_eq!(outcomes.len(), 3);
+    assert_eq!(outcomes[0], SubmitOutcome::Accepted);
+    assert!(
+        matches!(&outcomes[1], SubmitOutcome::Rejected { reason } if reason.contains("already has an active credential")),
+        "{:?}",
+        outcomes[1]
+    );
+    assert!(
+        matches!(&outcomes[2], SubmitOutcome::Rejected { reason } if reason.contains("more than once")),
+        "{:?}",
+        outcomes[2]
+    );
+}
@@ -0,0 +1,113 @@
+use axum::{Json, Router, routing::post};
+use chrono::{Duration, Utc};
+use pollux::db::{AntigravityCreate, ProviderCreate};
+use pollux::testutil::{TestDatabase, spawn_test_server, test_app, test_config};
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+
+async fn generate_handler() -> Json<Value> {
+    Json(json!({
+        "response": {
+            "candidates": [{
+                "index": 0,
+                "content": {"role": "model", "parts": [{"text": "hello"}]},
+                "finishReason": "STOP"
+            }]
+        }
+    }))
+}
+
+#[tokio::test]
+async fn completed_request_writes_a_lease_log_row() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("lease-log").await;
+    let credential_id = db
+        .handle
+        .create(ProviderCreate::Antigravity(AntigravityCreate {
+            email: None,
+            sub: Some("sub-lease-log".to_string()),
+            project_id: "project-lease-log".to_string(),
+            refresh_token: "refresh-lease-log".to_string(),
+            access_token: Some("access-lease-log".to_string()),
+            expiry: Utc::now() + Duration::hours(1),
+        }))
+        .await
+        .expect("insert antigravity credential");
+
+    let upstream = Router::new().route("/v1internal:generateContent", post(generate_handler));
+    let upstream_base = spawn_test_server(upstream).await;
+
+    let mut cfg = test_config("pwd");
+    cfg.basic.lease_log = true;
+    cfg.providers.antigravity.model_list = vec!["gemini-2.5-pro".to_string()];
+    cfg.providers.antigravity.api_url = upstream_base;
+
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+    let base = spawn_test_server(test_app(providers, &cfg)).await;
+    let client = reqwest::Client::new();
+
+    let started = Utc::now();
+    let resp = client
+        .post(
+            base.join("/antigravity/v1beta/models/gemini-2.5-pro:generateContent")
+                .expect("valid route url"),
+        )
+        .header("x-goog-api-key", "pwd")
+        .header("x-request-id", "req-lease-log-1")
+        .json(&json!({"contents": [{"role": "user", "parts": [{"text": "hi"}]}]}))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // The row is written asynchronously; poll until it shows up.
+    let query_url = base
+        .join(&format!(
+            "/admin/lease-log?provider=antigravity&credential_id={credential_id}"
+        ))
+        .expect("valid admin url");
+    let mut rows = Vec::new();
+    for _ in 0..50 {
+        rows = client
+            .get(query_url.clone())
+            .header("x-goog-api-key", "pwd")
+            .send()
+            .await
+            .expect("admin request failed")
+            .json::<Vec<Value>>()
+            .await
+            .expect("lease log json");
+        if !rows.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+
+    assert_eq!(rows.len(), 1, "{rows:?}");
+    let row = &rows[0];
+    assert_eq!(row["request_id"], "req-lease-log-1");
+    assert_eq!(row["provider"], "antigravity");
+    assert_eq!(row["credential_id"], credential_id);
+    assert_eq!(row["model"], "gemini-2.5-pro");
+    assert_eq!(row["outcome"], "ok");
+    let created_at: chrono::DateTime<Utc> =
+        serde_json::from_value(row["created_at"].clone()).expect("RFC3339 timestamp");
+    assert!(created_at >= started - Duration::seconds(1));
+
+    // A time range ending before the request matches nothing.
+    let before = (started - Duration::hours(1)).to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let rows: Vec<Value> = client
+        .get(
+            base.join(&format!("/admin/lease-log?until={before}"))
+                .expect("valid admin url"),
+        )
+        .header("x-goog-api-key", "pwd")
+        .send()
+        .await
+        .expect("admin request failed")
+        .json()
+        .await
+        .expect("lease log json");
+    assert!(rows.is_empty(), "{rows:?}");
+}
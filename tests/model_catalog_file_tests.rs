@@ -0,0 +1,61 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[test]
+fn catalog_file_models_resolve_through_the_global_catalog() {
+    // NOTE: the global catalog is built once per process from `config.toml` in the working
+    // directory. Keep this test file to a single test.
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+    let mut dir = std::env::temp_dir();
+    dir.push(format!(
+        "pollux-model-catalog-{}-{}",
+        std::process::id(),
+        nanos
+    ));
+    std::fs::create_dir_all(&dir).expect("create temp dir");
+
+    let catalog_path = dir.join("models.toml");
+    std::fs::write(
+        &catalog_path,
+        r#"
+[[models]]
+name = "gemini-9-ultra"
+mask = 1099511627776
+providers = ["geminicli", "antigravity"]
+aliases = ["gemini-ultra-latest"]
+"#,
+    )
+    .expect("write catalog");
+    std::fs::write(
+        dir.join("config.toml"),
+        format!(
+            "[basic]\npollux_key = \"pwd\"\nmodel_catalog = {:?}\n",
+            catalog_path.display().to_string()
+        ),
+    )
+    .expect("write config");
+    std::env::set_current_dir(&dir).expect("enter temp dir");
+
+    assert_eq!(pollux::model_catalog::mask("gemini-9-ultra"), Some(1 << 40));
+    assert_eq!(
+        pollux::model_catalog::mask("gemini-ultra-latest"),
+        Some(1 << 40)
+    );
+    assert_eq!(
+        pollux::model_catalog::canonical_name("gemini-ultra-latest"),
+        Some("gemini-9-ultra")
+    );
+
+    let info = pollux::model_catalog::describe("gemini-ultra-latest").expect("model described");
+    assert_eq!(info.name, "gemini-9-ultra");
+    assert!(info.geminicli && info.antigravity && !info.codex);
+    assert_eq!(info.aliases, ["gemini-ultra-latest"]);
+
+    // Built-in models are still there alongside the file-defined one.
+    assert!(pollux::model_catalog::mask("gemini-2.5-pro").is_some());
+    assert_eq!(pollux::model_catalog::mask("gemini-8-ultra"), None);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,88 @@
+use axum::{
+    body::{Body, to_bytes},
+    http::{Request, StatusCode, header},
+};
+use std::{
+    fs,
+    io::Read,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn large_json_response_is_gzipped_when_client_accepts_it() {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+
+    let mut temp_path = std::env::temp_dir();
+    temp_path.push(format!(
+        "pollux-response-compression-{}-{}.sqlite",
+        std::process::id(),
+        nanos
+    ));
+
+    let database_url = format!("sqlite:{}", temp_path.display());
+    let db = pollux::db::spawn(&database_url).await;
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.basic.pollux_key = "pwd".to_string();
+    // A long model list makes the models endpoint return a large JSON body.
+    cfg.providers.antigravity.model_list = (0..500).map(|i| format!("model-{i:04}")).collect();
+
+    let providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+    let pollux_key: Arc<str> = Arc::from(cfg.basic.pollux_key.clone());
+    let state = pollux::server::router::PolluxState::new(
+        providers,
+        pollux_key.clone(),
+        cfg.basic.insecure_cookie,
+    )
+    .with_response_compression(true);
+    let app = pollux::server::router::pollux_router(state);
+
+    let get_models = |accept_encoding: Option<&'static str>| {
+        let app = app.clone();
+        let pollux_key = pollux_key.clone();
+        async move {
+            let mut req = Request::builder()
+                .method("GET")
+                .uri("/antigravity/v1beta/models")
+                .header("x-goog-api-key", pollux_key.as_ref());
+            if let Some(encoding) = accept_encoding {
+                req = req.header(header::ACCEPT_ENCODING, encoding);
+            }
+            app.oneshot(req.body(Body::empty()).expect("failed to build request"))
+                .await
+                .expect("request failed")
+        }
+    };
+
+    let plain = get_models(None).await;
+    assert_eq!(plain.status(), StatusCode::OK);
+    assert!(plain.headers().get(header::CONTENT_ENCODING).is_none());
+    let plain = to_bytes(plain.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    assert!(plain.len() > 10_000, "{} bytes", plain.len());
+
+    let gzipped = get_models(Some("gzip")).await;
+    assert_eq!(gzipped.status(), StatusCode::OK);
+    assert_eq!(
+        gzipped.headers().get(header::CONTENT_ENCODING).unwrap(),
+        "gzip"
+    );
+    let gzipped = to_bytes(gzipped.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    assert!(gzipped.len() < plain.len() / 4, "{} bytes", gzipped.len());
+
+    let mut decoded = Vec::new();
+    flate2::read::GzDecoder::new(gzipped.as_ref())
+        .read_to_end(&mut decoded)
+        .expect("response must be valid gzip");
+    assert_eq!(decoded, plain);
+
+    let _ = fs::remove_file(&temp_path);
+}
@@ -0,0 +1,114 @@
+use axum::{
+    Router,
+    body::{Body, to_bytes},
+    http::{Request, StatusCode, header::CONTENT_TYPE},
+    response::IntoResponse,
+    routing::post,
+};
+use chrono::{Duration, Utc};
+use pollux::db::{AntigravityCreate, ProviderCreate};
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::net::TcpListener;
+use tower::ServiceExt;
+use url::Url;
+
+async fn spawn_test_server(app: Router) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind listener");
+    let addr = listener.local_addr().expect("local addr");
+    let base = Url::parse(&format!("http://{}", addr)).expect("valid base url");
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("server run");
+    });
+
+    base
+}
+
+async fn stream_handler() -> impl IntoResponse {
+    (
+        [(CONTENT_TYPE, "text/event-stream")],
+        concat!(
+            "data: {\"response\":{\"candidates\":[{\"index\":0,",
+            "\"content\":{\"role\":\"model\",\"parts\":[{\"text\":\"hi\"}]},",
+            "\"finishReason\":\"STOP\"}]}}\n\n",
+        ),
+    )
+}
+
+#[tokio::test]
+async fn stream_starts_with_configured_retry_hint() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+
+    let mut temp_path = std::env::temp_dir();
+    temp_path.push(format!(
+        "pollux-sse-retry-{}-{}.sqlite",
+        std::process::id(),
+        nanos
+    ));
+    let database_url = format!("sqlite:{}", temp_path.display());
+    let db = pollux::db::spawn(&database_url).await;
+
+    db.create(ProviderCreate::Antigravity(AntigravityCreate {
+        email: None,
+        sub: Some("sub-retry".to_string()),
+        project_id: "project-retry".to_string(),
+        refresh_token: "refresh-retry".to_string(),
+        access_token: Some("access-retry".to_string()),
+        expiry: Utc::now() + Duration::hours(1),
+    }))
+    .await
+    .expect("insert antigravity credential");
+
+    let upstream = Router::new().route("/v1internal:streamGenerateContent", post(stream_handler));
+    let base = spawn_test_server(upstream).await;
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.basic.pollux_key = "pwd".to_string();
+    cfg.providers.antigravity.model_list = vec!["gemini-2.5-pro".to_string()];
+    cfg.providers.antigravity.api_url = base;
+
+    let providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+    let pollux_key: Arc<str> = Arc::from(cfg.basic.pollux_key.clone());
+    let state = pollux::server::router::PolluxState::new(
+        providers,
+        pollux_key.clone(),
+        cfg.basic.insecure_cookie,
+    )
+    .with_sse_retry(Some(std::time::Duration::from_millis(2500)));
+    let app = pollux::server::router::pollux_router(state);
+
+    let resp = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/antigravity/v1beta/models/gemini-2.5-pro:streamGenerateContent")
+                .header("content-type", "application/json")
+                .header("x-goog-api-key", pollux_key.as_ref())
+                .body(Body::from(
+                    r#"{"contents":[{"role":"user","parts":[{"text":"hi"}]}]}"#,
+                ))
+                .expect("failed to build request"),
+        )
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body = to_bytes(resp.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    let body = String::from_utf8(body.to_vec()).expect("utf-8 body");
+    assert!(body.starts_with("retry: 2500\n\n"), "{body}");
+    assert!(body.contains("\"text\":\"hi\""), "{body}");
+
+    let _ = tokio::fs::remove_file(&temp_path).await;
+}
@@ -0,0 +1,77 @@
+use chrono::{Duration, Utc};
+use pollux::db::{GeminiCliCreate, ProviderCreate};
+use pollux::server::summary::{ProviderSummary, StartupSummary};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[tokio::test]
+async fn startup_summary_reflects_config_and_credentials() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+
+    let mut temp_path = std::env::temp_dir();
+    temp_path.push(format!(
+        "pollux-startup-summary-{}-{}.sqlite",
+        std::process::id(),
+        nanos
+    ));
+    let database_url = format!("sqlite:{}", temp_path.display());
+    let db = pollux::db::spawn(&database_url).await;
+
+    db.create(ProviderCreate::GeminiCli(GeminiCliCreate {
+        email: None,
+        project_id: "project-summary".to_string(),
+        sub: "sub-summary".to_string(),
+        refresh_token: "refresh-summary".to_string(),
+        access_token: Some("access-summary".to_string()),
+        expiry: Utc::now() + Duration::hours(1),
+        quota_tier: None,
+        supported_models: None,
+    }))
+    .await
+    .expect("insert geminicli credential");
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.basic.pollux_key = "pwd".to_string();
+    cfg.basic.listen_addr = "127.0.0.1".parse().unwrap();
+    cfg.basic.listen_port = 9188;
+    cfg.basic.insecure_cookie = true;
+    cfg.basic.sse_buffer_capacity = 32;
+    cfg.providers.geminicli.model_list = vec!["gemini-2.5-pro".to_string()];
+    cfg.providers.codex.model_list = Vec::new();
+
+    let providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+    let summary = StartupSummary::collect(&cfg, &providers, &db)
+        .await
+        .expect("collect startup summary");
+    summary.log();
+
+    assert_eq!(summary.bind.to_string(), "127.0.0.1:9188");
+    assert_eq!(summary.auth_mode, "pollux_key");
+    assert!(!summary.oauth_cookie_secure);
+    assert_eq!(summary.sse_buffer_capacity, 32);
+    assert_eq!(
+        summary.providers["geminicli"],
+        ProviderSummary {
+            enabled: true,
+            models: vec!["gemini-2.5-pro".to_string()],
+            credentials: 1,
+        }
+    );
+    assert_eq!(
+        summary.providers["codex"],
+        ProviderSummary {
+            enabled: false,
+            models: Vec::new(),
+            credentials: 0,
+        }
+    );
+    assert_eq!(summary.providers["antigravity"].credentials, 0);
+    assert!(summary.thoughtsig_cache_capacity["geminicli"] > 0);
+    assert!(summary.thoughtsig_cache_capacity["antigravity"] > 0);
+
+    let _ = tokio::fs::remove_file(&temp_path).await;
+}
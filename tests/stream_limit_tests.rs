@@ -0,0 +1,105 @@
+use axum::{
+    body::{Body, to_bytes},
+    http::{Request, StatusCode},
+};
+use std::{
+    fs,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn stream_over_the_limit_is_rejected_while_unary_requests_pass() {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time before UNIX_EPOCH")
+        .as_nanos();
+
+    let mut temp_path = std::env::temp_dir();
+    temp_path.push(format!(
+        "pollux-stream-limit-{}-{}.sqlite",
+        std::process::id(),
+        nanos
+    ));
+
+    let database_url = format!("sqlite:{}", temp_path.display());
+    let db = pollux::db::spawn(&database_url).await;
+
+    let mut cfg = pollux::config::Config::default();
+    cfg.basic.pollux_key = "pwd".to_string();
+    // Keep test behavior stable regardless of the repo's runtime `config.toml`.
+    let model = pollux::config::CONFIG
+        .geminicli()
+        .model_list
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "gemini-2.5-pro".to_string());
+    cfg.providers.geminicli.model_list = vec![model.clone()];
+
+    let providers = pollux::providers::Providers::spawn(db.clone(), &cfg).await;
+    let pollux_key: Arc<str> = Arc::from(cfg.basic.pollux_key.clone());
+    let state = pollux::server::router::PolluxState::new(
+        providers,
+        pollux_key.clone(),
+        cfg.basic.insecure_cookie,
+    )
+    .with_max_concurrent_streams(Some(2));
+    let limiter = state.stream_limiter.clone();
+    let app = pollux::server::router::pollux_router(state);
+
+    let send = |method: &str| {
+        let app = app.clone();
+        let pollux_key = pollux_key.clone();
+        let uri = format!("/geminicli/v1beta/models/{model}:{method}");
+        async move {
+            let resp = app
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri(uri)
+                        .header("content-type", "application/json")
+                        .header("x-goog-api-key", pollux_key.as_ref())
+                        .body(Body::from(
+                            r#"{"contents":[{"role":"user","parts":[{"text":"hi"}]}]}"#,
+                        ))
+                        .expect("failed to build request"),
+                )
+                .await
+                .expect("request failed");
+            let status = resp.status();
+            let body = to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .expect("failed to read response body");
+            (
+                status,
+                String::from_utf8(body.to_vec()).expect("utf-8 body"),
+            )
+        }
+    };
+
+    // Two streams are already open.
+    let open_streams = [
+        limiter.try_acquire().expect("slot 1"),
+        limiter.try_acquire().expect("slot 2"),
+    ];
+
+    let (status, body) = send("streamGenerateContent").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert!(body.contains("too many concurrent streams"), "{body}");
+
+    // Unary requests are not limited; with no credentials they get past the limiter and fail
+    // on credential selection instead.
+    let (_, body) = send("generateContent").await;
+    assert!(!body.contains("too many concurrent streams"), "{body}");
+
+    drop(open_streams);
+    let (_, body) = send("streamGenerateContent").await;
+    assert!(!body.contains("too many concurrent streams"), "{body}");
+    assert!(
+        limiter.try_acquire().is_some(),
+        "a failed stream request must release its slot"
+    );
+
+    let _ = fs::remove_file(&temp_path);
+}
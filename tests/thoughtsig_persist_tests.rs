@@ -0,0 +1,69 @@
+use pollux::db::DbSignatureCacheEntry;
+use pollux::providers::antigravity::AntigravityThoughtSigService;
+use pollux::providers::geminicli::GeminiThoughtSigService;
+use pollux::testutil::TestDatabase;
+use pollux_thoughtsig_core::{EnginePolicy, SignatureExpiry};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn persisted_signatures_are_reloaded_by_a_new_service() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("thoughtsig-persist").await;
+    let expiry = SignatureExpiry::ttl(3600);
+
+    let before =
+        GeminiThoughtSigService::persisted(EnginePolicy::default(), expiry, db.handle.clone())
+            .await;
+    assert!(before.snapshot().expect("snapshot").is_empty());
+    before
+        .put_many(vec![
+            (7, Arc::from("sig-7")),
+            (u64::MAX, Arc::from("sig-max")),
+        ])
+        .expect("put signatures");
+
+    // Writes reach the database in the background; wait for them.
+    wait_for_rows(&db, 2).await;
+
+    // A "restarted" service starts with the stored signatures.
+    let after =
+        GeminiThoughtSigService::persisted(EnginePolicy::default(), expiry, db.handle.clone())
+            .await;
+    let mut restored = after.snapshot().expect("snapshot");
+    restored.sort_by_key(|(key, _)| *key);
+    assert_eq!(
+        restored,
+        vec![(7, Arc::from("sig-7")), (u64::MAX, Arc::from("sig-max"))]
+    );
+
+    // Each provider only reloads its own signatures.
+    let antigravity =
+        AntigravityThoughtSigService::persisted(EnginePolicy::default(), expiry, db.handle.clone())
+            .await;
+    assert!(antigravity.snapshot().expect("snapshot").is_empty());
+
+    // Forgotten signatures leave the database too, so a restart does not bring them back.
+    after.forget(7).expect("forget signature");
+    let remaining = wait_for_rows(&db, 1).await;
+    assert_eq!(remaining[0].signature, "sig-max");
+    after.clear().expect("clear signatures");
+    wait_for_rows(&db, 0).await;
+}
+
+async fn wait_for_rows(db: &TestDatabase, expected: usize) -> Vec<DbSignatureCacheEntry> {
+    let mut rows = Vec::new();
+    for _ in 0..50 {
+        rows = db
+            .handle
+            .load_signatures("geminicli", 10)
+            .await
+            .expect("load signatures");
+        if rows.len() == expected {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(rows.len(), expected, "{rows:?}");
+    rows
+}
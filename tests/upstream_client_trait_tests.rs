@@ -0,0 +1,126 @@
+use axum::{Json, Router, body::Bytes, extract::State, routing::post};
+use chrono::{Duration, Utc};
+use pollux::db::{AntigravityCreate, GeminiCliCreate, ProviderCreate};
+use pollux::providers::UpstreamClient;
+use pollux::providers::antigravity::{AntigravityClient, AntigravityContext};
+use pollux::providers::geminicli::{GeminiContext, client::GeminiClient};
+use pollux::testutil::{Capture, TestDatabase, spawn_test_server, test_config};
+use pollux_schema::gemini::GeminiGenerateContentRequest;
+use serde_json::{Value, json};
+
+const MODEL: &str = "gemini-2.5-pro";
+
+async fn generate_handler(State(bodies): State<Capture<Value>>, body: Bytes) -> Json<Value> {
+    bodies.push(serde_json::from_slice(&body).expect("upstream body json"));
+    Json(json!({
+        "response": {
+            "candidates": [{
+                "index": 0,
+                "content": {"role": "model", "parts": [{"text": "hello"}]},
+                "finishReason": "STOP"
+            }]
+        }
+    }))
+}
+
+/// Generic over the provider: only the trait is used to reach the upstream.
+async fn call_through_trait<C: UpstreamClient>(
+    client: &C,
+    handle: &C::Handle,
+    ctx: &C::Context,
+    body: &GeminiGenerateContentRequest,
+) -> reqwest::StatusCode {
+    match client.call(handle, ctx, body).await {
+        Ok(resp) => resp.status(),
+        Err(_) => panic!("upstream call failed"),
+    }
+}
+
+#[tokio::test]
+async fn geminicli_and_antigravity_clients_dispatch_through_the_trait() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("upstream-client-trait").await;
+    db.handle
+        .create(ProviderCreate::GeminiCli(GeminiCliCreate {
+            email: None,
+            sub: "sub-geminicli".to_string(),
+            project_id: "project-geminicli".to_string(),
+            refresh_token: "refresh-geminicli".to_string(),
+            access_token: Some("access-geminicli".to_string()),
+            expiry: Utc::now() + Duration::hours(1),
+            quota_tier: None,
+            supported_models: None,
+        }))
+        .await
+        .expect("insert geminicli credential");
+    db.handle
+        .create(ProviderCreate::Antigravity(AntigravityCreate {
+            email: None,
+            sub: Some("sub-antigravity".to_string()),
+            project_id: "project-antigravity".to_string(),
+            refresh_token: "refresh-antigravity".to_string(),
+            access_token: Some("access-antigravity".to_string()),
+            expiry: Utc::now() + Duration::hours(1),
+        }))
+        .await
+        .expect("insert antigravity credential");
+
+    let bodies: Capture<Value> = Capture::default();
+    let upstream = Router::new()
+        .route("/v1internal:generateContent", post(generate_handler))
+        .with_state(bodies.clone());
+    let base = spawn_test_server(upstream).await;
+
+    let mut cfg = test_config("pwd");
+    cfg.providers.geminicli.model_list = vec![MODEL.to_string()];
+    cfg.providers.antigravity.model_list = vec![MODEL.to_string()];
+    cfg.providers.antigravity.api_url = base.clone();
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+
+    let body: GeminiGenerateContentRequest = serde_json::from_value(json!({
+        "contents": [{"role": "user", "parts": [{"text": "hi"}]}]
+    }))
+    .expect("request must parse");
+    let model_mask = pollux::model_catalog::mask(MODEL).expect("model is registered");
+    let http = reqwest::Client::new();
+
+    let geminicli = GeminiClient::new(&providers.geminicli_cfg, http.clone(), Some(base.clone()));
+    let geminicli_ctx = GeminiContext {
+        model: MODEL.to_string(),
+        stream: false,
+        path: format!("/v1beta/models/{MODEL}:generateContent"),
+        model_mask,
+        conversation: None,
+        priority: Default::default(),
+    };
+    let status = call_through_trait(&geminicli, &providers.geminicli, &geminicli_ctx, &body).await;
+    assert_eq!(status, reqwest::StatusCode::OK);
+
+    let antigravity = AntigravityClient::new(&providers.antigravity_cfg, http, Some(base));
+    let antigravity_ctx = AntigravityContext {
+        model: MODEL.to_string(),
+        stream: false,
+        path: format!("/v1beta/models/{MODEL}:generateContent"),
+        model_mask,
+        conversation: None,
+    };
+    let status = call_through_trait(
+        &antigravity,
+        &providers.antigravity,
+        &antigravity_ctx,
+        &body,
+    )
+    .await;
+    assert_eq!(status, reqwest::StatusCode::OK);
+
+    // Each client leased its own provider's credential and wrapped the body its own way.
+    let bodies = bodies.take();
+    assert_eq!(bodies.len(), 2);
+    assert_eq!(bodies[0]["project"], "project-geminicli");
+    assert_eq!(bodies[1]["project"], "project-antigravity");
+    for body in &bodies {
+        assert_eq!(body["model"], MODEL);
+        assert_eq!(body["request"]["contents"][0]["parts"][0]["text"], "hi");
+    }
+}
@@ -0,0 +1,98 @@
+use axum::{Json, Router, http::StatusCode as AxumStatus, routing::post};
+use chrono::{Duration, Utc};
+use pollux::db::{AntigravityCreate, ProviderCreate};
+use pollux::testutil::{TestDatabase, spawn_test_server, test_app, test_config};
+use serde_json::{Value, json};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Collects formatted log output so the test can search it.
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Captured {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+async fn failing_handler() -> (AxumStatus, [(&'static str, &'static str); 2], Json<Value>) {
+    (
+        AxumStatus::BAD_REQUEST,
+        [
+            ("x-quota-remaining", "0"),
+            ("x-internal-token", "do-not-log"),
+        ],
+        Json(json!({"error": {"code": 400, "message": "bad", "status": "INVALID_ARGUMENT"}})),
+    )
+}
+
+#[tokio::test]
+async fn allowlisted_upstream_headers_are_logged_with_the_error() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let logs = Captured::default();
+    tracing_subscriber::fmt()
+        .with_writer(logs.clone())
+        .with_ansi(false)
+        .with_max_level(tracing::Level::WARN)
+        .init();
+
+    let db = TestDatabase::spawn("header-capture").await;
+    db.handle
+        .create(ProviderCreate::Antigravity(AntigravityCreate {
+            email: None,
+            sub: Some("sub-header-capture".to_string()),
+            project_id: "project-header-capture".to_string(),
+            refresh_token: "refresh-header-capture".to_string(),
+            access_token: Some("access-header-capture".to_string()),
+            expiry: Utc::now() + Duration::hours(1),
+        }))
+        .await
+        .expect("insert antigravity credential");
+
+    let upstream = Router::new().route("/v1internal:generateContent", post(failing_handler));
+    let upstream_base = spawn_test_server(upstream).await;
+
+    let mut cfg = test_config("pwd");
+    cfg.providers.defaults.capture_response_headers = vec!["X-Quota-Remaining".to_string()];
+    cfg.providers.antigravity.model_list = vec!["gemini-2.5-pro".to_string()];
+    cfg.providers.antigravity.api_url = upstream_base;
+
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+    let base = spawn_test_server(test_app(providers, &cfg)).await;
+
+    let resp = reqwest::Client::new()
+        .post(
+            base.join("/antigravity/v1beta/models/gemini-2.5-pro:generateContent")
+                .expect("valid route url"),
+        )
+        .header("x-goog-api-key", "pwd")
+        .json(&json!({"contents": [{"role": "user", "parts": [{"text": "hi"}]}]}))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let logs = String::from_utf8(logs.0.lock().unwrap().clone()).expect("utf-8 logs");
+    let line = logs
+        .lines()
+        .find(|line| line.contains("[Antigravity] Upstream error"))
+        .unwrap_or_else(|| panic!("no upstream error logged:\n{logs}"));
+    assert!(line.contains("x-quota-remaining=0"), "{line}");
+    assert!(!logs.contains("do-not-log"), "{logs}");
+}
@@ -0,0 +1,84 @@
+use axum::{Json, Router, http::HeaderMap, routing::post};
+use chrono::{Duration, Utc};
+use pollux::db::{AntigravityCreate, ProviderCreate};
+use pollux::testutil::{TestDatabase, spawn_test_server, test_app, test_config};
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+
+/// Echoes the names and `Host` of the headers it received back in the response text.
+async fn generate_handler(headers: HeaderMap) -> Json<Value> {
+    let mut names: Vec<&str> = headers.keys().map(|name| name.as_str()).collect();
+    names.sort_unstable();
+    let host = headers
+        .get("host")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("<missing>");
+    let text = format!("{host}|{}", names.join(","));
+    Json(json!({
+        "response": {
+            "candidates": [{
+                "index": 0,
+                "content": {"role": "model", "parts": [{"text": text}]},
+                "finishReason": "STOP"
+            }]
+        }
+    }))
+}
+
+#[tokio::test]
+async fn internal_client_headers_never_reach_upstream() {
+    // NOTE: `pollux::db::spawn()` registers a singleton ractor actor by name within a process.
+    // Keep this test file to a single test.
+    let db = TestDatabase::spawn("internal-headers").await;
+    db.handle
+        .create(ProviderCreate::Antigravity(AntigravityCreate {
+            email: None,
+            sub: Some("sub-internal".to_string()),
+            project_id: "project-internal".to_string(),
+            refresh_token: "refresh-internal".to_string(),
+            access_token: Some("access-internal".to_string()),
+            expiry: Utc::now() + Duration::hours(1),
+        }))
+        .await
+        .expect("insert antigravity credential");
+
+    let upstream = Router::new().route("/v1internal:generateContent", post(generate_handler));
+    let upstream_base = spawn_test_server(upstream).await;
+    let upstream_host = upstream_base.authority().to_string();
+
+    let mut cfg = test_config("pwd");
+    cfg.providers.antigravity.model_list = vec!["gemini-2.5-pro".to_string()];
+    cfg.providers.antigravity.api_url = upstream_base;
+
+    let providers = pollux::providers::Providers::spawn(db.handle.clone(), &cfg).await;
+    let base = spawn_test_server(test_app(providers, &cfg)).await;
+
+    let resp = reqwest::Client::new()
+        .post(
+            base.join("/antigravity/v1beta/models/gemini-2.5-pro:generateContent")
+                .expect("valid route url"),
+        )
+        .header("x-goog-api-key", "pwd")
+        .header("cookie", "session=secret")
+        .header("x-pollux-conversation-id", "chat-1")
+        .json(&json!({"contents": [{"role": "user", "parts": [{"text": "hi"}]}]}))
+        .send()
+        .await
+        .expect("request failed");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: Value = resp.json().await.expect("response json");
+    let text = body["candidates"][0]["content"]["parts"][0]["text"]
+        .as_str()
+        .expect("echoed headers");
+    let (host, names) = text.split_once('|').expect("host and names");
+
+    assert_eq!(host, upstream_host);
+    let names: Vec<&str> = names.split(',').collect();
+    assert!(names.contains(&"authorization"), "{names:?}");
+    for leaked in ["x-goog-api-key", "cookie", "x-pollux-conversation-id"] {
+        assert!(
+            !names.contains(&leaked),
+            "{leaked} reached upstream: {names:?}"
+        );
+    }
+}